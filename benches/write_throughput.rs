@@ -0,0 +1,71 @@
+//! Measures how fast statements are written to a file with different write buffers.
+//!
+//! Run it with `cargo bench`. It writes 10 million statements per buffer size by
+//! default; set `FAKE_SQL_BENCH_STATEMENTS` to use another number:
+//!
+//! ```sh
+//! FAKE_SQL_BENCH_STATEMENTS=1000000 cargo bench
+//! ```
+//!
+//! The statements are generated once up front and then written round-robin, so the
+//! figures show the cost of writing alone. Generation throughput is reported separately.
+
+use fake_sql::output::{SqlWriter, DEFAULT_BUFFER_SIZE};
+use fake_sql::schema::{parse_schema, DEFAULT_SCHEMA};
+use fake_sql::{Dialect, Generator};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+/// Number of statements written per buffer size unless `FAKE_SQL_BENCH_STATEMENTS` is set.
+const DEFAULT_STATEMENTS: u64 = 10_000_000;
+
+/// Number of distinct statements generated up front.
+const POOL_SIZE: usize = 10_000;
+
+fn main() -> io::Result<()> {
+    let statements = env::var("FAKE_SQL_BENCH_STATEMENTS")
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(DEFAULT_STATEMENTS);
+
+    let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 42);
+    let start = Instant::now();
+    let pool: Vec<String> = (0..POOL_SIZE).map(|_| generator.next_statement()).collect();
+    report("generate", POOL_SIZE as u64, pool.iter().map(|sql| sql.len() as u64 + 1).sum(), start.elapsed());
+
+    let path = env::temp_dir().join(format!("fake-sql-bench-{}.sql", std::process::id()));
+    let unbuffered = write(File::create(&path)?, &pool, statements)?;
+    report("write unbuffered", statements, fs::metadata(&path)?.len(), unbuffered);
+    for buffer_size in [8 * 1024, DEFAULT_BUFFER_SIZE, 1024 * 1024] {
+        let elapsed = write(BufWriter::with_capacity(buffer_size, File::create(&path)?), &pool, statements)?;
+        let name = format!("write {} KiB buffer", buffer_size / 1024);
+        report(&name, statements, fs::metadata(&path)?.len(), elapsed);
+    }
+    fs::remove_file(&path)
+}
+
+/// Writes `statements` statements from `pool` to `out` and returns the time taken.
+fn write<W: Write>(out: W, pool: &[String], statements: u64) -> io::Result<Duration> {
+    let start = Instant::now();
+    let mut writer = SqlWriter::new(out, Dialect::Oracle);
+    for sql in pool.iter().cycle().take(statements as usize) {
+        writer.write_statement(sql)?;
+    }
+    writer.finish()?;
+    Ok(start.elapsed())
+}
+
+/// Prints the throughput of a run in statements and megabytes per second.
+fn report(name: &str, statements: u64, bytes: u64, elapsed: Duration) {
+    let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+    println!(
+        "{:<24} {:>11} statements in {:>8.3} s: {:>12.0} statements/s, {:>8.1} MB/s",
+        name,
+        statements,
+        seconds,
+        statements as f64 / seconds,
+        bytes as f64 / seconds / 1e6
+    );
+}
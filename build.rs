@@ -0,0 +1,19 @@
+//! Generates the gRPC service of the `grpc` feature from the messages of `src/grpc.rs`,
+//! without needing `protoc`; `proto/fake_sql.proto` describes the same service.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    {
+        let generate = tonic_build::manual::Method::builder()
+            .name("generate")
+            .route_name("Generate")
+            .input_type("crate::grpc::GenerateRequest")
+            .output_type("crate::grpc::GeneratedStatement")
+            .codec_path("tonic::codec::ProstCodec")
+            .server_streaming()
+            .build();
+        let service = tonic_build::manual::Service::builder().name("FakeSql").package("fake_sql").method(generate).build();
+        tonic_build::manual::Builder::new().compile(&[service]);
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * C API of fake-sql: random SQL statements for the tables of a schema.
+ *
+ * Link with libfake_sql (the cdylib or staticlib built by `cargo build --release`).
+ * Strings are UTF-8 and NUL-terminated. A function that fails returns NULL and leaves
+ * its error for fake_sql_last_error() on the calling thread; so does a function that
+ * panics, as panics never unwind into C.
+ */
+#ifndef FAKE_SQL_H
+#define FAKE_SQL_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A generator of random statements; used by one thread at a time. */
+typedef struct FakeSqlGenerator FakeSqlGenerator;
+
+/*
+ * Creates a generator for the tables of the CREATE TABLE statements in `ddl`, rendered for
+ * `dialect` (e.g. "postgres", "mysql", "sqlite", "sqlserver"; NULL for Oracle). Returns NULL
+ * if the schema or the dialect is invalid. Release it with fake_sql_generator_free().
+ */
+FakeSqlGenerator *fake_sql_generator_new(const char *ddl, const char *dialect);
+
+/* Creates a generator like fake_sql_generator_new() whose statements are the same for the same seed. */
+FakeSqlGenerator *fake_sql_generator_new_seeded(const char *ddl, const char *dialect, uint64_t seed);
+
+/* Returns the next random statement; release it with fake_sql_string_free(). */
+char *fake_sql_generator_next(FakeSqlGenerator *generator);
+
+/* Releases a generator; does nothing for NULL. */
+void fake_sql_generator_free(FakeSqlGenerator *generator);
+
+/* Releases a statement returned by fake_sql_generator_next(); does nothing for NULL. */
+void fake_sql_string_free(char *sql);
+
+/* Returns the error of the last failing call on this thread, or NULL; valid until the next failing call. */
+const char *fake_sql_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FAKE_SQL_H */
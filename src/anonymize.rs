@@ -0,0 +1,411 @@
+//! Anonymization of existing SQL dumps: the personal values of INSERT statements are
+//! replaced with generated fakes.
+
+use crate::check::{parse_ast, split_statements};
+use crate::dialect::Dialect;
+use crate::models::{Column, Table};
+use crate::providers::{matches_pattern, Provider};
+use crate::schema::{alter_table, object_name, parse_create_table};
+use crate::value::SqlValue;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sqlparser::ast::{Expr, SetExpr, Statement, TableObject, Value};
+use sqlparser::tokenizer::Span;
+use std::collections::{HashMap, HashSet};
+
+/// Providers of the columns replaced without being named: values that identify a person.
+const PERSONAL: [Provider; 7] = [
+    Provider::FirstName,
+    Provider::LastName,
+    Provider::FullName,
+    Provider::Email,
+    Provider::Phone,
+    Provider::Address,
+    Provider::ZipCode,
+];
+
+/// Number of fakes drawn for a value before a taken fake is made distinct with a number.
+const MAX_ATTEMPTS: usize = 100;
+
+/// Rewrites the INSERT statements of a SQL dump, replacing personal values with fakes.
+///
+/// Columns are personal when their name selects a name, email, phone, address or postal
+/// code provider, e.g. `customer_email`, or when they match a pattern added with
+/// [`Anonymizer::add_column`]. Fakes are generated for the column's type and length.
+///
+/// Replacements are consistent, so keys and references hold: the same value always gets
+/// the same fake, different values get different fakes, and foreign keys get the fakes of
+/// the column they reference. Everything else in the dump, including other columns,
+/// comments and formatting, is copied as it is.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::anonymize::Anonymizer;
+/// # use fake_sql::Dialect;
+/// let dump = "CREATE TABLE users (id int PRIMARY KEY, email varchar(100));\n\
+///             INSERT INTO users (id, email) VALUES (1, 'ada@lovelace.org');";
+/// let mut anonymizer = Anonymizer::with_seed(vec![], Dialect::Postgres, 7);
+/// let anonymized = anonymizer.anonymize(dump).unwrap();
+/// assert!(anonymized.starts_with("CREATE TABLE users"));
+/// assert!(anonymized.contains("VALUES (1, '") && !anonymized.contains("lovelace"));
+/// assert_eq!(anonymizer.values(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Anonymizer {
+    tables: Vec<Table>,
+    dialect: Dialect,
+    rng: StdRng,
+    /// Patterns of further `table.column` names to replace, with the provider of their fakes.
+    columns: Vec<(String, Option<Provider>)>,
+    pii_free: bool,
+    /// Fakes per column the original values belong to: the column itself, or the column a
+    /// foreign key references.
+    mappings: HashMap<(String, String), Mapping>,
+    /// Number of INSERT statements with replaced values.
+    statements: u64,
+    /// Number of replaced values.
+    values: u64,
+}
+
+/// The fakes of the values of a column.
+#[derive(Clone, Debug, Default)]
+struct Mapping {
+    /// The fake of each original value, by its text.
+    fakes: HashMap<String, SqlValue>,
+    /// Every fake handed out, so no two values share one.
+    used: HashSet<SqlValue>,
+}
+
+impl Anonymizer {
+    /// Initializes a new `Anonymizer` seeded from operating system entropy.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - The tables of the dump, if it does not create them itself.
+    /// * `dialect` - The dialect the dump is written in.
+    pub fn new(tables: Vec<Table>, dialect: Dialect) -> Anonymizer {
+        Anonymizer::with_rng(tables, dialect, StdRng::from_entropy())
+    }
+
+    /// Initializes a new `Anonymizer` with a fixed seed, so the same dump is always
+    /// anonymized the same way.
+    pub fn with_seed(tables: Vec<Table>, dialect: Dialect, seed: u64) -> Anonymizer {
+        Anonymizer::with_rng(tables, dialect, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(tables: Vec<Table>, dialect: Dialect, rng: StdRng) -> Anonymizer {
+        Anonymizer {
+            tables,
+            dialect,
+            rng,
+            columns: Vec::new(),
+            pii_free: false,
+            mappings: HashMap::new(),
+            statements: 0,
+            values: 0,
+        }
+    }
+
+    /// Replaces the values of the columns matching `pattern` too.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - A `table.column` pattern where `*` matches any run of characters,
+    ///   e.g. `users.nickname` or `*.ssn`.
+    /// * `provider` - The provider of the fakes, or `None` for the column's own.
+    pub fn add_column(&mut self, pattern: &str, provider: Option<Provider>) {
+        self.columns.push((pattern.to_string(), provider));
+    }
+
+    /// Sets whether the fakes are PII-free, see [`Provider::generate_pii_free`].
+    pub fn set_pii_free(&mut self, pii_free: bool) {
+        self.pii_free = pii_free;
+    }
+
+    /// Returns the number of INSERT statements with replaced values so far.
+    pub fn statements(&self) -> u64 {
+        self.statements
+    }
+
+    /// Returns the number of values replaced so far.
+    pub fn values(&self) -> u64 {
+        self.values
+    }
+
+    /// Anonymizes a SQL dump.
+    ///
+    /// `CREATE TABLE` statements define the tables of the INSERT statements, and keys
+    /// added with `ALTER TABLE ... ADD CONSTRAINT` apply wherever they are in the dump, as
+    /// `pg_dump` adds them after the data. INSERT statements with a `VALUES` list get their
+    /// personal values replaced, and every other statement is copied.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - A string slice that holds the SQL script.
+    ///
+    /// # Returns
+    ///
+    /// The anonymized script, or a message locating an INSERT statement that cannot be
+    /// anonymized, so no personal value is left behind unnoticed.
+    pub fn anonymize(&mut self, sql: &str) -> Result<String, String> {
+        let statements = split_statements(sql, self.dialect);
+        // The tables and their keys are read first, so foreign keys declared after the
+        // data are followed too
+        for (line, statement) in &statements {
+            let words: Vec<String> = statement.split_whitespace().take(2).map(str::to_lowercase).collect();
+            match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                ["create", "table"] => {
+                    let table = parse_create_table(statement).map_err(|err| format!("line {}: {}", line, err))?;
+                    self.tables.retain(|t| t.name != table.name);
+                    self.tables.push(table);
+                }
+                ["alter", "table"] => {
+                    // Other ALTER TABLE statements may not parse, and are copied as they are
+                    for parsed in parse_ast(statement, self.dialect).unwrap_or_default() {
+                        alter_table(&mut self.tables, &parsed);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut anonymized = String::with_capacity(sql.len());
+        let mut copied = 0;
+        for (line, statement) in statements {
+            let words: Vec<String> = statement.split_whitespace().take(2).map(str::to_lowercase).collect();
+            match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                ["copy", ..] if statement.to_lowercase().contains("from stdin") => {
+                    return Err(format!("line {}: COPY ... FROM stdin data cannot be anonymized; dump the data as INSERT statements", line));
+                }
+                ["insert" | "replace", ..] => {
+                    let rewritten = self.rewrite_insert(statement).map_err(|err| format!("line {}: {}", line, err))?;
+                    let start = statement.as_ptr() as usize - sql.as_ptr() as usize;
+                    anonymized.push_str(&sql[copied..start]);
+                    anonymized.push_str(&rewritten);
+                    copied = start + statement.len();
+                }
+                _ => {}
+            }
+        }
+        anonymized.push_str(&sql[copied..]);
+        Ok(anonymized)
+    }
+
+    /// Returns `statement`, an INSERT, with its personal values replaced.
+    fn rewrite_insert(&mut self, statement: &str) -> Result<String, String> {
+        let parsed = parse_ast(statement, self.dialect)?;
+        let insert = match parsed.as_slice() {
+            [Statement::Insert(insert)] => insert,
+            _ => return Err(format!("expected a single INSERT statement, found `{}`", statement)),
+        };
+        let TableObject::TableName(name) = &insert.table else {
+            return Err("INSERT INTO a table function cannot be anonymized".to_string());
+        };
+        let table_name = object_name(name);
+        let Some(SetExpr::Values(values)) = insert.source.as_ref().map(|query| query.body.as_ref()) else {
+            // INSERT ... SELECT copies rows already in the database
+            return Ok(statement.to_string());
+        };
+        let columns: Vec<String> = if insert.columns.is_empty() {
+            let table = self.tables.iter().find(|t| t.name == table_name).ok_or_else(|| {
+                format!("INSERT INTO {} has no column list and the table is not defined; pass its CREATE TABLE with --schema", table_name)
+            })?;
+            table.columns.iter().map(|c| c.name.clone()).collect()
+        } else {
+            insert.columns.iter().map(object_name).collect()
+        };
+        let targets: Vec<Option<((String, String), Column)>> = columns
+            .iter()
+            .map(|name| {
+                let column = self.tables.iter().find(|t| t.name == table_name).and_then(|t| t.column(name));
+                match column {
+                    Some(column) => self.target(&table_name, column, 0),
+                    None => self.target(&table_name, &Column::new(name, "varchar", None, None), 0),
+                }
+            })
+            .collect();
+
+        let mut replacements: Vec<(Span, String)> = Vec::new();
+        for row in &values.rows {
+            for (expr, target) in row.iter().zip(&targets) {
+                let (Expr::Value(value), Some((key, column))) = (expr, target) else { continue };
+                let Some(original) = original_text(&value.value) else { continue };
+                let fake = self.fake(key, column, original);
+                replacements.push((value.span, self.dialect.literal(&fake)));
+            }
+        }
+        if replacements.is_empty() {
+            return Ok(statement.to_string());
+        }
+        self.statements += 1;
+        self.values += replacements.len() as u64;
+        Ok(splice(statement, &replacements))
+    }
+
+    /// Returns the column whose fakes the values of `column` get and the column it is
+    /// generated like, or `None` if the values are kept.
+    ///
+    /// Foreign keys follow the column they reference, whatever their own name.
+    fn target(&self, table_name: &str, column: &Column, depth: usize) -> Option<((String, String), Column)> {
+        if let (Some(parent_name), true) = (&column.ref_table, depth < self.tables.len()) {
+            let parent = self.tables.iter().find(|t| &t.name == parent_name);
+            let referenced = parent.and_then(|parent| match &column.ref_column {
+                Some(name) => parent.column(name),
+                None => parent.columns.iter().find(|c| c.is_pkey),
+            });
+            if let Some(referenced) = referenced {
+                return self.target(parent_name, referenced, depth + 1);
+            }
+        }
+        let qualified = format!("{}.{}", table_name, column.name);
+        let listed = self.columns.iter().find(|(pattern, _)| matches_pattern(&pattern.to_lowercase(), &qualified.to_lowercase()));
+        let provider = match listed {
+            Some((_, provider)) => provider.or(column.provider),
+            None => Some(column.provider.or_else(|| Provider::infer(&column.name)).filter(|p| PERSONAL.contains(p))?),
+        };
+        let mut fake = column.clone();
+        fake.provider = provider;
+        fake.pii_free = self.pii_free;
+        fake.null_rate = None;
+        fake.pool = None;
+        fake.template = None;
+        Some(((table_name.to_string(), column.name.clone()), fake))
+    }
+
+    /// Returns the fake of the value `original` of the column `key`, generated like `column`.
+    fn fake(&mut self, key: &(String, String), column: &Column, original: String) -> SqlValue {
+        let mapping = self.mappings.entry(key.clone()).or_default();
+        if let Some(fake) = mapping.fakes.get(&original) {
+            return fake.clone();
+        }
+        let mut fake = column.generate_value(&mut self.rng);
+        for _ in 0..MAX_ATTEMPTS {
+            if !mapping.used.contains(&fake) {
+                break;
+            }
+            fake = column.generate_value(&mut self.rng);
+        }
+        if mapping.used.contains(&fake) {
+            // The column holds more values than its provider has fakes; the number replaces
+            // the end of the fake where the column is too short for both
+            let number = mapping.used.len().to_string();
+            let text = fake.as_text().unwrap_or_default();
+            let keep = column.length.map_or(usize::MAX, |length| (length.max(0) as usize).saturating_sub(number.len()));
+            fake = SqlValue::Text(text.chars().take(keep).chain(number.chars()).collect());
+        }
+        mapping.used.insert(fake.clone());
+        mapping.fakes.insert(original, fake.clone());
+        fake
+    }
+}
+
+/// Returns the text of a literal, or `None` for NULL.
+fn original_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        value => Some(value.clone().into_string().unwrap_or_else(|| value.to_string())),
+    }
+}
+
+/// Returns `sql` with the text of each span replaced, spans given in order.
+fn splice(sql: &str, replacements: &[(Span, String)]) -> String {
+    let line_starts: Vec<usize> = std::iter::once(0).chain(sql.match_indices('\n').map(|(i, _)| i + 1)).collect();
+    // Spans count lines and characters from 1
+    let offset = |line: u64, column: u64| {
+        let start = line_starts[line as usize - 1];
+        sql[start..].char_indices().nth(column as usize - 1).map_or(sql.len(), |(i, _)| start + i)
+    };
+    let mut spliced = String::with_capacity(sql.len());
+    let mut copied = 0;
+    for (span, text) in replacements {
+        let start = offset(span.start.line, span.start.column);
+        spliced.push_str(&sql[copied..start]);
+        spliced.push_str(text);
+        copied = offset(span.end.line, span.end.column);
+    }
+    spliced.push_str(&sql[copied..]);
+    spliced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::parse_schema;
+
+    #[test]
+    fn test_anonymize() {
+        let dump = "-- users and their orders\n\
+            CREATE TABLE users (email varchar(100) PRIMARY KEY, full_name varchar(50), city varchar(30));\n\
+            CREATE TABLE orders (id int PRIMARY KEY, buyer varchar(100) REFERENCES users(email), note text);\n\
+            INSERT INTO users VALUES ('ada@lovelace.org', 'Ada Lovelace', 'London'),\n  ('alan@turing.org', NULL, 'Wilmslow');\n\
+            INSERT INTO orders (id, buyer, note) VALUES (1, 'ada@lovelace.org', 'It''s urgent'), (2, 'alan@turing.org', 'é');\n\
+            INSERT INTO orders (id, buyer, note) VALUES (3, 'ada@lovelace.org', NULL);\n\
+            UPDATE users SET city = 'Paris';\n";
+        let mut anonymizer = Anonymizer::with_seed(vec![], Dialect::Postgres, 7);
+        let anonymized = anonymizer.anonymize(dump).unwrap();
+        assert!(!anonymized.contains("lovelace") && !anonymized.contains("turing") && !anonymized.contains("Ada Lovelace"), "{}", anonymized);
+        assert!(anonymized.contains("'London'") && anonymized.contains("NULL, 'Wilmslow'") && anonymized.contains("'It''s urgent'"));
+        assert!(anonymized.starts_with("-- users and their orders\nCREATE TABLE users") && anonymized.ends_with("UPDATE users SET city = 'Paris';\n"));
+        assert_eq!((anonymizer.statements(), anonymizer.values()), (3, 6));
+
+        // The orders reference the fake emails of their users
+        let tables = parse_schema(&anonymized).unwrap();
+        assert_eq!(tables.len(), 2);
+        let values = |statement: &str| -> Vec<String> {
+            let Statement::Insert(insert) = &parse_ast(statement, Dialect::Postgres).unwrap()[0] else { panic!() };
+            let SetExpr::Values(values) = insert.source.as_ref().unwrap().body.as_ref() else { panic!() };
+            values.rows.iter().flat_map(|row| row.iter().map(|expr| expr.to_string())).collect()
+        };
+        let statements = split_statements(&anonymized, Dialect::Postgres);
+        let users = values(statements[2].1);
+        let orders = [values(statements[3].1), values(statements[4].1)].concat();
+        assert_eq!((&orders[1], &orders[4], &orders[7]), (&users[0], &users[3], &users[0]));
+        assert_ne!(users[0], users[3]);
+    }
+
+    #[test]
+    fn test_keys_after_data() {
+        let dump = "CREATE TABLE public.users (email varchar(100) NOT NULL, initials varchar(2));\n\
+            CREATE TABLE public.orders (id int NOT NULL, buyer varchar(100));\n\
+            INSERT INTO public.users VALUES ('ada@lovelace.org', 'al'), ('alan@turing.org', 'at');\n\
+            INSERT INTO public.orders VALUES (1, 'alan@turing.org');\n\
+            ALTER TABLE ONLY public.users ADD CONSTRAINT users_pkey PRIMARY KEY (email);\n\
+            ALTER TABLE ONLY public.orders ADD CONSTRAINT orders_buyer_fkey FOREIGN KEY (buyer) REFERENCES public.users(email);\n";
+        let mut anonymizer = Anonymizer::with_seed(vec![], Dialect::Postgres, 7);
+        let anonymized = anonymizer.anonymize(dump).unwrap();
+        assert!(!anonymized.contains("turing") && anonymized.ends_with("REFERENCES public.users(email);\n"), "{}", anonymized);
+        let statements = split_statements(&anonymized, Dialect::Postgres);
+        let fake = statements[2].1.split('\'').nth(5).unwrap();
+        assert!(statements[3].1.contains(fake), "{}", anonymized);
+
+        // Fakes made distinct with a number keep to the length of the column
+        let tables = parse_schema("create table p (initials varchar(2))").unwrap();
+        let mut anonymizer = Anonymizer::with_seed(tables, Dialect::Postgres, 7);
+        anonymizer.add_column("p.initials", Some(Provider::LastName));
+        let rows: Vec<String> = (0..90).map(|n| format!("('#{}')", n)).collect();
+        let anonymized = anonymizer.anonymize(&format!("INSERT INTO p VALUES {};", rows.join(", "))).unwrap();
+        let fakes: HashSet<&str> = anonymized.split('\'').skip(1).step_by(2).collect();
+        assert_eq!(fakes.len(), 90);
+        assert!(fakes.iter().all(|fake| fake.chars().count() <= 2), "{}", anonymized);
+    }
+
+    #[test]
+    fn test_columns_and_errors() {
+        let tables = parse_schema("create table p (id int primary key, nickname varchar(20), ssn varchar(11))").unwrap();
+        let mut anonymizer = Anonymizer::with_seed(tables, Dialect::MySql, 7);
+        anonymizer.add_column("p.nick*", Some(Provider::FirstName));
+        anonymizer.set_pii_free(true);
+        let anonymized = anonymizer.anonymize("INSERT INTO p VALUES (1, 'zorro', '123-45-6789');").unwrap();
+        assert!(!anonymized.contains("zorro") && anonymized.contains("'123-45-6789'"), "{}", anonymized);
+
+        // Without a schema, the column list names the columns
+        let mut anonymizer = Anonymizer::with_seed(vec![], Dialect::MySql, 7);
+        let anonymized = anonymizer.anonymize("INSERT INTO q (id, phone) VALUES (1, '+1 212 555 7000');").unwrap();
+        assert!(anonymized.contains("-555-01") || !anonymized.contains("212"), "{}", anonymized);
+        for invalid in ["INSERT INTO q VALUES (1, 'x');", "SELECT 1;\nINSERT INTO p (id VALUES (1);", "COPY p FROM stdin;"] {
+            let err = Anonymizer::new(vec![], Dialect::Postgres).anonymize(invalid).unwrap_err();
+            assert!(err.starts_with("line "), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! Parquet files of generated rows, with an Arrow schema derived from the column types.
+//!
+//! Numbers keep their width and scale, e.g. `smallint` becomes `Int16` and `number(10,2)`
+//! becomes `Decimal128(10, 2)`, dates and times get their temporal types, arrays become
+//! lists and composites structs, so DuckDB, Spark or pandas read the files with the types
+//! of the schema. UUIDs, JSON documents and spatial values (as Well-Known Text) are strings.
+
+use crate::models::{Column, Table};
+use crate::output::RowWriter;
+use crate::value::SqlValue;
+use arrow_array::types::IntervalDayTime;
+use arrow_array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, Float64Array, Int16Array, Int32Array, Int64Array, IntervalDayTimeArray,
+    ListArray, RecordBatch, StringArray, StructArray, Time64MicrosecondArray, TimestampMicrosecondArray,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_schema::{DataType, Field, IntervalUnit, Schema, SchemaRef, TimeUnit};
+use chrono::{NaiveDate, Timelike};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Number of rows of a table buffered before they are written as a record batch.
+pub const BATCH_SIZE: usize = 8192;
+
+/// The time zone of `timestamptz` values, which are stored as instants in UTC.
+const UTC: &str = "+00:00";
+
+/// Returns the Arrow type of the values of `column`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::arrow::data_type;
+/// # use fake_sql::Table;
+/// # use arrow_schema::{DataType, TimeUnit};
+/// let table = Table::init_via_sql("create table t (id int primary key, price number(10,2), created timestamp)");
+/// assert_eq!(data_type(&table.columns[0]), DataType::Int32);
+/// assert_eq!(data_type(&table.columns[1]), DataType::Decimal128(10, 2));
+/// assert_eq!(data_type(&table.columns[2]), DataType::Timestamp(TimeUnit::Microsecond, None));
+/// ```
+pub fn data_type(column: &Column) -> DataType {
+    if let Some(element) = column.element() {
+        return DataType::List(Arc::new(Field::new("item", data_type(&element), true)));
+    }
+    if !column.attributes.is_empty() {
+        return DataType::Struct(column.attributes.iter().map(|a| Field::new(&a.name, data_type(a), true)).collect());
+    }
+    if !column.labels.is_empty() || column.is_spatial() || (column.template.is_some() && !column.is_numeric()) {
+        return DataType::Utf8;
+    }
+    match column.column_type.as_str() {
+        "date" => DataType::Date32,
+        "datetime" | "timestamp" => DataType::Timestamp(TimeUnit::Microsecond, None),
+        "timestamptz" => DataType::Timestamp(TimeUnit::Microsecond, Some(UTC.into())),
+        "time" => DataType::Time64(TimeUnit::Microsecond),
+        "interval" => DataType::Interval(IntervalUnit::DayTime),
+        "boolean" | "bool" | "bit" => DataType::Boolean,
+        "blob" | "bytea" | "binary" | "varbinary" | "raw" => DataType::Binary,
+        "float" => DataType::Float64,
+        _ if !column.is_numeric() => DataType::Utf8,
+        _ if column.decimal_places.unwrap_or(0) > 0 => {
+            let scale = column.decimal_places.unwrap_or(0).clamp(1, 38);
+            let precision = column.length.unwrap_or(18).clamp(scale, 38);
+            DataType::Decimal128(precision as u8, scale as i8)
+        }
+        "tinyint" | "smallint" => DataType::Int16,
+        "int" | "integer" if column.numeric_limit() <= i32::MAX as i128 => DataType::Int32,
+        _ => DataType::Int64,
+    }
+}
+
+/// Returns the Arrow schema of the rows of `table`: a field per column, in column order,
+/// nullable unless the column is `NOT NULL`.
+pub fn schema(table: &Table) -> Schema {
+    Schema::new(table.columns.iter().map(|c| Field::new(&c.name, data_type(c), c.is_nullable)).collect::<Vec<_>>())
+}
+
+/// Builds the Arrow array of `values` of type `data_type`.
+///
+/// Values of another kind, e.g. text from a data pool in an integer column, are
+/// converted where they can be.
+///
+/// # Returns
+///
+/// An error naming the first value that cannot be converted, which would otherwise be
+/// written as NULL.
+fn array(data_type: &DataType, values: &[&SqlValue]) -> Result<ArrayRef, String> {
+    let array: ArrayRef = match data_type {
+        DataType::Int16 => Arc::new(values.iter().map(|v| integer(v).and_then(|n| i16::try_from(n).ok())).collect::<Int16Array>()),
+        DataType::Int32 => Arc::new(values.iter().map(|v| integer(v).and_then(|n| i32::try_from(n).ok())).collect::<Int32Array>()),
+        DataType::Int64 => Arc::new(values.iter().map(|v| integer(v)).collect::<Int64Array>()),
+        DataType::Float64 => Arc::new(values.iter().map(|v| float(v)).collect::<Float64Array>()),
+        DataType::Decimal128(precision, scale) => {
+            let units = values.iter().map(|v| decimal(v, *scale as u32)).collect::<Decimal128Array>();
+            Arc::new(units.with_precision_and_scale(*precision, *scale).map_err(|err| err.to_string())?)
+        }
+        DataType::Boolean => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Boolean(b) => Some(*b),
+                    _ => integer(v).map(|n| n != 0),
+                })
+                .collect::<BooleanArray>(),
+        ),
+        DataType::Date32 => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Date(date) => Some(days(*date)),
+                    SqlValue::Timestamp(ts) => Some(days(ts.date())),
+                    _ => None,
+                })
+                .collect::<Date32Array>(),
+        ),
+        DataType::Timestamp(_, zone) => {
+            let micros = values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Timestamp(ts) => Some(ts.and_utc().timestamp_micros()),
+                    SqlValue::TimestampTz(ts) if zone.is_some() => Some(ts.timestamp_micros()),
+                    SqlValue::TimestampTz(ts) => Some(ts.naive_local().and_utc().timestamp_micros()),
+                    SqlValue::Date(date) => Some(date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_micros()),
+                    _ => None,
+                })
+                .collect::<TimestampMicrosecondArray>();
+            Arc::new(micros.with_timezone_opt(zone.clone()))
+        }
+        DataType::Time64(_) => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Time(time) => Some(time.num_seconds_from_midnight() as i64 * 1_000_000 + time.nanosecond() as i64 / 1000),
+                    _ => None,
+                })
+                .collect::<Time64MicrosecondArray>(),
+        ),
+        DataType::Interval(_) => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Interval(seconds) => Some(IntervalDayTime::new((seconds / 86_400) as i32, (seconds % 86_400 * 1000) as i32)),
+                    _ => None,
+                })
+                .collect::<IntervalDayTimeArray>(),
+        ),
+        DataType::Binary => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Bytes(bytes) | SqlValue::Base64(bytes) => Some(bytes.as_slice()),
+                    SqlValue::Text(text) => Some(text.as_bytes()),
+                    _ => None,
+                })
+                .collect::<BinaryArray>(),
+        ),
+        DataType::List(field) => {
+            let lists: Vec<Option<&Vec<SqlValue>>> = values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Array(elements) => Some(elements),
+                    _ => None,
+                })
+                .collect();
+            let elements: Vec<&SqlValue> = lists.iter().flatten().flat_map(|elements| elements.iter()).collect();
+            let offsets = OffsetBuffer::from_lengths(lists.iter().map(|list| list.map_or(0, |elements| elements.len())));
+            let nulls = NullBuffer::from(lists.iter().map(Option::is_some).collect::<Vec<bool>>());
+            let list = ListArray::try_new(field.clone(), offsets, array(field.data_type(), &elements)?, Some(nulls));
+            Arc::new(list.map_err(|err| err.to_string())?)
+        }
+        DataType::Struct(fields) => {
+            let composites: Vec<Option<&Vec<(String, SqlValue)>>> = values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Composite(attributes) => Some(attributes),
+                    _ => None,
+                })
+                .collect();
+            let children = fields
+                .iter()
+                .map(|field| {
+                    let values: Vec<&SqlValue> = composites
+                        .iter()
+                        .map(|composite| {
+                            let attribute = composite.and_then(|attributes| attributes.iter().find(|(name, _)| name == field.name()));
+                            attribute.map_or(&SqlValue::Null, |(_, value)| value)
+                        })
+                        .collect();
+                    array(field.data_type(), &values)
+                })
+                .collect::<Result<Vec<ArrayRef>, String>>()?;
+            let nulls = NullBuffer::from(composites.iter().map(Option::is_some).collect::<Vec<bool>>());
+            Arc::new(StructArray::try_new(fields.clone(), children, Some(nulls)).map_err(|err| err.to_string())?)
+        }
+        _ => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    SqlValue::Geometry(wkt, _) => Some(wkt.clone()),
+                    _ => v.as_text(),
+                })
+                .collect::<StringArray>(),
+        ),
+    };
+    if let Some(value) = values.iter().enumerate().find(|&(i, value)| **value != SqlValue::Null && array.is_null(i)).map(|(_, value)| value) {
+        return Err(format!("`{}` cannot be written as {}", value.as_text().unwrap_or_default(), data_type));
+    }
+    Ok(array)
+}
+
+/// Returns a value as an integer, if it is a number or numeric text.
+fn integer(value: &SqlValue) -> Option<i64> {
+    match value {
+        SqlValue::Integer(n) => Some(*n),
+        SqlValue::Decimal(..) => value.as_f64().map(|n| n.round() as i64),
+        SqlValue::Boolean(b) => Some(*b as i64),
+        SqlValue::Text(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Returns a value as a floating point number, if it is a number or numeric text.
+fn float(value: &SqlValue) -> Option<f64> {
+    match value {
+        SqlValue::Integer(n) => Some(*n as f64),
+        SqlValue::Decimal(..) => value.as_f64(),
+        SqlValue::Text(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Returns a value as a decimal number in units of `scale` digits after the point, if it
+/// is a number or numeric text. Decimals keep their exact digits.
+fn decimal(value: &SqlValue, scale: u32) -> Option<i128> {
+    match value {
+        SqlValue::Decimal(units, digits) if *digits <= scale => units.checked_mul(10i128.checked_pow(scale - digits)?),
+        SqlValue::Decimal(units, digits) => {
+            let factor = 10i128.checked_pow(digits - scale)?;
+            Some(units.div_euclid(factor) + (units.rem_euclid(factor) * 2 >= factor) as i128)
+        }
+        SqlValue::Integer(n) => (*n as i128).checked_mul(10i128.checked_pow(scale)?),
+        _ => float(value).map(|n| (n * 10f64.powi(scale as i32)).round() as i128),
+    }
+}
+
+/// Returns the number of days from 1970-01-01 to `date`, as Arrow counts dates.
+fn days(date: NaiveDate) -> i32 {
+    (date - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days() as i32
+}
+
+/// Writes generated rows to one Snappy-compressed Parquet file per table, named
+/// `<table>.parquet` and created when the first row of their table is written.
+///
+/// Rows are buffered and written in record batches of [`BATCH_SIZE`] rows, and the files
+/// are only readable once [`RowWriter::finish`] has written their footers.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::arrow::ParquetWriter;
+/// # use fake_sql::output::RowWriter;
+/// # use fake_sql::{SqlValue, Table};
+/// let dir = std::env::temp_dir().join(format!("fake-sql-parquet-doc-{}", std::process::id()));
+/// let table = Table::init_via_sql("create table t (id int primary key, name varchar(20))");
+/// let mut writer = ParquetWriter::create(&dir).unwrap();
+/// writer.write_row(&table, &[SqlValue::Integer(1), SqlValue::Text("Ann".to_string())]).unwrap();
+/// writer.finish().unwrap();
+/// assert!(dir.join("t.parquet").exists());
+/// # std::fs::remove_dir_all(dir).unwrap();
+/// ```
+pub struct ParquetWriter {
+    dir: PathBuf,
+    files: HashMap<String, ParquetFile>,
+}
+
+/// The Parquet file of a table and its rows not written yet.
+struct ParquetFile {
+    table: String,
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    rows: Vec<Vec<SqlValue>>,
+}
+
+impl ParquetFile {
+    /// Writes the buffered rows as a record batch.
+    fn write_batch(&mut self) -> io::Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                array(field.data_type(), &self.rows.iter().map(|row| &row[i]).collect::<Vec<_>>())
+                    .map_err(|err| format!("column `{}.{}`: {}", self.table, field.name(), err))
+            })
+            .collect::<Result<Vec<ArrayRef>, String>>()
+            .map_err(io::Error::other)?;
+        let batch = RecordBatch::try_new(self.schema.clone(), columns).map_err(io::Error::other)?;
+        self.rows.clear();
+        self.writer.write(&batch).map_err(io::Error::other)
+    }
+}
+
+impl ParquetWriter {
+    /// Initializes a new `ParquetWriter` writing into `dir`, creating the directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory the Parquet files are written to.
+    pub fn create<P: AsRef<Path>>(dir: P) -> io::Result<ParquetWriter> {
+        fs::create_dir_all(&dir)?;
+        Ok(ParquetWriter { dir: dir.as_ref().to_path_buf(), files: HashMap::new() })
+    }
+}
+
+impl RowWriter for ParquetWriter {
+    fn write_row(&mut self, table: &Table, row: &[SqlValue]) -> io::Result<()> {
+        if !self.files.contains_key(&table.name) {
+            let schema = Arc::new(schema(table));
+            let file = File::create(self.dir.join(format!("{}.parquet", table.name)))?;
+            let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+            let writer = ArrowWriter::try_new(file, schema.clone(), Some(properties)).map_err(io::Error::other)?;
+            self.files.insert(table.name.clone(), ParquetFile { table: table.name.clone(), writer, schema, rows: Vec::with_capacity(BATCH_SIZE) });
+        }
+        let file = self.files.get_mut(&table.name).unwrap();
+        file.rows.push(row.to_vec());
+        if file.rows.len() >= BATCH_SIZE {
+            file.write_batch()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.files.values_mut().try_for_each(ParquetFile::write_batch)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        for (_, mut file) in self.files.drain() {
+            file.write_batch()?;
+            file.writer.close().map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Decimal128Type, Int32Type};
+    use arrow_array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn test_parquet_writer() {
+        let dir = std::env::temp_dir().join(format!("fake-sql-parquet-{}", std::process::id()));
+        let table = Table::init_via_sql(
+            "create table t (id int primary key, price number(10,2), tags text[], seen timestamptz, place geometry, flag boolean, note varchar(20))",
+        );
+        let schema = schema(&table);
+        assert!(!schema.field(0).is_nullable() && schema.field(1).is_nullable());
+        assert_eq!(schema.field(2).data_type(), &DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))));
+        assert_eq!(schema.field(3).data_type(), &DataType::Timestamp(TimeUnit::Microsecond, Some(UTC.into())));
+
+        let seen = chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05+02:00").unwrap();
+        let mut writer = ParquetWriter::create(&dir).unwrap();
+        for id in 1..=3 {
+            let row = [
+                SqlValue::Integer(id),
+                SqlValue::Decimal(999 * id as i128, 2),
+                SqlValue::Array(vec![SqlValue::Text("a".to_string()), SqlValue::Null]),
+                SqlValue::TimestampTz(seen),
+                SqlValue::Geometry("POINT(13.4 52.5)".to_string(), false),
+                SqlValue::Text("1".to_string()),
+                SqlValue::Null,
+            ];
+            writer.write_row(&table, &row).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let file = File::open(dir.join("t.parquet")).unwrap();
+        let batches: Vec<RecordBatch> = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap().map(Result::unwrap).collect();
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.column(0).as_primitive::<Int32Type>().values().to_vec(), [1, 2, 3]);
+        assert_eq!(batch.column(1).as_primitive::<Decimal128Type>().value(2), 2997);
+        assert_eq!(batch.column(2).as_list::<i32>().value(0).len(), 2);
+        assert_eq!(batch.column(3).as_primitive::<arrow_array::types::TimestampMicrosecondType>().value(0), seen.timestamp_micros());
+        assert_eq!(batch.column(4).as_string::<i32>().value(0), "POINT(13.4 52.5)");
+        assert!(batch.column(5).as_boolean().value(0));
+        assert_eq!(batch.column(6).null_count(), 3);
+
+        // Values that cannot be converted are named instead of written as NULL
+        let mut writer = ParquetWriter::create(&dir).unwrap();
+        let row = [SqlValue::Text("one".to_string()), SqlValue::Null, SqlValue::Null, SqlValue::Null, SqlValue::Null, SqlValue::Null, SqlValue::Null];
+        writer.write_row(&table, &row).unwrap();
+        assert_eq!(writer.finish().unwrap_err().to_string(), "column `t.id`: `one` cannot be written as Int32");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
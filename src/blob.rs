@@ -0,0 +1,157 @@
+//! Sizes, encodings and side files of binary column values.
+
+use crate::distribution::Distribution;
+use crate::output::parse_size;
+use rand::Rng;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The range of sizes binary values are drawn from, written `16..64K` with the units of
+/// [`parse_size`], or a single size such as `1K` for values of exactly that size.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::blob::BlobSize;
+/// let size: BlobSize = "0..1MiB".parse().unwrap();
+/// assert_eq!((size.min, size.max), (0, 1 << 20));
+/// assert_eq!("1K".parse::<BlobSize>().unwrap().to_string(), "1024..1024");
+/// assert!("64K..16".parse::<BlobSize>().is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BlobSize {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl BlobSize {
+    /// Returns a random size within the range, spread by `distribution`.
+    pub fn sample<R: Rng + ?Sized>(&self, distribution: Distribution, rng: &mut R) -> usize {
+        distribution.sample(self.min as i128, self.max as i128, rng) as usize
+    }
+}
+
+impl fmt::Display for BlobSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.min, self.max)
+    }
+}
+
+impl FromStr for BlobSize {
+    type Err = String;
+
+    /// Parses a range such as `16..64K` or a single size such as `1K`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Empty values are allowed, unlike empty buffers
+        let size = |s: &str| if s.trim() == "0" { Ok(0) } else { parse_size(s) };
+        let (min, max) = match s.split_once("..") {
+            Some((min, max)) => (size(min)?, size(max)?),
+            None => (size(s)?, size(s)?),
+        };
+        if min > max {
+            return Err(format!("the blob size `{}` is empty; the minimum must not be larger than the maximum", s));
+        }
+        Ok(BlobSize { min, max })
+    }
+}
+
+/// How binary values are written into SQL statements.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BlobEncoding {
+    /// Hex literals such as `X'0aff'`, `'\x0aff'` or `0x0aff`.
+    #[default]
+    Hex,
+    /// Base64 text decoded by the database, such as `decode('Cv8=', 'base64')` or
+    /// `FROM_BASE64('Cv8=')`. SQLite and SQL Server have no such function and keep hex.
+    Base64,
+}
+
+impl fmt::Display for BlobEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BlobEncoding::Hex => "hex",
+            BlobEncoding::Base64 => "base64",
+        })
+    }
+}
+
+impl FromStr for BlobEncoding {
+    type Err = String;
+
+    /// Parses `hex` or `base64`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hex" => Ok(BlobEncoding::Hex),
+            "base64" => Ok(BlobEncoding::Base64),
+            _ => Err(format!("unknown blob encoding `{}`; expected `hex` or `base64`", s)),
+        }
+    }
+}
+
+/// A binary value written to a file of its own instead of into a statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blob {
+    /// The path the statement reads the value from.
+    pub path: PathBuf,
+    pub bytes: Vec<u8>,
+}
+
+/// Where binary values of at least `min_size` bytes are spilled to: files in `dir` that
+/// INSERT statements read with the dialect's file function, see
+/// [`Dialect::file_literal`](crate::Dialect::file_literal).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlobFiles {
+    pub min_size: usize,
+    /// The directory of the files, as the database server sees it.
+    pub dir: PathBuf,
+}
+
+impl BlobFiles {
+    /// Returns the side file of `bytes` if they are large enough to be spilled, named after
+    /// a hash of their content, so threads writing the same directory never clash.
+    pub fn spill(&self, bytes: &[u8]) -> Option<Blob> {
+        if bytes.len() < self.min_size {
+            return None;
+        }
+        let mut hasher = DefaultHasher::new();
+        hasher.write(bytes);
+        Some(Blob { path: self.dir.join(format!("{:016x}.bin", hasher.finish())), bytes: bytes.to_vec() })
+    }
+}
+
+/// Returns the directory blobs spilled from the statements of `output` are written to,
+/// e.g. `out.sql.blobs` for `out.sql`.
+pub fn blob_dir(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_os_string();
+    name.push(".blobs");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sizes_and_files() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let size: BlobSize = "1K..4K".parse().unwrap();
+        for distribution in [Distribution::Uniform, Distribution::Exponential] {
+            assert!((0..100).map(|_| size.sample(distribution, &mut rng)).all(|n| (1024..=4096).contains(&n)));
+        }
+        assert!("1K..".parse::<BlobSize>().is_err());
+        assert_eq!("BASE64".parse(), Ok(BlobEncoding::Base64));
+
+        let files = BlobFiles { min_size: 4, dir: PathBuf::from("/tmp/out.sql.blobs") };
+        assert_eq!(files.spill(&[1, 2, 3]), None);
+        let blob = files.spill(&[1, 2, 3, 4]).unwrap();
+        assert!(blob.path.starts_with("/tmp/out.sql.blobs") && blob.path.extension().unwrap() == "bin");
+        assert_eq!(files.spill(&[1, 2, 3, 4]).unwrap().path, blob.path);
+        assert_ne!(files.spill(&[4, 3, 2, 1]).unwrap().path, blob.path);
+        assert_eq!(blob_dir(Path::new("out/data.sql")), Path::new("out/data.sql.blobs"));
+    }
+}
@@ -0,0 +1,146 @@
+//! Fan-out of foreign keys: how many child rows reference each parent row.
+
+use crate::value::SqlValue;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Range of the number of child rows referencing each parent row through a foreign key,
+/// e.g. `1..20` orders per customer.
+///
+/// Child rows take the parent keys in the order the parents were inserted: the first
+/// parent gets a random number of children within the range, then the next one, and so
+/// on. Once every parent inserted so far has its children, further child rows pick a
+/// random parent as usual.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::cardinality::Cardinality;
+/// let cardinality: Cardinality = "1..20".parse().unwrap();
+/// assert_eq!((cardinality.min, cardinality.max), (1, 20));
+/// assert_eq!("3".parse::<Cardinality>().unwrap().to_string(), "3..3");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cardinality {
+    /// Fewest children of a parent; parents left with none are skipped when it is 0.
+    pub min: u64,
+    /// Most children of a parent.
+    pub max: u64,
+}
+
+impl Cardinality {
+    /// Initializes a new `Cardinality`.
+    ///
+    /// # Returns
+    ///
+    /// The cardinality, or an error if `min` is larger than `max` or `max` is 0.
+    pub fn new(min: u64, max: u64) -> Result<Cardinality, String> {
+        if min > max {
+            return Err(format!("the cardinality {}..{} has its minimum above its maximum", min, max));
+        }
+        if max == 0 {
+            return Err("the largest number of children must be at least 1".to_string());
+        }
+        Ok(Cardinality { min, max })
+    }
+}
+
+impl fmt::Display for Cardinality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.min, self.max)
+    }
+}
+
+impl FromStr for Cardinality {
+    type Err = String;
+
+    /// Parses a range such as `1..20`, or a fixed number of children such as `3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| n.trim().parse().map_err(|_| format!("expected a cardinality such as `1..20`, found `{}`", s));
+        match s.split_once("..") {
+            Some((min, max)) => Cardinality::new(parse(min)?, parse(max)?),
+            None => parse(s).and_then(|n| Cardinality::new(n, n)),
+        }
+    }
+}
+
+/// Which parent key the next child row of a foreign key takes.
+#[derive(Clone, Debug)]
+pub(crate) struct FanOut {
+    cardinality: Cardinality,
+    /// Position of the parent key the children are assigned to.
+    parent: usize,
+    /// Position of the parent key that gets children next.
+    next: usize,
+    /// Number of children the current parent still gets.
+    remaining: u64,
+}
+
+impl FanOut {
+    pub(crate) fn new(cardinality: Cardinality) -> FanOut {
+        FanOut { cardinality, parent: 0, next: 0, remaining: 0 }
+    }
+
+    /// Returns the number of children each parent gets.
+    pub(crate) fn cardinality(&self) -> Cardinality {
+        self.cardinality
+    }
+
+    /// Returns the parent key of the next child row.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The parent keys, in the order the parents were inserted.
+    /// * `rng` - The random number generator the numbers of children are drawn from.
+    ///
+    /// # Returns
+    ///
+    /// The key, or `None` once every parent has all its children.
+    pub(crate) fn next<R: Rng + ?Sized>(&mut self, keys: &[SqlValue], rng: &mut R) -> Option<SqlValue> {
+        while self.remaining == 0 {
+            if self.next >= keys.len() {
+                return None;
+            }
+            self.parent = self.next;
+            self.next += 1;
+            self.remaining = rng.gen_range(self.cardinality.min..=self.cardinality.max);
+        }
+        self.remaining -= 1;
+        Some(keys[self.parent].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("0..5".parse(), Ok(Cardinality { min: 0, max: 5 }));
+        assert_eq!(" 2 .. 4 ".parse(), Ok(Cardinality { min: 2, max: 4 }));
+        for invalid in ["5..1", "0", "many", "1..", "-1..2"] {
+            assert!(invalid.parse::<Cardinality>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_fan_out() {
+        let keys: Vec<SqlValue> = (1..=50).map(SqlValue::Integer).collect();
+        let mut fan_out = FanOut::new(Cardinality::new(2, 4).unwrap());
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut children = Vec::new();
+        while let Some(key) = fan_out.next(&keys, &mut rng) {
+            children.push(key);
+        }
+        for key in &keys {
+            let count = children.iter().filter(|&child| child == key).count();
+            assert!((2..=4).contains(&count), "{:?} has {} children", key, count);
+        }
+        // Parents inserted later get their children too
+        let keys: Vec<SqlValue> = (1..=51).map(SqlValue::Integer).collect();
+        assert_eq!(fan_out.next(&keys, &mut rng), Some(SqlValue::Integer(51)));
+    }
+}
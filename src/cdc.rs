@@ -0,0 +1,162 @@
+//! Change events in the shape Debezium captures them, so stream processors can be tested
+//! without a database and Kafka Connect in front of them.
+//!
+//! [`Generator::next_change`](crate::Generator::next_change) produces the inserts, updates
+//! and deletes of rows as [`Change`]s with their before and after images, and [`CdcWriter`]
+//! writes them as Debezium event payloads, one JSON object per line.
+
+use crate::dialect::Dialect;
+use crate::models::Table;
+use crate::rate::Rate;
+use crate::value::SqlValue;
+use chrono::NaiveDateTime;
+use serde_json::{json, Value};
+use std::io::{self, Write};
+
+/// What a change did to its row, with the Debezium `op` code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// An insert, `c`.
+    Create,
+    /// An update, `u`.
+    Update,
+    /// A delete, `d`.
+    Delete,
+}
+
+impl Operation {
+    /// Returns the Debezium `op` code: `c`, `u` or `d`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Operation::Create => "c",
+            Operation::Update => "u",
+            Operation::Delete => "d",
+        }
+    }
+}
+
+/// A change of one row of a table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    /// What the change did.
+    pub op: Operation,
+    /// Index of the table among the tables of the generator.
+    pub table: usize,
+    /// The row before the change, one value per column; `None` for inserts.
+    pub before: Option<Vec<SqlValue>>,
+    /// The row after the change, one value per column; `None` for deletes.
+    pub after: Option<Vec<SqlValue>>,
+}
+
+/// Writes changes as Debezium change event payloads, one JSON object per line, e.g.
+/// `{"before":null,"after":{"order_id":1,...},"source":{"version":"0.1.1","connector":"postgresql",
+/// "name":"fake-sql","ts_ms":1609459200000,"snapshot":"false","db":"fake_sql","schema":"public",
+/// "table":"orders"},"op":"c","ts_ms":1609459200000,"transaction":null}`.
+///
+/// Images hold the values as `--format jsonl` writes them. Events are timestamped from the
+/// given start, 100 per second unless another rate is set, and `source.schema` is named
+/// for the dialects that have one: `public` for Postgres and `dbo` for SQL Server.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::cdc::{CdcWriter, Change, Operation};
+/// # use fake_sql::{Dialect, SqlValue, Table};
+/// # use chrono::NaiveDate;
+/// let table = Table::init_via_sql("create table t (id int primary key, name varchar(10))");
+/// let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+/// let mut writer = CdcWriter::new(Vec::new(), Dialect::Postgres, start);
+/// let row = vec![SqlValue::Integer(1), SqlValue::Text("a".into())];
+/// writer.write_change(&table, &Change { op: Operation::Delete, table: 0, before: Some(row), after: None }).unwrap();
+/// let line = String::from_utf8(writer.into_inner()).unwrap();
+/// assert!(line.starts_with(r#"{"before":{"id":1,"name":"a"},"after":null,"source":{"#));
+/// assert!(line.contains(r#""table":"t"},"op":"d","ts_ms":1704067200000,"#));
+/// ```
+pub struct CdcWriter<W: Write> {
+    out: W,
+    dialect: Dialect,
+    /// Time of the first event, in milliseconds since the Unix epoch.
+    start_ms: i64,
+    /// Time between two events, in milliseconds.
+    gap_ms: f64,
+    /// Events written so far.
+    events: u64,
+}
+
+impl<W: Write> CdcWriter<W> {
+    /// Initializes a new `CdcWriter` writing to `out`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The destination of the events.
+    /// * `dialect` - The dialect whose Debezium connector the events appear to come from.
+    /// * `start` - The time of the first event, taken as UTC.
+    pub fn new(out: W, dialect: Dialect, start: NaiveDateTime) -> CdcWriter<W> {
+        CdcWriter {
+            out,
+            dialect,
+            start_ms: start.and_utc().timestamp_millis(),
+            gap_ms: 10.0,
+            events: 0,
+        }
+    }
+
+    /// Sets the rate the changes happen at, which spaces the event timestamps.
+    pub fn set_rate(&mut self, rate: Rate) {
+        self.gap_ms = 1000.0 / rate.per_second();
+    }
+
+    /// Writes the event of `change` of a row of `table`.
+    pub fn write_change(&mut self, table: &Table, change: &Change) -> io::Result<()> {
+        let image = |row: &Option<Vec<SqlValue>>| match row {
+            Some(row) => Value::Object(table.columns.iter().zip(row).map(|(column, value)| (column.name.clone(), value.to_json())).collect()),
+            None => Value::Null,
+        };
+        let ts_ms = self.start_ms + (self.events as f64 * self.gap_ms) as i64;
+        let mut source = json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "connector": connector(self.dialect),
+            "name": "fake-sql",
+            "ts_ms": ts_ms,
+            "snapshot": "false",
+            "db": "fake_sql",
+        });
+        match self.dialect {
+            Dialect::Postgres => source["schema"] = "public".into(),
+            Dialect::SqlServer => source["schema"] = "dbo".into(),
+            _ => {}
+        }
+        source["table"] = table.name.clone().into();
+        let event = json!({
+            "before": image(&change.before),
+            "after": image(&change.after),
+            "source": source,
+            "op": change.op.code(),
+            "ts_ms": ts_ms,
+            "transaction": null,
+        });
+        self.events += 1;
+        writeln!(self.out, "{}", event)
+    }
+
+    /// Flushes the output.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Returns the destination of the events.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Returns the name of the Debezium connector capturing changes of `dialect`.
+fn connector(dialect: Dialect) -> &'static str {
+    match dialect {
+        Dialect::Postgres => "postgresql",
+        Dialect::MySql => "mysql",
+        Dialect::SqlServer => "sqlserver",
+        Dialect::Oracle => "oracle",
+        Dialect::Sqlite => "sqlite",
+    }
+}
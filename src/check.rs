@@ -0,0 +1,272 @@
+//! Syntax checks of SQL scripts, such as generated or hand-edited workload files.
+
+use crate::dialect::Dialect;
+use sqlparser::ast::Statement;
+use sqlparser::dialect::{MsSqlDialect, MySqlDialect, OracleDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+use std::fmt;
+
+/// A statement of a script that could not be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invalid {
+    /// The line the statement starts on, starting at 1.
+    pub line: usize,
+    /// The statement as written in the script, without its semicolon.
+    pub sql: String,
+    /// The message of the parser, locating the error in the script rather than in the
+    /// statement.
+    pub error: String,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+/// Splits a SQL script into its statements on the semicolons outside of quotes and comments.
+///
+/// # Arguments
+///
+/// * `sql` - A string slice that holds the SQL script.
+/// * `dialect` - The dialect the script is written for; MySQL strings escape quotes with
+///   backslashes as well, and Postgres quotes function bodies with dollars, e.g. `$$ ... $$`.
+///
+/// # Returns
+///
+/// Every statement with the line it starts on, starting at 1. Statements holding nothing
+/// but comments and whitespace are left out.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::check::split_statements;
+/// # use fake_sql::Dialect;
+/// let statements = split_statements("-- setup;\nSELECT 'a;b';\n\nDELETE FROM t;", Dialect::Postgres);
+/// assert_eq!(statements, [(2, "SELECT 'a;b'"), (4, "DELETE FROM t")]);
+/// ```
+pub fn split_statements(sql: &str, dialect: Dialect) -> Vec<(usize, &str)> {
+    let mut statements = vec![];
+    let mut start = 0;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                // Doubled quotes escape themselves, so they simply close and reopen the quote
+                while let Some((_, next)) = chars.next() {
+                    if next == c {
+                        break;
+                    }
+                    if next == '\\' && dialect == Dialect::MySql {
+                        chars.next();
+                    }
+                }
+            }
+            '$' if dialect == Dialect::Postgres => {
+                if let Some(end) = dollar_quote_end(&sql[i..]) {
+                    while chars.next_if(|&(j, _)| j < i + end).is_some() {}
+                }
+            }
+            '-' if chars.peek().map(|&(_, next)| next) == Some('-') => {
+                for (_, next) in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().map(|&(_, next)| next) == Some('*') => {
+                chars.next();
+                let mut previous = ' ';
+                for (_, next) in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ';' => {
+                push_statement(sql, start, i, &mut statements);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    push_statement(sql, start, sql.len(), &mut statements);
+    statements
+}
+
+/// Returns the byte length of the dollar-quoted string `text` starts with, e.g. `$body$ ... $body$`,
+/// or `None` if it does not start with one.
+fn dollar_quote_end(text: &str) -> Option<usize> {
+    let name_length = text[1..].find(|c: char| !c.is_alphanumeric() && c != '_')?;
+    if !text[1 + name_length..].starts_with('$') || text[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let tag_length = name_length + 2;
+    let tag = &text[..tag_length];
+    // An unclosed quote runs to the end of the script
+    Some(text[tag_length..].find(tag).map_or(text.len(), |end| tag_length + end + tag_length))
+}
+
+/// Adds the statement between the byte offsets `start` and `end` of `sql`, unless it is
+/// empty.
+fn push_statement<'a>(sql: &'a str, start: usize, end: usize, statements: &mut Vec<(usize, &'a str)>) {
+    let text = &sql[start..end];
+    let Some(offset) = first_code(text) else { return };
+    let line = 1 + sql[..start + offset].matches('\n').count();
+    statements.push((line, text[offset..].trim_end()));
+}
+
+/// Returns the byte offset of the first character of `text` outside of comments and
+/// whitespace, or `None` if there is none.
+fn first_code(text: &str) -> Option<usize> {
+    let mut rest = 0;
+    loop {
+        let trimmed = text[rest..].trim_start();
+        rest = text.len() - trimmed.len();
+        if trimmed.starts_with("--") {
+            rest += trimmed.find('\n')?;
+        } else if trimmed.starts_with("/*") {
+            rest += trimmed.find("*/")? + 2;
+        } else if trimmed.is_empty() {
+            return None;
+        } else {
+            return Some(rest);
+        }
+    }
+}
+
+/// Parses every statement of a SQL script with the `sqlparser` dialect matching `dialect`.
+///
+/// # Arguments
+///
+/// * `sql` - A string slice that holds the SQL script.
+/// * `dialect` - The dialect the script is written for.
+///
+/// # Returns
+///
+/// The number of statements in the script and the statements that could not be parsed,
+/// in script order.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::check::check;
+/// # use fake_sql::Dialect;
+/// let (count, invalid) = check("SELECT 1;\nSELEC 2;\nDELETE FROM t;", Dialect::Postgres);
+/// assert_eq!(count, 3);
+/// assert_eq!(invalid.len(), 1);
+/// assert_eq!((invalid[0].line, invalid[0].sql.as_str()), (2, "SELEC 2"));
+/// ```
+pub fn check(sql: &str, dialect: Dialect) -> (usize, Vec<Invalid>) {
+    let statements = split_statements(sql, dialect);
+    let invalid = statements
+        .iter()
+        .filter_map(|&(line, statement)| {
+            let error = parse(statement, dialect).err()?;
+            Some(Invalid {
+                line,
+                sql: statement.to_string(),
+                error: relocate(&error, line, start_column(sql, statement)),
+            })
+        })
+        .collect();
+    (statements.len(), invalid)
+}
+
+/// Returns the column `statement`, a slice of `sql`, starts at, starting at 1.
+fn start_column(sql: &str, statement: &str) -> usize {
+    let start = statement.as_ptr() as usize - sql.as_ptr() as usize;
+    let line_start = sql[..start].rfind('\n').map_or(0, |i| i + 1);
+    1 + sql[line_start..start].chars().count()
+}
+
+/// Rewrites the `at Line: l, Column: c` suffix of a parser message, which counts from the
+/// start of the statement, into the position in the script.
+///
+/// # Arguments
+///
+/// * `error` - The message of the parser.
+/// * `line` - The line the statement starts on.
+/// * `column` - The column the statement starts at.
+fn relocate(error: &str, line: usize, column: usize) -> String {
+    let Some((message, location)) = error.rsplit_once(" at Line: ") else { return error.to_string() };
+    let Some((l, c)) = location.split_once(", Column: ") else { return error.to_string() };
+    let (Ok(l), Ok(c)) = (l.parse::<usize>(), c.parse::<usize>()) else { return error.to_string() };
+    let c = if l == 1 { column + c - 1 } else { c };
+    format!("{} at line {}, column {}", message, line + l - 1, c)
+}
+
+/// Parses a single statement, returning the message of the parser if it fails.
+fn parse(statement: &str, dialect: Dialect) -> Result<(), String> {
+    parse_ast(statement, dialect).map(|_| ())
+}
+
+/// Parses SQL with the `sqlparser` dialect matching `dialect`.
+pub(crate) fn parse_ast(sql: &str, dialect: Dialect) -> Result<Vec<Statement>, String> {
+    let result = match dialect {
+        Dialect::Oracle => Parser::parse_sql(&OracleDialect {}, sql),
+        Dialect::Postgres => Parser::parse_sql(&PostgreSqlDialect {}, sql),
+        Dialect::MySql => Parser::parse_sql(&MySqlDialect {}, sql),
+        Dialect::Sqlite => Parser::parse_sql(&SQLiteDialect {}, sql),
+        Dialect::SqlServer => Parser::parse_sql(&MsSqlDialect {}, sql),
+    };
+    result.map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{parse_schema, DEFAULT_SCHEMA};
+    use crate::GeneratorBuilder;
+
+    #[test]
+    fn test_split_statements() {
+        let sql = "/* header; */ INSERT INTO t VALUES ('it''s; fine', \"a;b\");\n-- note\nSELECT 1 -- trailing;\n;\n  ;\nDELETE FROM t";
+        assert_eq!(
+            split_statements(sql, Dialect::Postgres),
+            [(1, "INSERT INTO t VALUES ('it''s; fine', \"a;b\")"), (3, "SELECT 1 -- trailing;"), (6, "DELETE FROM t")]
+        );
+        assert!(split_statements("-- nothing;\n/* here */", Dialect::Postgres).is_empty());
+
+        let sql = r"SELECT 'a\'; SELECT 'b\';";
+        assert_eq!(split_statements(sql, Dialect::Postgres), [(1, r"SELECT 'a\'"), (1, r"SELECT 'b\'")]);
+        assert_eq!(split_statements(sql, Dialect::MySql), [(1, r"SELECT 'a\'; SELECT 'b\';")]);
+
+        let sql = "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END $body$ LANGUAGE plpgsql;\nSELECT $1, $$a;b$$;";
+        assert_eq!(
+            split_statements(sql, Dialect::Postgres),
+            [(1, "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END $body$ LANGUAGE plpgsql"), (2, "SELECT $1, $$a;b$$")]
+        );
+    }
+
+    #[test]
+    fn test_error_location() {
+        let (_, invalid) = check("SELECT 1; DELETE\n  FRM t;", Dialect::Postgres);
+        assert_eq!(invalid[0].line, 1);
+        assert!(invalid[0].error.ends_with("at line 2, column 7"), "{}", invalid[0].error);
+        let (_, invalid) = check("SELECT 1;\n\n  SELEC 2;", Dialect::Postgres);
+        assert!(invalid[0].error.ends_with("at line 3, column 3"), "{}", invalid[0].error);
+    }
+
+    #[test]
+    fn test_generated_statements_are_valid() {
+        for dialect in Dialect::ALL {
+            let mut generator = GeneratorBuilder::new()
+                .tables(parse_schema(DEFAULT_SCHEMA).unwrap())
+                .dialect(dialect)
+                .seed(1)
+                .build();
+            let mut script = generator.schema_statements().join("\n");
+            for statement in generator.by_ref().take(300) {
+                script.push('\n');
+                script.push_str(&statement.text);
+            }
+            let (count, mut invalid) = check(&script, dialect);
+            assert!(count > 300);
+            // sqlparser does not know T-SQL's `ALTER TABLE t ALTER COLUMN c type`
+            invalid.retain(|invalid| !(dialect == Dialect::SqlServer && invalid.sql.contains(" ALTER COLUMN ")));
+            assert_eq!(invalid, [], "{}", dialect);
+        }
+    }
+}
@@ -0,0 +1,1411 @@
+//! Configuration files describing a complete generation setup.
+
+use crate::blob::{BlobEncoding, BlobSize};
+use crate::cardinality::Cardinality;
+use crate::dialect::Dialect;
+use crate::duplicates::Duplicates;
+use crate::distribution::Distribution;
+use crate::geo::{self, BoundingBox};
+use crate::locale::Locale;
+use crate::mix::{Mix, TypeFilter};
+use crate::models::{Column, DateRange, Table};
+use crate::pool::ValuePool;
+use crate::preset::Preset;
+use crate::output::Format;
+use crate::params::ParamStyle;
+use crate::payload::{Payload, PayloadSource};
+use crate::providers::{matches_pattern, Provider};
+use crate::query::Complexity;
+use crate::rate::Rate;
+use crate::schema;
+use crate::scd::{self, Scd2};
+use crate::template::Template;
+use crate::timeseries::{self, TimeSeries};
+use crate::timezone::TimeZones;
+use crate::unique::KeyStrategy;
+use crate::value::SqlValue;
+use chrono::{Duration, NaiveDate};
+use indexmap::IndexMap;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A generation setup read from a TOML or YAML file.
+///
+/// Every setting is optional; command line flags take precedence over the file.
+///
+/// # Example
+///
+/// ```toml
+/// seed = 42
+/// dialect = "postgres"
+/// schema = "schema.sql"
+/// output = "fixtures.sql"
+/// mix = "insert=70,select=20,update=8,delete=2"
+///
+/// [rows]
+/// customers = 10
+/// orders = 1000
+///
+/// [columns."customers.customer_email"]
+/// generator = "email"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// SQL file with the `CREATE TABLE` statements, relative to the config file.
+    pub schema: Option<PathBuf>,
+    /// Inline `CREATE TABLE` statements, used when `schema` is not set.
+    pub ddl: Option<String>,
+    /// The tables with their column settings, as written by `fake-sql export-schema`, used
+    /// when neither `schema` nor `ddl` is set.
+    pub tables: Vec<Table>,
+    pub seed: Option<u64>,
+    #[serde(deserialize_with = "from_str")]
+    pub dialect: Option<Dialect>,
+    /// Number of random statements to generate.
+    pub count: Option<u64>,
+    /// Probability that a value of a nullable column is NULL.
+    pub null_rate: Option<f64>,
+    /// Probability that a text value is exactly as long as its column.
+    pub max_length_rate: Option<f64>,
+    /// Shares of duplicate and damaged rows, e.g. `exact=0.02,near=0.03,whitespace=0.01`.
+    #[serde(deserialize_with = "from_str")]
+    pub duplicates: Option<Duplicates>,
+    /// Probability that an INSERT statement carries an invalid value.
+    pub dirty_rate: Option<f64>,
+    /// Dates and timestamps are drawn from this range, e.g. `2020-01-01..2024-12-31`.
+    #[serde(deserialize_with = "from_str")]
+    pub date_range: Option<DateRange>,
+    /// Locale of names, addresses and phone numbers, e.g. `zh-TW`.
+    #[serde(deserialize_with = "from_str")]
+    pub locale: Option<Locale>,
+    /// UTC offsets of `timestamptz` values, e.g. `UTC,+05:30,-08:00`.
+    #[serde(deserialize_with = "from_str")]
+    pub timezones: Option<TimeZones>,
+    /// Area of `geometry` and `geography` values, e.g. `13.0,52.3,13.8,52.7`.
+    #[serde(deserialize_with = "from_str")]
+    pub bounding_box: Option<BoundingBox>,
+    /// Range of sizes of binary values, e.g. `1K..64K`.
+    #[serde(deserialize_with = "from_str")]
+    pub blob_size: Option<BlobSize>,
+    /// How binary values are written into statements: `hex` or `base64`.
+    #[serde(deserialize_with = "from_str")]
+    pub blob_encoding: Option<BlobEncoding>,
+    /// Size from which binary values are written to files next to the output, e.g. `64K`.
+    #[serde(deserialize_with = "size")]
+    pub spill_blobs: Option<usize>,
+    /// Whether names, email addresses and phone numbers only use placeholder values.
+    pub pii_free: Option<bool>,
+    /// Number of threads generating in parallel.
+    pub threads: Option<u64>,
+    /// Number of rows per INSERT statement.
+    pub batch_size: Option<u64>,
+    /// Number of statements per transaction.
+    pub transaction_size: Option<u64>,
+    /// Size of the write buffer of output files in bytes, or with a unit such as `1M`.
+    #[serde(deserialize_with = "size")]
+    pub buffer_size: Option<usize>,
+    /// Size after which SQL output moves on to the next numbered file, e.g. `1G`.
+    #[serde(deserialize_with = "size")]
+    pub max_file_size: Option<usize>,
+    /// Number of self-contained output files the work is split into.
+    pub shards: Option<u64>,
+    /// Pace statements are emitted at, e.g. `500/s`.
+    #[serde(deserialize_with = "from_str")]
+    pub rate: Option<Rate>,
+    /// Whether the gaps between paced statements follow a Poisson process.
+    pub poisson: Option<bool>,
+    /// Number of concurrent sessions a trace simulates.
+    pub sessions: Option<u32>,
+    #[serde(deserialize_with = "from_str")]
+    pub mix: Option<Mix>,
+    /// Statement types and families the generated statements are limited to, e.g. `insert,select`.
+    #[serde(deserialize_with = "from_str")]
+    pub only: Option<TypeFilter>,
+    /// Statement types and families that are never generated, e.g. `drop,delete`.
+    #[serde(deserialize_with = "from_str")]
+    pub exclude: Option<TypeFilter>,
+    /// How primary key values are generated: `sequential` or `random`.
+    #[serde(deserialize_with = "from_str")]
+    pub keys: Option<KeyStrategy>,
+    /// File the statements are written to, relative to the working directory.
+    pub output: Option<PathBuf>,
+    /// Whether statements are appended to an existing output file instead of replacing it.
+    pub append: Option<bool>,
+    #[serde(deserialize_with = "from_str")]
+    pub format: Option<Format>,
+    /// How INSERT statements bind their values: `positional` or `named` placeholders.
+    #[serde(deserialize_with = "from_str")]
+    pub params: Option<ParamStyle>,
+    /// How elaborate SELECT queries are: `simple`, `moderate` or `complex`.
+    #[serde(deserialize_with = "from_str")]
+    pub complexity: Option<Complexity>,
+    /// Built-in schema used when neither `schema`, `ddl` nor `tables` is set.
+    #[serde(deserialize_with = "from_str")]
+    pub preset: Option<Preset>,
+    /// Multiple of the preset's table sizes.
+    pub scale: Option<u64>,
+    /// Probability that a SELECT is filtered with a subquery.
+    pub subquery_rate: Option<f64>,
+    /// Maximum number of predicates in a WHERE clause.
+    pub max_predicates: Option<u64>,
+    /// Probability that a WHERE predicate is joined to the previous one with OR.
+    pub or_rate: Option<f64>,
+    /// Whether DELETE statements remove single rows inserted before by primary key.
+    pub delete_by_key: Option<bool>,
+    /// Whether statements never drop tables, indexes or columns, empty tables or delete
+    /// rows other than single ones by primary key.
+    pub safe: Option<bool>,
+    /// Number of statements between schema changes.
+    pub evolve_every: Option<u64>,
+    /// Number of INSERT rows per table, in the order the tables are filled once
+    /// parents are placed before their children.
+    pub rows: IndexMap<String, u64>,
+    /// Number of child rows per parent row, keyed by the `table.column` of the foreign key,
+    /// e.g. `"orders.customer_id" = "1..20"`.
+    #[serde(deserialize_with = "from_str_values")]
+    pub cardinality: IndexMap<String, Cardinality>,
+    /// Providers for the columns matching `table.column` patterns, where `*` matches any
+    /// run of characters, e.g. `"*.zip" = "zip_code"`. They override the providers inferred
+    /// from column names; `columns` settings take precedence over them.
+    #[serde(deserialize_with = "from_str_values")]
+    pub generators: IndexMap<String, Provider>,
+    /// Per-column settings keyed by `table.column`.
+    pub columns: IndexMap<String, ColumnConfig>,
+    /// Time-series settings keyed by table name.
+    pub timeseries: IndexMap<String, TimeSeriesConfig>,
+    /// Slowly changing dimension settings keyed by table name.
+    pub scd2: IndexMap<String, Scd2Config>,
+}
+
+/// The source of a column's values, as written in the configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeneratorConfig {
+    /// A built-in provider, e.g. `generator = "email"`.
+    Provider(Provider),
+    /// A newline-delimited dictionary file, e.g. `generator = { file = "cities.txt" }`.
+    /// A relative path is resolved against the directory of the config file.
+    File(PathBuf),
+    /// Any other name, of a custom generator registered in a
+    /// [`GeneratorRegistry`](crate::plugin::GeneratorRegistry), e.g. `generator = "iban"`.
+    Custom(String),
+}
+
+impl Serialize for GeneratorConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct File<'a> {
+            file: &'a Path,
+        }
+        match self {
+            GeneratorConfig::Provider(provider) => serializer.collect_str(provider),
+            GeneratorConfig::File(file) => File { file }.serialize(serializer),
+            GeneratorConfig::Custom(name) => serializer.serialize_str(name),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GeneratorConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Name(String),
+            File {
+                file: PathBuf,
+            },
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Name(name) if name.trim().is_empty() => Err(de::Error::custom("the generator name is empty")),
+            Raw::Name(name) => Ok(name.parse().map_or(GeneratorConfig::Custom(name), GeneratorConfig::Provider)),
+            Raw::File { file } => Ok(GeneratorConfig::File(file)),
+        }
+    }
+}
+
+/// The JSON schema of a column's documents, as written in the configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonSchemaConfig {
+    /// A schema written in the configuration file, e.g. `json_schema = { type = "object", ... }`.
+    Inline(serde_json::Value),
+    /// A `.json` file holding the schema, e.g. `json_schema = "event.schema.json"`. A
+    /// relative path is resolved against the directory of the config file.
+    File(PathBuf),
+}
+
+impl Serialize for JsonSchemaConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JsonSchemaConfig::Inline(schema) => schema.serialize(serializer),
+            JsonSchemaConfig::File(file) => file.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonSchemaConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::String(path) => Ok(JsonSchemaConfig::File(PathBuf::from(path))),
+            schema => Ok(JsonSchemaConfig::Inline(schema)),
+        }
+    }
+}
+
+impl JsonSchemaConfig {
+    /// Reads the payload the schema describes.
+    fn payload(&self) -> Result<Payload, String> {
+        match self {
+            JsonSchemaConfig::Inline(schema) => Payload::from_schema(schema),
+            JsonSchemaConfig::File(file) => {
+                let text = fs::read_to_string(file)
+                    .map_err(|err| format!("unable to read JSON schema file {}: {}", file.display(), err))?;
+                let schema = serde_json::from_str(&text).map_err(|err| format!("invalid JSON schema file {}: {}", file.display(), err))?;
+                Payload::from_schema(&schema)
+            }
+        }
+    }
+}
+
+/// A value of a column's value pool, as written in the configuration file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PoolValue {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl PoolValue {
+    /// Converts a value of a pool back to how it is written, e.g. a date to `"2024-01-31"`.
+    fn from_sql_value(value: &SqlValue) -> PoolValue {
+        match value {
+            SqlValue::Boolean(b) => PoolValue::Boolean(*b),
+            SqlValue::Integer(n) => PoolValue::Integer(*n),
+            SqlValue::Decimal(..) => PoolValue::Float(value.as_f64().unwrap()),
+            value => PoolValue::Text(value.as_text().unwrap_or_default()),
+        }
+    }
+
+    /// Converts the value to the type of `column`, e.g. `2.5` to a decimal of a
+    /// `number(5, 2)` column or `"2024-01-31"` to a date.
+    fn to_sql_value(&self, column: &Column) -> Result<SqlValue, String> {
+        let decimal_places = column.decimal_places.unwrap_or(0).max(0) as u32;
+        let value = match (self, column.column_type.as_str()) {
+            (PoolValue::Integer(n), _) if column.is_numeric() && decimal_places > 0 => {
+                SqlValue::Decimal((*n as i128).saturating_mul(10i128.pow(decimal_places.min(38))), decimal_places)
+            }
+            (PoolValue::Integer(n), _) if column.is_numeric() => SqlValue::Integer(*n),
+            (PoolValue::Float(f), _) if column.is_numeric() => SqlValue::decimal(*f, decimal_places.max(1)),
+            (PoolValue::Boolean(b), "boolean" | "bool" | "bit") => SqlValue::Boolean(*b),
+            (PoolValue::Text(s), "date") => SqlValue::Date(
+                NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| format!("invalid date `{}`", s))?,
+            ),
+            (PoolValue::Text(s), _) if !column.labels.is_empty() && !column.labels.contains(s) => {
+                return Err(format!("`{}` is not one of the enum members {}", s, column.labels.join(", ")))
+            }
+            (PoolValue::Text(s), _) if !column.is_numeric() => SqlValue::Text(s.clone()),
+            (PoolValue::Integer(n), "varchar" | "text") => SqlValue::Text(n.to_string()),
+            (PoolValue::Float(f), "varchar" | "text") => SqlValue::Text(f.to_string()),
+            (PoolValue::Boolean(b), "varchar" | "text") => SqlValue::Text(b.to_string()),
+            _ => return Err(format!("{:?} is not a valid {} value", self, column.column_type)),
+        };
+        Ok(value)
+    }
+}
+
+/// Settings for a single column.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// Where the column's values come from: a provider such as `email` or `company`, a
+    /// dictionary file written `{ file = "cities.txt" }`, or a custom generator.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator: Option<GeneratorConfig>,
+    /// Probability that a value of the column is NULL, if it is nullable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub null_rate: Option<f64>,
+    /// Whether the database generates the column's values, e.g. through a sequence.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_increment: Option<bool>,
+    /// Probability that a value of the column is exactly as long as the column.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length_rate: Option<f64>,
+    /// Dates and timestamps of the column are drawn from this range.
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub date_range: Option<DateRange>,
+    /// Locale of the column's provider text.
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+    /// UTC offsets of the column's `timestamptz` values.
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub timezones: Option<TimeZones>,
+    /// Area of the column's `geometry` or `geography` values.
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub bounding_box: Option<BoundingBox>,
+    /// Range of sizes of the column's binary values.
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub blob_size: Option<BlobSize>,
+    /// How the column's binary values are written into statements.
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub blob_encoding: Option<BlobEncoding>,
+    /// How the values are spread: `uniform`, `normal`, `zipfian` or `exponential`.
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<Distribution>,
+    /// The fixed values of a categorical column, e.g. `["new", "paid", "shipped"]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<PoolValue>>,
+    /// Relative weights of `values`, one per value; values are equally likely without them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<u32>>,
+    /// Smallest value generated for a numeric column.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Largest value generated for a numeric column.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// The format of the values, e.g. `ORD-{YYYY}{seq:06}`; see [`Template`].
+    #[serde(deserialize_with = "from_str", serialize_with = "display", skip_serializing_if = "Option::is_none")]
+    pub template: Option<Template>,
+    /// JSON schema of the documents of a `json` column, inline or in a file; see [`Payload`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_schema: Option<JsonSchemaConfig>,
+    /// Template document of a `json` column, whose strings are templates; see [`Payload`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_template: Option<serde_json::Value>,
+}
+
+impl ColumnConfig {
+    /// Returns the settings of `column`, such that applying them to a column without any
+    /// settings makes it generate the same values.
+    fn from_column(column: &Column) -> ColumnConfig {
+        let payload = column.payload.as_ref().map(Payload::source);
+        let weights: Option<Vec<u32>> = column.pool.as_ref().map(|pool| pool.weights().collect());
+        ColumnConfig {
+            generator: column.custom_generator.clone().map(GeneratorConfig::Custom).or(column.provider.map(GeneratorConfig::Provider)),
+            null_rate: column.null_rate,
+            auto_increment: column.auto_increment.then_some(true),
+            max_length_rate: column.max_length_rate,
+            date_range: column.date_range,
+            locale: column.locale,
+            timezones: column.timezones.clone(),
+            bounding_box: column.bounding_box,
+            blob_size: column.blob_size,
+            blob_encoding: column.blob_encoding,
+            distribution: column.distribution,
+            values: column.pool.as_ref().map(|pool| pool.values().map(PoolValue::from_sql_value).collect()),
+            // Equal weights are the default
+            weights: weights.filter(|weights| weights.iter().any(|&weight| weight != weights[0])),
+            min: column.min_value,
+            max: column.max_value,
+            template: column.template.clone(),
+            json_schema: match payload {
+                Some(PayloadSource::Schema(schema)) => Some(JsonSchemaConfig::Inline(schema.clone())),
+                _ => None,
+            },
+            json_template: match payload {
+                Some(PayloadSource::Template(template)) => Some(template.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Applies the settings to `column`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The `table.column` name of the column, for error messages.
+    /// * `column` - The column to update.
+    ///
+    /// # Returns
+    ///
+    /// An error describing the first setting that does not fit the column.
+    fn apply(&self, key: &str, column: &mut Column) -> Result<(), String> {
+        match &self.generator {
+            Some(GeneratorConfig::Provider(provider)) => column.provider = Some(*provider),
+            Some(GeneratorConfig::Custom(name)) => column.custom_generator = Some(name.clone()),
+            Some(GeneratorConfig::File(file)) => {
+                if self.values.is_some() {
+                    return Err(format!("`{}` cannot have both a generator file and values", key));
+                }
+                let pool = ValuePool::from_file(file)
+                    .map_err(|err| format!("unable to read dictionary file {}: {}", file.display(), err))?;
+                column.pool = Some(pool);
+            }
+            None => {}
+        }
+        if let Some(template) = &self.template {
+            if self.generator.is_some() || self.values.is_some() {
+                return Err(format!("`{}` cannot have both a template and a generator or values", key));
+            }
+            if !column.is_numeric() && !matches!(column.column_type.as_str(), "varchar" | "text") {
+                return Err(format!("templates are only supported for text and numeric columns, but `{}` is {}", key, column.column_type));
+            }
+            if column.is_numeric() && !template.is_numeric() {
+                return Err(format!("the template of `{}` does not render a number, but the column is {}", key, column.column_type));
+            }
+            if (column.is_pkey || column.is_unique) && !template.has_sequence() {
+                return Err(format!("the values of `{}` are unique, so its template needs a `{{seq}}` placeholder", key));
+            }
+            column.template = Some(template.clone());
+        }
+        let payload = match (&self.json_schema, &self.json_template) {
+            (Some(_), Some(_)) => return Err(format!("`{}` cannot have both a json_schema and a json_template", key)),
+            (Some(schema), None) => Some(schema.payload()),
+            (None, Some(template)) => Some(Payload::from_template(template)),
+            (None, None) => None,
+        };
+        if let Some(payload) = payload {
+            if self.generator.is_some() || self.values.is_some() || self.template.is_some() {
+                return Err(format!("`{}` cannot have both a JSON payload and a generator, values or template", key));
+            }
+            if !matches!(column.column_type.as_str(), "json" | "jsonb") {
+                return Err(format!("JSON payloads are only supported for json columns, but `{}` is {}", key, column.column_type));
+            }
+            column.payload = Some(payload.map_err(|err| format!("invalid JSON payload of `{}`: {}", key, err))?);
+        }
+        if let Some(null_rate) = self.null_rate {
+            check_rate(&format!("the null_rate of `{}`", key), null_rate)?;
+            if !column.is_nullable && null_rate > 0.0 {
+                return Err(format!("`{}` is NOT NULL and cannot have a null_rate", key));
+            }
+            column.null_rate = Some(null_rate);
+        }
+        if let Some(max_length_rate) = self.max_length_rate {
+            check_rate(&format!("the max_length_rate of `{}`", key), max_length_rate)?;
+            column.max_length_rate = Some(max_length_rate);
+        }
+        if let Some(values) = &self.values {
+            let weights = self.weights.clone().unwrap_or_else(|| vec![1; values.len()]);
+            if weights.len() != values.len() {
+                return Err(format!("`{}` has {} values but {} weights", key, values.len(), weights.len()));
+            }
+            let values = values
+                .iter()
+                .map(|value| value.to_sql_value(column))
+                .collect::<Result<Vec<SqlValue>, String>>()
+                .map_err(|err| format!("invalid values of `{}`: {}", key, err))?;
+            let pool = ValuePool::new(values.into_iter().zip(weights).collect())
+                .map_err(|err| format!("invalid values of `{}`: {}", key, err))?;
+            column.pool = Some(pool);
+        } else if self.weights.is_some() {
+            return Err(format!("`{}` has weights but no values", key));
+        }
+        if self.distribution.is_some() {
+            column.distribution = self.distribution;
+        }
+        if self.date_range.is_some() {
+            column.date_range = self.date_range;
+        }
+        if self.locale.is_some() {
+            column.locale = self.locale;
+        }
+        if self.timezones.is_some() {
+            column.timezones = self.timezones.clone();
+        }
+        if self.bounding_box.is_some() {
+            if geo::spatial_type(&column.column_type).is_none() {
+                return Err(format!("bounding boxes are only supported for geometry and geography columns, but `{}` is {}", key, column.column_type));
+            }
+            column.bounding_box = self.bounding_box;
+        }
+        if self.blob_size.is_some() || self.blob_encoding.is_some() {
+            if !matches!(column.column_type.as_str(), "blob" | "bytea" | "binary" | "varbinary" | "raw") {
+                return Err(format!("blob sizes and encodings are only supported for binary columns, but `{}` is {}", key, column.column_type));
+            }
+            column.blob_size = self.blob_size.or(column.blob_size);
+            column.blob_encoding = self.blob_encoding.or(column.blob_encoding);
+        }
+        if let Some(auto_increment) = self.auto_increment {
+            column.auto_increment = auto_increment;
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err(format!("the min of `{}` must not be greater than its max", key));
+            }
+        }
+        if self.min.is_some() || self.max.is_some() {
+            if !column.is_numeric() {
+                return Err(format!("min and max are only supported for numeric columns, but `{}` is {}", key, column.column_type));
+            }
+            column.min_value = self.min.or(column.min_value);
+            column.max_value = self.max.or(column.max_value);
+        }
+        Ok(())
+    }
+}
+
+/// A table as written in the `tables` of a configuration file: its name, comment, keys,
+/// checks and columns. See [`ColumnDocument`] for the columns.
+///
+/// # Example
+///
+/// ```yaml
+/// tables:
+/// - name: order_items
+///   primary_key: [order_id, line]
+///   columns:
+///   - name: order_id
+///     type: int
+///     primary_key: true
+///     references: orders.order_id
+///   - name: line
+///     type: smallint
+///     primary_key: true
+///   - name: status
+///     type: varchar
+///     length: 10
+///     values: [new, shipped]
+///     weights: [9, 1]
+/// ```
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TableDocument {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    /// The key columns in key order, written for keys spanning several columns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    primary_key: Vec<String>,
+    /// The `UNIQUE (a, b)` constraints spanning several columns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    unique_keys: Vec<Vec<String>>,
+    /// The conditions of the `CHECK (...)` constraints spanning several columns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    checks: Vec<String>,
+    columns: Vec<Column>,
+}
+
+impl From<Table> for TableDocument {
+    fn from(table: Table) -> TableDocument {
+        TableDocument {
+            name: table.name,
+            comment: table.comment,
+            primary_key: if table.pkey_columns.len() > 1 { table.pkey_columns } else { vec![] },
+            unique_keys: table.unique_keys,
+            checks: table.checks.into_iter().map(|check| check.condition).collect(),
+            columns: table.columns,
+        }
+    }
+}
+
+impl TryFrom<TableDocument> for Table {
+    type Error = String;
+
+    fn try_from(document: TableDocument) -> Result<Table, String> {
+        let mut table = Table::init(document.name, document.columns);
+        table.comment = document.comment;
+        for name in document.primary_key.iter().chain(document.unique_keys.iter().flatten()) {
+            if table.column(name).is_none() {
+                return Err(format!("the key column `{}` is not a column of `{}`", name, table.name));
+            }
+        }
+        if !document.primary_key.is_empty() {
+            table.set_primary_key(document.primary_key);
+        }
+        for key in document.unique_keys {
+            table.add_unique_key(key);
+        }
+        for condition in document.checks {
+            let check = schema::parse_table_check(&condition)?;
+            if let Some(name) = check.columns.iter().find(|name| table.column(name).is_none()) {
+                return Err(format!("the check column `{}` is not a column of `{}`", name, table.name));
+            }
+            table.checks.push(check);
+        }
+        Ok(table)
+    }
+}
+
+/// A column as written in the `tables` of a configuration file: its definition, and the
+/// settings of a [`ColumnConfig`] in the same place, e.g. `generator` or `null_rate`.
+///
+/// The type is read like the types of a `CREATE TABLE` statement, so `int4` is an `int`,
+/// and `references` names the referenced table and, optionally, column, e.g. `orders` or
+/// `orders.order_id`. Columns are nullable unless `nullable` is `false`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnDocument {
+    name: String,
+    #[serde(rename = "type")]
+    column_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    length: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    decimal_places: Option<i32>,
+    #[serde(default = "yes", skip_serializing_if = "is_true")]
+    nullable: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    primary_key: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    unique: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    references: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    check: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pii_free: bool,
+    /// The members of an enum column.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    /// The attributes of a column of a composite type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<Column>,
+    #[serde(flatten)]
+    settings: ColumnConfig,
+}
+
+impl From<Column> for ColumnDocument {
+    fn from(column: Column) -> ColumnDocument {
+        let references = column.ref_table.as_ref().map(|table| match &column.ref_column {
+            Some(ref_column) => format!("{}.{}", table, ref_column),
+            None => table.clone(),
+        });
+        ColumnDocument {
+            settings: ColumnConfig::from_column(&column),
+            name: column.name,
+            column_type: column.column_type,
+            length: column.length,
+            decimal_places: column.decimal_places,
+            nullable: column.is_nullable,
+            primary_key: column.is_pkey,
+            unique: column.is_unique,
+            references,
+            comment: column.comment,
+            default: column.default_value,
+            check: column.check_expr,
+            pii_free: column.pii_free,
+            labels: column.labels,
+            attributes: column.attributes,
+        }
+    }
+}
+
+impl TryFrom<ColumnDocument> for Column {
+    type Error = String;
+
+    fn try_from(document: ColumnDocument) -> Result<Column, String> {
+        let column_type = schema::canonical_type(&document.column_type.to_lowercase()).to_string();
+        let mut column = Column::new(&document.name, &column_type, document.length, document.decimal_places);
+        column.is_pkey = document.primary_key;
+        column.is_nullable = document.nullable && !document.primary_key;
+        column.is_unique = document.unique && !document.primary_key;
+        if let Some(references) = document.references {
+            let (table, ref_column) = match references.split_once('.') {
+                Some((table, ref_column)) => (table.to_string(), Some(ref_column.to_string())),
+                None => (references, None),
+            };
+            column.ref_table = Some(table);
+            column.ref_column = ref_column;
+        }
+        column.comment = document.comment;
+        column.default_value = document.default;
+        column.check_expr = document.check;
+        column.pii_free = document.pii_free;
+        column.labels = document.labels;
+        column.attributes = document.attributes;
+        document.settings.apply(&document.name, &mut column)?;
+        Ok(column)
+    }
+}
+
+/// Writes `tables` as a JSON or YAML configuration file holding nothing but their
+/// definitions and column settings, to be edited and read back with `--config`.
+///
+/// # Arguments
+///
+/// * `tables` - The tables to write.
+/// * `yaml` - Whether the file is written as YAML rather than JSON.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::config::{export_tables, Config};
+/// # use fake_sql::Table;
+/// let tables = vec![Table::init_via_sql("create table users (id int primary key, email varchar(80) not null)")];
+/// let yaml = export_tables(&tables, true).unwrap();
+/// assert!(yaml.contains("- name: email\n    type: varchar\n    length: 80\n    nullable: false\n"));
+/// let config = Config::from_yaml(&yaml).unwrap();
+/// assert_eq!(config.tables().unwrap().unwrap()[0].columns[1].length, Some(80));
+/// ```
+pub fn export_tables(tables: &[Table], yaml: bool) -> Result<String, String> {
+    #[derive(Serialize)]
+    struct Document<'a> {
+        tables: &'a [Table],
+    }
+    let document = Document { tables };
+    if yaml {
+        serde_yaml::to_string(&document).map_err(|err| err.to_string())
+    } else {
+        serde_json::to_string_pretty(&document).map(|json| json + "\n").map_err(|err| err.to_string())
+    }
+}
+
+/// Settings for a time-series table, see [`TimeSeries`]. Columns that are not given are
+/// inferred with [`TimeSeries::infer`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeSeriesConfig {
+    /// The column holding the time of a row.
+    pub timestamp: Option<String>,
+    /// Time between two consecutive rows, e.g. `10s`.
+    #[serde(deserialize_with = "duration")]
+    pub interval: Option<Duration>,
+    /// Largest random deviation from the interval, e.g. `500ms`.
+    #[serde(deserialize_with = "duration")]
+    pub jitter: Option<Duration>,
+    /// The column identifying the entity of a row, e.g. `device_id`.
+    pub entity: Option<String>,
+    /// Number of entities the rows cycle through.
+    pub entities: Option<u64>,
+    /// The columns following a random walk per entity.
+    pub metrics: Option<Vec<String>>,
+    /// Largest change of a metric between two readings, as a fraction of its range.
+    pub step: Option<f64>,
+}
+
+/// Settings for a slowly changing dimension of type 2, see [`Scd2`]. Columns that are not
+/// given are inferred with [`Scd2::infer`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scd2Config {
+    /// The column the versions of an entity share, e.g. `customer_id`.
+    pub business_key: Option<String>,
+    /// The column a version is valid from.
+    pub valid_from: Option<String>,
+    /// The column a version is valid until.
+    pub valid_to: Option<String>,
+    /// The column flagging the current version.
+    pub is_current: Option<String>,
+    /// Largest number of versions a new business key gets.
+    pub max_versions: Option<u32>,
+}
+
+impl Config {
+    /// Reads a configuration file.
+    ///
+    /// Files ending in `.yaml` or `.yml` are parsed as YAML, files ending in `.json` as
+    /// JSON, everything else as TOML.
+    /// Relative `schema`, dictionary and JSON schema file paths are resolved against the
+    /// directory of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the configuration file.
+    ///
+    /// # Returns
+    ///
+    /// The configuration, or a message describing why it could not be read.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, String> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("unable to read config file {}: {}", path.display(), err))?;
+        let mut config = match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => Config::from_yaml(&content),
+            Some("json") => Config::from_json(&content),
+            _ => Config::from_toml(&content),
+        }
+        .map_err(|err| format!("invalid config file {}: {}", path.display(), err))?;
+
+        if let Some(dir) = path.parent() {
+            if let Some(schema) = &config.schema {
+                config.schema = Some(dir.join(schema));
+            }
+            for column in config.columns.values_mut() {
+                if let Some(GeneratorConfig::File(file)) = &mut column.generator {
+                    *file = dir.join(&*file);
+                }
+                if let Some(JsonSchemaConfig::File(file)) = &mut column.json_schema {
+                    *file = dir.join(&*file);
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Parses a TOML configuration.
+    pub fn from_toml(content: &str) -> Result<Config, String> {
+        toml::from_str(content).map_err(|err| err.to_string())
+    }
+
+    /// Parses a YAML configuration.
+    pub fn from_yaml(content: &str) -> Result<Config, String> {
+        serde_yaml::from_str(content).map_err(|err| err.to_string())
+    }
+
+    /// Parses a JSON configuration.
+    pub fn from_json(content: &str) -> Result<Config, String> {
+        serde_json::from_str(content).map_err(|err| err.to_string())
+    }
+
+    /// Builds the tables described by `schema`, `ddl`, `tables` or `preset`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the configuration defines no schema, otherwise the tables or a
+    /// message describing why they could not be loaded.
+    pub fn tables(&self) -> Result<Option<Vec<Table>>, String> {
+        let tables = match (&self.schema, &self.ddl, self.preset) {
+            (Some(path), _, _) => schema::load_schema(path)
+                .map_err(|err| format!("unable to read schema file {}: {}", path.display(), err))?,
+            (None, Some(ddl), _) => schema::parse_schema(ddl).map_err(|err| format!("invalid ddl: {}", err))?,
+            (None, None, _) if !self.tables.is_empty() => self.tables.clone(),
+            (None, None, Some(preset)) => preset.tables(),
+            (None, None, None) => return Ok(None),
+        };
+        Ok(Some(tables))
+    }
+
+    /// Applies the per-column settings to `tables`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - The tables to update.
+    ///
+    /// # Returns
+    ///
+    /// An error naming the first `table.column` key that matches no column.
+    pub fn apply_columns(&self, tables: &mut [Table]) -> Result<(), String> {
+        for (pattern, provider) in &self.generators {
+            let mut matched = false;
+            for table in tables.iter_mut() {
+                for column in &mut table.columns {
+                    if matches_pattern(pattern, &format!("{}.{}", table.name, column.name)) {
+                        column.provider = Some(*provider);
+                        matched = true;
+                    }
+                }
+            }
+            if !matched && !pattern.contains('*') {
+                return Err(format!("column `{}` is not defined in the schema", pattern));
+            }
+        }
+
+        for (key, column_config) in &self.columns {
+            let (table_name, column_name) = key
+                .split_once('.')
+                .ok_or_else(|| format!("column key `{}` must be written as `table.column`", key))?;
+            let column = tables
+                .iter_mut()
+                .filter(|t| t.name == table_name)
+                .flat_map(|t| t.columns.iter_mut())
+                .find(|c| c.name == column_name)
+                .ok_or_else(|| format!("column `{}` is not defined in the schema", key))?;
+            column_config.apply(key, column)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the `cardinality` settings as table, column and cardinality.
+    ///
+    /// # Returns
+    ///
+    /// The settings, or an error naming the first key that is not written `table.column`.
+    pub fn cardinalities(&self) -> Result<Vec<(String, String, Cardinality)>, String> {
+        self.cardinality
+            .iter()
+            .map(|(key, cardinality)| {
+                let (table, column) = key
+                    .split_once('.')
+                    .ok_or_else(|| format!("cardinality key `{}` must be written as `table.column`", key))?;
+                Ok((table.to_string(), column.to_string(), *cardinality))
+            })
+            .collect()
+    }
+
+    /// Builds the time series of the `timeseries` settings for `tables`.
+    ///
+    /// # Returns
+    ///
+    /// The table names with their time series, or an error naming the first table that
+    /// is not defined or whose settings do not fit it.
+    pub fn time_series(&self, tables: &[Table]) -> Result<Vec<(String, TimeSeries)>, String> {
+        let mut all = Vec::new();
+        for (name, settings) in &self.timeseries {
+            let table = tables
+                .iter()
+                .find(|t| &t.name == name)
+                .ok_or_else(|| format!("time-series table `{}` is not defined in the schema", name))?;
+            let mut series = match &settings.timestamp {
+                Some(timestamp) => TimeSeries {
+                    entity: TimeSeries::infer(table).ok().and_then(|inferred| inferred.entity),
+                    ..TimeSeries::new(timestamp)
+                },
+                None => TimeSeries::infer(table)?,
+            };
+            if settings.entity.is_some() {
+                series.entity = settings.entity.clone();
+            }
+            series.interval = settings.interval.unwrap_or(series.interval);
+            series.jitter = settings.jitter.unwrap_or(series.jitter);
+            series.entities = settings.entities.unwrap_or(series.entities);
+            series.metrics = settings.metrics.clone().unwrap_or_default();
+            series.step = settings.step.unwrap_or(series.step);
+            series.check(table).map_err(|err| format!("invalid time series `{}`: {}", name, err))?;
+            all.push((name.clone(), series));
+        }
+        Ok(all)
+    }
+
+    /// Builds the slowly changing dimensions of the `scd2` settings for `tables`.
+    ///
+    /// # Returns
+    ///
+    /// The table names with their dimension, or an error naming the first table that is
+    /// not defined or whose settings do not fit it.
+    pub fn scd2(&self, tables: &[Table]) -> Result<Vec<(String, Scd2)>, String> {
+        let mut all = Vec::new();
+        for (name, settings) in &self.scd2 {
+            let table = tables
+                .iter()
+                .find(|t| &t.name == name)
+                .ok_or_else(|| format!("dimension table `{}` is not defined in the schema", name))?;
+            let inferred = Scd2::infer(table);
+            let column = |setting: &Option<String>, pick: fn(&Scd2) -> &String| {
+                setting
+                    .clone()
+                    .or_else(|| inferred.as_ref().ok().map(|scd| pick(scd).clone()))
+                    .ok_or_else(|| format!("invalid dimension `{}`: {}", name, inferred.as_ref().unwrap_err()))
+            };
+            let scd = Scd2 {
+                business_key: column(&settings.business_key, |scd| &scd.business_key)?,
+                valid_from: column(&settings.valid_from, |scd| &scd.valid_from)?,
+                valid_to: column(&settings.valid_to, |scd| &scd.valid_to)?,
+                is_current: settings.is_current.clone().or_else(|| inferred.as_ref().ok().and_then(|scd| scd.is_current.clone())),
+                max_versions: settings.max_versions.unwrap_or(scd::DEFAULT_MAX_VERSIONS),
+            };
+            scd.check(table).map_err(|err| format!("invalid dimension `{}`: {}", name, err))?;
+            all.push((name.clone(), scd));
+        }
+        Ok(all)
+    }
+}
+
+/// Returns `true`, the default of settings that are on unless turned off.
+fn yes() -> bool {
+    true
+}
+
+/// Returns whether a setting is on, to leave out settings that are on by default.
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+/// Checks that a probability setting lies between 0.0 and 1.0.
+pub fn check_rate(name: &str, rate: f64) -> Result<f64, String> {
+    if (0.0..=1.0).contains(&rate) {
+        Ok(rate)
+    } else {
+        Err(format!("{} must be between 0.0 and 1.0, found {}", name, rate))
+    }
+}
+
+/// Deserializes a map whose values are parsed through their `FromStr` implementation.
+fn from_str_values<'de, D, T>(deserializer: D) -> Result<IndexMap<String, T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    IndexMap::<String, String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| Ok((key, value.parse().map_err(de::Error::custom)?)))
+        .collect()
+}
+
+/// Deserializes an optional size given in bytes or as a string with a unit such as `64K`.
+fn size<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Bytes(usize),
+        Text(String),
+    }
+    match Option::<Raw>::deserialize(deserializer)? {
+        Some(Raw::Bytes(0)) => Err(de::Error::custom("the size must be greater than zero")),
+        Some(Raw::Bytes(bytes)) => Ok(Some(bytes)),
+        Some(Raw::Text(text)) => crate::output::parse_size(&text).map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// Deserializes an optional duration such as `10s` or `500ms`.
+fn duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| timeseries::parse_duration(&s).map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Serializes an optional value through its `Display` implementation, as [`from_str`] reads it.
+fn display<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: fmt::Display,
+{
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Deserializes an optional value through its `FromStr` implementation.
+fn from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SqlType;
+
+    const TOML: &str = r#"
+        seed = 42
+        dialect = "postgres"
+        keys = "random"
+        count = 100
+        append = true
+        params = "named"
+        buffer_size = "1M"
+        max_file_size = 4096
+        date_range = "2019-01-01..2019-12-31"
+        locale = "ja"
+        timezones = "UTC,+09:00"
+        bounding_box = "13.0,52.3,13.8,52.7"
+        blob_size = "1K..4K"
+        blob_encoding = "base64"
+        spill_blobs = "2K"
+        mix = "insert=9,select=1"
+        rate = "120/min"
+        poisson = true
+        sessions = 16
+        ddl = """
+            create table customers (customer_id number(10) primary key, contact varchar(100), status varchar(10));
+            create table orders (order_id number(10) primary key, order_date date, customer_id number(10) references customers(customer_id));
+        """
+
+        [rows]
+        orders = 1000
+        customers = 10
+
+        [cardinality]
+        "orders.customer_id" = "1..20"
+
+        [generators]
+        "*.contact" = "phone"
+        "orders.*_id" = "price"
+
+        [columns."customers.contact"]
+        generator = "email"
+        locale = "de"
+        null_rate = 0.25
+        max_length_rate = 0.5
+
+        [columns."orders.order_id"]
+        auto_increment = true
+
+        [columns."customers.status"]
+        values = ["new", "paid", "shipped"]
+        weights = [7, 2, 1]
+
+        [columns."orders.order_date"]
+        date_range = "2024-01-01..2024-01-31"
+        timezones = "-05:00"
+
+        [columns."orders.customer_id"]
+        distribution = "zipf"
+        min = 10
+        max = 20
+    "#;
+
+    #[test]
+    fn test_from_toml() {
+        let config = Config::from_toml(TOML).unwrap();
+        assert_eq!(config.seed, Some(42));
+        assert_eq!(config.dialect, Some(Dialect::Postgres));
+        assert_eq!(config.keys, Some(KeyStrategy::Random));
+        assert_eq!(config.date_range.unwrap().to_string(), "2019-01-01..2019-12-31");
+        assert_eq!(config.locale, Some(Locale::Ja));
+        assert_eq!(config.timezones.as_ref().map(TimeZones::to_string).as_deref(), Some("+00:00,+09:00"));
+        assert_eq!(config.bounding_box.map(|bounding_box| bounding_box.to_string()).as_deref(), Some("13,52.3,13.8,52.7"));
+        assert_eq!((config.blob_size, config.blob_encoding, config.spill_blobs), (Some("1K..4K".parse().unwrap()), Some(BlobEncoding::Base64), Some(2048)));
+        assert_eq!(config.append, Some(true));
+        assert_eq!(config.params, Some(ParamStyle::Named));
+        assert_eq!((config.buffer_size, config.max_file_size), (Some(1 << 20), Some(4096)));
+        assert_eq!((config.rate, config.poisson, config.sessions), (Some(Rate::new(2.0)), Some(true), Some(16)));
+        let cardinality = ("orders".to_string(), "customer_id".to_string(), Cardinality::new(1, 20).unwrap());
+        assert_eq!(config.cardinalities(), Ok(vec![cardinality]));
+        assert!(Config::from_toml("[cardinality]\norders = \"1..20\"").unwrap().cardinalities().is_err());
+        assert!(Config::from_toml("[cardinality]\n\"orders.customer_id\" = \"20..1\"").is_err());
+        assert_eq!(config.mix.unwrap().weights(), [(SqlType::Insert, 9), (SqlType::Select, 1)]);
+        let rows: Vec<(&String, &u64)> = config.rows.iter().collect();
+        assert_eq!(rows, [(&"orders".to_string(), &1000), (&"customers".to_string(), &10)]);
+    }
+
+    #[test]
+    fn test_from_yaml() {
+        let yaml = "
+dialect: mysql
+preset: finance
+scale: 3
+rows:
+  customers: 5
+columns:
+  customers.contact:
+    generator: phone
+";
+        let config = Config::from_yaml(yaml).unwrap();
+        assert_eq!(config.dialect, Some(Dialect::MySql));
+        assert_eq!((config.preset, config.scale), (Some(Preset::Finance), Some(3)));
+        assert_eq!(config.rows["customers"], 5);
+        assert_eq!(config.columns["customers.contact"].generator, Some(GeneratorConfig::Provider(Provider::Phone)));
+    }
+
+    #[test]
+    fn test_apply_columns() {
+        let config = Config::from_toml(TOML).unwrap();
+        let mut tables = config.tables().unwrap().unwrap();
+        config.apply_columns(&mut tables).unwrap();
+        assert_eq!(tables[0].columns[1].provider(), Provider::Email);
+        assert_eq!(tables[0].columns[1].null_rate, Some(0.25));
+        assert_eq!(tables[0].columns[1].max_length_rate, Some(0.5));
+        assert_eq!(tables[0].columns[1].locale, Some(Locale::De));
+        let statuses: Vec<String> = tables[0].columns[2].pool.as_ref().unwrap().values().filter_map(|v| v.as_text()).collect();
+        assert_eq!(statuses, ["new", "paid", "shipped"]);
+        assert!(tables[1].columns[0].auto_increment);
+        assert_eq!(tables[1].columns[0].provider, Some(Provider::Price));
+        assert_eq!(tables[0].columns[0].provider, None);
+        assert_eq!(tables[1].columns[2].distribution, Some(Distribution::Zipfian));
+        assert_eq!(tables[1].columns[1].date_range, Some("2024-01-01..2024-01-31".parse().unwrap()));
+        assert_eq!(tables[1].columns[1].timezones, Some("-05:00".parse().unwrap()));
+        assert_eq!((tables[1].columns[2].min_value, tables[1].columns[2].max_value), (Some(10.0), Some(20.0)));
+
+        let unknown = Config::from_toml("[columns.\"customers.missing\"]\ngenerator = \"email\"").unwrap();
+        assert!(unknown.apply_columns(&mut tables).is_err());
+        let custom = Config::from_toml("[columns.\"customers.contact\"]\ngenerator = \"IBAN\"").unwrap();
+        assert_eq!(custom.columns["customers.contact"].generator, Some(GeneratorConfig::Custom("IBAN".to_string())));
+        custom.apply_columns(&mut tables).unwrap();
+        assert_eq!(tables[0].columns[1].custom_generator.as_deref(), Some("IBAN"));
+
+        let template = Config::from_toml("[columns.\"orders.order_id\"]\ntemplate = \"{YYYY}{seq:04}\"").unwrap();
+        template.apply_columns(&mut tables).unwrap();
+        assert_eq!(tables[1].columns[0].template.as_ref().unwrap().to_string(), "{YYYY}{seq:04}");
+        let invalid = [
+            "[columns.\"orders.order_date\"]\ntemplate = \"{YYYY}\"",
+            "[columns.\"customers.status\"]\ntemplate = \"{seq}\"\nvalues = [\"new\"]",
+            "[columns.\"orders.order_id\"]\ntemplate = \"ORD-{seq}\"",
+            "[columns.\"orders.order_id\"]\ntemplate = \"{YYYY}{digits:4}\"",
+        ];
+        for invalid in invalid {
+            assert!(Config::from_toml(invalid).unwrap().apply_columns(&mut tables).is_err(), "{}", invalid);
+        }
+        assert!(Config::from_toml("[columns.\"orders.order_id\"]\ntemplate = \"{seq\"").is_err());
+    }
+
+    #[test]
+    fn test_json_payloads() {
+        let mut tables = vec![Table::init_via_sql("create table events (id int primary key, body jsonb, note varchar(20))")];
+        let config = Config::from_toml(
+            "[columns.\"events.body\"]\njson_schema = { type = \"object\", properties = { kind = { enum = [\"click\", \"view\"] } }, required = [\"kind\"] }",
+        )
+        .unwrap();
+        config.apply_columns(&mut tables).unwrap();
+        let SqlValue::Json(document) = tables[0].columns[1].generate_value(&mut rand::thread_rng()) else { panic!("expected a document") };
+        assert!(["click", "view"].contains(&document["kind"].as_str().unwrap()));
+
+        let yaml = "columns:\n  events.body:\n    json_template:\n      user: \"{first_name}\"\n      tags: [\"a\", \"{letters:2}\"]\n";
+        Config::from_yaml(yaml).unwrap().apply_columns(&mut tables).unwrap();
+        let SqlValue::Json(document) = tables[0].columns[1].generate_value(&mut rand::thread_rng()) else { panic!("expected a document") };
+        assert_eq!(document["tags"][0], "a");
+
+        for invalid in [
+            "[columns.\"events.note\"]\njson_template = { a = 1 }",
+            "[columns.\"events.body\"]\njson_schema = { type = \"date\" }",
+            "[columns.\"events.body\"]\njson_schema = \"missing.json\"",
+            "[columns.\"events.body\"]\njson_template = { a = \"{seq\" }",
+            "[columns.\"events.body\"]\njson_template = { a = 1 }\njson_schema = { type = \"object\" }",
+        ] {
+            assert!(Config::from_toml(invalid).unwrap().apply_columns(&mut tables).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let mut tables = vec![Table::init_via_sql("create table orders (id int primary key, status enum('new', 'paid', 'shipped'))")];
+        Config::from_toml("[columns.\"orders.status\"]\nvalues = [\"new\", \"paid\"]\nweights = [9, 1]").unwrap().apply_columns(&mut tables).unwrap();
+        assert_eq!(tables[0].columns[1].pool.as_ref().map(|pool| pool.values().count()), Some(2));
+        let invalid = Config::from_toml("[columns.\"orders.status\"]\nvalues = [\"new\", \"lost\"]").unwrap();
+        assert!(invalid.apply_columns(&mut tables).unwrap_err().contains("`lost` is not one of the enum members new, paid, shipped"));
+    }
+
+    #[test]
+    fn test_export_tables() {
+        let mut tables = schema::parse_schema(
+            "create type mood as enum ('sad', 'ok');
+             create table users (id serial primary key, email varchar(80) not null unique, score numeric(10,2), m mood, doc jsonb);
+             create table items (order_id int, line smallint, user_id int references users(id), status varchar(10), primary key (order_id, line), unique (user_id, status), check (line <= order_id));",
+        )
+        .unwrap();
+        let config = Config::from_toml(
+            "[columns.\"items.status\"]\nvalues = [\"new\", \"shipped\"]\nweights = [9, 1]\n\
+             [columns.\"users.score\"]\nmin = 1\nmax = 50\nnull_rate = 0.3\n\
+             [columns.\"users.doc\"]\njson_template = { name = \"{first_name}\" }",
+        )
+        .unwrap();
+        config.apply_columns(&mut tables).unwrap();
+
+        let yaml = export_tables(&tables, true).unwrap();
+        let imported = Config::from_yaml(&yaml).unwrap().tables().unwrap().unwrap();
+        assert_eq!(export_tables(&imported, true).unwrap(), yaml);
+        let json = export_tables(&tables, false).unwrap();
+        let imported = Config::from_json(&json).unwrap().tables().unwrap().unwrap();
+        assert_eq!(export_tables(&imported, true).unwrap(), yaml);
+        assert_eq!(imported[1].pkey_columns, ["order_id", "line"]);
+        assert_eq!(imported[1].checks, tables[1].checks);
+        assert_eq!((imported[1].columns[2].ref_table.as_deref(), imported[1].columns[2].ref_column.as_deref()), (Some("users"), Some("id")));
+        assert_eq!(imported[0].columns[3].labels, ["sad", "ok"]);
+        for (original, imported) in tables.iter().zip(&imported) {
+            assert_eq!(original.schema_statements(Dialect::Postgres), imported.schema_statements(Dialect::Postgres));
+        }
+
+        // Hand-written tables read their types like DDL and check their settings
+        let config = Config::from_toml("[[tables]]\nname = \"t\"\ncolumns = [{ name = \"id\", type = \"INT4\", primary_key = true }, { name = \"n\", type = \"varchar\", generator = \"city\" }]").unwrap();
+        let tables = config.tables().unwrap().unwrap();
+        assert_eq!((tables[0].columns[0].column_type.as_str(), tables[0].columns[1].provider), ("int", Some(Provider::City)));
+        for invalid in [
+            "[[tables]]\nname = \"t\"\ncolumns = [{ name = \"id\", type = \"int\", colour = \"red\" }]",
+            "[[tables]]\nname = \"t\"\ncolumns = [{ name = \"id\", type = \"int\", nullable = false, null_rate = 0.5 }]",
+            "[[tables]]\nname = \"t\"\nprimary_key = [\"a\", \"id\"]\ncolumns = [{ name = \"id\", type = \"int\" }]",
+            "[[tables]]\nname = \"t\"\nchecks = [\"a < id\"]\ncolumns = [{ name = \"id\", type = \"int\" }]",
+            "[[tables]]\nname = \"t\"\nchecks = [\"id <\"]\ncolumns = [{ name = \"id\", type = \"int\" }]",
+        ] {
+            assert!(Config::from_toml(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_blob_columns() {
+        let mut tables = vec![Table::init_via_sql("create table files (id int primary key, data bytea, name varchar(20))")];
+        Config::from_toml("[columns.\"files.data\"]\nblob_size = \"100..200\"\nblob_encoding = \"base64\"").unwrap().apply_columns(&mut tables).unwrap();
+        let SqlValue::Base64(bytes) = tables[0].columns[1].generate_value(&mut rand::thread_rng()) else { panic!("expected base64 bytes") };
+        assert!((100..=200).contains(&bytes.len()));
+        let invalid = Config::from_toml("[columns.\"files.name\"]\nblob_size = \"1K\"").unwrap();
+        assert!(invalid.apply_columns(&mut tables).is_err());
+    }
+
+    #[test]
+    fn test_bounding_boxes() {
+        let mut tables = vec![Table::init_via_sql("create table stores (id int primary key, location geography(Point, 4326), name varchar(20))")];
+        Config::from_toml("[columns.\"stores.location\"]\nbounding_box = \"-74.3,40.5,-73.7,40.9\"").unwrap().apply_columns(&mut tables).unwrap();
+        assert_eq!(tables[0].columns[1].bounding_box, Some("-74.3,40.5,-73.7,40.9".parse().unwrap()));
+        let SqlValue::Geometry(wkt, true) = tables[0].columns[1].generate_value(&mut rand::thread_rng()) else { panic!("expected a geography") };
+        assert!(wkt.starts_with("POINT(-7"), "{}", wkt);
+
+        let invalid = Config::from_toml("[columns.\"stores.name\"]\nbounding_box = \"-74.3,40.5,-73.7,40.9\"").unwrap();
+        assert!(invalid.apply_columns(&mut tables).is_err());
+        assert!(Config::from_toml("bounding_box = \"0,0,200,10\"").is_err());
+    }
+
+    #[test]
+    fn test_time_series() {
+        let config = Config::from_toml(
+            "[timeseries.orders]\ninterval = \"10s\"\njitter = \"500ms\"\nentities = 5\n\n[timeseries.customers]\ntimestamp = \"customer_id\"",
+        )
+        .unwrap();
+        let tables = Config::from_toml(TOML).unwrap().tables().unwrap().unwrap();
+        let series = config.time_series(&tables).unwrap();
+        assert_eq!(series[0].0, "orders");
+        assert_eq!((series[0].1.timestamp.as_str(), series[0].1.entity.as_deref()), ("order_date", Some("customer_id")));
+        assert_eq!((series[0].1.interval, series[0].1.jitter), (Duration::seconds(10), Duration::milliseconds(500)));
+        assert_eq!((series[1].1.timestamp.as_str(), series[1].1.entities), ("customer_id", 10));
+
+        for invalid in ["[timeseries.missing]", "[timeseries.customers]", "[timeseries.orders]\nmetrics = [\"order_date\"]"] {
+            let config = Config::from_toml(invalid).unwrap();
+            assert!(config.time_series(&tables).is_err(), "{}", invalid);
+        }
+        assert!(Config::from_toml("[timeseries.orders]\ninterval = \"soon\"").is_err());
+    }
+
+    #[test]
+    fn test_scd2() {
+        let tables = vec![Table::init_via_sql(
+            "create table dim_customer (customer_sk int primary key, customer_id int, city varchar(20), valid_from date, valid_to date, current_flag int)",
+        )];
+        let config = Config::from_toml("[scd2.dim_customer]\nmax_versions = 5").unwrap();
+        let dimensions = config.scd2(&tables).unwrap();
+        assert_eq!(dimensions[0].1.columns(), ["customer_id", "valid_from", "valid_to", "current_flag"]);
+        assert_eq!(dimensions[0].1.max_versions, 5);
+        let config = Config::from_toml("[scd2.dim_customer]\nbusiness_key = \"city\"\nis_current = \"current_flag\"").unwrap();
+        assert_eq!(config.scd2(&tables).unwrap()[0].1.business_key, "city");
+
+        for invalid in ["[scd2.missing]", "[scd2.dim_customer]\nbusiness_key = \"customer_sk\"", "[scd2.dim_customer]\nvalid_to = \"city\""] {
+            let config = Config::from_toml(invalid).unwrap();
+            assert!(config.scd2(&tables).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_dictionary_file() {
+        let dir = std::env::temp_dir().join(format!("fake-sql-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("contacts.txt"), "ops@example.com\nsales@example.com\n").unwrap();
+        let config_path = dir.join("fake-sql.toml");
+        let toml = TOML.replace("generator = \"email\"", "generator = { file = \"contacts.txt\" }");
+        fs::write(&config_path, toml).unwrap();
+
+        let config = Config::load(&config_path).unwrap();
+        assert_eq!(config.columns["customers.contact"].generator, Some(GeneratorConfig::File(dir.join("contacts.txt"))));
+        let mut tables = config.tables().unwrap().unwrap();
+        config.apply_columns(&mut tables).unwrap();
+        let pool = tables[0].columns[1].pool.as_ref().unwrap();
+        assert_eq!(pool.values().count(), 2);
+
+        let missing = Config::from_toml("[columns.\"customers.contact\"]\ngenerator = { file = \"missing.txt\" }").unwrap();
+        assert!(missing.apply_columns(&mut tables).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_invalid_values() {
+        assert!(Config::from_toml("dialect = \"db2\"").is_err());
+        assert!(Config::from_toml("unknown = 1").is_err());
+        assert!(Config::from_toml("date_range = \"2020-01-01\"").is_err());
+        assert!(Config::from_toml("[generators]\n\"*.zip\" = \"iban\"").is_err());
+
+        let config = Config::from_toml(&TOML.replace("null_rate = 0.25", "null_rate = 1.5")).unwrap();
+        let mut tables = config.tables().unwrap().unwrap();
+        assert!(config.apply_columns(&mut tables).is_err());
+
+        let config = Config::from_toml(&TOML.replace("min = 10", "min = 30")).unwrap();
+        assert!(config.apply_columns(&mut tables).is_err());
+        let config = Config::from_toml("[columns.\"customers.contact\"]\nmax = 5").unwrap();
+        assert!(config.apply_columns(&mut tables).is_err());
+        let config = Config::from_toml("[generators]\n\"customers.zip\" = \"zip_code\"").unwrap();
+        assert!(config.apply_columns(&mut tables).is_err());
+        for pool in ["values = [1, 2]\nweights = [1]", "weights = [1]", "values = [\"a\"]\nweights = [0]"] {
+            let config = Config::from_toml(&format!("[columns.\"customers.status\"]\n{}", pool)).unwrap();
+            assert!(config.apply_columns(&mut tables).is_err(), "{}", pool);
+        }
+        let config = Config::from_toml("[columns.\"customers.customer_id\"]\nvalues = [\"a\"]").unwrap();
+        assert!(config.apply_columns(&mut tables).is_err());
+        let config = Config::from_toml("[columns.\"customers.customer_id\"]\nnull_rate = 0.5").unwrap();
+        assert!(config.apply_columns(&mut tables).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Rows remembered per table so later statements can refer to them.
+
+use crate::value::SqlValue;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// The rows inserted so far, remembered per table so SELECT, UPDATE and DELETE
+/// statements filter on values that exist in the database.
+///
+/// Every table keeps at most `capacity` rows. Once a table is full, newly inserted rows
+/// replace remembered ones at random (reservoir sampling), so the remembered rows are a
+/// uniform sample of every row inserted into the table.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::data_pool::DataPool;
+/// # use fake_sql::SqlValue;
+/// let mut pool = DataPool::new(100);
+/// let mut rng = rand::thread_rng();
+/// pool.insert("orders", vec![SqlValue::Integer(1)], &mut rng);
+/// assert_eq!(pool.choose("orders", &mut rng), Some(&[SqlValue::Integer(1)][..]));
+/// assert_eq!(pool.take("orders", &mut rng), Some(vec![SqlValue::Integer(1)]));
+/// assert_eq!(pool.len("orders"), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DataPool {
+    capacity: usize,
+    tables: HashMap<String, TableRows>,
+}
+
+/// The remembered rows of a table.
+#[derive(Clone, Debug, Default)]
+struct TableRows {
+    rows: Vec<Vec<SqlValue>>,
+    /// Number of rows offered to the table so far, remembered or not.
+    seen: u64,
+}
+
+impl DataPool {
+    /// Number of rows remembered per table by default.
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    /// Initializes a new `DataPool` remembering up to `capacity` rows per table.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of rows per table; 0 remembers nothing.
+    pub fn new(capacity: usize) -> DataPool {
+        DataPool {
+            capacity,
+            tables: HashMap::new(),
+        }
+    }
+
+    /// Remembers an inserted row, or replaces a remembered row with it once the table is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table.
+    /// * `row` - One value per column, in column order.
+    /// * `rng` - The random number generator deciding which rows are kept.
+    pub fn insert<R: Rng + ?Sized>(&mut self, table: &str, row: Vec<SqlValue>, rng: &mut R) {
+        if self.capacity == 0 {
+            return;
+        }
+        let rows = self.tables.entry(table.to_string()).or_default();
+        rows.seen += 1;
+        if rows.rows.len() < self.capacity {
+            rows.rows.push(row);
+        } else {
+            let slot = rng.gen_range(0..rows.seen);
+            if let Some(remembered) = rows.rows.get_mut(slot as usize) {
+                *remembered = row;
+            }
+        }
+    }
+
+    /// Returns a random remembered row of `table`, or `None` if it has none.
+    pub fn choose<R: Rng + ?Sized>(&self, table: &str, rng: &mut R) -> Option<&[SqlValue]> {
+        let rows = &self.tables.get(table)?.rows;
+        if rows.is_empty() {
+            return None;
+        }
+        Some(&rows[rng.gen_range(0..rows.len())])
+    }
+
+    /// Removes a random remembered row of `table`, as when it is deleted.
+    ///
+    /// # Returns
+    ///
+    /// The row, or `None` if the table has none.
+    pub fn take<R: Rng + ?Sized>(&mut self, table: &str, rng: &mut R) -> Option<Vec<SqlValue>> {
+        let rows = &mut self.tables.get_mut(table)?.rows;
+        if rows.is_empty() {
+            return None;
+        }
+        Some(rows.swap_remove(rng.gen_range(0..rows.len())))
+    }
+
+    /// Adds a column to the remembered rows of `table`, holding NULL as the rows were
+    /// inserted before the column existed.
+    pub fn add_column(&mut self, table: &str) {
+        for row in self.tables.get_mut(table).into_iter().flat_map(|rows| rows.rows.iter_mut()) {
+            row.push(SqlValue::Null);
+        }
+    }
+
+    /// Removes the column at `index` from the remembered rows of `table`.
+    pub fn remove_column(&mut self, table: &str, index: usize) {
+        for row in self.tables.get_mut(table).into_iter().flat_map(|rows| rows.rows.iter_mut()) {
+            if index < row.len() {
+                row.remove(index);
+            }
+        }
+    }
+
+    /// Moves the remembered rows of the table called `old` to the name `new`.
+    pub fn rename_table(&mut self, old: &str, new: &str) {
+        if let Some(rows) = self.tables.remove(old) {
+            self.tables.insert(new.to_string(), rows);
+        }
+    }
+
+    /// Returns the number of remembered rows of `table`.
+    pub fn len(&self, table: &str) -> usize {
+        self.tables.get(table).map_or(0, |rows| rows.rows.len())
+    }
+
+    /// Returns whether no row of any table is remembered.
+    pub fn is_empty(&self) -> bool {
+        self.tables.values().all(|rows| rows.rows.is_empty())
+    }
+}
+
+impl Default for DataPool {
+    fn default() -> Self {
+        DataPool::new(DataPool::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_capacity() {
+        let mut pool = DataPool::new(10);
+        let mut rng = StdRng::seed_from_u64(1);
+        for n in 0..1000 {
+            pool.insert("t", vec![SqlValue::Integer(n)], &mut rng);
+        }
+        assert_eq!(pool.len("t"), 10);
+        // Later rows replace earlier ones, so the sample is not just the first rows
+        let late = (0..10).filter(|_| matches!(pool.choose("t", &mut rng), Some([SqlValue::Integer(n)]) if *n >= 10)).count();
+        assert!(late > 0);
+
+        let mut disabled = DataPool::new(0);
+        disabled.insert("t", vec![SqlValue::Integer(1)], &mut rng);
+        assert!(disabled.is_empty() && disabled.choose("t", &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_take() {
+        let mut pool = DataPool::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        for n in 0..3 {
+            pool.insert("t", vec![SqlValue::Integer(n)], &mut rng);
+        }
+        let mut taken: Vec<SqlValue> = (0..3).map(|_| pool.take("t", &mut rng).unwrap().remove(0)).collect();
+        taken.sort_by_key(|value| value.as_text());
+        assert_eq!(taken, [SqlValue::Integer(0), SqlValue::Integer(1), SqlValue::Integer(2)]);
+        assert!(pool.take("t", &mut rng).is_none() && pool.take("other", &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_schema_changes() {
+        let mut pool = DataPool::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        pool.insert("t", vec![SqlValue::Integer(1), SqlValue::Integer(2)], &mut rng);
+        pool.add_column("t");
+        pool.remove_column("t", 0);
+        pool.rename_table("t", "t_v2");
+        assert_eq!(pool.len("t"), 0);
+        assert_eq!(pool.choose("t_v2", &mut rng), Some(&[SqlValue::Integer(2), SqlValue::Null][..]));
+    }
+}
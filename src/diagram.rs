@@ -0,0 +1,238 @@
+//! Entity-relationship diagrams of the loaded tables, as Graphviz DOT or Mermaid.
+//!
+//! The diagrams show the tables, columns and keys the way `fake-sql` understood the schema,
+//! with the type names of a dialect, so a schema can be checked before data is generated.
+
+use crate::dialect::Dialect;
+use crate::models::{Column, Table};
+use std::fmt;
+use std::str::FromStr;
+
+/// The language a diagram is written in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DiagramFormat {
+    /// A Graphviz `digraph`, rendered with e.g. `dot -Tsvg schema.dot -o schema.svg`.
+    #[default]
+    Dot,
+    /// A Mermaid `erDiagram`, rendered by GitHub and GitLab in Markdown code blocks.
+    Mermaid,
+}
+
+impl fmt::Display for DiagramFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DiagramFormat::Dot => "dot",
+            DiagramFormat::Mermaid => "mermaid",
+        })
+    }
+}
+
+impl FromStr for DiagramFormat {
+    type Err = String;
+
+    /// Parses `dot` or `mermaid`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(DiagramFormat::Dot),
+            "mermaid" => Ok(DiagramFormat::Mermaid),
+            _ => Err(format!("unknown diagram format `{}`; expected `dot` or `mermaid`", s)),
+        }
+    }
+}
+
+/// Draws `tables` and their foreign keys in `format`.
+///
+/// # Arguments
+///
+/// * `tables` - The tables, drawn in the order given.
+/// * `dialect` - The dialect the column types are named in.
+/// * `format` - The language of the diagram.
+pub fn diagram(tables: &[Table], dialect: Dialect, format: DiagramFormat) -> String {
+    match format {
+        DiagramFormat::Dot => dot(tables, dialect),
+        DiagramFormat::Mermaid => mermaid(tables, dialect),
+    }
+}
+
+/// Draws `tables` as a Graphviz digraph: a node per table listing its columns, and an
+/// arrow from each foreign key column to the column it references, dashed when nullable.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::diagram::dot;
+/// # use fake_sql::schema::{parse_schema, DEFAULT_SCHEMA};
+/// # use fake_sql::Dialect;
+/// let tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+/// let dot = dot(&tables, Dialect::Postgres);
+/// assert!(dot.starts_with("digraph schema {"));
+/// assert!(dot.contains(r#""orders":"customer_id" -> "customers":"customer_id""#));
+/// ```
+pub fn dot(tables: &[Table], dialect: Dialect) -> String {
+    let mut dot = String::from("digraph schema {\n    rankdir=LR;\n    node [shape=plaintext, fontname=\"Helvetica\"];\n    edge [arrowhead=crow, arrowtail=tee, dir=both];\n");
+    for table in tables {
+        dot.push_str(&format!(
+            "    {} [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\" cellpadding=\"4\">\n        <tr><td bgcolor=\"lightgrey\"><b>{}</b></td></tr>\n",
+            quoted(&table.name),
+            html(&table.name)
+        ));
+        for column in &table.columns {
+            let keys = keys(table, column);
+            let keys = if keys.is_empty() { String::new() } else { format!(" <i>{}</i>", keys.join(", ")) };
+            dot.push_str(&format!(
+                "        <tr><td port={} align=\"left\">{} {}{}</td></tr>\n",
+                quoted(&column.name),
+                html(&column.name),
+                html(&type_name(column, dialect)),
+                keys
+            ));
+        }
+        dot.push_str("    </table>>];\n");
+    }
+    for (table, column, parent, parent_column) in references(tables) {
+        // The arrow points from the child rows to the parent row they reference
+        let target = match parent_column {
+            Some(parent_column) => format!("{}:{}", quoted(&parent.name), quoted(parent_column)),
+            None => quoted(&parent.name),
+        };
+        let style = if column.is_nullable { " [style=dashed]" } else { "" };
+        dot.push_str(&format!("    {}:{} -> {}{};\n", quoted(&table.name), quoted(&column.name), target, style));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Draws `tables` as a Mermaid entity-relationship diagram: an entity per table listing
+/// its columns with their `PK`, `FK` and `UK` keys, and a relationship per foreign key
+/// labelled with the column, one parent to many children (to at most one for unique
+/// columns), the parent optional when the column is nullable.
+///
+/// Mermaid names hold no spaces or commas, so these become underscores, e.g. `number(10_2)`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::diagram::mermaid;
+/// # use fake_sql::schema::{parse_schema, DEFAULT_SCHEMA};
+/// # use fake_sql::Dialect;
+/// let tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+/// let mermaid = mermaid(&tables, Dialect::Postgres);
+/// assert!(mermaid.starts_with("erDiagram\n    orders {\n"));
+/// assert!(mermaid.contains("    customers |o--o{ orders : customer_id\n"));
+/// ```
+pub fn mermaid(tables: &[Table], dialect: Dialect) -> String {
+    let mut mermaid = String::from("erDiagram\n");
+    for table in tables {
+        mermaid.push_str(&format!("    {} {{\n", mermaid_name(&table.name)));
+        for column in &table.columns {
+            let keys = keys(table, column);
+            let keys = if keys.is_empty() { String::new() } else { format!(" {}", keys.join(", ")) };
+            mermaid.push_str(&format!("        {} {}{}\n", mermaid_name(&type_name(column, dialect)), mermaid_name(&column.name), keys));
+        }
+        mermaid.push_str("    }\n");
+    }
+    for (table, column, parent, _) in references(tables) {
+        let parent_side = if column.is_nullable { "|o" } else { "||" };
+        // Only a key of its own makes a column one-to-one, not a part of a composite key
+        let one_to_one = column.is_unique || (column.is_pkey && table.pkey_columns.len() <= 1);
+        let child_side = if one_to_one { "o|" } else { "o{" };
+        mermaid.push_str(&format!(
+            "    {} {}--{} {} : {}\n",
+            mermaid_name(&parent.name),
+            parent_side,
+            child_side,
+            mermaid_name(&table.name),
+            mermaid_name(&column.name)
+        ));
+    }
+    mermaid
+}
+
+/// Returns the foreign keys among `tables`: the child table and column, the parent table,
+/// and the referenced column, or the parent's primary key if the reference names none.
+/// References to tables that are not loaded are left out.
+fn references(tables: &[Table]) -> Vec<(&Table, &Column, &Table, Option<&str>)> {
+    let mut references = vec![];
+    for table in tables {
+        for column in &table.columns {
+            let Some(parent) = column.ref_table.as_ref().and_then(|name| tables.iter().find(|t| t.name.eq_ignore_ascii_case(name))) else {
+                continue;
+            };
+            let parent_column = match &column.ref_column {
+                Some(name) => parent.columns.iter().find(|c| c.name.eq_ignore_ascii_case(name)).map(|c| c.name.as_str()),
+                None => parent.primary_key().map(|c| c.name.as_str()),
+            };
+            references.push((table, column, parent, parent_column));
+        }
+    }
+    references
+}
+
+/// Returns the keys `column` is part of: `PK`, `FK` and `UK`.
+fn keys(table: &Table, column: &Column) -> Vec<&'static str> {
+    let mut keys = vec![];
+    if column.is_pkey || table.pkey_columns.contains(&column.name) {
+        keys.push("PK");
+    }
+    if column.ref_table.is_some() {
+        keys.push("FK");
+    }
+    if column.is_unique || table.unique_keys.iter().any(|key| key.contains(&column.name)) {
+        keys.push("UK");
+    }
+    keys
+}
+
+/// Returns the type of `column` as `dialect` declares it, e.g. `varchar(40)`.
+fn type_name(column: &Column, dialect: Dialect) -> String {
+    if column.labels.is_empty() {
+        dialect.type_name(&column.column_type, column.length, column.decimal_places)
+    } else {
+        column.column_type.clone()
+    }
+}
+
+/// Quotes a DOT identifier, e.g. `"order items"`.
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Escapes text of a DOT HTML label.
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Replaces the characters Mermaid names cannot hold with underscores.
+fn mermaid_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() || "-_()[]*".contains(c) { c } else { '_' }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::parse_schema;
+
+    #[test]
+    fn test_diagrams() {
+        let tables = parse_schema(
+            "create table users (id int primary key, email varchar(80) unique, score number(10,2));
+             create table \"user profiles\" (user_id int not null unique references users(id), bio text);
+             create table posts (id int primary key, author_id int references users, editor_id int references users(id), missing_id int references nowhere(id));",
+        )
+        .unwrap();
+
+        let dot = dot(&tables, Dialect::Postgres);
+        assert!(dot.contains("<tr><td port=\"email\" align=\"left\">email varchar(80) <i>UK</i></td></tr>"), "{}", dot);
+        assert!(dot.contains("    \"user profiles\":\"user_id\" -> \"users\":\"id\";\n"), "{}", dot);
+        assert!(dot.contains("    \"posts\":\"author_id\" -> \"users\":\"id\" [style=dashed];\n"), "{}", dot);
+        assert!(!dot.contains("nowhere"));
+        assert_eq!(dot.matches(" -> ").count(), 3);
+
+        let mermaid = mermaid(&tables, Dialect::Postgres);
+        assert!(mermaid.contains("        integer id PK\n"), "{}", mermaid);
+        assert!(mermaid.contains("        numeric(10_2) score\n"), "{}", mermaid);
+        assert!(mermaid.contains("    users ||--o| user_profiles : user_id\n"), "{}", mermaid);
+        assert!(mermaid.contains("    users |o--o{ posts : editor_id\n"), "{}", mermaid);
+        assert_eq!("Mermaid".parse(), Ok(DiagramFormat::Mermaid));
+    }
+}
@@ -0,0 +1,875 @@
+//! SQL dialects and the syntax differences between them.
+
+use crate::geo;
+use crate::payload::PathSegment;
+use crate::value::{self, SqlValue};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Words reserved by at least one dialect, which are quoted when used as identifiers.
+///
+/// The list is sorted so it can be searched with `binary_search`.
+const RESERVED_WORDS: &[&str] = &[
+    "add", "all", "alter", "and", "any", "as", "asc", "between", "by", "case", "check", "column",
+    "comment", "constraint", "create", "cross", "current", "current_date", "current_timestamp",
+    "current_user", "default", "delete", "desc", "distinct", "drop", "else", "end", "exists",
+    "fetch", "for", "foreign", "from", "full", "grant", "group", "having", "in", "index", "inner",
+    "insert", "intersect", "into", "is", "join", "key", "left", "level", "like", "limit", "not",
+    "null", "number", "offset", "on", "or", "order", "outer", "primary", "references", "right",
+    "row", "rows", "select", "session", "set", "size", "table", "then", "to", "top", "trigger",
+    "union", "unique", "update", "user", "using", "values", "view", "when", "where", "with",
+];
+
+/// Enum representing the SQL dialect statements are rendered for.
+///
+/// The dialect controls identifier quoting, string and date literals and the
+/// type names emitted in DDL. `Oracle` is the default and matches the output of
+/// earlier versions (`to_date(...)`, `number(p,s)`).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    #[default]
+    Oracle,
+    Postgres,
+    MySql,
+    Sqlite,
+    SqlServer,
+}
+
+impl Dialect {
+    /// Every supported dialect.
+    pub const ALL: [Dialect; 5] = [
+        Dialect::Oracle,
+        Dialect::Postgres,
+        Dialect::MySql,
+        Dialect::Sqlite,
+        Dialect::SqlServer,
+    ];
+
+    /// Returns the lowercase name of the dialect, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dialect::Oracle => "oracle",
+            Dialect::Postgres => "postgres",
+            Dialect::MySql => "mysql",
+            Dialect::Sqlite => "sqlite",
+            Dialect::SqlServer => "sqlserver",
+        }
+    }
+
+    /// Returns the dialect of the database behind a URL such as `postgres://localhost/test`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The database URL.
+    ///
+    /// # Returns
+    ///
+    /// The dialect, or `None` for an unsupported database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::from_url("postgresql://localhost/test"), Some(Dialect::Postgres));
+    /// assert_eq!(Dialect::from_url("sqlite:test.db"), Some(Dialect::Sqlite));
+    /// assert_eq!(Dialect::from_url("oracle://localhost"), None);
+    /// ```
+    pub fn from_url(url: &str) -> Option<Dialect> {
+        let scheme = url.split(':').next()?.to_lowercase();
+        match scheme.as_str() {
+            "postgres" | "postgresql" => Some(Dialect::Postgres),
+            "mysql" | "mariadb" => Some(Dialect::MySql),
+            "sqlite" => Some(Dialect::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// Quotes an identifier unconditionally, e.g. `"order"`, `` `order` `` or `[order]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The identifier to quote.
+    pub fn quote_identifier(&self, name: &str) -> String {
+        match self {
+            Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+            Dialect::SqlServer => format!("[{}]", name.replace(']', "]]")),
+            _ => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    /// Renders an identifier, quoting it when it is not a plain lowercase identifier or
+    /// when it is a reserved word such as `order` or `user`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The table or column name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::Postgres.identifier("orders"), "orders");
+    /// assert_eq!(Dialect::Postgres.identifier("order"), "\"order\"");
+    /// assert_eq!(Dialect::MySql.identifier("OrderId"), "`OrderId`");
+    /// assert_eq!(Dialect::SqlServer.identifier("user"), "[user]");
+    /// ```
+    pub fn identifier(&self, name: &str) -> String {
+        let plain = name.chars().next().is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && RESERVED_WORDS.binary_search(&name).is_err();
+        if plain {
+            name.to_string()
+        } else {
+            self.quote_identifier(name)
+        }
+    }
+
+    /// Renders a string literal, escaping the characters that would end or corrupt it.
+    ///
+    /// Single quotes are doubled for every dialect. MySQL also treats backslashes as
+    /// escapes, so they are doubled and NUL characters become `\0`; the other dialects do
+    /// not accept NUL characters in text, so they are dropped. SQL Server strings with
+    /// non-ASCII characters get the `N` prefix so they are not mangled by the code page.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::Postgres.string_literal("O'Brien"), "'O''Brien'");
+    /// assert_eq!(Dialect::MySql.string_literal(r"C:\temp"), r"'C:\\temp'");
+    /// assert_eq!(Dialect::SqlServer.string_literal("Zoë"), "N'Zoë'");
+    /// ```
+    pub fn string_literal(&self, value: &str) -> String {
+        let mut literal = String::with_capacity(value.len() + 2);
+        if *self == Dialect::SqlServer && !value.is_ascii() {
+            literal.push('N');
+        }
+        literal.push('\'');
+        for c in value.chars() {
+            match (c, self) {
+                ('\'', _) => literal.push_str("''"),
+                ('\\', Dialect::MySql) => literal.push_str("\\\\"),
+                ('\0', Dialect::MySql) => literal.push_str("\\0"),
+                ('\0', _) => {}
+                _ => literal.push(c),
+            }
+        }
+        literal.push('\'');
+        literal
+    }
+
+    /// Renders a date literal, e.g. `to_date('2021-01-01','YYYY-MM-DD')` or `DATE '2021-01-01'`.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date value.
+    pub fn date_literal(&self, date: NaiveDate) -> String {
+        match self {
+            Dialect::Oracle => format!("to_date('{}','YYYY-MM-DD')", date),
+            Dialect::Postgres | Dialect::MySql => format!("DATE '{}'", date),
+            Dialect::Sqlite | Dialect::SqlServer => format!("'{}'", date),
+        }
+    }
+
+    /// Renders a timestamp literal, e.g. `TIMESTAMP '2021-01-01 12:30:00'`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The timestamp value.
+    pub fn timestamp_literal(&self, timestamp: NaiveDateTime) -> String {
+        let text = timestamp.format("%Y-%m-%d %H:%M:%S");
+        match self {
+            Dialect::Oracle => format!("to_timestamp('{}','YYYY-MM-DD HH24:MI:SS')", text),
+            Dialect::Postgres | Dialect::MySql => format!("TIMESTAMP '{}'", text),
+            Dialect::Sqlite | Dialect::SqlServer => format!("'{}'", text),
+        }
+    }
+
+    /// Renders a literal for a timestamp with a UTC offset, e.g. `TIMESTAMPTZ '2021-01-01 12:30:00+01:00'`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The timestamp value.
+    pub fn timestamp_tz_literal(&self, timestamp: DateTime<FixedOffset>) -> String {
+        match self {
+            Dialect::Oracle => format!(
+                "to_timestamp_tz('{}','YYYY-MM-DD HH24:MI:SS TZH:TZM')",
+                timestamp.format("%Y-%m-%d %H:%M:%S %:z")
+            ),
+            Dialect::Postgres => format!("TIMESTAMPTZ '{}'", timestamp.format("%Y-%m-%d %H:%M:%S%:z")),
+            Dialect::MySql => format!("TIMESTAMP '{}'", timestamp.format("%Y-%m-%d %H:%M:%S%:z")),
+            Dialect::Sqlite => format!("'{}'", timestamp.format("%Y-%m-%d %H:%M:%S%:z")),
+            Dialect::SqlServer => format!("'{}'", timestamp.format("%Y-%m-%d %H:%M:%S %:z")),
+        }
+    }
+
+    /// Renders a time of day literal, e.g. `TIME '12:30:00'`. Oracle has no time type and
+    /// stores the time as text.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time of day.
+    pub fn time_literal(&self, time: NaiveTime) -> String {
+        let text = time.format("%H:%M:%S");
+        match self {
+            Dialect::Postgres | Dialect::MySql => format!("TIME '{}'", text),
+            Dialect::Oracle | Dialect::Sqlite | Dialect::SqlServer => format!("'{}'", text),
+        }
+    }
+
+    /// Renders an interval literal, e.g. `INTERVAL '3 days 04:05:06'`.
+    ///
+    /// MySQL stores intervals in `time` columns, as hours up to 838. SQLite and SQL Server
+    /// have no interval type and store the number of seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - The length of the interval in seconds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// let seconds = 3 * 86_400 + 4 * 3600 + 5 * 60 + 6;
+    /// assert_eq!(Dialect::Postgres.interval_literal(seconds), "INTERVAL '3 days 04:05:06'");
+    /// assert_eq!(Dialect::Oracle.interval_literal(seconds), "INTERVAL '3 04:05:06' DAY TO SECOND");
+    /// assert_eq!(Dialect::MySql.interval_literal(seconds), "'76:05:06'");
+    /// assert_eq!(Dialect::SqlServer.interval_literal(seconds), "273906");
+    /// ```
+    pub fn interval_literal(&self, seconds: i64) -> String {
+        let sign = if seconds < 0 { "-" } else { "" };
+        let total = seconds.unsigned_abs();
+        let (days, hours, minutes, rest) = (total / 86_400, total / 3600 % 24, total / 60 % 60, total % 60);
+        match self {
+            // Postgres signs each field of an interval
+            Dialect::Postgres => format!("INTERVAL '{}{} days {}{:02}:{:02}:{:02}'", sign, days, sign, hours, minutes, rest),
+            Dialect::Oracle => format!("INTERVAL '{}{} {:02}:{:02}:{:02}' DAY TO SECOND", sign, days, hours, minutes, rest),
+            Dialect::MySql => format!("'{}{:02}:{:02}:{:02}'", sign, total / 3600, minutes, rest),
+            Dialect::Sqlite | Dialect::SqlServer => seconds.to_string(),
+        }
+    }
+
+    /// Renders the expression extracting the string or number at `path` of a JSON column:
+    /// `->` and `->>` in Postgres, `JSON_EXTRACT` in MySQL, `json_extract` in SQLite and
+    /// `JSON_VALUE` in Oracle and SQL Server.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - The rendered column reference, e.g. `payload` or `e.payload`.
+    /// * `path` - The keys and indexes leading to the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// # use fake_sql::payload::PathSegment;
+    /// let path = [PathSegment::Key("tags".to_string()), PathSegment::Index(0)];
+    /// assert_eq!(Dialect::Postgres.json_value("doc", &path), "doc->'tags'->>0");
+    /// assert_eq!(Dialect::MySql.json_value("doc", &path), "JSON_EXTRACT(doc, '$.tags[0]')");
+    /// assert_eq!(Dialect::Oracle.json_value("doc", &path), "JSON_VALUE(doc, '$.tags[0]')");
+    /// assert_eq!(Dialect::Sqlite.json_value("doc", &[PathSegment::Key("2 fa".to_string())]), r#"json_extract(doc, '$."2 fa"')"#);
+    /// ```
+    pub fn json_value(&self, reference: &str, path: &[PathSegment]) -> String {
+        if *self == Dialect::Postgres {
+            // A document that is a single string or number is extracted with an empty path
+            if path.is_empty() {
+                return format!("{} #>> '{{}}'", reference);
+            }
+            let mut expression = reference.to_string();
+            for (i, segment) in path.iter().enumerate() {
+                expression.push_str(if i + 1 == path.len() { "->>" } else { "->" });
+                match segment {
+                    PathSegment::Key(key) => expression.push_str(&self.string_literal(key)),
+                    PathSegment::Index(index) => expression.push_str(&index.to_string()),
+                }
+            }
+            return expression;
+        }
+        let mut json_path = "$".to_string();
+        for segment in path {
+            match segment {
+                PathSegment::Key(key) if key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+                    json_path.push('.');
+                    json_path.push_str(key);
+                }
+                PathSegment::Key(key) => json_path.push_str(&format!(".\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))),
+                PathSegment::Index(index) => json_path.push_str(&format!("[{}]", index)),
+            }
+        }
+        let function = match self {
+            Dialect::MySql => "JSON_EXTRACT",
+            Dialect::Sqlite => "json_extract",
+            _ => "JSON_VALUE",
+        };
+        format!("{}({}, {})", function, reference, self.string_literal(&json_path))
+    }
+
+    /// Renders a binary literal, e.g. `X'0a1b'`, `'\x0a1b'` or `0x0a1b`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The binary value.
+    pub fn bytes_literal(&self, bytes: &[u8]) -> String {
+        let hex = value::hex(bytes);
+        match self {
+            Dialect::Oracle => format!("hextoraw('{}')", hex),
+            Dialect::Postgres => format!("'\\x{}'", hex),
+            Dialect::MySql | Dialect::Sqlite => format!("X'{}'", hex),
+            Dialect::SqlServer => format!("0x{}", hex),
+        }
+    }
+
+    /// Renders a binary literal from base64 text the database decodes, e.g.
+    /// `decode('Cv8=', 'base64')` or `FROM_BASE64('Cv8=')`. SQLite and SQL Server have no
+    /// base64 function and get a hex literal instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The binary value.
+    pub fn base64_literal(&self, bytes: &[u8]) -> String {
+        let text = self.string_literal(&value::base64(bytes));
+        match self {
+            Dialect::Oracle => format!("utl_encode.base64_decode(utl_raw.cast_to_raw({}))", text),
+            Dialect::Postgres => format!("decode({}, 'base64')", text),
+            Dialect::MySql => format!("FROM_BASE64({})", text),
+            Dialect::Sqlite | Dialect::SqlServer => self.bytes_literal(bytes),
+        }
+    }
+
+    /// Renders an expression reading a binary value from a file on the database server:
+    /// `pg_read_binary_file(...)` for Postgres, `LOAD_FILE(...)` for MySQL, `readfile(...)`
+    /// for SQLite, `OPENROWSET(BULK ...)` for SQL Server, and for Oracle a `BFILENAME` in
+    /// the `BLOB_DIR` directory object, which has to point to the file's directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// # use std::path::Path;
+    /// assert_eq!(Dialect::Postgres.file_literal(Path::new("/tmp/a.bin")), "pg_read_binary_file('/tmp/a.bin')");
+    /// assert_eq!(Dialect::Oracle.file_literal(Path::new("/tmp/a.bin")), "TO_BLOB(BFILENAME('BLOB_DIR', 'a.bin'))");
+    /// ```
+    pub fn file_literal(&self, path: &Path) -> String {
+        let text = self.string_literal(&path.to_string_lossy());
+        match self {
+            Dialect::Oracle => {
+                let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+                format!("TO_BLOB(BFILENAME('BLOB_DIR', {}))", self.string_literal(&name))
+            }
+            Dialect::Postgres => format!("pg_read_binary_file({})", text),
+            Dialect::MySql => format!("LOAD_FILE({})", text),
+            Dialect::Sqlite => format!("readfile({})", text),
+            Dialect::SqlServer => format!("(SELECT BulkColumn FROM OPENROWSET(BULK {}, SINGLE_BLOB) AS blob)", text),
+        }
+    }
+
+    /// Renders a boolean literal: `TRUE`/`FALSE` where the dialect has them, `1`/`0` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The boolean value.
+    pub fn boolean_literal(&self, value: bool) -> String {
+        match (self, value) {
+            (Dialect::Postgres | Dialect::MySql, true) => "TRUE".to_string(),
+            (Dialect::Postgres | Dialect::MySql, false) => "FALSE".to_string(),
+            (_, value) => (value as u8).to_string(),
+        }
+    }
+
+    /// Renders a spatial value in SRID 4326 from its Well-Known Text: `ST_GeomFromText(...)`
+    /// for PostGIS and MySQL, `SDO_GEOMETRY(...)` for Oracle, `geometry::STGeomFromText(...)`
+    /// for SQL Server and the plain text for SQLite.
+    ///
+    /// # Arguments
+    ///
+    /// * `wkt` - The Well-Known Text, e.g. `POINT(13.4 52.5)`.
+    /// * `geography` - Whether the value is a `geography` rather than a `geometry`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::Postgres.geometry_literal("POINT(13.4 52.5)", false), "ST_GeomFromText('POINT(13.4 52.5)', 4326)");
+    /// assert_eq!(Dialect::SqlServer.geometry_literal("POINT(13.4 52.5)", true), "geography::STGeomFromText('POINT(13.4 52.5)', 4326)");
+    /// ```
+    pub fn geometry_literal(&self, wkt: &str, geography: bool) -> String {
+        let text = self.string_literal(wkt);
+        match (self, geography) {
+            (Dialect::Postgres, false) => format!("ST_GeomFromText({}, {})", text, geo::SRID),
+            (Dialect::Postgres, true) => format!("ST_GeogFromText({})", self.string_literal(&format!("SRID={};{}", geo::SRID, wkt))),
+            // MySQL reads SRID 4326 as latitude first unless told otherwise
+            (Dialect::MySql, _) => format!("ST_GeomFromText({}, {}, 'axis-order=long-lat')", text, geo::SRID),
+            (Dialect::Oracle, _) => format!("SDO_GEOMETRY({}, {})", text, geo::SRID),
+            (Dialect::SqlServer, false) => format!("geometry::STGeomFromText({}, {})", text, geo::SRID),
+            (Dialect::SqlServer, true) => format!("geography::STGeomFromText({}, {})", text, geo::SRID),
+            (Dialect::Sqlite, _) => text,
+        }
+    }
+
+    /// Renders a value as a SQL literal.
+    ///
+    /// Postgres arrays are written in their text form, e.g. `'{1,2}'`, and composites as
+    /// `ROW(...)`; other dialects store both as JSON text, e.g. `'[1,2]'`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to render.
+    pub fn literal(&self, value: &SqlValue) -> String {
+        match value {
+            SqlValue::Null => "NULL".to_string(),
+            SqlValue::Integer(n) => n.to_string(),
+            SqlValue::Decimal(units, scale) => value::format_decimal(*units, *scale),
+            SqlValue::Text(s) => self.string_literal(s),
+            SqlValue::Date(date) => self.date_literal(*date),
+            SqlValue::Boolean(b) => self.boolean_literal(*b),
+            SqlValue::Uuid(uuid) => self.string_literal(&value::format_uuid(*uuid)),
+            SqlValue::Timestamp(ts) => self.timestamp_literal(*ts),
+            SqlValue::TimestampTz(ts) => self.timestamp_tz_literal(*ts),
+            SqlValue::Time(time) => self.time_literal(*time),
+            SqlValue::Interval(seconds) => self.interval_literal(*seconds),
+            SqlValue::Bytes(bytes) => self.bytes_literal(bytes),
+            SqlValue::Base64(bytes) => self.base64_literal(bytes),
+            SqlValue::Json(json) => self.string_literal(&json.to_string()),
+            SqlValue::Raw(sql) => sql.clone(),
+            SqlValue::Geometry(wkt, geography) => self.geometry_literal(wkt, *geography),
+            SqlValue::Array(_) if *self == Dialect::Postgres => self.string_literal(&value.as_text().unwrap()),
+            SqlValue::Composite(fields) if *self == Dialect::Postgres => {
+                let values: Vec<String> = fields.iter().map(|(_, value)| self.literal(value)).collect();
+                format!("ROW({})", values.join(", "))
+            }
+            SqlValue::Array(_) | SqlValue::Composite(_) => self.string_literal(&value.to_json().to_string()),
+        }
+    }
+
+    /// Renders a statement removing every row of a table, without the trailing semicolon:
+    /// `TRUNCATE TABLE t`, or `DELETE FROM t` for SQLite which has no TRUNCATE.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The rendered table name.
+    pub fn truncate_table(&self, table: &str) -> String {
+        match self {
+            Dialect::Sqlite => format!("DELETE FROM {}", table),
+            _ => format!("TRUNCATE TABLE {}", table),
+        }
+    }
+
+    /// Renders a DROP INDEX statement without the trailing semicolon. MySQL and SQL Server
+    /// name the table the index belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The rendered index name.
+    /// * `table` - The rendered name of the indexed table.
+    pub fn drop_index(&self, index: &str, table: &str) -> String {
+        match self {
+            Dialect::MySql | Dialect::SqlServer => format!("DROP INDEX {} ON {}", index, table),
+            _ => format!("DROP INDEX {}", index),
+        }
+    }
+
+    /// Renders an ALTER TABLE statement adding a column, without the trailing semicolon:
+    /// `ADD COLUMN` for most dialects, `ADD` for Oracle and SQL Server.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The rendered table name.
+    /// * `definition` - The column definition, see [`Column::definition`](crate::Column::definition).
+    pub fn add_column(&self, table: &str, definition: &str) -> String {
+        match self {
+            Dialect::Oracle | Dialect::SqlServer => format!("ALTER TABLE {} ADD {}", table, definition),
+            _ => format!("ALTER TABLE {} ADD COLUMN {}", table, definition),
+        }
+    }
+
+    /// Renders an ALTER TABLE statement dropping a column, without the trailing semicolon.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The rendered table name.
+    /// * `column` - The rendered column name.
+    pub fn drop_column(&self, table: &str, column: &str) -> String {
+        format!("ALTER TABLE {} DROP COLUMN {}", table, column)
+    }
+
+    /// Renders an ALTER TABLE statement changing the type of a column, without the
+    /// trailing semicolon, or `None` for SQLite which cannot change column types.
+    ///
+    /// MySQL and SQL Server redefine the whole column, so its nullability is repeated.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The rendered table name.
+    /// * `column` - The rendered column name.
+    /// * `type_name` - The new type, see [`Dialect::type_name`].
+    /// * `nullable` - Whether the column accepts NULL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::Postgres.modify_column("t", "name", "varchar(40)", false).unwrap(), "ALTER TABLE t ALTER COLUMN name TYPE varchar(40)");
+    /// assert_eq!(Dialect::MySql.modify_column("t", "name", "varchar(40)", false).unwrap(), "ALTER TABLE t MODIFY COLUMN name varchar(40) NOT NULL");
+    /// assert_eq!(Dialect::Sqlite.modify_column("t", "name", "varchar(40)", false), None);
+    /// ```
+    pub fn modify_column(&self, table: &str, column: &str, type_name: &str, nullable: bool) -> Option<String> {
+        let not_null = if nullable { "" } else { " NOT NULL" };
+        match self {
+            Dialect::Oracle => Some(format!("ALTER TABLE {} MODIFY {} {}", table, column, type_name)),
+            Dialect::Postgres => Some(format!("ALTER TABLE {} ALTER COLUMN {} TYPE {}", table, column, type_name)),
+            Dialect::MySql => Some(format!("ALTER TABLE {} MODIFY COLUMN {} {}{}", table, column, type_name, not_null)),
+            Dialect::SqlServer => Some(format!("ALTER TABLE {} ALTER COLUMN {} {}{}", table, column, type_name, not_null)),
+            Dialect::Sqlite => None,
+        }
+    }
+
+    /// Renders a statement renaming a column, without the trailing semicolon. SQL Server
+    /// uses `sp_rename`.
+    ///
+    /// Unlike the other statement helpers, it takes the plain names and quotes them itself,
+    /// as `sp_rename` expects the new name unquoted.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table name.
+    /// * `column` - The current column name.
+    /// * `new_name` - The new column name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::Postgres.rename_column("t", "a", "b"), "ALTER TABLE t RENAME COLUMN a TO b");
+    /// assert_eq!(Dialect::SqlServer.rename_column("t", "a", "b"), "EXEC sp_rename 't.a', 'b', 'COLUMN'");
+    /// ```
+    pub fn rename_column(&self, table: &str, column: &str, new_name: &str) -> String {
+        match self {
+            Dialect::SqlServer => format!(
+                "EXEC sp_rename {}, {}, 'COLUMN'",
+                self.string_literal(&format!("{}.{}", self.identifier(table), self.identifier(column))),
+                self.string_literal(new_name)
+            ),
+            _ => format!(
+                "ALTER TABLE {} RENAME COLUMN {} TO {}",
+                self.identifier(table),
+                self.identifier(column),
+                self.identifier(new_name)
+            ),
+        }
+    }
+
+    /// Renders a statement renaming a table, without the trailing semicolon. SQL Server
+    /// uses `sp_rename`; like [`Dialect::rename_column`], it takes the plain names.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The current table name.
+    /// * `new_name` - The new table name.
+    pub fn rename_table(&self, table: &str, new_name: &str) -> String {
+        match self {
+            Dialect::SqlServer => format!(
+                "EXEC sp_rename {}, {}",
+                self.string_literal(&self.identifier(table)),
+                self.string_literal(new_name)
+            ),
+            _ => format!("ALTER TABLE {} RENAME TO {}", self.identifier(table), self.identifier(new_name)),
+        }
+    }
+
+    /// Renders a statement commenting a table or column, without the trailing semicolon:
+    /// `COMMENT ON` for Oracle and Postgres, `sp_addextendedproperty` for SQL Server. Returns
+    /// `None` for MySQL, which comments inline in the CREATE TABLE statement, and SQLite,
+    /// which has no comments. Like [`Dialect::rename_column`], it takes the plain names.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table name.
+    /// * `column` - The column name, or `None` to comment the table.
+    /// * `comment` - The comment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::Postgres.comment_on("t", Some("a"), "it's a").unwrap(), "COMMENT ON COLUMN t.a IS 'it''s a'");
+    /// assert_eq!(
+    ///     Dialect::SqlServer.comment_on("t", None, "orders").unwrap(),
+    ///     "EXEC sp_addextendedproperty 'MS_Description', 'orders', 'SCHEMA', 'dbo', 'TABLE', 't'"
+    /// );
+    /// assert_eq!(Dialect::MySql.comment_on("t", None, "orders"), None);
+    /// ```
+    pub fn comment_on(&self, table: &str, column: Option<&str>, comment: &str) -> Option<String> {
+        match (self, column) {
+            (Dialect::Oracle | Dialect::Postgres, None) => {
+                Some(format!("COMMENT ON TABLE {} IS {}", self.identifier(table), self.string_literal(comment)))
+            }
+            (Dialect::Oracle | Dialect::Postgres, Some(column)) => Some(format!(
+                "COMMENT ON COLUMN {}.{} IS {}",
+                self.identifier(table),
+                self.identifier(column),
+                self.string_literal(comment)
+            )),
+            (Dialect::SqlServer, _) => {
+                let mut statement = format!(
+                    "EXEC sp_addextendedproperty 'MS_Description', {}, 'SCHEMA', 'dbo', 'TABLE', {}",
+                    self.string_literal(comment),
+                    self.string_literal(table)
+                );
+                if let Some(column) = column {
+                    statement.push_str(&format!(", 'COLUMN', {}", self.string_literal(column)));
+                }
+                Some(statement)
+            }
+            (Dialect::MySql | Dialect::Sqlite, _) => None,
+        }
+    }
+
+    /// Returns `true` if the dialect supports `CREATE MATERIALIZED VIEW`.
+    pub fn supports_materialized_views(&self) -> bool {
+        matches!(self, Dialect::Oracle | Dialect::Postgres)
+    }
+
+    /// Returns the statement opening a transaction, or `None` for Oracle where
+    /// transactions start implicitly.
+    pub fn begin_transaction(&self) -> Option<&'static str> {
+        match self {
+            Dialect::Oracle => None,
+            Dialect::Postgres => Some("BEGIN;"),
+            Dialect::MySql => Some("START TRANSACTION;"),
+            Dialect::Sqlite | Dialect::SqlServer => Some("BEGIN TRANSACTION;"),
+        }
+    }
+
+    /// Returns the statement committing the current transaction.
+    pub fn commit(&self) -> &'static str {
+        match self {
+            Dialect::SqlServer => "COMMIT TRANSACTION;",
+            _ => "COMMIT;",
+        }
+    }
+
+    /// Returns the statement rolling back the current transaction.
+    pub fn rollback(&self) -> &'static str {
+        match self {
+            Dialect::SqlServer => "ROLLBACK TRANSACTION;",
+            _ => "ROLLBACK;",
+        }
+    }
+
+    /// Maps a column type to the type name used by the dialect.
+    ///
+    /// Column types are the (lowercase) names found in the ingested DDL. Types the
+    /// dialect has no mapping for are emitted unchanged. Arrays such as `int[]` are Postgres
+    /// types; other dialects store them as JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `column_type` - The column type, e.g. `number` or `varchar`.
+    /// * `length` - The declared length or precision, if any.
+    /// * `decimal_places` - The declared scale, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(Dialect::Oracle.type_name("number", Some(10), Some(2)), "number(10,2)");
+    /// assert_eq!(Dialect::Postgres.type_name("number", Some(10), Some(2)), "numeric(10,2)");
+    /// assert_eq!(Dialect::Postgres.type_name("datetime", None, None), "timestamp");
+    /// assert_eq!(Dialect::SqlServer.type_name("uuid", None, None), "uniqueidentifier");
+    /// assert_eq!(Dialect::Postgres.type_name("varchar[]", Some(20), None), "varchar(20)[]");
+    /// assert_eq!(Dialect::MySql.type_name("int[]", None, None), "json");
+    /// assert_eq!(Dialect::Postgres.type_name("geography(point)", None, None), "geography(Point,4326)");
+    /// assert_eq!(Dialect::MySql.type_name("geometry(polygon)", None, None), "polygon");
+    /// ```
+    pub fn type_name(&self, column_type: &str, length: Option<i32>, decimal_places: Option<i32>) -> String {
+        if let Some(element) = column_type.strip_suffix("[]") {
+            return match self {
+                Dialect::Postgres => format!("{}[]", self.type_name(element, length, decimal_places)),
+                _ => self.type_name("json", None, None),
+            };
+        }
+        if let Some((geography, shape)) = geo::spatial_type(column_type) {
+            let base = if geography { "geography" } else { "geometry" };
+            return match self {
+                Dialect::Postgres => format!("{}({},{})", base, shape.map_or("Geometry", |shape| shape.name()), geo::SRID),
+                Dialect::MySql => shape.map_or("geometry", |shape| shape.name()).to_lowercase(),
+                Dialect::Oracle => "sdo_geometry".to_string(),
+                Dialect::SqlServer => base.to_string(),
+                Dialect::Sqlite => "text".to_string(),
+            };
+        }
+        let name = match (self, column_type) {
+            (Dialect::Oracle, "boolean" | "bool") => return "number(1)".to_string(),
+            (Dialect::Oracle, "uuid") => return "char(36)".to_string(),
+            (Dialect::Oracle, "timestamptz") => "timestamp with time zone",
+            (Dialect::Oracle, "bytea") => return "blob".to_string(),
+            (Dialect::Oracle, "json" | "jsonb") => return "clob".to_string(),
+            (Dialect::Oracle, "text") => return "clob".to_string(),
+            (Dialect::Oracle, "tinyint") => return "number(3)".to_string(),
+            (Dialect::Oracle, "time") => return "char(8)".to_string(),
+            (Dialect::Oracle, "interval") => return "interval day to second".to_string(),
+            (Dialect::Oracle, _) => column_type,
+            (Dialect::Postgres, "bool") => "boolean",
+            (Dialect::Postgres, "blob") => return "bytea".to_string(),
+            (Dialect::Postgres, "number") => "numeric",
+            (Dialect::Postgres, "int") => return "integer".to_string(),
+            (Dialect::Postgres, "tinyint") => return "smallint".to_string(),
+            (Dialect::Postgres, "datetime") => "timestamp",
+            (Dialect::MySql, "number") => "decimal",
+            (Dialect::MySql, "int") => return "int".to_string(),
+            (Dialect::MySql, "bool") => "boolean",
+            (Dialect::MySql, "uuid") => return "char(36)".to_string(),
+            (Dialect::MySql, "timestamptz") => "timestamp",
+            (Dialect::MySql, "bytea") => return "blob".to_string(),
+            (Dialect::MySql, "jsonb") => return "json".to_string(),
+            (Dialect::MySql, "interval") => return "time".to_string(),
+            (Dialect::Sqlite, "number") if decimal_places.is_none() => return "integer".to_string(),
+            (Dialect::Sqlite, "number") => "numeric",
+            (Dialect::Sqlite, "int") => return "integer".to_string(),
+            (Dialect::Sqlite, "bytea") => return "blob".to_string(),
+            (Dialect::Sqlite, "interval") => return "integer".to_string(),
+            (Dialect::SqlServer, "number") => "numeric",
+            (Dialect::SqlServer, "int") => return "int".to_string(),
+            (Dialect::SqlServer, "text") => return "varchar(max)".to_string(),
+            (Dialect::SqlServer, "datetime" | "timestamp") => "datetime2",
+            (Dialect::SqlServer, "timestamptz") => "datetimeoffset",
+            (Dialect::SqlServer, "boolean" | "bool") => return "bit".to_string(),
+            (Dialect::SqlServer, "uuid") => return "uniqueidentifier".to_string(),
+            (Dialect::SqlServer, "blob" | "bytea") => return "varbinary(max)".to_string(),
+            (Dialect::SqlServer, "json" | "jsonb") => return "nvarchar(max)".to_string(),
+            (Dialect::SqlServer, "interval") => return "bigint".to_string(),
+            _ => column_type,
+        };
+        match (length, decimal_places) {
+            (Some(length), Some(decimal_places)) => format!("{}({},{})", name, length, decimal_places),
+            (Some(length), None) => format!("{}({})", name, length),
+            _ => name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+
+    /// Parses a dialect name such as `postgres`, `pg`, `mysql` or `mssql`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "oracle" => Ok(Dialect::Oracle),
+            "postgres" | "postgresql" | "pg" => Ok(Dialect::Postgres),
+            "mysql" | "mariadb" => Ok(Dialect::MySql),
+            "sqlite" => Ok(Dialect::Sqlite),
+            "sqlserver" | "mssql" | "tsql" => Ok(Dialect::SqlServer),
+            _ => Err(format!("unknown dialect `{}`", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_quoting() {
+        assert_eq!(Dialect::Postgres.identifier("orders"), "orders");
+        assert_eq!(Dialect::Postgres.identifier("Order Items"), "\"Order Items\"");
+        assert_eq!(Dialect::MySql.identifier("Order"), "`Order`");
+        assert_eq!(Dialect::SqlServer.identifier("Order"), "[Order]");
+    }
+
+    #[test]
+    fn test_reserved_words() {
+        assert!(RESERVED_WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Dialect::Oracle.identifier("level"), "\"level\"");
+        assert_eq!(Dialect::MySql.identifier("group"), "`group`");
+        assert_eq!(Dialect::Sqlite.identifier("order_id"), "order_id");
+    }
+
+    #[test]
+    fn test_date_literal() {
+        let date = NaiveDate::from_ymd_opt(2021, 3, 4).unwrap();
+        assert_eq!(Dialect::Oracle.date_literal(date), "to_date('2021-03-04','YYYY-MM-DD')");
+        assert_eq!(Dialect::Postgres.date_literal(date), "DATE '2021-03-04'");
+        assert_eq!(Dialect::Sqlite.date_literal(date), "'2021-03-04'");
+    }
+
+    #[test]
+    fn test_string_literal_escaping() {
+        let value = SqlValue::Text("it's a \\ path\0".to_string());
+        assert_eq!(Dialect::Oracle.literal(&value), "'it''s a \\ path'");
+        assert_eq!(Dialect::Postgres.literal(&value), "'it''s a \\ path'");
+        assert_eq!(Dialect::MySql.literal(&value), "'it''s a \\\\ path\\0'");
+        assert_eq!(Dialect::Sqlite.literal(&value), "'it''s a \\ path'");
+        assert_eq!(Dialect::SqlServer.literal(&value), "'it''s a \\ path'");
+        assert_eq!(Dialect::SqlServer.string_literal("Müller"), "N'Müller'");
+        assert_eq!(Dialect::Postgres.string_literal("Müller"), "'Müller'");
+        assert_eq!(Dialect::Oracle.string_literal("''"), "''''''");
+    }
+
+    #[test]
+    fn test_literals_for_new_types() {
+        let ts = NaiveDate::from_ymd_opt(2021, 3, 4).unwrap().and_hms_opt(5, 6, 7).unwrap();
+        assert_eq!(Dialect::Oracle.timestamp_literal(ts), "to_timestamp('2021-03-04 05:06:07','YYYY-MM-DD HH24:MI:SS')");
+        assert_eq!(Dialect::Postgres.timestamp_literal(ts), "TIMESTAMP '2021-03-04 05:06:07'");
+        let tz = ts.and_local_timezone(FixedOffset::east_opt(3600).unwrap()).unwrap();
+        assert_eq!(Dialect::Postgres.timestamp_tz_literal(tz), "TIMESTAMPTZ '2021-03-04 05:06:07+01:00'");
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Boolean(true)), "TRUE");
+        assert_eq!(Dialect::SqlServer.literal(&SqlValue::Boolean(true)), "1");
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Bytes(vec![0x0a, 0xff])), "'\\x0aff'");
+        assert_eq!(Dialect::MySql.literal(&SqlValue::Bytes(vec![0x0a, 0xff])), "X'0aff'");
+        assert_eq!(Dialect::SqlServer.literal(&SqlValue::Bytes(vec![0x0a, 0xff])), "0x0aff");
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Base64(vec![0x0a, 0xff])), "decode('Cv8=', 'base64')");
+        assert_eq!(Dialect::MySql.literal(&SqlValue::Base64(b"fake".to_vec())), "FROM_BASE64('ZmFrZQ==')");
+        assert_eq!(Dialect::SqlServer.literal(&SqlValue::Base64(vec![0x0a, 0xff])), "0x0aff");
+        assert_eq!(
+            Dialect::Sqlite.literal(&SqlValue::Uuid(0x67e5504410b1426f9247bb680e5fe0c8)),
+            "'67e55044-10b1-426f-9247-bb680e5fe0c8'"
+        );
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Json(serde_json::json!({"a": 1}))), "'{\"a\":1}'");
+        let time = NaiveTime::from_hms_opt(7, 5, 0).unwrap();
+        assert_eq!(Dialect::MySql.literal(&SqlValue::Time(time)), "TIME '07:05:00'");
+        assert_eq!(Dialect::SqlServer.literal(&SqlValue::Time(time)), "'07:05:00'");
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Interval(-90)), "INTERVAL '-0 days -00:01:30'");
+        assert_eq!(Dialect::Sqlite.literal(&SqlValue::Interval(-90)), "-90");
+        let array = SqlValue::Array(vec![SqlValue::Integer(1), SqlValue::Null, SqlValue::Text("it's \"a\"".to_string())]);
+        assert_eq!(Dialect::Postgres.literal(&array), r#"'{1,NULL,"it''s \"a\""}'"#);
+        assert_eq!(Dialect::MySql.literal(&array), r#"'[1,null,"it''s \\"a\\""]'"#);
+        let composite = SqlValue::Composite(vec![("street".to_string(), SqlValue::Text("Main St".to_string())), ("zip".to_string(), SqlValue::Null)]);
+        assert_eq!(Dialect::Postgres.literal(&composite), "ROW('Main St', NULL)");
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Array(vec![composite.clone()])), r#"'{"(\"Main St\",)"}'"#);
+        assert_eq!(Dialect::Sqlite.literal(&composite), r#"'{"street":"Main St","zip":null}'"#);
+        let point = SqlValue::Geometry("POINT(13.4 52.5)".to_string(), true);
+        assert_eq!(point.as_text().unwrap(), "SRID=4326;POINT(13.4 52.5)");
+        assert_eq!(Dialect::Postgres.literal(&point), "ST_GeogFromText('SRID=4326;POINT(13.4 52.5)')");
+        assert_eq!(Dialect::MySql.literal(&point), "ST_GeomFromText('POINT(13.4 52.5)', 4326, 'axis-order=long-lat')");
+        assert_eq!(Dialect::Oracle.literal(&point), "SDO_GEOMETRY('POINT(13.4 52.5)', 4326)");
+        assert_eq!(Dialect::Sqlite.literal(&point), "'POINT(13.4 52.5)'");
+        assert_eq!(Dialect::Postgres.type_name("geometry", None, None), "geometry(Geometry,4326)");
+        assert_eq!(Dialect::MySql.type_name("geography(multipoint)", None, None), "multipoint");
+        assert_eq!(Dialect::SqlServer.type_name("geography(point)", None, None), "geography");
+    }
+
+    #[test]
+    fn test_from_str() {
+        for dialect in Dialect::ALL {
+            assert_eq!(dialect.name().parse::<Dialect>(), Ok(dialect));
+        }
+        assert_eq!("PostgreSQL".parse::<Dialect>(), Ok(Dialect::Postgres));
+        assert!("db2".parse::<Dialect>().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! Invalid values injected into INSERT statements to test how ingestion tools handle errors.
+
+use crate::dialect::Dialect;
+use crate::models::Table;
+use crate::value::SqlValue;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// What makes an injected value invalid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DirtyKind {
+    /// A number beyond what the column's type or precision holds.
+    OutOfRange,
+    /// A date or timestamp that does not exist or is written in the wrong format, such as
+    /// `2023-02-30` or `31/12/2023`.
+    MalformedDate,
+    /// Text longer than the column's declared length.
+    OverLength,
+    /// Text in double quotes, typographic quotes or without quotes instead of a string literal.
+    WrongQuoting,
+}
+
+impl DirtyKind {
+    /// Every kind of invalid value.
+    pub const ALL: [DirtyKind; 4] = [DirtyKind::OutOfRange, DirtyKind::MalformedDate, DirtyKind::OverLength, DirtyKind::WrongQuoting];
+
+    /// Returns the name of the kind in the dirty report, e.g. `out_of_range`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DirtyKind::OutOfRange => "out_of_range",
+            DirtyKind::MalformedDate => "malformed_date",
+            DirtyKind::OverLength => "over_length",
+            DirtyKind::WrongQuoting => "wrong_quoting",
+        }
+    }
+
+    /// Returns whether a value of `kind` can be injected into the column at `index` of `table`.
+    ///
+    /// Keys are left alone, so rows referencing a dirty row are not rejected for it too.
+    fn applies_to(&self, table: &Table, index: usize) -> bool {
+        let column = &table.columns[index];
+        if column.is_pkey || column.auto_increment || column.ref_table.is_some() {
+            return false;
+        }
+        let text = matches!(column.column_type.as_str(), "varchar" | "text" | "char");
+        match self {
+            DirtyKind::OutOfRange => column.is_numeric(),
+            DirtyKind::MalformedDate => matches!(column.column_type.as_str(), "date" | "datetime" | "timestamp" | "timestamptz"),
+            DirtyKind::OverLength => text && column.length.is_some(),
+            DirtyKind::WrongQuoting => text,
+        }
+    }
+}
+
+impl fmt::Display for DirtyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An invalid value injected into an INSERT statement, as listed in the dirty report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dirt {
+    /// Position of the row within the statement, starting at 1.
+    pub row: usize,
+    /// Name of the column holding the value.
+    pub column: String,
+    pub kind: DirtyKind,
+    /// The value as written into the statement.
+    pub value: String,
+}
+
+/// Replaces one value of one of `rows` of `table` with an invalid one.
+///
+/// # Arguments
+///
+/// * `table` - The table the rows are inserted into.
+/// * `rows` - The rows of the INSERT statement, one value per column.
+/// * `dialect` - The dialect the statement is rendered for, which renders the reported value.
+/// * `quoting` - Whether values are written as literals, so wrong quoting can be injected;
+///   statements with bind placeholders have no quotes to get wrong.
+/// * `rng` - The random number generator the row, column and value are drawn from.
+///
+/// # Returns
+///
+/// What was injected, or `None` if no column of the table can hold an invalid value.
+pub(crate) fn inject<R: Rng + ?Sized>(table: &Table, rows: &mut [Vec<SqlValue>], dialect: Dialect, quoting: bool, rng: &mut R) -> Option<Dirt> {
+    let kinds: Vec<DirtyKind> = DirtyKind::ALL
+        .into_iter()
+        .filter(|kind| quoting || *kind != DirtyKind::WrongQuoting)
+        .filter(|kind| (0..table.columns.len()).any(|i| kind.applies_to(table, i)))
+        .collect();
+    let kind = *kinds.choose(rng)?;
+    let columns: Vec<usize> = (0..table.columns.len()).filter(|&i| kind.applies_to(table, i)).collect();
+    let index = *columns.choose(rng).unwrap();
+    let row = rng.gen_range(0..rows.len());
+    let value = dirty_value(table, index, kind, &rows[row][index], rng);
+    let dirt = Dirt {
+        row: row + 1,
+        column: table.columns[index].name.clone(),
+        kind,
+        value: if quoting { dialect.literal(&value) } else { value.as_text().unwrap_or_default() },
+    };
+    rows[row][index] = value;
+    Some(dirt)
+}
+
+/// Returns an invalid value of `kind` for the column at `index` of `table`, in place of the
+/// generated `value`.
+fn dirty_value<R: Rng + ?Sized>(table: &Table, index: usize, kind: DirtyKind, value: &SqlValue, rng: &mut R) -> SqlValue {
+    let column = &table.columns[index];
+    let text = value.as_text().filter(|text| !text.is_empty()).unwrap_or_else(|| "value".to_string());
+    match kind {
+        DirtyKind::OutOfRange => {
+            let limit = column.numeric_limit();
+            let units = limit.saturating_add(rng.gen_range(1..=limit.max(1))) * if rng.gen_bool(0.5) { 1 } else { -1 };
+            match column.number_value(units) {
+                // Past the 64-bit range, only the SQL text can hold the integer
+                SqlValue::Integer(_) if i64::try_from(units).is_err() => SqlValue::Raw(units.to_string()),
+                value => value,
+            }
+        }
+        DirtyKind::MalformedDate => {
+            let date = column.date_range().random_date(rng);
+            let malformed = match rng.gen_range(0..5) {
+                0 => date.format("%Y-02-30").to_string(),
+                1 => date.format("%Y-13-%d").to_string(),
+                2 => date.format("%d/%m/%Y").to_string(),
+                3 => date.format("%Y-%m-%d 25:61:00").to_string(),
+                _ => "0000-00-00".to_string(),
+            };
+            SqlValue::Text(malformed)
+        }
+        DirtyKind::OverLength => {
+            let length = column.length.unwrap_or(1).max(1) as usize;
+            SqlValue::Text(text.chars().cycle().take(length + rng.gen_range(1..=length)).collect())
+        }
+        DirtyKind::WrongQuoting => {
+            // Without single quotes and semicolons, the statement still ends where it should
+            let text: String = text.chars().filter(|c| *c != '\'' && *c != ';').collect();
+            match rng.gen_range(0..3) {
+                0 => SqlValue::Raw(format!("\"{}\"", text.replace('"', ""))),
+                1 => SqlValue::Raw(format!("\u{2018}{}\u{2019}", text)),
+                _ => SqlValue::Raw(text),
+            }
+        }
+    }
+}
+
+/// Returns the file the dirty statements written to `path` are reported in: `output.sql`
+/// becomes `output.dirty.jsonl`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::dirty::report_path;
+/// # use std::path::Path;
+/// assert_eq!(report_path(Path::new("out/data.sql")), Path::new("out/data.dirty.jsonl"));
+/// ```
+pub fn report_path(path: &Path) -> PathBuf {
+    path.with_extension("dirty.jsonl")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_inject() {
+        let table = Table::init_via_sql(
+            "create table t (id int primary key, qty smallint, price number(5, 2), born date, name varchar(8), parent_id int references p(id))",
+        );
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut seen = Vec::new();
+        for _ in 0..200 {
+            let clean = vec![
+                SqlValue::Integer(1),
+                SqlValue::Integer(5),
+                SqlValue::Decimal(150, 2),
+                SqlValue::Text("2023-05-06".to_string()),
+                SqlValue::Text("Ada".to_string()),
+                SqlValue::Integer(1),
+            ];
+            let mut rows = vec![clean.clone(), clean.clone()];
+            let dirt = inject(&table, &mut rows, Dialect::Postgres, true, &mut rng).unwrap();
+            let index = table.columns.iter().position(|c| c.name == dirt.column).unwrap();
+            let value = &rows[dirt.row - 1][index];
+            assert_eq!(Dialect::Postgres.literal(value), dirt.value);
+            assert_eq!(rows.iter().flatten().filter(|v| !clean.contains(v)).count(), 1, "{:?}", rows);
+            match (dirt.kind, dirt.column.as_str(), value) {
+                (DirtyKind::OutOfRange, "qty", SqlValue::Integer(n)) => assert!(n.abs() > i16::MAX as i64),
+                (DirtyKind::OutOfRange, "price", SqlValue::Decimal(n, 2)) => assert!(n.abs() > 99999),
+                (DirtyKind::MalformedDate, "born", SqlValue::Text(date)) => assert!(date.parse::<chrono::NaiveDate>().is_err(), "{}", date),
+                (DirtyKind::OverLength, "name", SqlValue::Text(name)) => assert!(name.len() > 8),
+                (DirtyKind::WrongQuoting, "name", SqlValue::Raw(name)) => assert!(!name.starts_with('\'')),
+                other => panic!("unexpected dirt {:?}", other),
+            }
+            seen.push(dirt.kind);
+        }
+        assert!(DirtyKind::ALL.iter().all(|kind| seen.contains(kind)));
+
+        let mut rows = vec![vec![SqlValue::Integer(1), SqlValue::Text("Ada".to_string())]];
+        let names = Table::init_via_sql("create table n (id int primary key, name text)");
+        let dirt = inject(&names, &mut rows, Dialect::Postgres, false, &mut rng);
+        assert_eq!(dirt, None);
+    }
+
+    #[test]
+    fn test_out_of_range_bigint() {
+        let table = Table::init_via_sql("create table t (id int primary key, total bigint)");
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut rows = vec![vec![SqlValue::Integer(1), SqlValue::Integer(2)]];
+        let dirt = inject(&table, &mut rows, Dialect::MySql, true, &mut rng).unwrap();
+        assert_eq!(dirt.kind, DirtyKind::OutOfRange);
+        assert!(dirt.value.trim_start_matches('-').parse::<i64>().is_err(), "{}", dirt.value);
+        assert!(dirt.value.trim_start_matches('-').parse::<i128>().is_ok(), "{}", dirt.value);
+    }
+}
@@ -0,0 +1,161 @@
+//! Value distributions for skewed data.
+
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Enum representing how values are spread over the range of a column.
+///
+/// Numeric columns draw their values from the column's range with the distribution,
+/// and foreign key columns pick the parent keys with it, so `Zipfian` makes a few
+/// customers own most of the orders.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Distribution {
+    /// Every value is equally likely.
+    #[default]
+    Uniform,
+    /// Values cluster around the middle of the range, with 99.7% of them inside it.
+    Normal,
+    /// The first values of the range are much more frequent than the rest; the
+    /// frequency of the n-th value is roughly proportional to 1/n.
+    Zipfian,
+    /// The frequency of values decays exponentially from the start of the range.
+    Exponential,
+}
+
+impl Distribution {
+    /// Every distribution.
+    pub const ALL: [Distribution; 4] =
+        [Distribution::Uniform, Distribution::Normal, Distribution::Zipfian, Distribution::Exponential];
+
+    /// Returns the lowercase name of the distribution, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Uniform => "uniform",
+            Distribution::Normal => "normal",
+            Distribution::Zipfian => "zipfian",
+            Distribution::Exponential => "exponential",
+        }
+    }
+
+    /// Draws a value between `min` and `max`, both included.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The smallest value.
+    /// * `max` - The largest value; must not be smaller than `min`.
+    /// * `rng` - The random number generator the value is drawn from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::distribution::Distribution;
+    /// let mut rng = rand::thread_rng();
+    /// let value = Distribution::Zipfian.sample(1, 1000, &mut rng);
+    /// assert!((1..=1000).contains(&value));
+    /// ```
+    pub fn sample<R: Rng + ?Sized>(&self, min: i128, max: i128, rng: &mut R) -> i128 {
+        // Work on the offset from `min` as a float; spans beyond 2^53 lose precision only
+        let span = max as f64 - min as f64;
+        let offset = match self {
+            Distribution::Uniform => return rng.gen_range(min..=max),
+            Distribution::Normal => {
+                // Box-Muller transform, with the range spanning six standard deviations
+                let (u1, u2): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (span / 2.0 + z * span / 6.0).round()
+            }
+            Distribution::Zipfian => {
+                // Inverse of the continuous 1/x density over 1..span + 2
+                ((span + 2.0).powf(rng.gen::<f64>()) - 1.0).floor()
+            }
+            Distribution::Exponential => {
+                // About 99% of the values fall inside the range before clamping
+                let u: f64 = 1.0 - rng.gen::<f64>();
+                (-u.ln() / 4.6 * span).floor()
+            }
+        };
+        min.saturating_add(offset.clamp(0.0, span) as i128).min(max)
+    }
+
+    /// Draws an index into a list of `len` entries, e.g. the parent keys of a foreign key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    pub fn sample_index<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> usize {
+        assert!(len > 0, "cannot sample from an empty list");
+        self.sample(0, len as i128 - 1, rng) as usize
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Distribution {
+    type Err = String;
+
+    /// Parses a distribution name such as `normal`, `zipf` or `exp`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "uniform" => Ok(Distribution::Uniform),
+            "normal" | "gaussian" => Ok(Distribution::Normal),
+            "zipfian" | "zipf" => Ok(Distribution::Zipfian),
+            "exponential" | "exp" => Ok(Distribution::Exponential),
+            _ => Err(format!("unknown distribution `{}`", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn histogram(distribution: Distribution) -> [usize; 10] {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut buckets = [0; 10];
+        for _ in 0..10_000 {
+            let value = distribution.sample(0, 99, &mut rng);
+            assert!((0..=99).contains(&value));
+            buckets[value as usize / 10] += 1;
+        }
+        buckets
+    }
+
+    #[test]
+    fn test_shapes() {
+        let uniform = histogram(Distribution::Uniform);
+        assert!(uniform.iter().all(|&count| (800..1200).contains(&count)), "{:?}", uniform);
+        let normal = histogram(Distribution::Normal);
+        assert!(normal[4] + normal[5] > 4 * (normal[0] + normal[9]), "{:?}", normal);
+        let zipfian = histogram(Distribution::Zipfian);
+        assert!(zipfian[0] > 5 * zipfian[9], "{:?}", zipfian);
+        let exponential = histogram(Distribution::Exponential);
+        assert!(exponential[0] > exponential[1] && exponential[1] > exponential[5], "{:?}", exponential);
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for distribution in Distribution::ALL {
+            assert_eq!(distribution.sample(7, 7, &mut rng), 7);
+            assert_eq!(distribution.sample_index(1, &mut rng), 0);
+            let value = distribution.sample(i128::MIN + 1, i128::MAX, &mut rng);
+            assert!(value > i128::MIN);
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        for distribution in Distribution::ALL {
+            assert_eq!(distribution.name().parse::<Distribution>(), Ok(distribution));
+        }
+        assert_eq!("Zipf".parse::<Distribution>(), Ok(Distribution::Zipfian));
+        assert!("poisson".parse::<Distribution>().is_err());
+    }
+}
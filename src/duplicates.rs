@@ -0,0 +1,258 @@
+//! Duplicate, near-duplicate and whitespace-damaged rows for data-quality testing.
+
+use crate::models::Table;
+use crate::value::SqlValue;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Whitespace inserted by [`Anomaly::Whitespace`]: spaces, a tab and non-breaking spaces.
+const WHITESPACE: [&str; 4] = [" ", "  ", "\t", "\u{a0}"];
+
+/// Shares of generated rows that copy an earlier row or carry damaged text, written
+/// `exact=0.02,near=0.03,whitespace=0.01`.
+///
+/// - `exact` rows repeat the values of a random earlier row of the table.
+/// - `near` rows repeat an earlier row with one text value changed by a typo (swapped,
+///   dropped or doubled letters) or a different case.
+/// - `whitespace` rows get leading, trailing or doubled spaces, tabs or non-breaking spaces
+///   in one of their text values.
+///
+/// Primary keys, unique and auto-increment columns are never copied, so the rows still
+/// satisfy the constraints of the schema while their content repeats.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::duplicates::Duplicates;
+/// let duplicates: Duplicates = "exact=0.02,near=0.03".parse().unwrap();
+/// assert_eq!((duplicates.exact, duplicates.near, duplicates.whitespace), (0.02, 0.03, 0.0));
+/// assert_eq!("0.1".parse::<Duplicates>().unwrap().exact, 0.1);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Duplicates {
+    /// Share of rows copying an earlier row.
+    pub exact: f64,
+    /// Share of rows copying an earlier row with a typo or case change.
+    pub near: f64,
+    /// Share of rows with a whitespace anomaly.
+    pub whitespace: f64,
+}
+
+/// The damage done to a generated row.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Anomaly {
+    Exact,
+    Near,
+    Whitespace,
+}
+
+impl Duplicates {
+    /// Initializes a new `Duplicates`.
+    ///
+    /// # Returns
+    ///
+    /// The shares, or an error if one is outside 0.0 to 1.0 or they add up to more than 1.0.
+    pub fn new(exact: f64, near: f64, whitespace: f64) -> Result<Duplicates, String> {
+        for (name, rate) in [("exact", exact), ("near", near), ("whitespace", whitespace)] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(format!("the {} duplicate rate must be between 0.0 and 1.0, found {}", name, rate));
+            }
+        }
+        if exact + near + whitespace > 1.0 {
+            return Err(format!("the duplicate rates add up to {}, more than 1.0", exact + near + whitespace));
+        }
+        Ok(Duplicates { exact, near, whitespace })
+    }
+
+    /// Returns whether no row is damaged.
+    pub fn is_empty(&self) -> bool {
+        self.exact + self.near + self.whitespace == 0.0
+    }
+
+    /// Picks the anomaly of the next row, if any.
+    pub(crate) fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Anomaly> {
+        if self.is_empty() {
+            return None;
+        }
+        let draw: f64 = rng.gen();
+        if draw < self.exact {
+            Some(Anomaly::Exact)
+        } else if draw < self.exact + self.near {
+            Some(Anomaly::Near)
+        } else if draw < self.exact + self.near + self.whitespace {
+            Some(Anomaly::Whitespace)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Duplicates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "exact={},near={},whitespace={}", self.exact, self.near, self.whitespace)
+    }
+}
+
+impl FromStr for Duplicates {
+    type Err = String;
+
+    /// Parses shares such as `exact=0.02,near=0.03,whitespace=0.01`, or a single number for
+    /// exact duplicates only.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rate = |value: &str| value.trim().parse::<f64>().map_err(|_| format!("invalid duplicate rate `{}`", value.trim()));
+        if !s.contains('=') {
+            return Duplicates::new(rate(s)?, 0.0, 0.0);
+        }
+        let (mut exact, mut near, mut whitespace) = (0.0, 0.0, 0.0);
+        for part in s.split(',') {
+            let (kind, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected `kind=rate`, found `{}`", part.trim()))?;
+            match kind.trim().to_lowercase().as_str() {
+                "exact" => exact = rate(value)?,
+                "near" => near = rate(value)?,
+                "whitespace" => whitespace = rate(value)?,
+                other => return Err(format!("unknown duplicate kind `{}`; expected exact, near or whitespace", other)),
+            }
+        }
+        Duplicates::new(exact, near, whitespace)
+    }
+}
+
+impl Anomaly {
+    /// Damages the drawn `row` of `table`.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table of the row.
+    /// * `earlier` - A row inserted before, copied by exact and near duplicates; without
+    ///   one, near duplicates change the drawn row and exact duplicates leave it alone.
+    /// * `row` - The drawn row, one value per column.
+    /// * `rng` - The random number generator used for the damage.
+    pub(crate) fn apply<R: Rng + ?Sized>(&self, table: &Table, earlier: Option<&[SqlValue]>, row: &mut [SqlValue], rng: &mut R) {
+        // Key columns keep their drawn values so constraints still hold
+        let copied: Vec<usize> = (0..table.columns.len())
+            .filter(|&i| {
+                let column = &table.columns[i];
+                !column.is_pkey && !column.is_unique && !column.auto_increment && !table.unique_keys.iter().any(|key| key.contains(&column.name))
+            })
+            .collect();
+        if let (Anomaly::Exact | Anomaly::Near, Some(earlier)) = (self, earlier) {
+            for &i in &copied {
+                row[i] = earlier[i].clone();
+            }
+        }
+        // Enums only accept their members as they are
+        let texts: Vec<usize> = copied
+            .into_iter()
+            .filter(|&i| table.columns[i].labels.is_empty() && matches!(&row[i], SqlValue::Text(text) if !text.is_empty()))
+            .collect();
+        let Some(&i) = texts.choose(rng) else { return };
+        let SqlValue::Text(text) = &row[i] else { return };
+        let changed = match self {
+            Anomaly::Exact => return,
+            Anomaly::Near if rng.gen_bool(0.5) => change_case(text, rng),
+            Anomaly::Near => typo(text, rng),
+            Anomaly::Whitespace => add_whitespace(text, rng),
+        };
+        row[i] = SqlValue::Text(match table.columns[i].length {
+            Some(length) => changed.chars().take(length.max(1) as usize).collect(),
+            None => changed,
+        });
+    }
+}
+
+/// Returns `text` with a typo: two neighbouring characters swapped, one dropped or one doubled.
+fn typo<R: Rng + ?Sized>(text: &str, rng: &mut R) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    let i = rng.gen_range(0..chars.len());
+    match rng.gen_range(0..3) {
+        0 if chars.len() > 1 => {
+            let j = if i + 1 < chars.len() { i + 1 } else { i - 1 };
+            chars.swap(i, j);
+        }
+        1 if chars.len() > 1 => {
+            chars.remove(i);
+        }
+        _ => chars.insert(i, chars[i]),
+    }
+    let changed: String = chars.into_iter().collect();
+    if changed == text {
+        // Swapping equal letters changes nothing, doubling one always does
+        let mut chars: Vec<char> = text.chars().collect();
+        chars.insert(i, chars[i]);
+        return chars.into_iter().collect();
+    }
+    changed
+}
+
+/// Returns `text` in upper case, lower case or with its first letter's case flipped,
+/// whichever differs from it.
+fn change_case<R: Rng + ?Sized>(text: &str, rng: &mut R) -> String {
+    let mut first = text.chars();
+    let flipped: String = match first.next() {
+        Some(c) if c.is_uppercase() => c.to_lowercase().chain(first).collect(),
+        Some(c) => c.to_uppercase().chain(first).collect(),
+        None => String::new(),
+    };
+    let candidates = [text.to_uppercase(), text.to_lowercase(), flipped];
+    let different: Vec<&String> = candidates.iter().filter(|candidate| candidate.as_str() != text).collect();
+    different.choose(rng).map_or_else(|| text.to_string(), |candidate| candidate.to_string())
+}
+
+/// Returns `text` with whitespace added at its start or end, or in place of a space.
+fn add_whitespace<R: Rng + ?Sized>(text: &str, rng: &mut R) -> String {
+    let whitespace = WHITESPACE.choose(rng).unwrap();
+    match rng.gen_range(0..3) {
+        0 => format!("{}{}", whitespace, text),
+        1 if text.contains(' ') => text.replacen(' ', if *whitespace == " " { "  " } else { whitespace }, 1),
+        _ => format!("{}{}", text, whitespace),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("near=0.5, whitespace=0.25".parse(), Duplicates::new(0.0, 0.5, 0.25));
+        for invalid in ["exact=0.8,near=0.3", "1.5", "typo=0.1", "exact"] {
+            assert!(invalid.parse::<Duplicates>().is_err(), "{}", invalid);
+        }
+        let mut rng = StdRng::seed_from_u64(7);
+        let duplicates = Duplicates::new(0.2, 0.0, 0.3).unwrap();
+        let anomalies: Vec<Option<Anomaly>> = (0..1000).map(|_| duplicates.choose(&mut rng)).collect();
+        let share = |anomaly| anomalies.iter().filter(|&&a| a == anomaly).count();
+        assert!((150..250).contains(&share(Some(Anomaly::Exact))) && (250..350).contains(&share(Some(Anomaly::Whitespace))));
+        assert_eq!(share(Some(Anomaly::Near)), 0);
+    }
+
+    #[test]
+    fn test_apply() {
+        let table = Table::init_via_sql("create table t (id int primary key, name varchar(20), city varchar(20), code varchar(5) unique)");
+        let earlier = [SqlValue::Integer(1), SqlValue::Text("Ada Lovelace".to_string()), SqlValue::Text("London".to_string()), SqlValue::Text("A1".to_string())];
+        let drawn = [SqlValue::Integer(2), SqlValue::Text("Alan Turing".to_string()), SqlValue::Text("Wilmslow".to_string()), SqlValue::Text("B2".to_string())];
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let mut row = drawn.to_vec();
+            Anomaly::Exact.apply(&table, Some(&earlier), &mut row, &mut rng);
+            assert_eq!(row, [drawn[0].clone(), earlier[1].clone(), earlier[2].clone(), drawn[3].clone()]);
+
+            let mut row = drawn.to_vec();
+            Anomaly::Near.apply(&table, Some(&earlier), &mut row, &mut rng);
+            assert_eq!((&row[0], &row[3]), (&drawn[0], &drawn[3]));
+            let changed = (1..3).filter(|&i| row[i] != earlier[i]).count();
+            assert_eq!(changed, 1, "{:?}", row);
+
+            let mut row = drawn.to_vec();
+            Anomaly::Whitespace.apply(&table, Some(&earlier), &mut row, &mut rng);
+            let texts: Vec<String> = row[1..3].iter().filter_map(|v| v.as_text()).collect();
+            assert!(texts.iter().any(|text| text.trim() != text || text.contains("  ") || text.contains('\t') || text.contains('\u{a0}')));
+        }
+    }
+}
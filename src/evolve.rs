@@ -0,0 +1,312 @@
+//! Schema changes that evolve the tables while statements are generated.
+
+use crate::dialect::Dialect;
+use crate::models::{Column, SqlType, Table};
+use crate::providers::Provider;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Columns added by [`SchemaChange::AddColumn`]: name, type, length and scale.
+const NEW_COLUMNS: [(&str, &str, Option<i32>, Option<i32>); 7] = [
+    ("note", "varchar", Some(100), None),
+    ("status", "varchar", Some(20), None),
+    ("tag", "varchar", Some(30), None),
+    ("score", "number", Some(10), Some(2)),
+    ("rating", "int", None, None),
+    ("version", "int", None, None),
+    ("updated_on", "date", None, None),
+];
+
+/// Largest length a modified text column is widened to.
+const MAX_TEXT_LENGTH: i32 = 4000;
+
+/// Largest precision a modified numeric column is widened to.
+const MAX_PRECISION: i32 = 38;
+
+/// A change to one table of the schema, as done by a migration.
+#[derive(Clone, Debug)]
+pub enum SchemaChange {
+    /// Adds a nullable column, so rows inserted before stay valid.
+    AddColumn(Box<Column>),
+    /// Drops the named column.
+    DropColumn(String),
+    /// Widens the named text or numeric column to the given length or precision.
+    ModifyColumn(String, i32),
+    /// Renames the column to the new name.
+    RenameColumn(String, String),
+    /// Renames the table to the new name.
+    RenameTable(String),
+    /// Creates an index with the given name on the columns.
+    CreateIndex(String, Vec<String>),
+}
+
+impl SchemaChange {
+    /// Picks a random change of `table` that keeps the schema consistent.
+    ///
+    /// Only columns without a role in keys are dropped, modified or renamed: primary key,
+    /// foreign key and auto-increment columns and the columns in `fixed` stay as they are.
+    /// Only tables no foreign key references are renamed.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to change.
+    /// * `tables` - Every table of the schema, so new table names are unique.
+    /// * `fixed` - Further columns of `table` that must not change, e.g. indexed ones.
+    /// * `index_names` - The names of the indexes created so far.
+    /// * `dialect` - The dialect, as SQLite cannot change column types.
+    /// * `rng` - The random number generator picking the change.
+    ///
+    /// # Returns
+    ///
+    /// The change; every table can at least get a new column.
+    pub fn random<R: Rng + ?Sized>(
+        table: &Table,
+        tables: &[Table],
+        fixed: &[String],
+        index_names: &[String],
+        dialect: Dialect,
+        rng: &mut R,
+    ) -> SchemaChange {
+        let free: Vec<&Column> = table
+            .columns
+            .iter()
+            .filter(|c| is_free(c) && !fixed.contains(&c.name))
+            .collect();
+        let widened: Vec<&Column> = free.iter().copied().filter(|c| widened_length(c).is_some()).collect();
+        let referenced = tables.iter().flat_map(|t| &t.columns).any(|c| c.ref_table.as_ref() == Some(&table.name));
+
+        let mut changes = vec![SchemaChange::AddColumn(Box::new(new_column(table, rng)))];
+        if let Some(column) = free.choose(rng) {
+            // Keep a column besides the keys, so the table still has data to generate
+            if table.columns.len() > 2 {
+                changes.push(SchemaChange::DropColumn(column.name.clone()));
+            }
+            changes.push(SchemaChange::RenameColumn(column.name.clone(), unused_name(&column.name, |name| table.column(name).is_some())));
+        }
+        if let Some(column) = widened.choose(rng).filter(|_| dialect != Dialect::Sqlite) {
+            changes.push(SchemaChange::ModifyColumn(column.name.clone(), widened_length(column).unwrap()));
+        }
+        if !referenced {
+            changes.push(SchemaChange::RenameTable(unused_name(&table.name, |name| tables.iter().any(|t| t.name == name))));
+        }
+        // JSON documents cannot be indexed as they are
+        let indexable: Vec<&Column> = table.columns.iter().filter(|c| c.is_filterable() && !c.is_json(dialect)).collect();
+        if let Some(column) = indexable.choose(rng) {
+            let base = format!("idx_{}_{}", table.name, column.name);
+            let name = unused_name(&base, |name| index_names.iter().any(|index| index == name));
+            changes.push(SchemaChange::CreateIndex(name, vec![column.name.clone()]));
+        }
+        changes.swap_remove(rng.gen_range(0..changes.len()))
+    }
+
+    /// Picks a random single-column change of `table`: adding a column, widening a column
+    /// (except on SQLite) or dropping a column that is not part of a key.
+    ///
+    /// Unlike [`SchemaChange::random`], it does not keep the schema consistent with other
+    /// statements; [`Table::generate_with`] uses it for random `ALTER TABLE` statements.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to change.
+    /// * `dialect` - The dialect, as SQLite cannot change column types.
+    /// * `rng` - The random number generator picking the change.
+    pub fn random_alter<R: Rng + ?Sized>(table: &Table, dialect: Dialect, rng: &mut R) -> SchemaChange {
+        let free: Vec<&Column> = table.columns.iter().filter(|c| is_free(c)).collect();
+        let widened: Vec<&Column> = free.iter().copied().filter(|c| widened_length(c).is_some()).collect();
+        let mut changes = vec![SchemaChange::AddColumn(Box::new(new_column(table, rng)))];
+        if let Some(column) = widened.choose(rng).filter(|_| dialect != Dialect::Sqlite) {
+            changes.push(SchemaChange::ModifyColumn(column.name.clone(), widened_length(column).unwrap()));
+        }
+        if let Some(column) = free.choose(rng) {
+            changes.push(SchemaChange::DropColumn(column.name.clone()));
+        }
+        changes.swap_remove(rng.gen_range(0..changes.len()))
+    }
+
+    /// Returns the SQL type of the statement making the change: `CreateIndex` for a new
+    /// index and `AlterTable` otherwise.
+    pub fn sql_type(&self) -> SqlType {
+        match self {
+            SchemaChange::CreateIndex(..) => SqlType::CreateIndex,
+            _ => SqlType::AlterTable,
+        }
+    }
+
+    /// Renders the statement making the change to `table`, which is still unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table before the change.
+    /// * `dialect` - The dialect the statement is rendered for.
+    ///
+    /// # Returns
+    ///
+    /// The statement, terminated by a semicolon.
+    pub fn statement(&self, table: &Table, dialect: Dialect) -> String {
+        let table_name = dialect.identifier(&table.name);
+        let sql = match self {
+            SchemaChange::AddColumn(column) => dialect.add_column(&table_name, &column.definition(dialect)),
+            SchemaChange::DropColumn(name) => dialect.drop_column(&table_name, &dialect.identifier(name)),
+            SchemaChange::ModifyColumn(name, length) => {
+                let column = table.column(name).expect("modified column exists");
+                let type_name = dialect.type_name(&column.column_type, Some(*length), column.decimal_places);
+                dialect
+                    .modify_column(&table_name, &dialect.identifier(name), &type_name, column.is_nullable)
+                    .expect("dialect supports modifying columns")
+            }
+            SchemaChange::RenameColumn(name, new_name) => dialect.rename_column(&table.name, name, new_name),
+            SchemaChange::RenameTable(new_name) => dialect.rename_table(&table.name, new_name),
+            SchemaChange::CreateIndex(name, columns) => {
+                let columns: Vec<String> = columns.iter().map(|c| dialect.identifier(c)).collect();
+                format!("CREATE INDEX {} ON {} ({})", dialect.identifier(name), table_name, columns.join(", "))
+            }
+        };
+        format!("{};", sql)
+    }
+
+    /// Makes the change to the in-memory `table`.
+    ///
+    /// A renamed column keeps the provider inferred from its old name, so its values do
+    /// not change with the name.
+    pub fn apply(&self, table: &mut Table) {
+        match self {
+            SchemaChange::AddColumn(column) => table.columns.push(column.as_ref().clone()),
+            SchemaChange::DropColumn(name) => table.columns.retain(|c| &c.name != name),
+            SchemaChange::ModifyColumn(name, length) => {
+                if let Some(column) = table.columns.iter_mut().find(|c| &c.name == name) {
+                    column.length = Some(*length);
+                }
+            }
+            SchemaChange::RenameColumn(name, new_name) => {
+                if let Some(column) = table.columns.iter_mut().find(|c| &c.name == name) {
+                    column.provider = column.provider.or_else(|| Provider::infer(&column.name));
+                    column.name = new_name.clone();
+                }
+            }
+            SchemaChange::RenameTable(new_name) => table.name = new_name.clone(),
+            SchemaChange::CreateIndex(..) => {}
+        }
+    }
+}
+
+/// Returns a new column for `table`, named after one of [`NEW_COLUMNS`] and suffixed
+/// if the table already has a column of that name.
+fn new_column<R: Rng + ?Sized>(table: &Table, rng: &mut R) -> Column {
+    let &(name, column_type, length, decimal_places) = NEW_COLUMNS.choose(rng).unwrap();
+    let name = unused_name(name, |name| table.column(name).is_some());
+    Column::new(&name, column_type, length, decimal_places)
+}
+
+/// Returns whether a column may be dropped, changed or renamed: it is not part of a key,
+/// not unique, not filled by the database and has no DEFAULT or CHECK, which SQL Server
+/// refuses to drop along with the column. Enums are checked in dialects without enum types.
+fn is_free(column: &Column) -> bool {
+    !column.is_pkey
+        && !column.is_unique
+        && !column.auto_increment
+        && column.ref_table.is_none()
+        && column.default_value.is_none()
+        && column.check_expr.is_none()
+        && column.labels.is_empty()
+}
+
+/// Returns the length or precision `column` is widened to, or `None` if it has no
+/// length or is as wide as allowed already.
+fn widened_length(column: &Column) -> Option<i32> {
+    let length = column.length?;
+    let max = if column.is_numeric() { MAX_PRECISION } else { MAX_TEXT_LENGTH };
+    let widened = if column.is_numeric() { length + 2 } else { length * 2 };
+    Some(widened.min(max)).filter(|&widened| widened > length)
+}
+
+/// Returns `name` if it is not `taken`, or else the first of `name_v2`, `name_v3`, ...
+/// that is not. A `_vN` suffix of `name` is replaced, so repeated renames count up.
+fn unused_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.to_string();
+    }
+    let base = match name.rsplit_once("_v") {
+        Some((base, n)) if !base.is_empty() && !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => base,
+        _ => name,
+    };
+    (2..).map(|n| format!("{}_v{}", base, n)).find(|candidate| !taken(candidate)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_unused_name() {
+        assert_eq!(unused_name("note", |_| false), "note");
+        assert_eq!(unused_name("note", |name| name == "note"), "note_v2");
+        assert_eq!(unused_name("orders_v2", |name| name == "orders_v2"), "orders_v3");
+        assert_eq!(unused_name("orders", |name| name == "orders" || name == "orders_v2"), "orders_v3");
+    }
+
+    #[test]
+    fn test_statement_and_apply() {
+        let mut table = Table::init_via_sql("create table t (id int primary key, name varchar(20), price number(10,2))");
+        let change = SchemaChange::ModifyColumn("name".to_string(), 40);
+        assert_eq!(change.statement(&table, Dialect::Postgres), "ALTER TABLE t ALTER COLUMN name TYPE varchar(40);");
+        change.apply(&mut table);
+        assert_eq!(table.column("name").unwrap().length, Some(40));
+
+        let change = SchemaChange::RenameColumn("name".to_string(), "name_v2".to_string());
+        assert_eq!(change.statement(&table, Dialect::MySql), "ALTER TABLE t RENAME COLUMN name TO name_v2;");
+        change.apply(&mut table);
+        assert!(table.column("name").is_none() && table.column("name_v2").is_some());
+
+        let change = SchemaChange::AddColumn(Box::new(Column::new("note", "varchar", Some(100), None)));
+        assert_eq!(change.statement(&table, Dialect::Oracle), "ALTER TABLE t ADD note varchar(100);");
+        change.apply(&mut table);
+        assert_eq!(table.columns.len(), 4);
+
+        let change = SchemaChange::DropColumn("price".to_string());
+        assert_eq!(change.statement(&table, Dialect::Sqlite), "ALTER TABLE t DROP COLUMN price;");
+        change.apply(&mut table);
+        assert_eq!(table.columns.len(), 3);
+    }
+
+    #[test]
+    fn test_random_alter() {
+        let table = Table::init_via_sql("create table t (id int primary key, c_id int references c (id), name varchar(20))");
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut kinds = Vec::new();
+        for _ in 0..100 {
+            let change = SchemaChange::random_alter(&table, Dialect::Sqlite, &mut rng);
+            match &change {
+                SchemaChange::AddColumn(column) => assert!(column.is_nullable && table.column(&column.name).is_none()),
+                SchemaChange::DropColumn(name) => assert_eq!(name, "name"),
+                other => panic!("unexpected change {:?}", other),
+            }
+            kinds.push(change.statement(&table, Dialect::Sqlite).split_whitespace().nth(3).unwrap().to_string());
+        }
+        assert!(kinds.contains(&"ADD".to_string()) && kinds.contains(&"DROP".to_string()));
+        assert!((0..100).any(|_| matches!(SchemaChange::random_alter(&table, Dialect::MySql, &mut rng), SchemaChange::ModifyColumn(..))));
+    }
+
+    #[test]
+    fn test_random_keeps_keys() {
+        let tables = crate::schema::parse_schema(
+            "create table c (id int primary key, name varchar(20));
+             create table o (id int primary key, c_id int references c (id), total number(10,2));",
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            match SchemaChange::random(&tables[0], &tables, &[], &[], Dialect::Sqlite, &mut rng) {
+                SchemaChange::DropColumn(name) | SchemaChange::RenameColumn(name, _) => assert_eq!(name, "name"),
+                SchemaChange::ModifyColumn(..) | SchemaChange::RenameTable(_) => panic!("unexpected change"),
+                _ => {}
+            }
+            if let SchemaChange::DropColumn(name) | SchemaChange::RenameColumn(name, _) | SchemaChange::ModifyColumn(name, _) =
+                SchemaChange::random(&tables[1], &tables, &["total".to_string()], &[], Dialect::Postgres, &mut rng)
+            {
+                panic!("changed fixed column {}", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! A C API of the generator, so database proxies and C/C++ test harnesses can embed it.
+//!
+//! The functions are declared in `include/fake_sql.h` and exported by the `cdylib` and
+//! `staticlib` builds of the crate (`libfake_sql.so`/`.dylib`/`.dll` and `libfake_sql.a`):
+//!
+//! ```c
+//! FakeSqlGenerator *generator = fake_sql_generator_new("create table t (id int primary key)", "postgres");
+//! if (generator == NULL) {
+//!     fprintf(stderr, "%s\n", fake_sql_last_error());
+//!     return 1;
+//! }
+//! char *sql = fake_sql_generator_next(generator);
+//! puts(sql);
+//! fake_sql_string_free(sql);
+//! fake_sql_generator_free(generator);
+//! ```
+//!
+//! Strings are UTF-8 and NUL-terminated. A function that fails returns `NULL` and leaves
+//! its error for [`fake_sql_last_error`] on the calling thread; so does a function that
+//! panics, as panics never unwind into C.
+
+use crate::dialect::Dialect;
+use crate::generator::Generator;
+use crate::schema::parse_schema;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// A generator created by `fake_sql_generator_new`, opaque to C.
+pub struct FakeSqlGenerator {
+    generator: Generator,
+}
+
+thread_local! {
+    /// The error of the last call on this thread that failed.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Records `error` as the last error of the thread.
+fn set_last_error(error: String) {
+    let error = CString::new(error.replace('\0', " ")).unwrap();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+}
+
+/// Runs `body`, the body of an exported function, without letting a panic unwind into C:
+/// the panic message is recorded as the last error and `failed` is returned instead.
+fn catch<T>(failed: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "the generator panicked".to_string());
+        set_last_error(message);
+        failed
+    })
+}
+
+/// Reads a NUL-terminated UTF-8 string argument called `name`.
+///
+/// # Safety
+///
+/// `text` must be `NULL` or point to a NUL-terminated string.
+unsafe fn read_str<'a>(text: *const c_char, name: &str) -> Result<Option<&'a str>, String> {
+    if text.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(text).to_str().map(Some).map_err(|_| format!("the {} is not valid UTF-8", name))
+}
+
+/// Creates the generator of [`fake_sql_generator_new`] and [`fake_sql_generator_new_seeded`].
+///
+/// # Safety
+///
+/// `ddl` and `dialect` must be `NULL` or point to NUL-terminated strings.
+unsafe fn new_generator(ddl: *const c_char, dialect: *const c_char, seed: Option<u64>) -> Result<Generator, String> {
+    let tables = parse_schema(read_str(ddl, "schema")?.ok_or("the schema is NULL")?)?;
+    if tables.is_empty() {
+        return Err("the schema holds no CREATE TABLE statements".to_string());
+    }
+    let mut generator = match seed {
+        Some(seed) => Generator::with_seed(tables, seed),
+        None => Generator::new(tables),
+    };
+    if let Some(dialect) = read_str(dialect, "dialect")? {
+        generator.set_dialect(dialect.parse::<Dialect>()?);
+    }
+    Ok(generator)
+}
+
+/// Hands `generator` over to C, or records its error and returns `NULL`.
+fn into_raw(generator: Result<Generator, String>) -> *mut FakeSqlGenerator {
+    match generator {
+        Ok(generator) => Box::into_raw(Box::new(FakeSqlGenerator { generator })),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a generator of random statements for the tables of the CREATE TABLE statements
+/// in `ddl`, rendered for `dialect` (`NULL` for Oracle). Returns `NULL` if the schema or
+/// the dialect is invalid.
+///
+/// # Safety
+///
+/// `ddl` and `dialect` must be `NULL` or point to NUL-terminated strings. The generator
+/// must be released with [`fake_sql_generator_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fake_sql_generator_new(ddl: *const c_char, dialect: *const c_char) -> *mut FakeSqlGenerator {
+    catch(ptr::null_mut(), || into_raw(new_generator(ddl, dialect, None)))
+}
+
+/// Creates a generator like [`fake_sql_generator_new`] whose statements are the same for
+/// the same `seed`.
+///
+/// # Safety
+///
+/// The same as for [`fake_sql_generator_new`].
+#[no_mangle]
+pub unsafe extern "C" fn fake_sql_generator_new_seeded(ddl: *const c_char, dialect: *const c_char, seed: u64) -> *mut FakeSqlGenerator {
+    catch(ptr::null_mut(), || into_raw(new_generator(ddl, dialect, Some(seed))))
+}
+
+/// Returns the next random statement of `generator`, which the caller releases with
+/// [`fake_sql_string_free`]. Returns `NULL` if `generator` is `NULL`.
+///
+/// # Safety
+///
+/// `generator` must be `NULL` or a generator that was not freed, used by one thread at a time.
+#[no_mangle]
+pub unsafe extern "C" fn fake_sql_generator_next(generator: *mut FakeSqlGenerator) -> *mut c_char {
+    catch(ptr::null_mut(), || {
+        let Some(generator) = generator.as_mut() else {
+            set_last_error("the generator is NULL".to_string());
+            return ptr::null_mut();
+        };
+        // Generated statements hold no NUL characters; they are replaced like in errors if one does
+        let sql = generator.generator.next_statement().replace('\0', " ");
+        CString::new(sql).unwrap().into_raw()
+    })
+}
+
+/// Releases a generator; does nothing if `generator` is `NULL`.
+///
+/// # Safety
+///
+/// `generator` must be `NULL` or a generator that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fake_sql_generator_free(generator: *mut FakeSqlGenerator) {
+    catch((), || {
+        if !generator.is_null() {
+            drop(Box::from_raw(generator));
+        }
+    })
+}
+
+/// Releases a statement returned by [`fake_sql_generator_next`]; does nothing if `sql` is
+/// `NULL`.
+///
+/// # Safety
+///
+/// `sql` must be `NULL` or a string returned by `fake_sql_generator_next` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fake_sql_string_free(sql: *mut c_char) {
+    catch((), || {
+        if !sql.is_null() {
+            drop(CString::from_raw(sql));
+        }
+    })
+}
+
+/// Returns the error of the last call on this thread that returned `NULL`, or `NULL` if
+/// there was none. The string stays valid until the next failing call on the thread.
+#[no_mangle]
+pub extern "C" fn fake_sql_last_error() -> *const c_char {
+    catch(ptr::null(), || LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |error| error.as_ptr())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi() {
+        let ddl = CString::new("create table t (id int primary key, name varchar(10))").unwrap();
+        let dialect = CString::new("mysql").unwrap();
+        unsafe {
+            let generator = fake_sql_generator_new_seeded(ddl.as_ptr(), dialect.as_ptr(), 3);
+            assert!(!generator.is_null());
+            let sql = fake_sql_generator_next(generator);
+            assert!(CStr::from_ptr(sql).to_str().unwrap().ends_with(';'));
+            fake_sql_string_free(sql);
+            fake_sql_generator_free(generator);
+
+            let unknown = CString::new("db2").unwrap();
+            assert!(fake_sql_generator_new(ddl.as_ptr(), unknown.as_ptr()).is_null());
+            assert_eq!(CStr::from_ptr(fake_sql_last_error()).to_str(), Ok("unknown dialect `db2`"));
+            assert!(fake_sql_generator_new(ptr::null(), ptr::null()).is_null());
+            assert_eq!(CStr::from_ptr(fake_sql_last_error()).to_str(), Ok("the schema is NULL"));
+            assert!(fake_sql_generator_next(ptr::null_mut()).is_null());
+        }
+
+        assert!(catch(ptr::null_mut::<c_char>(), || panic!("out of {}", "keys")).is_null());
+        assert_eq!(unsafe { CStr::from_ptr(fake_sql_last_error()) }.to_str(), Ok("out of keys"));
+    }
+}
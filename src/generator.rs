@@ -0,0 +1,2338 @@
+//! Random statement generation over a set of tables.
+
+use crate::blob::{Blob, BlobEncoding, BlobFiles, BlobSize};
+use crate::cardinality::{Cardinality, FanOut};
+use crate::cdc::{Change, Operation};
+use crate::data_pool::DataPool;
+use crate::dialect::Dialect;
+use crate::dirty::{self, Dirt};
+use crate::duplicates::Duplicates;
+use crate::evolve::SchemaChange;
+use crate::geo::BoundingBox;
+use crate::locale::Locale;
+use crate::mix::Mix;
+use crate::models::{Column, DateRange, SqlType, Table};
+use crate::params::ParamStyle;
+use crate::plugin::{GeneratorRegistry, RowContext, ValueGenerator};
+use crate::query::{self, Complexity, Predicates};
+use crate::scd::{History, Scd2};
+use crate::schema;
+use crate::statement::Statement;
+use crate::timeseries::{Series, TimeSeries};
+use crate::timezone::TimeZones;
+use crate::unique::{rename_table_keys, KeyStrategy, UniqueValues};
+use crate::value::SqlValue;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem;
+
+/// Number of rows drawn before giving up on finding an unused composite key.
+const MAX_KEY_ATTEMPTS: usize = 100;
+
+/// Generates random SQL statements for a set of tables.
+///
+/// Each call to [`Generator::next_statement`] picks a random table and a SQL type
+/// drawn from the generator's [`Mix`].
+/// The generator is also an endless [`Iterator`] of [`Statement`] values, and with the
+/// `stream` feature a `futures_core::Stream` of them, so consumers can pull statements
+/// lazily and filter them with the usual adapters.
+/// A generator created with [`Generator::with_seed`] produces the same sequence of
+/// statements for the same seed and tables, which makes failures that depend on the
+/// generated SQL reproducible.
+///
+/// The generator remembers a sample of the rows it inserted in a [`DataPool`], and SELECT,
+/// UPDATE and DELETE statements filter on their values, so they hit existing rows when
+/// the output is replayed against a database. UPDATEs target the remembered rows, and
+/// DELETEs forget the rows they target; values changed by UPDATEs are not tracked.
+///
+/// The generator remembers the primary key values it inserted. INSERTs into a
+/// table whose columns reference another table (`references parent (id)`) pick
+/// their foreign key values from the keys already inserted into the parent, so
+/// the output loads into a database with foreign key constraints enabled.
+///
+/// Primary key values are never repeated: they are handed out sequentially or as
+/// unique random values depending on the [`KeyStrategy`]. Rows of tables with a
+/// composite primary key are redrawn until their combination of key values is new.
+/// Values of `UNIQUE` columns and combinations of `UNIQUE (a, b)` keys are not repeated
+/// either.
+///
+/// [`Generator::split`] turns a generator into independent shards that can run on
+/// separate threads.
+#[derive(Clone)]
+pub struct Generator {
+    tables: Vec<Table>,
+    mix: Mix,
+    dialect: Dialect,
+    rng: StdRng,
+    /// Columns whose inserted values are remembered: primary keys and referenced columns.
+    key_columns: HashSet<(String, String)>,
+    /// Values inserted so far, per `(table, column)` key column.
+    keys: HashMap<(String, String), Vec<SqlValue>>,
+    unique: UniqueValues,
+    /// Composite primary and unique key values inserted so far, per table and key, the
+    /// comma separated key columns.
+    composite_keys: HashMap<(String, String), HashSet<Vec<SqlValue>>>,
+    /// Number of rows per INSERT statement.
+    batch_size: usize,
+    complexity: Complexity,
+    /// Probability that a SELECT is filtered with a subquery.
+    subquery_rate: f64,
+    predicates: Predicates,
+    /// Whether DELETE statements remove single rows inserted before, by primary key.
+    delete_by_key: bool,
+    /// Whether statements never drop tables, indexes or columns, empty tables or delete
+    /// rows other than single ones by primary key.
+    safe: bool,
+    /// Rows inserted so far, which SELECT, UPDATE and DELETE statements filter on.
+    data: DataPool,
+    /// How INSERT statements bind their values, or `None` to render literals.
+    param_style: Option<ParamStyle>,
+    /// Parameters of the last generated statement, if it has placeholders.
+    params: Option<serde_json::Value>,
+    /// Number of statements between schema changes, or `None` to keep the schema.
+    evolve_every: Option<u64>,
+    /// SQL types that schema changes must not have, see [`Generator::set_excluded_types`].
+    excluded: Vec<SqlType>,
+    /// Number of statements generated so far.
+    generated: u64,
+    /// Indexes created by schema changes: table, index name and columns.
+    indexes: Vec<(String, String, Vec<String>)>,
+    /// Time series per table name, see [`Generator::set_time_series`].
+    series: HashMap<String, Series>,
+    /// Slowly changing dimensions per table name, see [`Generator::set_scd2`].
+    scd: HashMap<String, History>,
+    /// Statements generated ahead, returned by the next calls to [`Generator::generate`].
+    pending: VecDeque<Statement>,
+    /// Fan-out of foreign keys per table and column, see [`Generator::set_cardinality`].
+    fan_outs: HashMap<(String, String), FanOut>,
+    /// Shares of duplicate and damaged rows, see [`Generator::set_duplicates`].
+    duplicates: Duplicates,
+    /// Probability that an INSERT carries an invalid value, see [`Generator::set_dirty_rate`].
+    dirty_rate: f64,
+    /// The invalid value of the last generated statement, if it has one.
+    dirt: Option<Dirt>,
+    /// Whether personal-looking text is PII-free, see [`Generator::set_pii_free`].
+    pii_free: bool,
+    /// Where large binary values are spilled to, see [`Generator::set_blob_files`].
+    blob_files: Option<BlobFiles>,
+    /// The binary values spilled by the last generated statement.
+    blobs: Vec<Blob>,
+    /// Custom generators per table and column, see [`Generator::set_registry`].
+    custom: HashMap<(String, String), Box<dyn ValueGenerator>>,
+}
+
+impl Generator {
+    /// Initializes a new `Generator` seeded from operating system entropy.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - The tables to generate statements for.
+    ///
+    /// # Returns
+    ///
+    /// A `Generator` producing every SQL type.
+    pub fn new(tables: Vec<Table>) -> Generator {
+        Generator::with_rng(tables, StdRng::from_entropy())
+    }
+
+    /// Initializes a new `Generator` whose output is fully determined by `seed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - The tables to generate statements for.
+    /// * `seed` - The seed of the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// A `Generator` producing every SQL type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, Table};
+    /// let table = Table::init_via_sql("create table t (id number(10) primary key, name varchar(20))");
+    /// let mut first = Generator::with_seed(vec![table.clone()], 7);
+    /// let mut second = Generator::with_seed(vec![table], 7);
+    /// assert_eq!(first.next_statement(), second.next_statement());
+    /// ```
+    pub fn with_seed(tables: Vec<Table>, seed: u64) -> Generator {
+        Generator::with_rng(tables, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(tables: Vec<Table>, rng: StdRng) -> Generator {
+        let mut key_columns = HashSet::new();
+        for table in &tables {
+            for column in &table.columns {
+                if column.is_pkey {
+                    key_columns.insert((table.name.clone(), column.name.clone()));
+                }
+                if let Some(parent) = referenced_column(&tables, column) {
+                    key_columns.insert(parent);
+                }
+            }
+        }
+        Generator {
+            tables,
+            mix: Mix::default(),
+            dialect: Dialect::default(),
+            rng,
+            key_columns,
+            keys: HashMap::new(),
+            unique: UniqueValues::default(),
+            composite_keys: HashMap::new(),
+            batch_size: 1,
+            complexity: Complexity::default(),
+            subquery_rate: 0.0,
+            predicates: Predicates::default(),
+            delete_by_key: false,
+            safe: false,
+            data: DataPool::default(),
+            param_style: None,
+            params: None,
+            evolve_every: None,
+            excluded: Vec::new(),
+            generated: 0,
+            indexes: Vec::new(),
+            series: HashMap::new(),
+            scd: HashMap::new(),
+            pending: VecDeque::new(),
+            fan_outs: HashMap::new(),
+            duplicates: Duplicates::default(),
+            dirty_rate: 0.0,
+            dirt: None,
+            pii_free: false,
+            blob_files: None,
+            blobs: Vec::new(),
+            custom: HashMap::new(),
+        }
+    }
+
+    /// Restricts the SQL types the generator picks from.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql_types` - The SQL types to generate. An empty vector selects every type.
+    pub fn set_sql_types(&mut self, sql_types: Vec<SqlType>) {
+        self.mix = Mix::uniform(&sql_types);
+    }
+
+    /// Sets the weighted mix of SQL types the generator picks from.
+    ///
+    /// # Arguments
+    ///
+    /// * `mix` - The workload profile, e.g. `insert=70,select=20,update=8,delete=2`.
+    pub fn set_mix(&mut self, mix: Mix) {
+        self.mix = mix;
+    }
+
+    /// Sets how primary key values are generated.
+    ///
+    /// This resets the values handed out so far, so it should be called before
+    /// generating statements.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - `KeyStrategy::Sequential` (the default) or `KeyStrategy::Random`.
+    pub fn set_key_strategy(&mut self, strategy: KeyStrategy) {
+        self.unique = UniqueValues::new(strategy);
+    }
+
+    /// Sets the probability that a generated value of a nullable column is NULL.
+    ///
+    /// The rate applies to INSERT and UPDATE values of every nullable column that
+    /// has no `null_rate` of its own. NOT NULL and primary key columns never get NULL.
+    ///
+    /// # Arguments
+    ///
+    /// * `null_rate` - A probability between 0.0 and 1.0.
+    pub fn set_null_rate(&mut self, null_rate: f64) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.null_rate.get_or_insert(null_rate);
+        }
+    }
+
+    /// Sets the probability that a text value fills its column's declared length exactly.
+    ///
+    /// The rate applies to every text column that has no `max_length_rate` of its own, and
+    /// helps testing how the database handles values at the boundary.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_length_rate` - A probability between 0.0 and 1.0.
+    pub fn set_max_length_rate(&mut self, max_length_rate: f64) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.max_length_rate.get_or_insert(max_length_rate);
+        }
+    }
+
+    /// Sets the range dates and timestamps are drawn from.
+    ///
+    /// The range applies to every column that has no `date_range` of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `date_range` - The dates to draw from, e.g. `2020-01-01..2024-12-31`.
+    pub fn set_date_range(&mut self, date_range: DateRange) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.date_range.get_or_insert(date_range);
+        }
+    }
+
+    /// Sets the locale of names, addresses, phone numbers and other provider text.
+    ///
+    /// The locale applies to every column that has no `locale` of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale, e.g. `Locale::ZhTw` for Traditional Chinese text.
+    pub fn set_locale(&mut self, locale: Locale) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.locale.get_or_insert(locale);
+        }
+    }
+
+    /// Sets the UTC offsets `timestamptz` values are drawn with.
+    ///
+    /// The offsets apply to every column that has no `timezones` of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `timezones` - The offsets, e.g. `UTC,+05:30,-08:00`.
+    pub fn set_timezones(&mut self, timezones: TimeZones) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.timezones.get_or_insert_with(|| timezones.clone());
+        }
+    }
+
+    /// Sets the area `geometry` and `geography` values are drawn within.
+    ///
+    /// The box applies to every column that has no `bounding_box` of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounding_box` - The box, e.g. `13.0,52.3,13.8,52.7`.
+    pub fn set_bounding_box(&mut self, bounding_box: BoundingBox) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.bounding_box.get_or_insert(bounding_box);
+        }
+    }
+
+    /// Sets the range of sizes binary values are drawn from.
+    ///
+    /// The range applies to every column that has no `blob_size` of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `blob_size` - The range, e.g. `1K..64K`.
+    pub fn set_blob_size(&mut self, blob_size: BlobSize) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.blob_size.get_or_insert(blob_size);
+        }
+    }
+
+    /// Sets how binary values are written into statements, as hex or base64 literals.
+    ///
+    /// The encoding applies to every column that has no `blob_encoding` of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `blob_encoding` - The encoding.
+    pub fn set_blob_encoding(&mut self, blob_encoding: BlobEncoding) {
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.blob_encoding.get_or_insert(blob_encoding);
+        }
+    }
+
+    /// Spills binary values of at least `blob_files.min_size` bytes out of INSERT
+    /// statements into files, which the statements read with the dialect's file function,
+    /// e.g. `pg_read_binary_file('/data/out.sql.blobs/5f0c....bin')`.
+    ///
+    /// The generator does not write the files itself: the spilled values of each statement
+    /// are returned by [`Generator::take_blobs`]. Statements with placeholders keep their
+    /// values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::blob::BlobFiles;
+    /// # use fake_sql::{Dialect, Generator, Table};
+    /// let table = Table::init_via_sql("create table t (id int primary key, data blob(64))");
+    /// let mut generator = Generator::with_seed(vec![table], 7);
+    /// generator.set_dialect(Dialect::MySql);
+    /// generator.set_blob_files(BlobFiles { min_size: 1, dir: "/tmp/blobs".into() });
+    /// let sql = generator.insert_into("t").unwrap();
+    /// let blob = generator.take_blobs().pop().unwrap();
+    /// assert!(sql.contains(&format!("LOAD_FILE('{}')", blob.path.display())));
+    /// ```
+    pub fn set_blob_files(&mut self, blob_files: BlobFiles) {
+        self.blob_files = Some(blob_files);
+    }
+
+    /// Makes names, email addresses and phone numbers PII-free, so the generated data is
+    /// safe to share: placeholder family names such as `Doe`, email addresses at
+    /// `example.com`, `example.net` and `example.org`, and fictional `555-01xx` phone numbers.
+    ///
+    /// The setting applies to every column, including the columns added by schema changes.
+    /// See [`Provider::generate_pii_free`](crate::providers::Provider::generate_pii_free).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, Table};
+    /// let table = Table::init_via_sql("create table t (id int primary key, email varchar(100))");
+    /// let mut generator = Generator::with_seed(vec![table], 7);
+    /// generator.set_pii_free(true);
+    /// assert!(generator.insert_into("t").unwrap().contains("@example."));
+    /// ```
+    pub fn set_pii_free(&mut self, pii_free: bool) {
+        self.pii_free = pii_free;
+        for column in self.tables.iter_mut().flat_map(|t| t.columns.iter_mut()) {
+            column.pii_free = pii_free;
+        }
+    }
+
+    /// Sets the number of rows each random INSERT statement carries.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size` - Rows per INSERT; values below 1 are treated as 1.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1);
+    }
+
+    /// Sets the dialect statements are rendered for.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The SQL dialect, `Dialect::Oracle` by default.
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    /// Sets how elaborate generated SELECT queries are.
+    ///
+    /// # Arguments
+    ///
+    /// * `complexity` - How often queries are sorted and paginated, `Complexity::Moderate` by default.
+    pub fn set_complexity(&mut self, complexity: Complexity) {
+        self.complexity = complexity;
+    }
+
+    /// Sets the probability that a single-table SELECT gets a subquery predicate
+    /// (`IN (SELECT ...)` or `EXISTS (...)`) following one of the table's foreign keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `subquery_rate` - A probability between 0.0 and 1.0, 0.0 by default.
+    pub fn set_subquery_rate(&mut self, subquery_rate: f64) {
+        self.subquery_rate = subquery_rate.clamp(0.0, 1.0);
+    }
+
+    /// Sets the maximum number of predicates in the WHERE clause of SELECT, UPDATE and
+    /// DELETE statements; every clause filters on one to `max` random columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of predicates, 3 by default; values below 1 are treated as 1.
+    pub fn set_max_predicates(&mut self, max: usize) {
+        self.predicates.max = max.max(1);
+    }
+
+    /// Sets the probability that a WHERE predicate is joined to the previous one with `OR`
+    /// instead of `AND`.
+    ///
+    /// # Arguments
+    ///
+    /// * `or_rate` - A probability between 0.0 and 1.0, 0.2 by default.
+    pub fn set_or_rate(&mut self, or_rate: f64) {
+        self.predicates.or_rate = or_rate.clamp(0.0, 1.0);
+    }
+
+    /// Sets whether DELETE statements only remove single rows inserted before, e.g.
+    /// `DELETE FROM orders WHERE order_id = 42;`, and never filter on random predicates.
+    ///
+    /// Only rows of tables no foreign key references are deleted, so the statements replay
+    /// against a database with foreign key constraints and never delete a row twice. While
+    /// no such row exists, a row is inserted instead rather than a DELETE on random values.
+    ///
+    /// # Arguments
+    ///
+    /// * `delete_by_key` - Whether to delete by primary key, `false` by default.
+    pub fn set_delete_by_key(&mut self, delete_by_key: bool) {
+        self.delete_by_key = delete_by_key;
+    }
+
+    /// Sets whether statements are safe to replay against a shared database: DROP TABLE,
+    /// DROP INDEX and TRUNCATE statements the mix picks are generated as SELECTs instead,
+    /// ALTER TABLE statements and schema changes never drop a column, and DELETE statements
+    /// remove single rows by primary key as with [`Generator::set_delete_by_key`].
+    ///
+    /// Leave the destructive types out of the mix to keep its proportions.
+    ///
+    /// # Arguments
+    ///
+    /// * `safe` - Whether to leave data alone, `false` by default.
+    pub fn set_safe(&mut self, safe: bool) {
+        self.safe = safe;
+    }
+
+    /// Sets whether INSERT statements bind their values with placeholders, e.g.
+    /// `INSERT INTO t (a, b) VALUES ($1, $2);`, instead of rendering them as literals.
+    ///
+    /// The values of the last generated statement are returned by [`Generator::take_params`].
+    /// Other statements keep their literals.
+    ///
+    /// # Arguments
+    ///
+    /// * `param_style` - The style of the placeholders, or `None` for literals (the default).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::params::ParamStyle;
+    /// # use fake_sql::{Dialect, Generator, Table};
+    /// let table = Table::init_via_sql("create table t (id number(10) primary key)");
+    /// let mut generator = Generator::with_seed(vec![table], 7);
+    /// generator.set_dialect(Dialect::Postgres);
+    /// generator.set_param_style(Some(ParamStyle::Positional));
+    /// assert_eq!(generator.insert_into("t").unwrap(), "INSERT INTO t (id) VALUES ($1);");
+    /// assert_eq!(generator.take_params(), Some(serde_json::json!([1])));
+    /// ```
+    pub fn set_param_style(&mut self, param_style: Option<ParamStyle>) {
+        self.param_style = param_style;
+    }
+
+    /// Sets how often the schema changes while statements are generated: every `N`th
+    /// statement adds, drops, widens or renames a column, renames a table or creates an
+    /// index, and later statements use the changed tables.
+    ///
+    /// The changes keep the schema consistent: added columns are nullable, and key columns,
+    /// indexed columns and tables referenced by foreign keys are never dropped, changed or
+    /// renamed. Rows remembered for later statements follow the changes. Only
+    /// [`Generator::next_statement`] and [`Generator::generate`] change the schema;
+    /// [`Generator::insert_into`] no longer finds a renamed table under its old name.
+    ///
+    /// # Arguments
+    ///
+    /// * `every` - The number of statements per schema change, or `None` to keep the schema
+    ///   (the default); values below 1 are treated as 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, SqlType, Table};
+    /// let table = Table::init_via_sql("create table t (id int primary key, name varchar(20))");
+    /// let mut generator = Generator::with_seed(vec![table], 7);
+    /// generator.set_evolve_every(Some(2));
+    /// let sql_types: Vec<SqlType> = generator.by_ref().take(4).map(|s| s.sql_type).skip(1).step_by(2).collect();
+    /// assert!(sql_types.iter().all(|t| matches!(t, SqlType::AlterTable | SqlType::CreateIndex)));
+    /// ```
+    pub fn set_evolve_every(&mut self, every: Option<u64>) {
+        self.evolve_every = every.map(|every| every.max(1));
+    }
+
+    /// Leaves the SQL types out of the schema changes of [`Generator::set_evolve_every`],
+    /// as `--only` and `--exclude` do; the mix is restricted with [`Mix::retain`].
+    ///
+    /// Excluding `AlterTable` leaves only new indexes, and excluding `CreateIndex` as well
+    /// keeps the schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql_types` - The SQL types schema changes must not have.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, SqlType, Table};
+    /// let table = Table::init_via_sql("create table t (id int primary key, name varchar(20))");
+    /// let mut generator = Generator::with_seed(vec![table], 7);
+    /// generator.set_evolve_every(Some(1));
+    /// generator.set_excluded_types(vec![SqlType::AlterTable]);
+    /// assert!(generator.by_ref().take(4).all(|s| s.sql_type == SqlType::CreateIndex));
+    /// ```
+    pub fn set_excluded_types(&mut self, sql_types: Vec<SqlType>) {
+        self.excluded = sql_types;
+    }
+
+    /// Makes the table called `table_name` a time series: its rows get increasing
+    /// timestamps, cycle through a pool of entities and carry metrics that follow a random
+    /// walk per entity, as the readings of sensors would. See [`TimeSeries`].
+    ///
+    /// The columns of the time series are left alone by schema changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    /// * `series` - The columns and pace of the time series.
+    ///
+    /// # Returns
+    ///
+    /// An error if there is no such table or the time series does not fit it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::timeseries::TimeSeries;
+    /// # use fake_sql::{Dialect, Generator, Table};
+    /// let table = Table::init_via_sql("create table readings (device_id int, taken_at timestamp)");
+    /// let mut generator = Generator::with_seed(vec![table.clone()], 7);
+    /// generator.set_dialect(Dialect::Postgres);
+    /// generator.set_time_series("readings", TimeSeries::infer(&table).unwrap()).unwrap();
+    /// generator.insert_into("readings");
+    /// assert_eq!(
+    ///     generator.insert_into("readings").unwrap(),
+    ///     "INSERT INTO readings (device_id, taken_at) VALUES (2, TIMESTAMP '2021-01-01 00:00:01');"
+    /// );
+    /// ```
+    pub fn set_time_series(&mut self, table_name: &str, series: TimeSeries) -> Result<(), String> {
+        let table = self
+            .table_index(table_name)
+            .map(|index| &self.tables[index])
+            .ok_or_else(|| format!("table `{}` is not defined in the schema", table_name))?;
+        series.check(table)?;
+        self.series.insert(table_name.to_string(), Series::new(series));
+        Ok(())
+    }
+
+    /// Makes a share of the generated rows duplicates of earlier rows, near-duplicates with
+    /// a typo or case change, or rows with whitespace anomalies, to test deduplication and
+    /// data-quality checks. See [`Duplicates`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, Table};
+    /// let table = Table::init_via_sql("create table t (id int primary key, name varchar(20))");
+    /// let mut generator = Generator::with_seed(vec![table], 7);
+    /// generator.set_duplicates("exact=1.0".parse().unwrap());
+    /// let first = generator.insert_into("t").unwrap();
+    /// let second = generator.insert_into("t").unwrap();
+    /// assert_eq!(first.rsplit_once(", ").unwrap().1, second.rsplit_once(", ").unwrap().1);
+    /// ```
+    pub fn set_duplicates(&mut self, duplicates: Duplicates) {
+        self.duplicates = duplicates;
+    }
+
+    /// Sets the probability that an INSERT statement carries an invalid value, to test how
+    /// ingestion tools handle errors: a number out of range, a malformed date, text longer
+    /// than its column or a string with the wrong quotes, see [`DirtyKind`](crate::dirty::DirtyKind).
+    ///
+    /// One value of one row of the statement is replaced, in a column that is no key. The
+    /// rows remembered for later statements keep their valid values. What was injected is
+    /// returned by [`Generator::take_dirt`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dirty_rate` - A probability between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, Table};
+    /// let table = Table::init_via_sql("create table t (id int primary key, name varchar(5))");
+    /// let mut generator = Generator::with_seed(vec![table], 7);
+    /// generator.set_dirty_rate(1.0);
+    /// let sql = generator.insert_into("t").unwrap();
+    /// let dirt = generator.take_dirt().unwrap();
+    /// assert_eq!(dirt.column, "name");
+    /// assert!(sql.contains(&dirt.value));
+    /// ```
+    pub fn set_dirty_rate(&mut self, dirty_rate: f64) {
+        self.dirty_rate = dirty_rate;
+    }
+
+    /// Sets how many rows of the table called `table_name` reference each parent row through
+    /// the foreign key `column_name`, e.g. 1 to 20 orders per customer, instead of picking a
+    /// random parent per row. See [`Cardinality`].
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the child table.
+    /// * `column_name` - The name of the foreign key column.
+    /// * `cardinality` - The range of the number of children per parent.
+    ///
+    /// # Returns
+    ///
+    /// An error if there is no such table or column, or the column is not a foreign key to
+    /// several rows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Dialect, Generator, Table};
+    /// let customers = Table::init_via_sql("create table customers (id int primary key)");
+    /// let orders = Table::init_via_sql("create table orders (id int primary key, customer_id int references customers(id))");
+    /// let mut generator = Generator::with_seed(vec![customers, orders], 7);
+    /// generator.set_cardinality("orders", "customer_id", "2".parse().unwrap()).unwrap();
+    /// for _ in 0..2 {
+    ///     generator.insert_into("customers");
+    /// }
+    /// let orders: Vec<String> = (0..4).map(|_| generator.insert_into("orders").unwrap()).collect();
+    /// assert!(orders[1].ends_with(", 1);") && orders[3].ends_with(", 2);"));
+    /// ```
+    pub fn set_cardinality(&mut self, table_name: &str, column_name: &str, cardinality: Cardinality) -> Result<(), String> {
+        let table = self
+            .table_index(table_name)
+            .map(|index| &self.tables[index])
+            .ok_or_else(|| format!("table `{}` is not defined in the schema", table_name))?;
+        let column = table
+            .column(column_name)
+            .ok_or_else(|| format!("column `{}.{}` is not defined in the schema", table_name, column_name))?;
+        if referenced_column(&self.tables, column).is_none() || column.is_unique {
+            return Err(format!("column `{}.{}` is not a foreign key that parents share between children", table_name, column_name));
+        }
+        self.fan_outs.insert((table_name.to_string(), column_name.to_string()), FanOut::new(cardinality));
+        Ok(())
+    }
+
+    /// Makes the table called `table_name` a slowly changing dimension of type 2: INSERTs
+    /// write the versions of new business keys, and UPDATEs insert a new version of a
+    /// business key and close its current one. See [`Scd2`].
+    ///
+    /// The columns of the dimension are left alone by schema changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    /// * `scd` - The business key and validity columns of the dimension.
+    ///
+    /// # Returns
+    ///
+    /// An error if there is no such table or the dimension does not fit it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::scd::Scd2;
+    /// # use fake_sql::{Dialect, Generator, SqlType, Table};
+    /// let table = Table::init_via_sql(
+    ///     "create table dim_customer (customer_id int, city varchar(20), valid_from date, valid_to date, is_current boolean)",
+    /// );
+    /// let mut generator = Generator::with_seed(vec![table.clone()], 7);
+    /// generator.set_dialect(Dialect::Postgres);
+    /// let scd = Scd2 { max_versions: 1, ..Scd2::infer(&table).unwrap() };
+    /// generator.set_scd2("dim_customer", scd).unwrap();
+    /// generator.insert_into("dim_customer");
+    /// generator.set_sql_types(vec![SqlType::Update]);
+    /// assert!(generator.generate().text.starts_with("INSERT INTO dim_customer"));
+    /// assert!(generator.generate().text.starts_with("UPDATE dim_customer SET valid_to = DATE"));
+    /// ```
+    pub fn set_scd2(&mut self, table_name: &str, scd: Scd2) -> Result<(), String> {
+        let table = self
+            .table_index(table_name)
+            .map(|index| &self.tables[index])
+            .ok_or_else(|| format!("table `{}` is not defined in the schema", table_name))?;
+        scd.check(table)?;
+        self.scd.insert(table_name.to_string(), History::new(scd));
+        Ok(())
+    }
+
+    /// Fills the columns naming a custom generator, e.g. with `generator = "iban"` in the
+    /// config file, with the generators of `registry`; every column gets a copy of its own.
+    ///
+    /// # Returns
+    ///
+    /// An error naming the first column whose generator is not registered, or that is part
+    /// of a primary, unique or foreign key, see [`GeneratorRegistry::check`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::plugin::{GeneratorRegistry, RowContext, ValueGenerator};
+    /// # use fake_sql::{Generator, SqlValue, Table};
+    /// #[derive(Clone)]
+    /// struct Icd10;
+    ///
+    /// impl ValueGenerator for Icd10 {
+    ///     fn generate(&mut self, _ctx: &RowContext) -> SqlValue {
+    ///         SqlValue::Text("J45.909".to_string())
+    ///     }
+    /// }
+    ///
+    /// let mut table = Table::init_via_sql("create table diagnoses (id int primary key, code varchar(8))");
+    /// table.columns[1].custom_generator = Some("icd10".to_string());
+    /// let mut generator = Generator::with_seed(vec![table], 1);
+    /// assert!(generator.set_registry(&GeneratorRegistry::new()).is_err());
+    /// let mut registry = GeneratorRegistry::new();
+    /// registry.register("icd10", Icd10).unwrap();
+    /// generator.set_registry(&registry).unwrap();
+    /// assert_eq!(generator.insert_into("diagnoses").unwrap(), "INSERT INTO diagnoses (id, code) VALUES (1, 'J45.909');");
+    /// ```
+    pub fn set_registry(&mut self, registry: &GeneratorRegistry) -> Result<(), String> {
+        registry.check(&self.tables)?;
+        self.custom.clear();
+        for table in &self.tables {
+            for column in &table.columns {
+                if let Some(generator) = column.custom_generator.as_ref().and_then(|name| registry.get(name)) {
+                    self.custom.insert((table.name.clone(), column.name.clone()), generator);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the parameters bound by the last generated statement and forgets them.
+    ///
+    /// # Returns
+    ///
+    /// A JSON array or object as described by [`Table::insert_statement_params`], or `None`
+    /// if the statement has no placeholders.
+    pub fn take_params(&mut self) -> Option<serde_json::Value> {
+        self.params.take()
+    }
+
+    /// Returns the invalid value injected into the last generated statement and forgets it.
+    ///
+    /// # Returns
+    ///
+    /// The row, column, kind and rendered value, or `None` if the statement is clean.
+    pub fn take_dirt(&mut self) -> Option<Dirt> {
+        self.dirt.take()
+    }
+
+    /// Takes the binary values the last generated statement spilled to files, see
+    /// [`Generator::set_blob_files`].
+    pub fn take_blobs(&mut self) -> Vec<Blob> {
+        mem::take(&mut self.blobs)
+    }
+
+    /// Splits the generator into `count` shards for generating in parallel.
+    ///
+    /// Every shard starts from the generator's settings and the keys it inserted so far,
+    /// and gets its own random number generator seeded from this one, so a generator
+    /// created with [`Generator::with_seed`] splits into the same shards every time.
+    /// The shards hand out disjoint primary key values: shard `i` of `n` uses the
+    /// sequential keys `i + 1`, `i + 1 + n`, `i + 1 + 2n`, ..., and random keys that are
+    /// not numbers are assigned to the shards by their hash. Foreign keys only
+    /// reference keys inserted by the same shard, so the output of every shard loads on
+    /// its own, and the outputs together load in any order of shards. Values of
+    /// auto-increment columns are assigned by the database, though, so foreign keys to
+    /// them only match when the shards are loaded into separate databases.
+    ///
+    /// Time series stamp the rows of shard `i` from `i / n` of their interval on and
+    /// split their entity ids between the shards.
+    ///
+    /// Custom generators set with [`Generator::set_registry`] are copied into every shard
+    /// with their state, so every shard repeats the values of a stateful generator such as
+    /// a counter.
+    ///
+    /// Call it after the settings, as [`Generator::set_key_strategy`] resets the keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of shards; values below 1 are treated as 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, Table};
+    /// let table = Table::init_via_sql("create table t (id number(10) primary key)");
+    /// let mut shards = Generator::with_seed(vec![table], 7).split(2);
+    /// assert_eq!(shards[0].insert_into("t").unwrap(), "INSERT INTO t (id) VALUES (1);");
+    /// assert_eq!(shards[1].insert_into("t").unwrap(), "INSERT INTO t (id) VALUES (2);");
+    /// ```
+    pub fn split(mut self, count: usize) -> Vec<Generator> {
+        let count = count.max(1);
+        (0..count)
+            .map(|index| {
+                let mut shard = self.clone();
+                shard.rng = StdRng::seed_from_u64(self.rng.gen());
+                shard.unique.set_shard(index, count);
+                for series in shard.series.values_mut() {
+                    series.set_shard(index, count);
+                }
+                shard
+            })
+            .collect()
+    }
+
+    /// Returns the dialect statements are rendered for.
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Returns the tables the generator produces statements for.
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// Generates the next random statement.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the SQL statement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator has no tables.
+    pub fn next_statement(&mut self) -> String {
+        self.generate().text
+    }
+
+    /// Generates the next random statement together with its type.
+    ///
+    /// # Returns
+    ///
+    /// The statement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator has no tables.
+    pub fn generate(&mut self) -> Statement {
+        self.params = None;
+        self.dirt = None;
+        self.blobs.clear();
+        assert!(!self.tables.is_empty(), "generator has no tables");
+        if let Some(statement) = self.pending.pop_front() {
+            return statement;
+        }
+        self.generated += 1;
+        if self.evolve_every.is_some_and(|every| self.generated.is_multiple_of(every)) {
+            if let Some(statement) = self.change_schema() {
+                return statement;
+            }
+        }
+        let sql_type = match self.mix.choose(&mut self.rng) {
+            sql_type if self.safe && sql_type.is_destructive() => SqlType::Select,
+            sql_type => sql_type,
+        };
+        let table_index = self.rng.gen_range(0..self.tables.len());
+        self.generate_statement(sql_type, table_index)
+    }
+
+    /// Generates a statement of `sql_type`, preferably for the table at `table_index`.
+    fn generate_statement(&mut self, sql_type: SqlType, table_index: usize) -> Statement {
+        let text = match sql_type {
+            SqlType::Insert => {
+                let table_index = self.insertable_table(table_index);
+                let rows: Vec<Vec<SqlValue>> = (0..self.batch_size).map(|_| self.generate_row(table_index)).collect();
+                return self.insert_statement(table_index, &rows);
+            }
+            SqlType::Select => {
+                let subquery = if self.subquery_rate > 0.0 && self.rng.gen_bool(self.subquery_rate) {
+                    query::subquery_condition(&self.tables, table_index, self.dialect, &mut self.rng)
+                } else {
+                    None
+                };
+                let table = &self.tables[table_index];
+                let row = self.data.choose(&table.name, &mut self.rng);
+                query::select(table, row, subquery.as_deref(), self.dialect, self.complexity, self.predicates, &mut self.rng)
+            }
+            SqlType::SelectJoin => query::select_join(&self.tables, self.dialect, self.complexity, &mut self.rng)
+                .unwrap_or_else(|| {
+                    let table = &self.tables[table_index];
+                    let row = self.data.choose(&table.name, &mut self.rng);
+                    query::select(table, row, None, self.dialect, self.complexity, self.predicates, &mut self.rng)
+                }),
+            SqlType::CreateView => query::create_view(&self.tables, table_index, self.dialect, &mut self.rng),
+            SqlType::Aggregate => {
+                query::select_aggregate(&self.tables[table_index], self.dialect, self.complexity, &mut self.rng)
+            }
+            SqlType::Update if self.scd.contains_key(&self.tables[table_index].name) => {
+                return self.change_version(table_index);
+            }
+            SqlType::Update => {
+                let table = &self.tables[table_index];
+                let row = self.data.choose(&table.name, &mut self.rng);
+                table.update_statement(self.dialect, row, self.predicates, &mut self.rng)
+            }
+            SqlType::AlterTable if self.safe => {
+                let table = &self.tables[table_index];
+                loop {
+                    match SchemaChange::random_alter(table, self.dialect, &mut self.rng) {
+                        SchemaChange::DropColumn(_) => continue,
+                        change => break change.statement(table, self.dialect),
+                    }
+                }
+            }
+            SqlType::Delete if self.delete_by_key || self.safe => return self.delete_inserted_row(table_index),
+            SqlType::Delete => {
+                // Rows of referenced tables may have children, so only other tables are deleted from
+                let Some(table_index) = self.unreferenced_table(table_index) else {
+                    return self.delete_inserted_row(table_index);
+                };
+                let row = self.data.take(&self.tables[table_index].name, &mut self.rng);
+                if let Some(row) = &row {
+                    self.forget_keys(table_index, row);
+                }
+                let table = &self.tables[table_index];
+                // Predicates could match further remembered rows, so rows with a key are deleted by it
+                let text = match row {
+                    Some(row) if !table.pkey_columns.is_empty() => table.delete_row_statement(self.dialect, &row),
+                    row => table.delete_statement(self.dialect, row.as_deref(), self.predicates, &mut self.rng),
+                };
+                return Statement::new(sql_type, &table.name, text);
+            }
+            _ => return self.tables[table_index].generate_with(sql_type, self.dialect, &mut self.rng),
+        };
+        Statement::new(sql_type, &self.tables[table_index].name, text)
+    }
+
+    /// Returns the positions of the tables a foreign key references.
+    fn referenced_tables(&self) -> HashSet<usize> {
+        query::relationships(&self.tables).into_iter().map(|r| r.parent).collect()
+    }
+
+    /// Returns the table at `table_index` if no foreign key references it, or else a random
+    /// table no foreign key references, or `None` if every table is referenced.
+    fn unreferenced_table(&mut self, table_index: usize) -> Option<usize> {
+        let referenced = self.referenced_tables();
+        if !referenced.contains(&table_index) {
+            return Some(table_index);
+        }
+        let unreferenced: Vec<usize> = (0..self.tables.len()).filter(|i| !referenced.contains(i)).collect();
+        unreferenced.choose(&mut self.rng).copied()
+    }
+
+    /// Generates `count` example statements of every type of the mix for every table, so the
+    /// settings can be checked before a long run, together with how every column is filled.
+    ///
+    /// The statements come from a copy of the generator that first inserts a row into every
+    /// table, parents first, so they filter on and reference existing rows; the generator
+    /// itself is left unchanged. Statements that need rows of another table, such as joins
+    /// or deletes by key, may be generated for that table instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Generator, SqlType, Table};
+    /// let table = Table::init_via_sql("create table t (id int primary key, email varchar(40))");
+    /// let mut generator = Generator::with_seed(vec![table], 1);
+    /// generator.set_sql_types(vec![SqlType::Insert, SqlType::Update]);
+    /// let preview = &generator.preview(2)[0];
+    /// assert_eq!(preview.columns[0], ("id".to_string(), "sequential keys".to_string()));
+    /// assert_eq!(preview.columns[1], ("email".to_string(), "email, up to 40 characters".to_string()));
+    /// assert_eq!(preview.statements.len(), 2);
+    /// assert!(preview.statements.iter().all(|(_, statements)| statements.len() == 2));
+    /// ```
+    pub fn preview(&self, count: usize) -> Vec<Preview> {
+        let mut generator = self.clone();
+        for table_index in schema::dependency_order(&generator.tables) {
+            generator.generate_row(table_index);
+        }
+        let sql_types: Vec<SqlType> = self
+            .mix
+            .weights()
+            .iter()
+            .filter(|(sql_type, weight)| *weight > 0 && !(self.safe && sql_type.is_destructive()))
+            .map(|(sql_type, _)| *sql_type)
+            .collect();
+        (0..self.tables.len())
+            .map(|table_index| {
+                let table = &self.tables[table_index];
+                let columns = table.columns.iter().map(|column| (column.name.clone(), self.describe_column(table, column))).collect();
+                let statements = sql_types
+                    .iter()
+                    .map(|&sql_type| {
+                        let statements = (0..count)
+                            .map(|_| {
+                                let text = generator.generate_statement(sql_type, table_index).text;
+                                // Closing versions of dimensions are not part of the sample
+                                generator.pending.clear();
+                                text
+                            })
+                            .collect();
+                        (sql_type, statements)
+                    })
+                    .collect();
+                Preview { table: table.name.clone(), columns, statements }
+            })
+            .collect()
+    }
+
+    /// Describes how the values of `column` of `table` are generated, like
+    /// [`Column::describe_values`] but with the keys, references and fan-outs of the generator.
+    fn describe_column(&self, table: &Table, column: &Column) -> String {
+        if let Some((parent, parent_column)) = referenced_column(&self.tables, column) {
+            return match self.fan_outs.get(&(table.name.clone(), column.name.clone())) {
+                Some(fan_out) => format!("references {}.{}, {} children per parent", parent, parent_column, fan_out.cardinality()),
+                None => format!("references {}.{}", parent, parent_column),
+            };
+        }
+        if column.auto_increment {
+            return "auto-increment 1, 2, 3, ...".to_string();
+        }
+        if let Some(name) = column.custom_generator.as_ref().filter(|_| self.custom.contains_key(&(table.name.clone(), column.name.clone()))) {
+            return format!("custom generator `{}`", name);
+        }
+        if column.template.is_none() && column.is_pkey {
+            return format!("{} keys", self.unique.strategy());
+        }
+        if column.is_unique {
+            return format!("unique {}", column.describe_values());
+        }
+        column.describe_values()
+    }
+
+    /// Generates the statements creating every table of the schema, with its foreign keys
+    /// and their indexes, parents before the tables referencing them. Postgres composite
+    /// types the columns use are created first, once each.
+    ///
+    /// # Returns
+    ///
+    /// The statements, in the order they have to run.
+    pub fn schema_statements(&self) -> Vec<String> {
+        let mut statements: Vec<String> = vec![];
+        for statement in self.tables.iter().flat_map(|t| &t.columns).flat_map(|c| c.type_statements(self.dialect)) {
+            if !statements.contains(&statement) {
+                statements.push(statement);
+            }
+        }
+        statements.extend(
+            schema::dependency_order(&self.tables)
+                .into_iter()
+                .flat_map(|i| self.tables[i].schema_statements(self.dialect)),
+        );
+        statements
+    }
+
+    /// Generates an INSERT statement for the table called `table_name`.
+    ///
+    /// This is used to produce a fixed number of rows per table, e.g. 10 customers
+    /// and 1,000 orders, instead of picking a random table per statement.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table to insert into.
+    ///
+    /// # Returns
+    ///
+    /// The INSERT statement, or `None` if the generator has no such table.
+    pub fn insert_into(&mut self, table_name: &str) -> Option<String> {
+        self.insert_batch_into(table_name, 1)
+    }
+
+    /// Generates a single INSERT statement carrying `count` rows for the table called `table_name`.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table to insert into.
+    /// * `count` - The number of rows in the statement.
+    ///
+    /// # Returns
+    ///
+    /// The INSERT statement, or `None` if the generator has no such table.
+    pub fn insert_batch_into(&mut self, table_name: &str, count: usize) -> Option<String> {
+        self.params = None;
+        self.dirt = None;
+        self.blobs.clear();
+        let table_index = self.table_index(table_name)?;
+        let rows: Vec<Vec<SqlValue>> = (0..count).map(|_| self.generate_row(table_index)).collect();
+        Some(self.insert_statement(table_index, &rows).text)
+    }
+
+    /// Generates the values of a row for a random table, as an INSERT would.
+    ///
+    /// Row-oriented output formats such as CSV use this instead of rendering statements.
+    ///
+    /// # Returns
+    ///
+    /// The table and one value per column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator has no tables.
+    pub fn next_row(&mut self) -> (&Table, Vec<SqlValue>) {
+        assert!(!self.tables.is_empty(), "generator has no tables");
+        let table_index = self.rng.gen_range(0..self.tables.len());
+        let row = self.generate_row(table_index);
+        (&self.tables[table_index], row)
+    }
+
+    /// Generates the values of a row for a random table like [`Generator::next_row`], but
+    /// parents first: while a non-nullable foreign key of the table references a table
+    /// without rows, the row goes to that table instead.
+    ///
+    /// Bulk loads such as Postgres `COPY` use this, as they check foreign keys and stop at
+    /// the first row referencing a missing parent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator has no tables.
+    pub fn next_row_parents_first(&mut self) -> (&Table, Vec<SqlValue>) {
+        assert!(!self.tables.is_empty(), "generator has no tables");
+        let table_index = self.rng.gen_range(0..self.tables.len());
+        let table_index = self.insertable_table(table_index);
+        let row = self.generate_row(table_index);
+        (&self.tables[table_index], row)
+    }
+
+    /// Generates the next change of a row, as a CDC connector would capture it: an insert,
+    /// an update or a delete, picked by the weights of the statement mix; the other
+    /// statement types have no changes and are left out, and without any of the three only
+    /// rows are inserted.
+    ///
+    /// Updates set one to three of the [updatable columns](Table::updatable_columns) of a
+    /// row inserted before, and deletes remove one from a table no foreign key references,
+    /// so the images are consistent over the stream. Both insert a row instead while their
+    /// table has none to change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::cdc::Operation;
+    /// # use fake_sql::{Generator, SqlType, Table};
+    /// let orders = Table::init_via_sql("create table orders (id int primary key, status varchar(10))");
+    /// let mut generator = Generator::with_seed(vec![orders], 1);
+    /// generator.set_sql_types(vec![SqlType::Update]);
+    /// let insert = generator.next_change();
+    /// assert_eq!((insert.op, insert.before), (Operation::Create, None));
+    /// let update = generator.next_change();
+    /// assert_eq!(update.op, Operation::Update);
+    /// assert_ne!(update.before, update.after);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator has no tables.
+    pub fn next_change(&mut self) -> Change {
+        assert!(!self.tables.is_empty(), "generator has no tables");
+        let weights: Vec<(SqlType, u32)> =
+            self.mix.weights().iter().filter(|(sql_type, _)| matches!(sql_type, SqlType::Insert | SqlType::Update | SqlType::Delete)).copied().collect();
+        let sql_type = Mix::new(weights).map_or(SqlType::Insert, |mix| mix.choose(&mut self.rng));
+        let table_index = self.rng.gen_range(0..self.tables.len());
+        let deletable = if sql_type == SqlType::Delete { self.deletable_table(table_index) } else { None };
+        match (sql_type, deletable) {
+            (SqlType::Update, _) if self.data.len(&self.tables[table_index].name) > 0 => {
+                let name = self.tables[table_index].name.clone();
+                let before = self.data.take(&name, &mut self.rng).unwrap();
+                let mut after = before.clone();
+                let columns = self.tables[table_index].updatable_columns();
+                let count = self.rng.gen_range(1..=columns.len().clamp(1, 3)).min(columns.len());
+                for i in rand::seq::index::sample(&mut self.rng, columns.len(), count) {
+                    after[columns[i]] = self.tables[table_index].columns[columns[i]].generate_value(&mut self.rng);
+                }
+                self.data.insert(&name, after.clone(), &mut self.rng);
+                Change { op: Operation::Update, table: table_index, before: Some(before), after: Some(after) }
+            }
+            (_, Some(table_index)) => {
+                let before = self.data.take(&self.tables[table_index].name, &mut self.rng);
+                Change { op: Operation::Delete, table: table_index, before, after: None }
+            }
+            _ => {
+                let table_index = self.insertable_table(table_index);
+                let after = self.generate_row(table_index);
+                Change { op: Operation::Create, table: table_index, before: None, after: Some(after) }
+            }
+        }
+    }
+
+    /// Generates the values of a row for the table called `table_name`, as an INSERT would.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    ///
+    /// # Returns
+    ///
+    /// The table and one value per column, or `None` if the generator has no such table.
+    pub fn row_for(&mut self, table_name: &str) -> Option<(&Table, Vec<SqlValue>)> {
+        let table_index = self.table_index(table_name)?;
+        let row = self.generate_row(table_index);
+        Some((&self.tables[table_index], row))
+    }
+
+    /// Returns the table to insert into instead of the table at `table_index`, so parents
+    /// are populated before their children.
+    ///
+    /// While a non-nullable foreign key references a table without rows, the row goes to
+    /// that table instead, following the references up to a table that can be filled.
+    fn insertable_table(&self, table_index: usize) -> usize {
+        let mut index = table_index;
+        // Bounded by the number of tables so cycles of references cannot loop forever
+        for _ in 0..self.tables.len() {
+            let table = &self.tables[index];
+            let empty_parent = table
+                .columns
+                .iter()
+                .filter(|c| !c.is_nullable)
+                .filter_map(|c| referenced_column(&self.tables, c))
+                .find(|parent| parent.0 != table.name && self.keys.get(parent).is_none_or(|keys| keys.is_empty()));
+            match empty_parent.and_then(|(parent, _)| self.table_index(&parent)) {
+                Some(parent) => index = parent,
+                None => break,
+            }
+        }
+        index
+    }
+
+    /// Generates a DELETE of a row inserted before, preferably into the table at
+    /// `table_index`, from a table no foreign key references. The row is forgotten, so it
+    /// is neither deleted again nor filtered on by later statements.
+    ///
+    /// Generates an INSERT into the table at `table_index` instead while there is no such row.
+    fn delete_inserted_row(&mut self, table_index: usize) -> Statement {
+        match self.deletable_table(table_index) {
+            Some(index) => {
+                let table = &self.tables[index];
+                let row = self.data.take(&table.name, &mut self.rng).unwrap();
+                Statement::new(SqlType::Delete, &table.name, table.delete_row_statement(self.dialect, &row))
+            }
+            None => {
+                let table_index = self.insertable_table(table_index);
+                let rows: Vec<Vec<SqlValue>> = (0..self.batch_size).map(|_| self.generate_row(table_index)).collect();
+                self.insert_statement(table_index, &rows)
+            }
+        }
+    }
+
+    /// Forgets the key values of `row`, a deleted row of the table at `table_index`, so
+    /// rows generated later no longer reference it.
+    fn forget_keys(&mut self, table_index: usize, row: &[SqlValue]) {
+        let table = &self.tables[table_index];
+        for (column, value) in table.columns.iter().zip(row) {
+            let Some(keys) = self.keys.get_mut(&(table.name.clone(), column.name.clone())) else { continue };
+            if let Some(position) = keys.iter().position(|key| key == value) {
+                keys.remove(position);
+            }
+        }
+    }
+
+    /// Returns the table at `table_index` if a row inserted before can be deleted from it,
+    /// or else a random table that has one: a table with a primary key no foreign key
+    /// references, so deletes leave no rows referencing a missing parent.
+    fn deletable_table(&mut self, table_index: usize) -> Option<usize> {
+        let referenced = self.referenced_tables();
+        let deletable: Vec<usize> = (0..self.tables.len())
+            .filter(|i| !referenced.contains(i))
+            .filter(|&i| !self.tables[i].pkey_columns.is_empty() && self.data.len(&self.tables[i].name) > 0)
+            .collect();
+        if deletable.contains(&table_index) {
+            Some(table_index)
+        } else {
+            deletable.choose(&mut self.rng).copied()
+        }
+    }
+
+    /// Generates a new version of a business key of the dimension at `table_index`, as the
+    /// INSERT returned, and queues the UPDATE closing the version it replaces.
+    ///
+    /// Generates an INSERT of new business keys instead while no current version is known.
+    fn change_version(&mut self, table_index: usize) -> Statement {
+        let name = self.tables[table_index].name.clone();
+        let old = self.scd.get_mut(&name).unwrap().start_change(&name, &mut self.rng);
+        let rows: Vec<Vec<SqlValue>> = match old {
+            Some(_) => vec![self.generate_row(table_index)],
+            None => (0..self.batch_size).map(|_| self.generate_row(table_index)).collect(),
+        };
+        let insert = self.insert_statement(table_index, &rows);
+        if let Some(old) = old {
+            let close = self.scd[&name].close_statement(&self.tables[table_index], &old, &rows[0], self.dialect);
+            self.pending.push_back(Statement::new(SqlType::Update, &name, close));
+        }
+        insert
+    }
+
+    /// Renders an INSERT of `rows` into the table at `table_index`, with placeholders
+    /// and remembered parameters if a parameter style is set, an invalid value as
+    /// often as the dirty rate says, and large binary values read from files if they
+    /// are spilled.
+    fn insert_statement(&mut self, table_index: usize, rows: &[Vec<SqlValue>]) -> Statement {
+        let table = &self.tables[table_index];
+        let mut changed_rows;
+        let rows = if self.dirty_rate > 0.0 && !rows.is_empty() && self.rng.gen_bool(self.dirty_rate) {
+            changed_rows = rows.to_vec();
+            self.dirt = dirty::inject(table, &mut changed_rows, self.dialect, self.param_style.is_none(), &mut self.rng);
+            &changed_rows
+        } else {
+            rows
+        };
+        let rows = match &self.blob_files {
+            Some(files) if self.param_style.is_none() => {
+                changed_rows = rows.to_vec();
+                for value in changed_rows.iter_mut().flatten() {
+                    if let SqlValue::Bytes(bytes) | SqlValue::Base64(bytes) = value {
+                        if let Some(blob) = files.spill(bytes) {
+                            *value = SqlValue::Raw(self.dialect.file_literal(&blob.path));
+                            self.blobs.push(blob);
+                        }
+                    }
+                }
+                &changed_rows
+            }
+            _ => rows,
+        };
+        let text = match self.param_style {
+            Some(style) => {
+                let (sql, params) = table.insert_statement_params(self.dialect, style, rows);
+                self.params = Some(params);
+                sql
+            }
+            None => table.insert_statement_rows(self.dialect, rows),
+        };
+        Statement::new(SqlType::Insert, &table.name, text).with_values(table.inserted_values(rows))
+    }
+
+    /// Makes a random [`SchemaChange`] to a random table and updates what the generator
+    /// remembers about the table.
+    ///
+    /// Returns `None` if no change of a type that is not excluded was found in
+    /// `MAX_KEY_ATTEMPTS` tries, e.g. no new index while `AlterTable` is excluded.
+    fn change_schema(&mut self) -> Option<Statement> {
+        if self.excluded.contains(&SqlType::AlterTable) && self.excluded.contains(&SqlType::CreateIndex) {
+            return None;
+        }
+        let table_index = self.rng.gen_range(0..self.tables.len());
+        let table = &self.tables[table_index];
+        let fixed: Vec<String> = table
+            .columns
+            .iter()
+            .map(|c| &c.name)
+            .filter(|&name| {
+                self.key_columns.contains(&(table.name.clone(), name.clone()))
+                    || table.unique_keys.iter().any(|key| key.contains(name))
+                    || table.checks.iter().any(|check| check.columns.contains(name))
+                    || self.series.get(&table.name).is_some_and(|series| series.settings().columns().contains(name))
+                    || self.scd.get(&table.name).is_some_and(|history| history.settings().columns().contains(name))
+                    || self.fan_outs.contains_key(&(table.name.clone(), name.clone()))
+                    || self.custom.contains_key(&(table.name.clone(), name.clone()))
+                    || self.indexes.iter().any(|(t, _, columns)| t == &table.name && columns.contains(name))
+            })
+            .cloned()
+            .collect();
+        let index_names: Vec<String> = self.indexes.iter().map(|(_, name, _)| name.clone()).collect();
+        // Adding a column is always possible, so safe mode finds a change that drops none
+        let mut change = (0..MAX_KEY_ATTEMPTS)
+            .map(|_| SchemaChange::random(table, &self.tables, &fixed, &index_names, self.dialect, &mut self.rng))
+            .find(|change| match change {
+                SchemaChange::DropColumn(_) if self.safe => false,
+                change => !self.excluded.contains(&change.sql_type()),
+            })?;
+        let text = change.statement(table, self.dialect);
+        let name = table.name.clone();
+        match &mut change {
+            SchemaChange::AddColumn(column) => {
+                column.pii_free = self.pii_free;
+                self.data.add_column(&name);
+                if let Some(history) = self.scd.get_mut(&name) {
+                    history.current_mut().add_column(&name);
+                }
+            }
+            SchemaChange::DropColumn(column) => {
+                let index = table.columns.iter().position(|c| &c.name == column).unwrap();
+                self.data.remove_column(&name, index);
+                if let Some(history) = self.scd.get_mut(&name) {
+                    history.current_mut().remove_column(&name, index);
+                }
+            }
+            SchemaChange::RenameTable(new_name) => {
+                rename_table_keys(&mut self.keys, &name, new_name);
+                self.key_columns = self
+                    .key_columns
+                    .drain()
+                    .map(|(table, column)| (if table == name { new_name.clone() } else { table }, column))
+                    .collect();
+                rename_table_keys(&mut self.composite_keys, &name, new_name);
+                rename_table_keys(&mut self.fan_outs, &name, new_name);
+                rename_table_keys(&mut self.custom, &name, new_name);
+                self.unique.rename_table(&name, new_name);
+                self.data.rename_table(&name, new_name);
+                if let Some(series) = self.series.remove(&name) {
+                    self.series.insert(new_name.clone(), series);
+                }
+                if let Some(mut history) = self.scd.remove(&name) {
+                    history.current_mut().rename_table(&name, new_name);
+                    self.scd.insert(new_name.clone(), history);
+                }
+                for index in self.indexes.iter_mut().filter(|(table, _, _)| table == &name) {
+                    index.0 = new_name.clone();
+                }
+            }
+            SchemaChange::CreateIndex(index, columns) => self.indexes.push((name.clone(), index.clone(), columns.clone())),
+            SchemaChange::ModifyColumn(..) | SchemaChange::RenameColumn(..) => {}
+        }
+        change.apply(&mut self.tables[table_index]);
+        Some(Statement::new(change.sql_type(), &name, text))
+    }
+
+    /// Returns the position of the table called `table_name`.
+    fn table_index(&self, table_name: &str) -> Option<usize> {
+        self.tables.iter().position(|t| t.name == table_name)
+    }
+
+    /// Generates a row for the table at `table_index` and remembers its key values.
+    ///
+    /// For a composite primary key or unique keys, rows are redrawn until the combinations
+    /// of key values have not been inserted before, or `MAX_KEY_ATTEMPTS` rows were tried.
+    /// Rows are redrawn as well while they fail a [`TableCheck`](crate::models::TableCheck).
+    fn generate_row(&mut self, table_index: usize) -> Vec<SqlValue> {
+        let mut row = self.draw_row(table_index);
+        let table = &self.tables[table_index];
+        let composite_pkey = Some(&table.pkey_columns).filter(|columns| columns.len() > 1);
+        let keys: Vec<((String, String), Vec<usize>)> = composite_pkey
+            .into_iter()
+            .chain(&table.unique_keys)
+            .map(|names| {
+                let positions = names.iter().filter_map(|name| table.columns.iter().position(|c| &c.name == name)).collect();
+                ((table.name.clone(), names.join(",")), positions)
+            })
+            .collect();
+        for attempt in 1..=MAX_KEY_ATTEMPTS {
+            let satisfied = self.tables[table_index].satisfy_checks(&mut row);
+            let values: Vec<Vec<SqlValue>> = keys.iter().map(|(_, positions)| positions.iter().map(|&i| row[i].clone()).collect()).collect();
+            let is_new = keys
+                .iter()
+                .zip(&values)
+                .all(|(key, values)| self.composite_keys.get(&key.0).is_none_or(|inserted| !inserted.contains(values)));
+            if (is_new && satisfied) || attempt == MAX_KEY_ATTEMPTS {
+                for (key, values) in keys.iter().zip(values) {
+                    self.composite_keys.entry(key.0.clone()).or_default().insert(values);
+                }
+                break;
+            }
+            row = self.draw_row(table_index);
+        }
+
+        let table = &self.tables[table_index];
+        for (column, value) in table.columns.iter().zip(&row) {
+            let key = (table.name.clone(), column.name.clone());
+            if self.key_columns.contains(&key) && *value != SqlValue::Null {
+                self.keys.entry(key).or_default().push(value.clone());
+            }
+        }
+        self.data.insert(&table.name, row.clone(), &mut self.rng);
+        row
+    }
+
+    /// Draws the values of a row for the table at `table_index`.
+    ///
+    /// Foreign key columns take a value already inserted into the parent table. While
+    /// the parent has no rows yet, nullable foreign keys are set to NULL and non-nullable
+    /// ones fall back to a random value. Foreign keys with a [`Cardinality`] give each parent
+    /// its number of children before moving to the next. Auto-increment columns count 1, 2,
+    /// 3, ... like the database does, so child rows can reference the keys it assigns.
+    /// Columns with a custom generator get its values, see [`Generator::set_registry`].
+    /// Rows of a time-series table then get their timestamp, entity and metrics from the
+    /// series, and rows of a slowly changing dimension their business key and validity.
+    /// Last, a share of the rows copy an earlier row or get damaged text, see [`Duplicates`].
+    fn draw_row(&mut self, table_index: usize) -> Vec<SqlValue> {
+        let table = &self.tables[table_index];
+        let mut row = Vec::with_capacity(table.columns.len());
+        for column in &table.columns {
+            let parent_keys = referenced_column(&self.tables, column).and_then(|parent| self.keys.get(&parent));
+            let value = match parent_keys {
+                Some(values) if !values.is_empty() => {
+                    let draw = |rng: &mut StdRng| match column.distribution {
+                        Some(distribution) => values[distribution.sample_index(values.len(), rng)].clone(),
+                        None => values.choose(rng).unwrap().clone(),
+                    };
+                    let planned = self
+                        .fan_outs
+                        .get_mut(&(table.name.clone(), column.name.clone()))
+                        .and_then(|fan_out| fan_out.next(values, &mut self.rng));
+                    if let Some(key) = planned {
+                        key
+                    } else if column.is_unique {
+                        // One-to-one references take a parent key no row referenced before
+                        self.unique
+                            .distinct(&table.name, column, &mut self.rng, draw)
+                            .unwrap_or_else(|| if column.is_nullable { SqlValue::Null } else { draw(&mut self.rng) })
+                    } else {
+                        draw(&mut self.rng)
+                    }
+                }
+                _ if column.ref_table.is_some() && column.is_nullable => SqlValue::Null,
+                _ if column.auto_increment => self.unique.sequential(&table.name, column),
+                _ if column.custom_generator.is_some() && self.custom.contains_key(&(table.name.clone(), column.name.clone())) => {
+                    if column.is_nullable && column.null_rate.is_some_and(|rate| self.rng.gen_bool(rate.clamp(0.0, 1.0))) {
+                        SqlValue::Null
+                    } else {
+                        let generator = self.custom.get_mut(&(table.name.clone(), column.name.clone())).unwrap();
+                        generator.generate(&RowContext::new(table, column, &row, &mut self.rng))
+                    }
+                }
+                _ if column.template.is_some() => {
+                    // Values of key columns are redrawn with the next number while a cut to
+                    // the column length or random parts repeat one
+                    let mut attempts = 0;
+                    loop {
+                        let seq = self.unique.sequence(&table.name, column);
+                        let value = column.template_value(seq, &mut self.rng).unwrap();
+                        attempts += 1;
+                        if !(column.is_pkey || column.is_unique) || self.unique.claim(&table.name, column, &value) {
+                            break value;
+                        }
+                        if attempts == MAX_KEY_ATTEMPTS {
+                            break self.unique.unused(&table.name, column).unwrap_or(value);
+                        }
+                    }
+                }
+                _ if column.is_pkey => self.unique.generate(&table.name, column, &mut self.rng),
+                _ if column.is_unique => self
+                    .unique
+                    .distinct(&table.name, column, &mut self.rng, |rng| column.generate_value(rng))
+                    .or_else(|| self.unique.unused(&table.name, column))
+                    .unwrap_or_else(|| column.generate_value(&mut self.rng)),
+                _ => column.generate_value(&mut self.rng),
+            };
+            row.push(value);
+        }
+        if let Some(series) = self.series.get_mut(&table.name) {
+            let entity = series.settings().entity.as_deref().and_then(|name| table.column(name));
+            let parent_keys = entity.and_then(|c| referenced_column(&self.tables, c)).and_then(|parent| self.keys.get(&parent));
+            series.apply(table, &mut row, parent_keys.map(Vec::as_slice), &mut self.rng);
+        }
+        if let Some(history) = self.scd.get_mut(&table.name) {
+            let new_key = history
+                .needs_key()
+                .then(|| table.column(&history.settings().business_key))
+                .flatten()
+                .map(|column| self.unique.generate(&table.name, column, &mut self.rng));
+            history.apply(table, &mut row, new_key, &mut self.rng);
+        }
+        if let Some(anomaly) = self.duplicates.choose(&mut self.rng) {
+            let earlier = self.data.choose(&table.name, &mut self.rng).map(<[SqlValue]>::to_vec);
+            anomaly.apply(table, earlier.as_deref(), &mut row, &mut self.rng);
+        }
+        row
+    }
+}
+
+/// Example statements and column settings of a table, see [`Generator::preview`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preview {
+    /// The name of the table.
+    pub table: String,
+    /// Every column with a description of how its values are generated.
+    pub columns: Vec<(String, String)>,
+    /// The example statements per statement type, in the order of the mix.
+    pub statements: Vec<(SqlType, Vec<String>)>,
+}
+
+/// Configures a [`Generator`] step by step, for programs that stream statements without
+/// going through files, environment variables or the command line.
+///
+/// Settings that are not given keep the defaults of [`Generator`].
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::{Dialect, GeneratorBuilder, SqlType, Table};
+/// let table = Table::init_via_sql("create table t (id int primary key, name varchar(20))");
+/// let statements: Vec<_> = GeneratorBuilder::new()
+///     .table(table)
+///     .dialect(Dialect::Postgres)
+///     .seed(7)
+///     .mix("insert=1".parse().unwrap())
+///     .build()
+///     .take(3)
+///     .collect();
+/// assert!(statements.iter().all(|s| s.sql_type == SqlType::Insert && s.text.starts_with("INSERT INTO t")));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GeneratorBuilder {
+    tables: Vec<Table>,
+    dialect: Option<Dialect>,
+    seed: Option<u64>,
+    mix: Option<Mix>,
+    keys: Option<KeyStrategy>,
+    complexity: Option<Complexity>,
+    batch_size: Option<usize>,
+    null_rate: Option<f64>,
+    date_range: Option<DateRange>,
+    locale: Option<Locale>,
+}
+
+impl GeneratorBuilder {
+    /// Initializes a new `GeneratorBuilder` without tables.
+    pub fn new() -> GeneratorBuilder {
+        GeneratorBuilder::default()
+    }
+
+    /// Adds a table to generate statements for.
+    pub fn table(mut self, table: Table) -> GeneratorBuilder {
+        self.tables.push(table);
+        self
+    }
+
+    /// Adds several tables to generate statements for, e.g. the result of
+    /// [`parse_schema`](crate::schema::parse_schema).
+    pub fn tables(mut self, tables: impl IntoIterator<Item = Table>) -> GeneratorBuilder {
+        self.tables.extend(tables);
+        self
+    }
+
+    /// Sets the dialect statements are rendered for, see [`Generator::set_dialect`].
+    pub fn dialect(mut self, dialect: Dialect) -> GeneratorBuilder {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// Makes the statements fully determined by `seed`, see [`Generator::with_seed`].
+    pub fn seed(mut self, seed: u64) -> GeneratorBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the weighted mix of SQL types, see [`Generator::set_mix`].
+    pub fn mix(mut self, mix: Mix) -> GeneratorBuilder {
+        self.mix = Some(mix);
+        self
+    }
+
+    /// Sets how primary key values are generated, see [`Generator::set_key_strategy`].
+    pub fn keys(mut self, strategy: KeyStrategy) -> GeneratorBuilder {
+        self.keys = Some(strategy);
+        self
+    }
+
+    /// Sets how elaborate SELECT queries are, see [`Generator::set_complexity`].
+    pub fn complexity(mut self, complexity: Complexity) -> GeneratorBuilder {
+        self.complexity = Some(complexity);
+        self
+    }
+
+    /// Sets the number of rows per INSERT statement, see [`Generator::set_batch_size`].
+    pub fn batch_size(mut self, batch_size: usize) -> GeneratorBuilder {
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Sets the probability that a value of a nullable column is NULL, see
+    /// [`Generator::set_null_rate`].
+    pub fn null_rate(mut self, null_rate: f64) -> GeneratorBuilder {
+        self.null_rate = Some(null_rate);
+        self
+    }
+
+    /// Sets the range dates and timestamps are drawn from, see [`Generator::set_date_range`].
+    pub fn date_range(mut self, date_range: DateRange) -> GeneratorBuilder {
+        self.date_range = Some(date_range);
+        self
+    }
+
+    /// Sets the locale of provider text, see [`Generator::set_locale`].
+    pub fn locale(mut self, locale: Locale) -> GeneratorBuilder {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Builds the generator, which iterates over the endless sequence of statements it
+    /// generates; use [`Iterator::take`] to bound it.
+    ///
+    /// # Panics
+    ///
+    /// The generator panics on its first statement if no table was added.
+    pub fn build(self) -> Generator {
+        let mut generator = match self.seed {
+            Some(seed) => Generator::with_seed(self.tables, seed),
+            None => Generator::new(self.tables),
+        };
+        if let Some(dialect) = self.dialect {
+            generator.set_dialect(dialect);
+        }
+        if let Some(mix) = self.mix {
+            generator.set_mix(mix);
+        }
+        if let Some(keys) = self.keys {
+            generator.set_key_strategy(keys);
+        }
+        if let Some(complexity) = self.complexity {
+            generator.set_complexity(complexity);
+        }
+        if let Some(batch_size) = self.batch_size {
+            generator.set_batch_size(batch_size);
+        }
+        if let Some(null_rate) = self.null_rate {
+            generator.set_null_rate(null_rate);
+        }
+        if let Some(date_range) = self.date_range {
+            generator.set_date_range(date_range);
+        }
+        if let Some(locale) = self.locale {
+            generator.set_locale(locale);
+        }
+        generator
+    }
+}
+
+impl Iterator for Generator {
+    type Item = Statement;
+
+    /// Generates the next random statement, see [`Generator::generate`]; never returns `None`.
+    fn next(&mut self) -> Option<Statement> {
+        Some(self.generate())
+    }
+}
+
+#[cfg(feature = "stream")]
+impl futures_core::Stream for Generator {
+    type Item = Statement;
+
+    /// Generates the next random statement; the stream is always ready and never ends.
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Statement>> {
+        std::task::Poll::Ready(Some(self.get_mut().generate()))
+    }
+}
+
+/// Resolves the `(table, column)` a foreign key column references.
+///
+/// When the reference does not name a column, the parent's primary key is used.
+/// Returns `None` for columns without a reference or references to unknown tables.
+fn referenced_column(tables: &[Table], column: &Column) -> Option<(String, String)> {
+    let parent_name = column.ref_table.as_ref()?;
+    let parent = tables.iter().find(|t| &t.name == parent_name)?;
+    let parent_column = match &column.ref_column {
+        Some(name) => name.clone(),
+        None => parent.primary_key()?.name.clone(),
+    };
+    Some((parent.name.clone(), parent_column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{parse_schema, DEFAULT_SCHEMA};
+
+    fn statements(generator: &mut Generator, count: usize) -> Vec<String> {
+        (0..count).map(|_| generator.next_statement()).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_output() {
+        let mut first = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 42);
+        let mut second = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 42);
+        assert_eq!(statements(&mut first, 200), statements(&mut second, 200));
+    }
+
+    #[test]
+    fn test_different_seed_different_output() {
+        let mut first = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 1);
+        let mut second = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 2);
+        assert_ne!(statements(&mut first, 50), statements(&mut second, 50));
+    }
+
+    #[test]
+    fn test_foreign_keys_reference_inserted_parents() {
+        let tables = parse_schema(
+            "create table customers (customer_id number(10) primary key, customer_name varchar(50));
+             create table orders (order_id number(10) primary key, customer_id number(10) references customers(customer_id));",
+        )
+        .unwrap();
+        let mut generator = Generator::with_seed(tables, 11);
+        generator.set_sql_types(vec![SqlType::Insert]);
+
+        let mut customer_ids = HashSet::new();
+        for sql in statements(&mut generator, 300) {
+            if let Some(values) = sql.strip_prefix("INSERT INTO customers (customer_id, customer_name) VALUES (") {
+                customer_ids.insert(values.split(',').next().unwrap().to_string());
+            } else {
+                let values = sql.strip_prefix("INSERT INTO orders (order_id, customer_id) VALUES (").unwrap();
+                let customer_id = values.trim_end_matches(");").split(", ").nth(1).unwrap();
+                assert!(customer_id == "NULL" || customer_ids.contains(customer_id), "{}", sql);
+            }
+        }
+    }
+
+    #[test]
+    fn test_primary_keys_are_unique() {
+        for strategy in [KeyStrategy::Sequential, KeyStrategy::Random] {
+            let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 9);
+            generator.set_key_strategy(strategy);
+            let ids: HashSet<String> = (0..500)
+                .map(|_| generator.insert_into("orders").unwrap())
+                .map(|sql| sql.split("VALUES (").nth(1).unwrap().split(',').next().unwrap().to_string())
+                .collect();
+            assert_eq!(ids.len(), 500);
+        }
+    }
+
+    #[test]
+    fn test_composite_keys_are_unique() {
+        let tables = parse_schema(
+            "create table a (id number(1) primary key);
+             create table b (id number(1) primary key);
+             create table ab (a_id number(1) references a(id), b_id number(1) references b(id), primary key (a_id, b_id));",
+        )
+        .unwrap();
+        let mut generator = Generator::with_seed(tables, 5);
+        for _ in 0..5 {
+            generator.insert_into("a").unwrap();
+            generator.insert_into("b").unwrap();
+        }
+        let pairs: HashSet<Vec<SqlValue>> = (0..20).map(|_| generator.row_for("ab").unwrap().1).collect();
+        assert_eq!(pairs.len(), 20);
+    }
+
+    #[test]
+    fn test_unique_columns() {
+        let tables = parse_schema(
+            "create table a (id int primary key, code number(2) unique, email varchar(40) unique);
+             create table b (id int primary key, a_id int unique references a(id), x int, y int, unique (x, y));",
+        )
+        .unwrap();
+        let mut generator = Generator::with_seed(tables, 5);
+        // 99 codes fit a number(2): random draws run out and sequential values fill the rest
+        let rows: Vec<Vec<SqlValue>> = (0..99).map(|_| generator.row_for("a").unwrap().1).collect();
+        for column in 1..3 {
+            let values: HashSet<&SqlValue> = rows.iter().map(|row| &row[column]).collect();
+            assert_eq!(values.len(), 99);
+        }
+        let rows: Vec<Vec<SqlValue>> = (0..50).map(|_| generator.row_for("b").unwrap().1).collect();
+        assert_eq!(rows.iter().map(|row| &row[1]).collect::<HashSet<_>>().len(), 50);
+        assert_eq!(rows.iter().map(|row| &row[2..]).collect::<HashSet<_>>().len(), 50);
+    }
+
+    #[test]
+    fn test_auto_increment_keys_are_referenced() {
+        let tables = parse_schema(
+            "create table a (id serial primary key, name varchar(10));
+             create table b (id serial primary key, a_id int not null references a(id));",
+        )
+        .unwrap();
+        let mut generator = Generator::with_seed(tables, 3);
+        generator.set_key_strategy(KeyStrategy::Random);
+        let ids: Vec<SqlValue> = (0..3).map(|_| generator.row_for("a").unwrap().1[0].clone()).collect();
+        assert_eq!(ids, [SqlValue::Integer(1), SqlValue::Integer(2), SqlValue::Integer(3)]);
+        let (_, row) = generator.row_for("b").unwrap();
+        assert!(ids.contains(&row[1]));
+        let sql = generator.insert_into("a").unwrap();
+        assert!(sql.starts_with("INSERT INTO a (name) VALUES ('"), "{}", sql);
+    }
+
+    #[test]
+    fn test_statements_filter_on_inserted_rows() {
+        let tables = parse_schema("create table t (id int primary key, n int)").unwrap();
+        let mut generator = Generator::with_seed(tables, 3);
+        generator.set_max_predicates(1);
+        assert!(generator.insert_into("t").unwrap().starts_with("INSERT INTO t (id, n) VALUES (1,"));
+        generator.set_sql_types(vec![SqlType::Select]);
+        generator.set_complexity(Complexity::Simple);
+        for sql in statements(&mut generator, 20) {
+            let filter = sql.split(" WHERE ").nth(1).unwrap().trim_end_matches(';');
+            assert!(["id = 1", "id >= 1", "id <= 1"].contains(&filter) || filter.starts_with("n "), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_updates_target_inserted_keys() {
+        let tables = parse_schema("create table t (id int primary key, a varchar(10), b int)").unwrap();
+        let mut generator = Generator::with_seed(tables, 5);
+        generator.set_key_strategy(KeyStrategy::Sequential);
+        generator.insert_batch_into("t", 3).unwrap();
+        generator.set_sql_types(vec![SqlType::Update]);
+        let updates = statements(&mut generator, 50);
+        let by_key = updates.iter().filter(|sql| sql.ends_with(" WHERE id = 1;") || sql.ends_with(" WHERE id = 2;") || sql.ends_with(" WHERE id = 3;"));
+        assert!(by_key.count() > 25, "{:?}", updates);
+        assert!(updates.iter().all(|sql| !sql.contains("SET id =")), "{:?}", updates);
+    }
+
+    #[test]
+    fn test_next_change() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 4);
+        generator.set_mix("insert=5,update=3,delete=2,select=10".parse().unwrap());
+        // The last image of every row by table and primary key
+        let mut rows: HashMap<(usize, String), Vec<SqlValue>> = HashMap::new();
+        let mut ops = HashMap::new();
+        for _ in 0..500 {
+            let change = generator.next_change();
+            *ops.entry(change.op).or_insert(0) += 1;
+            if let Some(before) = &change.before {
+                assert_eq!(rows.remove(&(change.table, format!("{:?}", before[0]))).as_ref(), Some(before), "{:?}", change);
+                // Orders and products reference nothing but customers are referenced by orders
+                assert!(change.op == Operation::Update || generator.tables()[change.table].name != "customers");
+            }
+            if let Some(after) = change.after {
+                assert!(rows.insert((change.table, format!("{:?}", after[0])), after).is_none());
+            }
+        }
+        assert!(ops[&Operation::Create] > 150 && ops[&Operation::Update] > 75 && ops[&Operation::Delete] > 50, "{:?}", ops);
+    }
+
+    #[test]
+    fn test_delete_by_key() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 9);
+        generator.set_delete_by_key(true);
+        generator.set_sql_types(vec![SqlType::Delete]);
+        // Nothing is inserted yet, so rows are inserted instead
+        let mut sqls = vec![generator.next_statement()];
+        assert!(sqls[0].starts_with("INSERT INTO "));
+        generator.set_sql_types(vec![SqlType::Insert, SqlType::Delete]);
+        sqls.extend(statements(&mut generator, 300));
+        let (mut live, mut deleted) = (HashSet::new(), 0);
+        for sql in sqls {
+            if let Some(key) = sql.strip_prefix("DELETE FROM ") {
+                // Customers are referenced by orders, so only orders and products are deleted
+                assert!(!key.starts_with("customers"), "{}", sql);
+                assert!(live.remove(key), "{} deletes a row twice or one never inserted", sql);
+                deleted += 1;
+            } else if let Some(values) = sql.strip_prefix("INSERT INTO orders (order_id, order_date, customer_id) VALUES (") {
+                live.insert(format!("orders WHERE order_id = {};", values.split(',').next().unwrap()));
+            } else if let Some(values) = sql.strip_prefix("INSERT INTO products (product_id, product_name, product_price) VALUES (") {
+                live.insert(format!("products WHERE product_id = {};", values.split(',').next().unwrap()));
+            }
+        }
+        assert!(deleted > 50, "{}", deleted);
+    }
+
+    #[test]
+    fn test_next_row_parents_first() {
+        let tables = parse_schema("create table c (id int primary key, p_id int not null references p(id)); create table p (id int primary key);").unwrap();
+        let mut generator = Generator::with_seed(tables, 2);
+        let (table, _) = generator.next_row_parents_first();
+        assert_eq!(table.name, "p");
+        let names: HashSet<String> = (0..20).map(|_| generator.next_row_parents_first().0.name.clone()).collect();
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn test_table_checks() {
+        let table = Table::init_via_sql("create table t (id int primary key, low int not null, high int not null, check (high > low and low >= id))");
+        let mut generator = Generator::with_seed(vec![table], 5);
+        for _ in 0..50 {
+            let (_, row) = generator.row_for("t").unwrap();
+            let [SqlValue::Integer(id), SqlValue::Integer(low), SqlValue::Integer(high)] = row.as_slice() else { panic!("{:?}", row) };
+            assert!(high > low && low >= id, "{:?}", row);
+        }
+    }
+
+    #[test]
+    fn test_delete_forgets_keys() {
+        let tables = parse_schema("create table products (product_id int primary key, product_name varchar(20));").unwrap();
+        let mut generator = Generator::with_seed(tables, 3);
+        for _ in 0..5 {
+            generator.generate_row(0);
+        }
+        generator.set_sql_types(vec![SqlType::Delete]);
+        while generator.data.len("products") > 1 {
+            generator.next_statement();
+        }
+        // Only the key of the product left is remembered
+        let keys = generator.keys[&("products".to_string(), "product_id".to_string())].clone();
+        let left = generator.data.choose("products", &mut StdRng::seed_from_u64(1)).unwrap().to_vec();
+        assert_eq!(keys, [left[0].clone()]);
+    }
+
+    #[test]
+    fn test_delete_replays_consistently() {
+        let tables = parse_schema(
+            "create table users (id int primary key, name varchar(20));
+             create table orders (order_id int primary key, user_id int not null references users(id), total int);",
+        )
+        .unwrap();
+        let mut generator = Generator::with_seed(tables, 3);
+        generator.set_dialect(Dialect::Sqlite);
+        generator.set_mix("insert=60,delete=20,update=10,select=10".parse().unwrap());
+        let (mut users, mut orders, mut deleted) = (HashSet::new(), HashSet::new(), 0);
+        for statement in generator.by_ref().take(600) {
+            match (statement.sql_type, statement.table.as_str()) {
+                (SqlType::Insert, "users") => assert!(users.insert(statement.values[0].clone())),
+                (SqlType::Insert, _) => {
+                    assert!(users.contains(&statement.values[1]), "{} references a missing user", statement);
+                    assert!(orders.insert(Dialect::Sqlite.literal(&statement.values[0])));
+                }
+                (SqlType::Delete, _) => {
+                    // Every DELETE removes exactly one order inserted before, or filters on random values while none is left
+                    match statement.text.strip_prefix("DELETE FROM orders WHERE order_id = ").and_then(|key| key.strip_suffix(';')).filter(|key| !key.contains(' ')) {
+                        Some(key) => assert!(orders.remove(key), "{}", statement),
+                        None => assert!(orders.is_empty(), "{}", statement),
+                    }
+                    deleted += 1;
+                }
+                _ => {}
+            }
+        }
+        assert!(deleted > 50, "{}", deleted);
+    }
+
+    #[test]
+    fn test_delete_skips_referenced_tables() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_mix("insert=6,delete=4".parse().unwrap());
+        let deletes: Vec<Statement> = generator.by_ref().take(300).filter(|s| s.sql_type == SqlType::Delete).collect();
+        // Customers are referenced by orders, so deleting one could leave orders without it
+        assert!(deletes.len() > 50, "{}", deletes.len());
+        assert!(deletes.iter().all(|s| s.table != "customers"), "{:?}", deletes);
+    }
+
+    #[test]
+    fn test_split() {
+        let tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+        let mut first = Generator::with_seed(tables.clone(), 5).split(3);
+        let mut second = Generator::with_seed(tables, 5).split(3);
+        assert_eq!(first[2].next_statement(), second[2].next_statement());
+
+        let mut customers = HashSet::new();
+        for shard in &mut first {
+            for _ in 0..10 {
+                let (_, row) = shard.row_for("customers").unwrap();
+                assert!(customers.insert(row[0].clone()), "{:?}", row[0]);
+            }
+            // Children only reference the keys of their own shard
+            let (_, row) = shard.row_for("orders").unwrap();
+            assert!(shard.keys[&("customers".to_string(), "customer_id".to_string())].contains(&row[2]));
+        }
+        assert!((1..=30).all(|id| customers.contains(&SqlValue::Integer(id))));
+    }
+
+    #[test]
+    fn test_schema_statements() {
+        let generator = Generator::new(parse_schema(DEFAULT_SCHEMA).unwrap());
+        let statements = generator.schema_statements();
+        assert_eq!(statements.len(), 4);
+        assert!(statements[0].starts_with("CREATE TABLE customers "));
+        assert!(statements[1].starts_with("CREATE TABLE orders "));
+        assert_eq!(statements[2], "CREATE INDEX idx_orders_customer_id ON orders (customer_id);");
+        assert!(statements[3].starts_with("CREATE TABLE products "));
+    }
+
+    #[test]
+    fn test_inserts_fill_parents_first() {
+        let tables = parse_schema(
+            "create table items (id int primary key, order_id int not null references orders(id));
+             create table orders (id int primary key, customer_id int not null references customers(id));
+             create table customers (id int primary key);",
+        )
+        .unwrap();
+        let mut generator = Generator::with_seed(tables, 8);
+        generator.set_sql_types(vec![SqlType::Insert]);
+        let mut inserted = HashSet::new();
+        for statement in statements(&mut generator, 30) {
+            let table = statement.split(' ').nth(2).unwrap().to_string();
+            match table.as_str() {
+                "orders" => assert!(inserted.contains("customers"), "{}", statement),
+                "items" => assert!(inserted.contains("orders"), "{}", statement),
+                _ => {}
+            }
+            inserted.insert(table);
+        }
+    }
+
+    #[test]
+    fn test_insert_into() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 4);
+        let sql = generator.insert_into("customers").unwrap();
+        assert!(sql.starts_with("INSERT INTO customers "), "{}", sql);
+        assert!(generator.insert_into("missing").is_none());
+    }
+
+    #[test]
+    fn test_null_rate() {
+        let tables = parse_schema("create table t (id number(10) primary key, a varchar(20), b varchar(20) not null)").unwrap();
+        let mut generator = Generator::with_seed(tables, 8);
+        generator.set_null_rate(0.5);
+        let rows: Vec<Vec<SqlValue>> = (0..200).map(|_| generator.row_for("t").unwrap().1).collect();
+        let nulls = |i: usize| rows.iter().filter(|row| row[i] == SqlValue::Null).count();
+        assert_eq!(nulls(0), 0);
+        assert!((60..140).contains(&nulls(1)), "{}", nulls(1));
+        assert_eq!(nulls(2), 0);
+    }
+
+    #[test]
+    fn test_batch_size() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 4);
+        generator.set_dialect(Dialect::Postgres);
+        generator.set_sql_types(vec![SqlType::Insert]);
+        generator.set_batch_size(3);
+        assert!(statements(&mut generator, 10).iter().all(|sql| sql.matches("), (").count() == 2));
+        let batch = generator.insert_batch_into("customers", 5).unwrap();
+        assert_eq!(batch.matches("), (").count(), 4, "{}", batch);
+    }
+
+    #[test]
+    fn test_set_mix() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_mix("insert=1,delete=1,drop-table=0".parse().unwrap());
+        assert!(statements(&mut generator, 50)
+            .iter()
+            .all(|sql| sql.starts_with("INSERT") || sql.starts_with("DELETE")));
+    }
+
+    #[test]
+    fn test_set_sql_types() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_sql_types(vec![SqlType::DropTable]);
+        assert!(statements(&mut generator, 20).iter().all(|sql| sql.starts_with("DROP TABLE")));
+    }
+
+    #[test]
+    fn test_builder() {
+        let tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+        let mut generator = Generator::with_seed(tables.clone(), 5);
+        generator.set_dialect(Dialect::MySql);
+        generator.set_batch_size(2);
+        let expected = statements(&mut generator, 20);
+
+        let built: Vec<String> = GeneratorBuilder::new()
+            .tables(tables)
+            .dialect(Dialect::MySql)
+            .batch_size(2)
+            .seed(5)
+            .build()
+            .take(20)
+            .map(String::from)
+            .collect();
+        assert_eq!(built, expected);
+    }
+
+    #[test]
+    fn test_iterator() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        let expected = statements(&mut generator.clone(), 30);
+        let inserts: Vec<Statement> = generator.by_ref().take(30).filter(|s| s.sql_type == SqlType::Insert).collect();
+        let expected_inserts: Vec<&String> = expected.iter().filter(|sql| sql.starts_with("INSERT")).collect();
+        assert!(!inserts.is_empty());
+        assert_eq!(inserts.iter().map(|s| &s.text).collect::<Vec<_>>(), expected_inserts);
+        assert!(generator.next().is_some());
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn test_stream() {
+        use futures_core::Stream;
+        use std::pin::Pin;
+        use std::task::{Context, Poll, Waker};
+
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        let expected = generator.clone().next_statement();
+        let mut cx = Context::from_waker(Waker::noop());
+        match Pin::new(&mut generator).poll_next(&mut cx) {
+            Poll::Ready(Some(statement)) => assert_eq!(statement.text, expected),
+            other => panic!("unexpected poll result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_safe() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 11);
+        generator.set_safe(true);
+        generator.set_evolve_every(Some(7));
+        let sqls = statements(&mut generator, 2000);
+        let delete = regex::Regex::new(r"^DELETE FROM \w+ WHERE (order|product)_id = \d+;$").unwrap();
+        assert!(sqls.iter().any(|sql| sql.starts_with("DELETE ")) && sqls.iter().any(|sql| sql.starts_with("ALTER ")));
+        for sql in &sqls {
+            assert!(!sql.starts_with("DROP ") && !sql.starts_with("TRUNCATE ") && !sql.contains(" DROP COLUMN "), "{}", sql);
+            assert!(!sql.starts_with("DELETE ") || delete.is_match(sql), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_mix("insert=5,select=3,drop-table=1".parse().unwrap());
+        generator.set_safe(true);
+        let mut unpreviewed = generator.clone();
+        let previews = generator.preview(4);
+        assert_eq!(previews.iter().map(|p| p.table.as_str()).collect::<Vec<_>>(), ["orders", "customers", "products"]);
+        let orders = &previews[0];
+        assert!(orders.columns.contains(&("customer_id".to_string(), "references customers.customer_id".to_string())), "{:?}", orders.columns);
+        assert_eq!(orders.statements.iter().map(|(sql_type, _)| *sql_type).collect::<Vec<_>>(), [SqlType::Insert, SqlType::Select]);
+        assert!(orders.statements[0].1.iter().all(|sql| sql.starts_with("INSERT INTO orders ")), "{:?}", orders.statements);
+        // The preview leaves the generator unchanged
+        assert_eq!(statements(&mut generator, 20), statements(&mut unpreviewed, 20));
+    }
+
+    #[derive(Clone)]
+    struct Counter(i64);
+
+    impl ValueGenerator for Counter {
+        fn generate(&mut self, ctx: &RowContext) -> SqlValue {
+            self.0 += ctx.rng().gen_range(1..=3);
+            SqlValue::Integer(self.0)
+        }
+    }
+
+    #[test]
+    fn test_set_registry() {
+        let mut table = Table::init_via_sql("create table t (id int primary key, a int, b int)");
+        table.columns[1].custom_generator = Some("counter".to_string());
+        table.columns[2].custom_generator = Some("Counter".to_string());
+        let mut registry = GeneratorRegistry::new();
+        registry.register("counter", Counter(0)).unwrap();
+        let mut generator = Generator::with_seed(vec![table], 5);
+        generator.set_registry(&registry).unwrap();
+        let rows: Vec<Vec<SqlValue>> = (0..20).map(|_| generator.row_for("t").unwrap().1).collect();
+        // Every column counts with a copy of its own
+        for column in 1..=2 {
+            let counts: Vec<i64> = rows.iter().map(|row| if let SqlValue::Integer(n) = row[column] { n } else { panic!("{:?}", row) }).collect();
+            assert!(counts.windows(2).all(|pair| pair[0] < pair[1]) && counts[19] <= 60, "{:?}", counts);
+        }
+        let mut same = Generator::with_seed(generator.tables.clone(), 5);
+        same.set_registry(&registry).unwrap();
+        assert_eq!((0..20).map(|_| same.row_for("t").unwrap().1).collect::<Vec<_>>(), rows);
+    }
+
+    #[test]
+    fn test_generate_sql_type() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_delete_by_key(true);
+        for _ in 0..50 {
+            let statement = generator.generate();
+            let keyword = statement.text.split_whitespace().next().unwrap().to_string();
+            let expected = match statement.sql_type {
+                SqlType::CreateTable | SqlType::CreateIndex | SqlType::CreateView => "CREATE",
+                SqlType::AlterTable => "ALTER",
+                SqlType::DropTable => "DROP",
+                SqlType::Insert => "INSERT",
+                SqlType::Update => "UPDATE",
+                SqlType::Delete => "DELETE",
+                _ => keyword.as_str(),
+            };
+            assert_eq!(keyword, expected, "{}", statement);
+        }
+    }
+
+    #[test]
+    fn test_evolve_every() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_dialect(Dialect::Postgres);
+        generator.set_mix("insert=1".parse().unwrap());
+        generator.set_evolve_every(Some(2));
+        let mut changes = 0;
+        for n in 1..=300 {
+            let statement = generator.generate();
+            if n % 2 == 0 {
+                assert!(matches!(statement.sql_type, SqlType::AlterTable | SqlType::CreateIndex), "{}", statement);
+                changes += 1;
+                continue;
+            }
+            // Every INSERT names exactly the columns the table has after the changes so far
+            let table = generator.tables().iter().find(|t| t.name == statement.table).unwrap();
+            let columns: Vec<&str> = table.columns.iter().filter(|c| !c.auto_increment).map(|c| c.name.as_str()).collect();
+            let prefix = format!("INSERT INTO {} ({}) VALUES", table.name, columns.join(", "));
+            assert!(statement.text.starts_with(&prefix), "{} does not start with {}", statement, prefix);
+        }
+        assert_eq!(changes, 150);
+        // Referenced tables and key columns survive
+        let tables = generator.tables();
+        assert!(tables.iter().any(|t| t.name == "customers" && t.column("customer_id").is_some()));
+        assert!(tables.iter().flat_map(|t| &t.columns).any(|c| c.ref_table.as_deref() == Some("customers")));
+    }
+
+    #[test]
+    fn test_evolve_every_excluded_types() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_mix("insert=1".parse().unwrap());
+        generator.set_evolve_every(Some(2));
+        generator.set_excluded_types(vec![SqlType::AlterTable]);
+        assert!(generator.by_ref().take(100).all(|s| matches!(s.sql_type, SqlType::Insert | SqlType::CreateIndex)));
+        generator.set_excluded_types(vec![SqlType::AlterTable, SqlType::CreateIndex]);
+        assert!(generator.by_ref().take(100).all(|s| s.sql_type == SqlType::Insert));
+    }
+
+    #[test]
+    fn test_param_style() {
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 3);
+        generator.set_dialect(Dialect::Postgres);
+        generator.set_param_style(Some(ParamStyle::Named));
+        generator.set_mix("insert=1,select=1".parse().unwrap());
+        for _ in 0..50 {
+            let sql = generator.next_statement();
+            match generator.take_params() {
+                Some(serde_json::Value::Object(params)) => {
+                    assert!(sql.starts_with("INSERT"));
+                    assert!(params.keys().all(|name| sql.contains(&format!(":{}", name))), "{}", sql);
+                }
+                Some(params) => panic!("unexpected parameters {}", params),
+                None => assert!(sql.starts_with("SELECT")),
+            }
+            assert!(generator.take_params().is_none());
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Spatial values of `geometry` and `geography` columns.
+
+use rand::Rng;
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+/// The spatial reference system of generated values: WGS 84 longitudes and latitudes.
+pub const SRID: u32 = 4326;
+
+/// Most points, lines or polygons of a generated line or multi-geometry.
+const MAX_PARTS: usize = 4;
+
+/// The area spatial values are drawn within, written `min_lon,min_lat,max_lon,max_lat`,
+/// e.g. `13.0,52.3,13.8,52.7` for Berlin. Defaults to the whole world.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::geo::BoundingBox;
+/// let berlin: BoundingBox = "13.0, 52.3, 13.8, 52.7".parse().unwrap();
+/// assert_eq!(berlin.to_string(), "13,52.3,13.8,52.7");
+/// assert!("13.8,52.3,13.0,52.7".parse::<BoundingBox>().is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+impl BoundingBox {
+    /// Returns a random point within the box, as longitude and latitude.
+    fn random_point<R: Rng + ?Sized>(&self, rng: &mut R) -> (f64, f64) {
+        (rng.gen_range(self.min_lon..=self.max_lon), rng.gen_range(self.min_lat..=self.max_lat))
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        BoundingBox { min_lon: -180.0, min_lat: -90.0, max_lon: 180.0, max_lat: 90.0 }
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.min_lon, self.min_lat, self.max_lon, self.max_lat)
+    }
+}
+
+impl FromStr for BoundingBox {
+    type Err = String;
+
+    /// Parses a box such as `13.0,52.3,13.8,52.7`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid bounding box `{}`; expected `min_lon,min_lat,max_lon,max_lat`, e.g. `13.0,52.3,13.8,52.7`", s);
+        let numbers = s.split(',').map(|n| n.trim().parse::<f64>()).collect::<Result<Vec<f64>, _>>().map_err(|_| invalid())?;
+        let [min_lon, min_lat, max_lon, max_lat] = numbers[..] else { return Err(invalid()) };
+        if !(-180.0..=180.0).contains(&min_lon) || !(-180.0..=180.0).contains(&max_lon) || !(-90.0..=90.0).contains(&min_lat) || !(-90.0..=90.0).contains(&max_lat) {
+            return Err(format!("the bounding box `{}` is beyond longitudes -180 to 180 and latitudes -90 to 90", s));
+        }
+        if min_lon >= max_lon || min_lat >= max_lat {
+            return Err(format!("the bounding box `{}` is empty; the minimums must be smaller than the maximums", s));
+        }
+        Ok(BoundingBox { min_lon, min_lat, max_lon, max_lat })
+    }
+}
+
+/// The kind of a spatial value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+}
+
+impl Shape {
+    /// Every shape, in the order of the Well-Known Text specification.
+    pub const ALL: [Shape; 6] = [Shape::Point, Shape::LineString, Shape::Polygon, Shape::MultiPoint, Shape::MultiLineString, Shape::MultiPolygon];
+
+    /// Returns the name of the shape, e.g. `LineString`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Point => "Point",
+            Shape::LineString => "LineString",
+            Shape::Polygon => "Polygon",
+            Shape::MultiPoint => "MultiPoint",
+            Shape::MultiLineString => "MultiLineString",
+            Shape::MultiPolygon => "MultiPolygon",
+        }
+    }
+
+    /// Returns the shape called `name`, ignoring case, e.g. `point` or `MULTIPOLYGON`.
+    pub fn from_name(name: &str) -> Option<Shape> {
+        Shape::ALL.into_iter().find(|shape| shape.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Returns whether a spatial column type is a geography and the shape it holds, e.g.
+/// `(false, Some(Shape::Point))` for `geometry(point)` or `(true, None)` for `geography`.
+///
+/// # Returns
+///
+/// `None` for column types that are not spatial.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::geo::{spatial_type, Shape};
+/// assert_eq!(spatial_type("geography(polygon)"), Some((true, Some(Shape::Polygon))));
+/// assert_eq!(spatial_type("geometry"), Some((false, None)));
+/// assert_eq!(spatial_type("int"), None);
+/// ```
+pub fn spatial_type(column_type: &str) -> Option<(bool, Option<Shape>)> {
+    let (base, shape) = match column_type.split_once('(') {
+        Some((base, shape)) => (base, Some(Shape::from_name(shape.strip_suffix(')')?)?)),
+        None => (column_type, None),
+    };
+    match base {
+        "geometry" => Some((false, shape)),
+        "geography" => Some((true, shape)),
+        _ => None,
+    }
+}
+
+/// Generates a random value of `shape` within `bounding_box`, as Well-Known Text such as
+/// `POINT(13.404954 52.520008)`.
+///
+/// Polygons are small rings around a random center, counter-clockwise as SQL Server's
+/// `geography` expects, and lines join random points of the box.
+///
+/// # Arguments
+///
+/// * `shape` - The shape of the value; a point, line or polygon when `None`.
+/// * `bounding_box` - The area every coordinate lies within.
+/// * `rng` - The random number generator the coordinates are drawn from.
+pub fn random_wkt<R: Rng + ?Sized>(shape: Option<Shape>, bounding_box: BoundingBox, rng: &mut R) -> String {
+    let shape = shape.unwrap_or_else(|| [Shape::Point, Shape::LineString, Shape::Polygon][rng.gen_range(0..3)]);
+    let parts = |rng: &mut R, part: &dyn Fn(&mut R) -> String| -> String {
+        let parts: Vec<String> = (0..rng.gen_range(2..=MAX_PARTS)).map(|_| part(rng)).collect();
+        parts.join(", ")
+    };
+    let text = match shape {
+        Shape::Point => format!("({})", coordinates(&[bounding_box.random_point(rng)])),
+        Shape::LineString => format!("({})", line(bounding_box, rng)),
+        Shape::Polygon => format!("(({}))", ring(bounding_box, rng)),
+        Shape::MultiPoint => format!("({})", parts(rng, &|rng| format!("({})", coordinates(&[bounding_box.random_point(rng)])))),
+        Shape::MultiLineString => format!("({})", parts(rng, &|rng| format!("({})", line(bounding_box, rng)))),
+        Shape::MultiPolygon => format!("({})", parts(rng, &|rng| format!("(({}))", ring(bounding_box, rng)))),
+    };
+    format!("{}{}", shape.name().to_uppercase(), text)
+}
+
+/// Returns the coordinates of a line through two to four random points.
+fn line<R: Rng + ?Sized>(bounding_box: BoundingBox, rng: &mut R) -> String {
+    let points: Vec<(f64, f64)> = (0..rng.gen_range(2..=MAX_PARTS)).map(|_| bounding_box.random_point(rng)).collect();
+    coordinates(&points)
+}
+
+/// Returns the coordinates of a closed counter-clockwise ring of three to six points,
+/// spanning at most a hundredth of the box.
+fn ring<R: Rng + ?Sized>(bounding_box: BoundingBox, rng: &mut R) -> String {
+    let (center_lon, center_lat) = bounding_box.random_point(rng);
+    let radius = (bounding_box.max_lon - bounding_box.min_lon).min(bounding_box.max_lat - bounding_box.min_lat) / 200.0;
+    let count = rng.gen_range(3..=6);
+    let mut points: Vec<(f64, f64)> = (0..count)
+        .map(|i| {
+            // Angles increase around the center, so the ring runs counter-clockwise
+            let angle = (i as f64 + rng.gen_range(0.0..0.5)) * 2.0 * PI / count as f64;
+            let distance = radius * rng.gen_range(0.5..=1.0);
+            let lon = (center_lon + distance * angle.cos()).clamp(bounding_box.min_lon, bounding_box.max_lon);
+            let lat = (center_lat + distance * angle.sin()).clamp(bounding_box.min_lat, bounding_box.max_lat);
+            (lon, lat)
+        })
+        .collect();
+    points.push(points[0]);
+    coordinates(&points)
+}
+
+/// Formats points as `lon lat, lon lat, ...` with six decimal places, about 10 cm.
+fn coordinates(points: &[(f64, f64)]) -> String {
+    let points: Vec<String> = points.iter().map(|(lon, lat)| format!("{:.6} {:.6}", lon, lat)).collect();
+    points.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_random_wkt() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let berlin: BoundingBox = "13.0,52.3,13.8,52.7".parse().unwrap();
+        for shape in Shape::ALL {
+            for _ in 0..20 {
+                let wkt = random_wkt(Some(shape), berlin, &mut rng);
+                assert!(wkt.starts_with(&format!("{}(", shape.name().to_uppercase())), "{}", wkt);
+                assert_eq!(wkt.matches('(').count(), wkt.matches(')').count(), "{}", wkt);
+                let numbers = wkt.split(|c: char| !c.is_ascii_digit() && c != '.' && c != '-').filter(|n| !n.is_empty());
+                for (i, number) in numbers.enumerate() {
+                    let number: f64 = number.parse().unwrap();
+                    let (min, max) = if i % 2 == 0 { (13.0, 13.8) } else { (52.3, 52.7) };
+                    assert!((min..=max).contains(&number), "{}", wkt);
+                }
+            }
+        }
+        let polygon = random_wkt(Some(Shape::Polygon), berlin, &mut rng);
+        let points: Vec<&str> = polygon["POLYGON((".len()..polygon.len() - 2].split(", ").collect();
+        assert_eq!(points.first(), points.last());
+
+        for invalid in ["1,2,3", "0,0,0,0", "-181,0,0,1", "a,b,c,d"] {
+            assert!(invalid.parse::<BoundingBox>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! A gRPC service streaming generated statements at a requested rate, so long-running load
+//! tests can be driven from other languages.
+//!
+//! Only available with the `grpc` feature, which serves the `fake_sql.FakeSql` service of
+//! `proto/fake_sql.proto` through `tonic`. Its server-streaming `Generate` call sends one
+//! [`GeneratedStatement`] per statement, paced by the `rate` of the [`GenerateRequest`],
+//! until `count` statements were sent or, with a `count` of 0, until the client cancels it.
+
+use crate::dialect::Dialect;
+use crate::generator::Generator;
+use crate::models::{SqlType, Table};
+use crate::rate::{Pacer, Rate};
+use crate::schema::parse_schema;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/fake_sql.FakeSql.rs"));
+}
+
+pub use proto::fake_sql_client::FakeSqlClient;
+pub use proto::fake_sql_server::{FakeSql, FakeSqlServer};
+
+/// Number of generated statements buffered ahead of a client that reads slower than the rate.
+pub const STREAM_BUFFER: usize = 64;
+
+/// What a `Generate` call asks for, the `GenerateRequest` message of `proto/fake_sql.proto`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GenerateRequest {
+    /// CREATE TABLE statements to generate for; empty for the schema of the server.
+    #[prost(string, tag = "1")]
+    pub schema: String,
+    /// Only generate statements for this table; empty for every table.
+    #[prost(string, tag = "2")]
+    pub table: String,
+    /// Statement types such as `insert` or `select-join`; empty for all of them.
+    #[prost(string, repeated, tag = "3")]
+    pub types: Vec<String>,
+    /// Number of statements; 0 streams until the call is cancelled.
+    #[prost(uint64, tag = "4")]
+    pub count: u64,
+    /// Pace of the statements such as `500/s`, like `--rate`; empty for as fast as possible.
+    #[prost(string, tag = "5")]
+    pub rate: String,
+    /// Spread the statements as a Poisson process instead of evenly, like `--poisson`.
+    #[prost(bool, tag = "6")]
+    pub poisson: bool,
+    /// SQL dialect such as `postgres`; empty for the dialect of the server.
+    #[prost(string, tag = "7")]
+    pub dialect: String,
+    /// Seed of the random generator, for reproducible streams.
+    #[prost(uint64, optional, tag = "8")]
+    pub seed: Option<u64>,
+}
+
+/// A statement of a `Generate` stream, the `GeneratedStatement` message of
+/// `proto/fake_sql.proto`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GeneratedStatement {
+    /// The SQL text, terminated by a semicolon.
+    #[prost(string, tag = "1")]
+    pub sql: String,
+    /// The table the statement was generated for.
+    #[prost(string, tag = "2")]
+    pub table: String,
+    /// The kebab-case name of the statement type, e.g. `insert`.
+    #[prost(string, tag = "3")]
+    pub r#type: String,
+    /// Position of the statement in the stream, counted from 1.
+    #[prost(uint64, tag = "4")]
+    pub number: u64,
+}
+
+/// Implements the `FakeSql` service for the tables it was started with.
+///
+/// Every call runs a generator of its own, so calls are independent and a call with a
+/// `seed` streams the same statements for the same schema.
+pub struct GrpcService {
+    tables: Vec<Table>,
+    dialect: Dialect,
+}
+
+impl GrpcService {
+    /// Initializes a new `GrpcService`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - The tables of calls that send no schema.
+    /// * `dialect` - The dialect of calls that name none.
+    pub fn new(tables: Vec<Table>, dialect: Dialect) -> GrpcService {
+        GrpcService { tables, dialect }
+    }
+
+    /// Listens on `address`, e.g. `127.0.0.1:50051`, and serves calls until the process ends.
+    pub fn run(self, address: &str) -> Result<(), String> {
+        let address = address.parse().map_err(|err| format!("invalid address {}: {}", address, err))?;
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().map_err(|err| err.to_string())?;
+        runtime.block_on(async {
+            tonic::transport::Server::builder()
+                .add_service(FakeSqlServer::new(self))
+                .serve(address)
+                .await
+                .map_err(|err| format!("cannot serve on {}: {}", address, err))
+        })
+    }
+
+    /// Returns the generator and pace of a call, or why the request is invalid.
+    fn prepare(&self, request: &GenerateRequest) -> Result<(Generator, Option<Rate>), String> {
+        let tables = match request.schema.trim() {
+            "" => self.tables.clone(),
+            schema => parse_schema(schema)?,
+        };
+        let tables = match request.table.as_str() {
+            "" => tables,
+            name => vec![tables.into_iter().find(|t| t.name.eq_ignore_ascii_case(name)).ok_or_else(|| format!("table `{}` is not in the schema", name))?],
+        };
+        if tables.is_empty() {
+            return Err("the schema holds no CREATE TABLE statements".to_string());
+        }
+        let mut generator = match request.seed {
+            Some(seed) => Generator::with_seed(tables, seed),
+            None => Generator::new(tables),
+        };
+        generator.set_dialect(match request.dialect.as_str() {
+            "" => self.dialect,
+            dialect => dialect.parse()?,
+        });
+        if !request.types.is_empty() {
+            generator.set_sql_types(request.types.iter().map(|name| name.parse()).collect::<Result<Vec<SqlType>, String>>()?);
+        }
+        let rate = match request.rate.as_str() {
+            "" => None,
+            rate => Some(rate.parse::<Rate>()?.with_poisson(request.poisson)),
+        };
+        Ok((generator, rate))
+    }
+}
+
+#[tonic::async_trait]
+impl FakeSql for GrpcService {
+    type GenerateStream = ReceiverStream<Result<GeneratedStatement, Status>>;
+
+    async fn generate(&self, request: Request<GenerateRequest>) -> Result<Response<Self::GenerateStream>, Status> {
+        let request = request.into_inner();
+        let (mut generator, rate) = self.prepare(&request).map_err(Status::invalid_argument)?;
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        tokio::spawn(async move {
+            let mut pacer = rate.map(Pacer::new);
+            let mut number = 0;
+            while request.count == 0 || number < request.count {
+                if let Some(pacer) = &mut pacer {
+                    tokio::time::sleep(pacer.delay()).await;
+                }
+                let statement = generator.generate();
+                number += 1;
+                let message = GeneratedStatement { sql: statement.text, table: statement.table, r#type: statement.sql_type.name().to_string(), number };
+                // The client cancelled the call or went away
+                if sender.send(Ok(message)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::DEFAULT_SCHEMA;
+    use std::time::Instant;
+    use tokio_stream::StreamExt;
+
+    /// Returns the statements of a call, or its error.
+    async fn call(service: &GrpcService, request: GenerateRequest) -> Result<Vec<GeneratedStatement>, Status> {
+        let stream = service.generate(Request::new(request)).await?.into_inner();
+        stream.collect::<Result<Vec<_>, Status>>().await
+    }
+
+    #[test]
+    fn test_generate() {
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+        runtime.block_on(generate());
+    }
+
+    async fn generate() {
+        let service = GrpcService::new(parse_schema(DEFAULT_SCHEMA).unwrap(), Dialect::Postgres);
+        let request = GenerateRequest { types: vec!["insert".into()], count: 20, seed: Some(7), ..Default::default() };
+        let statements = call(&service, request.clone()).await.unwrap();
+        assert_eq!(statements.len(), 20);
+        assert_eq!(statements.iter().map(|s| s.number).collect::<Vec<_>>(), (1..=20).collect::<Vec<_>>());
+        assert!(statements.iter().all(|s| s.r#type == "insert" && s.sql.starts_with(&format!("INSERT INTO {} (", s.table))));
+        assert_eq!(call(&service, request).await.unwrap(), statements);
+
+        let request = GenerateRequest { schema: "create table t (id int primary key)".into(), count: 5, rate: "100/s".into(), dialect: "mysql".into(), ..Default::default() };
+        let start = Instant::now();
+        let statements = call(&service, request).await.unwrap();
+        assert!(start.elapsed().as_millis() >= 40);
+        assert!(statements.iter().all(|s| s.table == "t"));
+
+        for request in [GenerateRequest { table: "nowhere".into(), ..Default::default() }, GenerateRequest { types: vec!["merge".into()], ..Default::default() }, GenerateRequest { rate: "fast".into(), ..Default::default() }] {
+            assert_eq!(call(&service, request).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Publishing of generated statements or rows to a Kafka topic, for testing streaming
+//! consumers such as CDC pipelines without a database in between.
+//!
+//! Only available with the `kafka` feature, which talks to the brokers through the pure
+//! Rust `kafka` crate over plaintext connections.
+
+use crate::models::Table;
+use crate::output::{row_json, RowWriter};
+use crate::statement::Statement;
+use crate::value::SqlValue;
+use kafka::producer::{Producer, Record, RequiredAcks};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Number of messages sent to the brokers in one request.
+pub const BATCH_SIZE: usize = 500;
+
+/// How messages are spread over the partitions of the topic, through their keys.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Partitioning {
+    /// Keyed by table name, so the messages of a table stay in order in one partition.
+    #[default]
+    Table,
+    /// Keyed by a simulated client session, `session-1` to `session-N`, taking turns
+    /// message by message, so each session's messages stay in order in one partition.
+    Session,
+    /// Without keys, spread over the partitions in turn.
+    None,
+}
+
+impl fmt::Display for Partitioning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Partitioning::Table => "table",
+            Partitioning::Session => "session",
+            Partitioning::None => "none",
+        })
+    }
+}
+
+impl FromStr for Partitioning {
+    type Err = String;
+
+    /// Parses `table`, `session` or `none`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(Partitioning::Table),
+            "session" => Ok(Partitioning::Session),
+            "none" => Ok(Partitioning::None),
+            _ => Err(format!("unknown partitioning `{}`; expected `table`, `session` or `none`", s)),
+        }
+    }
+}
+
+/// Returns the key of the `number`-th message of a run, counted from 0, for a statement or
+/// row of `table`; an empty key leaves the partition to the producer.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::kafka::{message_key, Partitioning};
+/// assert_eq!(message_key(Partitioning::Table, "orders", 7, 4), "orders");
+/// assert_eq!(message_key(Partitioning::Session, "orders", 7, 4), "session-4");
+/// assert_eq!(message_key(Partitioning::None, "orders", 7, 4), "");
+/// ```
+pub fn message_key(partitioning: Partitioning, table: &str, number: u64, sessions: u32) -> String {
+    match partitioning {
+        Partitioning::Table => table.to_string(),
+        Partitioning::Session => format!("session-{}", number % sessions.max(1) as u64 + 1),
+        Partitioning::None => String::new(),
+    }
+}
+
+/// Publishes statements or rows to a Kafka topic, one message per statement or row.
+///
+/// Statements are sent as their SQL text and rows as JSON objects with the table name as
+/// their only key, like the lines of `--format jsonl`. Messages are sent in batches of
+/// [`BATCH_SIZE`] and every batch waits for the partition leaders to acknowledge it.
+pub struct KafkaSink {
+    producer: Producer,
+    topic: String,
+    partitioning: Partitioning,
+    sessions: u32,
+    /// Keys and values of the messages not sent yet.
+    pending: Vec<(String, Vec<u8>)>,
+    /// Messages sent or pending.
+    messages: u64,
+}
+
+impl KafkaSink {
+    /// Connects to the brokers and initializes a `KafkaSink` publishing to `topic`, keyed
+    /// by table.
+    ///
+    /// # Arguments
+    ///
+    /// * `brokers` - Addresses of the brokers to bootstrap from, e.g. `localhost:9092`.
+    /// * `topic` - The topic, which must exist unless the brokers create topics on demand.
+    pub fn connect(brokers: Vec<String>, topic: &str) -> Result<KafkaSink, String> {
+        let producer = Producer::from_hosts(brokers.clone())
+            .with_ack_timeout(Duration::from_secs(10))
+            .with_required_acks(RequiredAcks::One)
+            .with_client_id("fake-sql".to_string())
+            .create()
+            .map_err(|err| format!("cannot connect to Kafka at {}: {}", brokers.join(","), err))?;
+        Ok(KafkaSink {
+            producer,
+            topic: topic.to_string(),
+            partitioning: Partitioning::default(),
+            sessions: crate::output::DEFAULT_TRACE_SESSIONS,
+            pending: Vec::with_capacity(BATCH_SIZE),
+            messages: 0,
+        })
+    }
+
+    /// Sets how messages are spread over the partitions of the topic.
+    pub fn set_partitioning(&mut self, partitioning: Partitioning) {
+        self.partitioning = partitioning;
+    }
+
+    /// Sets the number of sessions messages are keyed by with [`Partitioning::Session`].
+    pub fn set_sessions(&mut self, sessions: u32) {
+        self.sessions = sessions.max(1);
+    }
+
+    /// Returns the number of messages published so far, including those still pending.
+    pub fn messages(&self) -> u64 {
+        self.messages
+    }
+
+    /// Publishes the SQL text of `statement`.
+    pub fn send_statement(&mut self, statement: &Statement) -> io::Result<()> {
+        self.push(&statement.table, statement.text.clone().into_bytes())
+    }
+
+    /// Queues a message for `table`, sending the batch once it is full.
+    fn push(&mut self, table: &str, value: Vec<u8>) -> io::Result<()> {
+        let key = message_key(self.partitioning, table, self.messages, self.sessions);
+        self.pending.push((key, value));
+        self.messages += 1;
+        if self.pending.len() >= BATCH_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl RowWriter for KafkaSink {
+    fn write_row(&mut self, table: &Table, row: &[SqlValue]) -> io::Result<()> {
+        self.push(&table.name, row_json(table, row).to_string().into_bytes())
+    }
+
+    /// Sends the pending messages and checks every partition acknowledged them.
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let records: Vec<_> = self.pending.iter().map(|(key, value)| Record::from_key_value(&self.topic, key.as_bytes(), &value[..])).collect();
+        let confirms = self.producer.send_all(&records).map_err(|err| io::Error::other(format!("cannot publish to {}: {}", self.topic, err)))?;
+        for confirm in confirms {
+            for partition in confirm.partition_confirms {
+                if let Err(code) = partition.offset {
+                    return Err(io::Error::other(format!("partition {} of {} rejected the messages: {:?}", partition.partition, confirm.topic, code)));
+                }
+            }
+        }
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_keys() {
+        let keys: Vec<String> = (0..5).map(|number| message_key(Partitioning::Session, "orders", number, 2)).collect();
+        assert_eq!(keys, ["session-1", "session-2", "session-1", "session-2", "session-1"]);
+        assert_eq!(message_key(Partitioning::Session, "orders", 3, 0), "session-1");
+        assert_eq!("Session".parse(), Ok(Partitioning::Session));
+        assert!("random".parse::<Partitioning>().is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! `fake-sql` generates random SQL statements for a set of tables.
+//!
+//! The library exposes the table model ([`Table`], [`Column`]) together with the
+//! [`SqlType`] enum so other crates can embed the generator, for example to build
+//! test fixtures, instead of shelling out to the `fake-sql` binary.
+//!
+//! # Example
+//!
+//! ```
+//! use fake_sql::{SqlType, Table};
+//!
+//! let orders = Table::init_via_sql("create table orders(order_id number(10) primary key, order_date date)");
+//! let statement = orders.generate(SqlType::CreateTable);
+//! assert_eq!(statement.text, "CREATE TABLE orders (order_id number(10) NOT NULL PRIMARY KEY, order_date date);");
+//! ```
+//!
+//! [`GeneratorBuilder`] streams random statements for a set of tables:
+//!
+//! ```
+//! use fake_sql::{Dialect, GeneratorBuilder, Table};
+//!
+//! let orders = Table::init_via_sql("create table orders(order_id number(10) primary key, order_date date)");
+//! let mut generator = GeneratorBuilder::new().table(orders).dialect(Dialect::Postgres).seed(7).build();
+//! assert!(generator.next().unwrap().text.ends_with(';'));
+//! ```
+
+pub mod anonymize;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod blob;
+pub mod cardinality;
+pub mod cdc;
+pub mod check;
+pub mod config;
+pub mod data_pool;
+pub mod diagram;
+pub mod dialect;
+pub mod dirty;
+pub mod distribution;
+pub mod duplicates;
+pub mod evolve;
+#[cfg(feature = "exec")]
+pub mod exec;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ffi;
+pub mod generator;
+pub mod geo;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "introspect")]
+pub mod introspect;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod locale;
+pub mod mix;
+pub mod models;
+pub mod output;
+pub mod params;
+pub mod payload;
+pub mod plugin;
+pub mod pool;
+pub mod preset;
+pub mod providers;
+pub mod query;
+pub mod rate;
+pub mod scd;
+pub mod schema;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod statement;
+pub mod stats;
+#[cfg(feature = "exec")]
+pub mod target;
+pub mod template;
+pub mod timeseries;
+pub mod timezone;
+pub mod unique;
+pub mod value;
+pub mod wasm;
+
+#[cfg(all(target_arch = "wasm32", any(feature = "exec", feature = "introspect", feature = "kafka", feature = "serve", feature = "grpc")))]
+compile_error!("the exec, introspect, kafka, serve and grpc features need files, sockets or threads and cannot be built for WebAssembly");
+
+pub use config::Config;
+pub use dialect::Dialect;
+pub use generator::{Generator, GeneratorBuilder};
+pub use mix::Mix;
+pub use models::{Column, DateRange, SqlType, Table};
+pub use statement::Statement;
+pub use value::SqlValue;
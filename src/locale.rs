@@ -0,0 +1,191 @@
+//! Locales selecting the language and formats of the text providers.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Word lists of a locale the providers draw from.
+pub(crate) struct Words {
+    pub first_names: &'static [&'static str],
+    pub last_names: &'static [&'static str],
+    pub streets: &'static [&'static str],
+    pub cities: &'static [&'static str],
+    pub countries: &'static [&'static str],
+    pub companies: &'static [&'static str],
+    pub company_suffixes: &'static [&'static str],
+    /// Family names of placeholder people that are clearly not real, such as `Doe` in
+    /// `John Doe` or `テスト` (test) for Japanese.
+    pub placeholder_last_names: &'static [&'static str],
+}
+
+const EN: Words = Words {
+    first_names: &[
+        "Alice", "Bob", "Charlie", "David", "Emma", "Frank", "Grace", "Henry", "Isabella", "Jack",
+        "Karen", "Liam", "Mia", "Noah", "Olivia", "Paul", "Quinn", "Rachel", "Samuel", "Sophia",
+        "Thomas", "Uma", "Victor", "Wendy", "Xavier", "Yvonne", "Zachary",
+    ],
+    last_names: &[
+        "Smith", "Johnson", "Williams", "Brown", "Jones", "Garcia", "Miller", "Davis", "Rodriguez",
+        "Martinez", "Hernandez", "Lopez", "Wilson", "Anderson", "Taylor", "Thomas", "Moore", "Jackson",
+        "Martin", "Lee", "Thompson", "White", "Harris", "Clark", "Lewis", "Walker", "Young",
+    ],
+    streets: &[
+        "Main", "Oak", "Pine", "Maple", "Cedar", "Elm", "Washington", "Lake", "Hill", "Park", "Sunset",
+        "River", "Church", "Mill", "Spring",
+    ],
+    cities: &[
+        "Springfield", "Riverside", "Franklin", "Greenville", "Bristol", "Clinton", "Fairview", "Salem",
+        "Madison", "Georgetown", "Arlington", "Ashland", "Burlington", "Manchester", "Oxford",
+    ],
+    countries: &[
+        "United States", "Canada", "United Kingdom", "Germany", "France", "Japan", "Taiwan", "Australia",
+        "Brazil", "India", "Spain", "Italy", "Netherlands", "Sweden", "Mexico",
+    ],
+    companies: &[
+        "Acme", "Globex", "Initech", "Umbrella", "Stark", "Wayne", "Hooli", "Vandelay", "Soylent",
+        "Cyberdyne", "Tyrell", "Wonka", "Aperture", "Massive", "Oscorp",
+    ],
+    company_suffixes: &["Inc", "LLC", "Ltd", "Corp", "Group", "Holdings", "Systems", "Labs"],
+    placeholder_last_names: &["Doe", "Roe", "Example", "Sample", "Placeholder"],
+};
+
+const ZH_TW: Words = Words {
+    first_names: &[
+        "志明", "春嬌", "淑芬", "家豪", "雅婷", "冠宇", "怡君", "宗翰", "詩涵", "承恩", "佳穎", "俊傑",
+        "欣怡", "建宏", "美玲", "柏翰", "宜蓁", "彥廷",
+    ],
+    last_names: &["陳", "林", "黃", "張", "李", "王", "吳", "劉", "蔡", "楊", "許", "鄭", "謝", "郭", "洪"],
+    streets: &["中山路", "中正路", "民生路", "忠孝東路", "仁愛路", "信義路", "和平東路", "復興南路", "光復路", "建國路"],
+    cities: &["臺北市", "新北市", "桃園市", "臺中市", "臺南市", "高雄市", "基隆市", "新竹市", "嘉義市", "花蓮縣"],
+    countries: &["臺灣", "日本", "美國", "德國", "韓國", "新加坡", "加拿大", "英國", "法國", "澳洲"],
+    companies: &["台灣積體", "鴻海精密", "聯華電子", "宏碁", "華碩", "統一", "長榮", "國泰", "大同", "光寶"],
+    company_suffixes: &["股份有限公司", "有限公司", "企業社", "集團"],
+    placeholder_last_names: &["測試", "範例", "樣本"],
+};
+
+const JA: Words = Words {
+    first_names: &[
+        "太郎", "花子", "翔太", "陽菜", "蓮", "結衣", "大翔", "さくら", "悠真", "美咲", "健太", "愛",
+        "拓海", "七海", "颯太", "葵",
+    ],
+    last_names: &["佐藤", "鈴木", "高橋", "田中", "伊藤", "渡辺", "山本", "中村", "小林", "加藤", "吉田", "山田"],
+    streets: &["本町", "栄町", "中央", "旭町", "緑町", "幸町", "大手町", "桜木町", "錦町", "日吉町"],
+    cities: &["東京都", "大阪市", "横浜市", "名古屋市", "札幌市", "福岡市", "神戸市", "京都市", "仙台市", "広島市"],
+    countries: &["日本", "アメリカ", "中国", "韓国", "台湾", "ドイツ", "フランス", "イギリス", "カナダ", "オーストラリア"],
+    companies: &["山田商事", "東西電機", "日の出食品", "富士工業", "青空物産", "さくら銀行", "北斗建設", "大和運輸"],
+    company_suffixes: &["株式会社", "有限会社", "ホールディングス"],
+    placeholder_last_names: &["テスト", "見本", "サンプル"],
+};
+
+const DE: Words = Words {
+    first_names: &[
+        "Lukas", "Anna", "Jonas", "Lea", "Leon", "Marie", "Felix", "Sophie", "Maximilian", "Lena",
+        "Paul", "Hannah", "Jürgen", "Jörg", "Sören", "Käthe",
+    ],
+    last_names: &[
+        "Müller", "Schmidt", "Schneider", "Fischer", "Weber", "Meyer", "Wagner", "Becker", "Schulz",
+        "Hoffmann", "Schäfer", "Koch", "Bauer", "Richter", "Klein", "Weiß", "Größer",
+    ],
+    streets: &[
+        "Hauptstraße", "Schulstraße", "Gartenstraße", "Bahnhofstraße", "Dorfstraße", "Bergstraße",
+        "Lindenstraße", "Kirchweg", "Am Mühlbach", "Rosenweg",
+    ],
+    cities: &["Berlin", "Hamburg", "München", "Köln", "Frankfurt am Main", "Stuttgart", "Düsseldorf", "Leipzig", "Nürnberg", "Lübeck"],
+    countries: &["Deutschland", "Österreich", "Schweiz", "Frankreich", "Italien", "Niederlande", "Polen", "Dänemark", "Belgien", "Spanien"],
+    companies: &["Müller", "Schmidt & Söhne", "Nordlicht", "Südwerk", "Alpenblick", "Rheinland", "Eisenwerk", "Bäckerei Koch"],
+    company_suffixes: &["GmbH", "AG", "KG", "GmbH & Co. KG", "e.K."],
+    placeholder_last_names: &["Mustermann", "Musterfrau", "Beispiel"],
+};
+
+/// Enum representing the locale realistic text values are generated for.
+///
+/// The locale switches the names, addresses, cities, companies and the phone and
+/// postal code formats. Non-English locales produce multibyte UTF-8 text, which
+/// helps testing how a database and its clients handle encodings.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// English (United States).
+    #[default]
+    En,
+    /// Traditional Chinese (Taiwan).
+    ZhTw,
+    /// Japanese.
+    Ja,
+    /// German.
+    De,
+}
+
+impl Locale {
+    /// Every supported locale.
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::ZhTw, Locale::Ja, Locale::De];
+
+    /// Returns the language tag of the locale, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::ZhTw => "zh-TW",
+            Locale::Ja => "ja",
+            Locale::De => "de",
+        }
+    }
+
+    /// Returns the word lists of the locale.
+    pub(crate) fn words(&self) -> &'static Words {
+        match self {
+            Locale::En => &EN,
+            Locale::ZhTw => &ZH_TW,
+            Locale::Ja => &JA,
+            Locale::De => &DE,
+        }
+    }
+
+    /// Returns whether full names are written family name first, without a space.
+    pub(crate) fn family_name_first(&self) -> bool {
+        matches!(self, Locale::ZhTw | Locale::Ja)
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Parses a language tag such as `en`, `en-US`, `zh-TW`, `zh_tw`, `ja` or `de-DE`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "en" | "en-us" | "en-gb" => Ok(Locale::En),
+            "zh-tw" | "zh-hant" | "zh" => Ok(Locale::ZhTw),
+            "ja" | "ja-jp" => Ok(Locale::Ja),
+            "de" | "de-de" | "de-at" | "de-ch" => Ok(Locale::De),
+            _ => Err(format!("unknown locale `{}`", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        for locale in Locale::ALL {
+            assert_eq!(locale.name().parse::<Locale>(), Ok(locale));
+        }
+        assert_eq!("zh_TW".parse::<Locale>(), Ok(Locale::ZhTw));
+        assert_eq!("de-AT".parse::<Locale>(), Ok(Locale::De));
+        assert!("fr".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_words_are_not_empty() {
+        for locale in Locale::ALL {
+            let words = locale.words();
+            for list in [words.first_names, words.last_names, words.streets, words.cities, words.countries, words.companies, words.company_suffixes] {
+                assert!(!list.is_empty(), "{}", locale);
+            }
+        }
+    }
+}
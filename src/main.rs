@@ -1,158 +1,1503 @@
-//! This program generates random SQL statements and writes them to a file named `output.sql`.
-//! The number of SQL statements to generate is determined by the `NUM_RECORDS` environment variable,
-//! defaulting to 30 if the variable is not set.
+//! This program generates random SQL statements and writes them to a file (`output.sql` by default).
 //!
-//! The program defines three tables: `orders`, `customers`, and `products`, and generates random SQL
-//! statements for these tables, including `CREATE TABLE`, `ALTER TABLE`, `DROP TABLE`, `INSERT`, `SELECT`,
-//! `UPDATE`, and `DELETE` operations.
+//! Tables are read from the `CREATE TABLE` statements of the file passed with `--schema` (or as
+//! the first argument, `-` for standard input, e.g. `pg_dump --schema-only shop | fake-sql generate -`), or taken
+//! from a benchmark-style schema picked with `--preset` (`tpcc`, `tpch`, `ecommerce` or `finance`),
+//! which fills its tables at realistic ratios unless `--count` is given. Without either,
+//! the program uses three built-in tables: `orders`, `customers`, and `products`. It generates random
+//! SQL statements for these tables, including `CREATE TABLE`, `ALTER TABLE`, `DROP TABLE`, `INSERT`,
+//! `SELECT`, `UPDATE`, and `DELETE` operations.
 //!
 //! # Usage
 //!
-//! Set the `NUM_RECORDS` environment variable to specify the number of SQL statements to generate.
-//! If not set, the program defaults to generating 30 SQL statements.
+//! ```sh
+//! fake-sql generate --count 1000 --output out.sql --types insert,select,update --schema schema.sql
+//! ```
+//!
+//! An existing output file is only replaced with `--force`; `--append` adds to it instead.
+//!
+//! `--output -` writes to standard output, so the statements can be piped straight into a
+//! database client, e.g. `fake-sql --output - | psql mydb`.
+//!
+//! `--ddl-only` writes just the schema, parents before the tables referencing them, while
+//! `--dml-only` restricts the statements to INSERT, UPDATE, DELETE and SELECT traffic.
+//!
+//! Statements are rendered for Oracle unless another dialect is chosen with `--dialect`.
+//!
+//! `--pii-free` keeps names, email addresses and phone numbers to placeholder names,
+//! `example.com` domains and fictional `555-01xx` numbers, so the output is safe to share.
+//!
+//! With `--format csv` only the generated rows are written, as one CSV file per table in the
+//! directory given by `--output`. `--format jsonl` writes each row as a JSON object per line.
+//! `--format trace` and `--format trace-json` write the statements like a query log, each with
+//! a synthetic timestamp, session id and duration. `--format bulk` loads the rows of each
+//! table in one go, with a Postgres `COPY ... FROM STDIN` block or a MySQL `LOAD DATA LOCAL
+//! INFILE` statement reading a data file written next to the output. Built with the `arrow`
+//! feature, `--format parquet` writes the rows of each table to a Parquet file in the
+//! directory given by `--output`, typed with an Arrow schema derived from the columns.
+//! `--format cdc-json` writes inserts, updates and deletes of rows as Debezium change events.
+//!
+//! `--threads N` splits the work across N threads, each generating its share with a random
+//! number generator of its own and disjoint primary keys. The outputs of the threads are
+//! merged in thread order: all statements of thread 0, then those of thread 1, and so on,
+//! each in the order they were generated. With `--seed`, the output is the same for the
+//! same number of threads. `--split-output` keeps the file of every thread instead, numbered
+//! like shards.
+//!
+//! `--shards N` splits the work into N self-contained files instead, `output_0001.sql`,
+//! `output_0002.sql`, ..., generated on `--threads` threads; CSV output gets a `shard_0001`
+//! directory per shard. `--max-file-size` moves on to the next numbered file once a file
+//! is full, e.g. `output_0001.sql`, `output_0002.sql` (or `output_0001_0001.sql`, ... per
+//! shard).
+//!
+//! `--params positional` or `--params named` writes INSERT statements with bind placeholders,
+//! e.g. `VALUES ($1, $2)`, and their values to `output.params.jsonl`, one line per line of
+//! the output (empty for the `BEGIN` and `COMMIT` of `--transaction-size`).
+//!
+//! `--dirty-rate 0.01` puts an invalid value (a number out of range, a malformed date, text
+//! longer than its column or a string with the wrong quotes) into that share of the INSERT
+//! statements, and lists each one in `output.dirty.jsonl`: the line of the statement in the
+//! output, table, row, column, kind and value.
+//!
+//! `--spill-blobs 64K` writes binary values of at least that size to files in `output.sql.blobs`
+//! instead of the statements, which read them back with the dialect's file function, e.g.
+//! `pg_read_binary_file` or `LOAD_FILE`.
+//!
+//! `--cardinality orders.customer_id=1..20` gives each parent row a number of children within
+//! the range, in the order the parents were inserted, instead of random parents per child row.
+//!
+//! `--time-series readings` generates the rows of a table as a time series: increasing timestamps
+//! `--interval` apart, give or take `--jitter`, a pool of entity ids and metrics that follow a
+//! random walk per entity.
+//!
+//! `--scd2 dim_customer` generates a slowly changing dimension of type 2: INSERTs write the
+//! versions of new business keys, each valid until the next one starts, and UPDATEs insert a
+//! new current version and close the previous one.
+//!
+//! `--rate 500/s` emits the statements at a steady pace instead of as fast as possible, flushing
+//! each one, so `fake-sql --rate 500/s --output -` drives a live workload; `--poisson` spaces
+//! them randomly with the same average.
+//!
+//! With the `exec` feature, `fake-sql exec --url postgres://localhost/test` executes the
+//! statements against a live Postgres, MySQL or SQLite database instead, on `--concurrency`
+//! connections at once.
+//!
+//! With the `introspect` feature, `fake-sql introspect --url postgres://localhost/test` reads
+//! the tables of an existing database and writes them as CREATE TABLE statements for `--schema`.
+//!
+//! `fake-sql check out.sql --dialect postgres` parses every statement of a SQL file, generated
+//! or edited by hand, and prints the lines of the statements that are invalid.
+//!
+//! `fake-sql schema-dot schema.sql` draws the tables, columns and foreign keys the way they
+//! were understood as a Graphviz diagram, or with `--format mermaid` as a Mermaid one, to
+//! check a schema before generating data for it.
+//!
+//! `fake-sql export-schema schema.sql -o schema.yaml` writes the tables and their column
+//! settings as a config file, to be edited, e.g. with generators, null rates or value pools,
+//! and used with `--config schema.yaml`.
+//!
+//! `fake-sql anonymize --input dump.sql --output safe.sql` replaces the names, emails, phone
+//! numbers and addresses in the INSERT statements of an existing dump with fakes, the same
+//! fake for the same value, so keys and foreign keys still match.
+//!
+//! A generation setup can also be kept in a TOML or YAML file passed with `--config`; command
+//! line flags override the settings of the file.
+//!
+//! Running `fake-sql` without a subcommand is the same as `fake-sql generate`. The number of
+//! statements defaults to 30 and can also be set through the `NUM_RECORDS` environment variable.
 //!
 //! ```sh
 //! export NUM_RECORDS=50
 //! cargo run
 //! ```
-//!
-//! The generated SQL statements are appended to the `output.sql` file in the current directory.
-//! Initializes a new Table with the given name and columns.
-//! 
-//! 
-//! Example
-//!  ```rust
-//! let columns = vec![
-//!     Column {
-//!         name: "id".to_string(),
-//!         column_type: "number".to_string(),
-//!         length: Some(10),
-//!         decimal_places: None,
-//!         is_nullable: false,
-//!         is_pkey: true,
-//!         ref_table: None,
-//!         ref_column: None,
-//!     },
-//!     Column {
-//!         name: "name".to_string(),
-//!         column_type: "varchar".to_string(),
-//!         length: Some(255),
-//!         decimal_places: None,
-//!         is_nullable: true,
-//!         is_pkey: false,
-//!         ref_table: None,
-//!         ref_column: None,
-//!     },
-//! ];
-//! let table = Table::init("test_table".to_string(), columns);
-//! assert_eq!(table.name, "test_table");
-//! assert_eq!(table.columns.len(), 2);
-//!  ```
-//! 
-//!  To Run simple test
-//!  ```rust
-//!    // Initialize tables
-//!    let order: Table = Table::init_via_sql("create table orders(order_id number(10) primary key, order_date date, customer_id number(10))");
-//!    let sql = order.generate(SqlType::CreateTable);
-//!  ```
-//!  use for main.rs
-//! 
-//! ```rust
-//! mod models;
-//! 
-//! use models::{Table, SqlType};
-//! use rand::seq::SliceRandom;
-//! use rand::thread_rng;
-//! use std::fs::OpenOptions;
-//! use std::io::Write;
-//! 
-//! fn main() {
-//!     // Get the number of records to generate from the environment variable `NUM_RECORDS`
-//!     let num_records = std::env::var("NUM_RECORDS").unwrap_or("30".to_string()).parse::<i32>().unwrap();
-//! 
-//!     // Open the output file in append mode, creating it if it doesn't exist
-//!     let mut file = OpenOptions::new()
-//!         .append(true)
-//!         .create(true)
-//!         .open("output.sql")
-//!         .expect("Unable to open file");
-//! 
-//!     // Initialize tables
-//!     let order: Table = Table::init_via_sql("create table orders(order_id number(10) primary key, order_date date, customer_id number(10))");
-//!     let customers: Table = Table::init_via_sql("create table customers(customer_id number(10) primary key, customer_name varchar(255), customer_email varchar(255))");
-//!     let products: Table = Table::init_via_sql("create table products(product_id number(10) primary key, product_name varchar(255), product_price number(10, 2))");
-//! 
-//!     let tables = vec![order, customers, products];
-//! 
-//!     // Define SQL types
-//!     let sql_types = vec![
-//!         SqlType::CreateTable,
-//!         SqlType::AlterTable,
-//!         SqlType::DropTable,
-//!         SqlType::Insert,
-//!         SqlType::Select,
-//!         SqlType::Update,
-//!         SqlType::Delete,
-//!     ];
-//! 
-//!     // Generate and write SQL statements to the file
-//!     for _ in 0..num_records {
-//!         let mut rng = thread_rng();
-//!         let random_sql_type = sql_types.choose(&mut rng).unwrap();
-//!         let random_table = tables.choose(&mut rng).unwrap();
-//! 
-//!         let sql = random_table.generate(*random_sql_type);
-//!         writeln!(file, "{}", sql).expect("Unable to write to file");
-//!     }
-//! }
-//! ```
 
+mod cli;
 
+use chrono::{NaiveDateTime, NaiveTime};
+use clap::Parser;
+#[cfg(feature = "exec")]
+use cli::ExecArgs;
+#[cfg(feature = "grpc")]
+use cli::GrpcArgs;
+#[cfg(feature = "introspect")]
+use cli::IntrospectArgs;
+#[cfg(feature = "kafka")]
+use cli::KafkaArgs;
+#[cfg(feature = "serve")]
+use cli::ServeArgs;
+use cli::{AnonymizeArgs, CheckArgs, Cli, Command, ExportSchemaArgs, GenerateArgs, SchemaDotArgs};
+use fake_sql::anonymize::Anonymizer;
+use fake_sql::blob::{self, Blob, BlobFiles};
+use fake_sql::cdc::{CdcWriter, Change, Operation};
+use fake_sql::dirty::{self, Dirt};
+use fake_sql::output::{
+    data_dir, numbered_path, BulkWriter, CountingWriter, CsvWriter, Format, JsonlWriter, RowWriter, SqlWriter, TraceWriter, DEFAULT_BUFFER_SIZE,
+};
+#[cfg(feature = "kafka")]
+use fake_sql::kafka::KafkaSink;
+use fake_sql::params::params_path;
+use fake_sql::plugin::GeneratorRegistry;
+use fake_sql::preset::Preset;
+use fake_sql::rate::{Pacer, Rate};
+use fake_sql::scd::Scd2;
+use fake_sql::schema::{self, DEFAULT_SCHEMA};
+use fake_sql::stats::Stats;
+#[cfg(feature = "exec")]
+use fake_sql::target::Target;
+use fake_sql::timeseries::TimeSeries;
+use fake_sql::{Config, Dialect, Generator, Mix, SqlType, Statement, Table};
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 
-mod models;
+/// The `--output` value that selects standard output.
+const STDOUT: &str = "-";
 
-use models::{Table, SqlType};
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::fs::OpenOptions;
-use std::io::Write;
+/// The `check` file, schema file or `anonymize` input that selects standard input.
+const STDIN: &str = "-";
 
 fn main() {
-    // Get the number of records to generate from the environment variable `NUM_RECORDS`
-    let num_records = std::env::var("NUM_RECORDS").unwrap_or("30".to_string()).parse::<i32>().unwrap();
-
-    // Open the output file in append mode, creating it if it doesn't exist
-    let mut file = OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open("output.sql")
-        .expect("Unable to open file");
-
-    // Initialize tables
-    let order: Table = Table::init_via_sql("create table orders(order_id number(10) primary key, order_date date, customer_id number(10))");
-    let customers: Table = Table::init_via_sql("create table customers(customer_id number(10) primary key, customer_name varchar(255), customer_email varchar(255))");
-    let products: Table = Table::init_via_sql("create table products(product_id number(10) primary key, product_name varchar(255), product_price number(10, 2))");
-
-    let tables = vec![order, customers, products];
-
-    // Define SQL types
-    let sql_types = vec![
-        SqlType::CreateTable,
-        SqlType::AlterTable,
-        SqlType::DropTable,
-        SqlType::Insert,
-        SqlType::Select,
-        SqlType::Update,
-        SqlType::Delete,
-    ];
-
-    // Generate and write SQL statements to the file
-    for _ in 0..num_records {
-        let mut rng = thread_rng();
-        let random_sql_type = sql_types.choose(&mut rng).unwrap();
-        let random_table = tables.choose(&mut rng).unwrap();
-
-        let sql = random_table.generate(*random_sql_type);
-        writeln!(file, "{}", sql).expect("Unable to write to file");
-    }
-}
\ No newline at end of file
+    let result = match Cli::parse().into_command() {
+        Command::Generate(args) => generate(args),
+        #[cfg(feature = "exec")]
+        Command::Exec(args) => exec(args),
+        #[cfg(feature = "introspect")]
+        Command::Introspect(args) => introspect(args),
+        #[cfg(feature = "grpc")]
+        Command::Grpc(args) => grpc(args),
+        #[cfg(feature = "kafka")]
+        Command::Kafka(args) => kafka(args),
+        Command::Check(args) => check(args),
+        Command::Anonymize(args) => anonymize(args),
+        Command::SchemaDot(args) => schema_dot(args),
+        Command::ExportSchema(args) => export_schema(args),
+        #[cfg(feature = "serve")]
+        Command::Serve(args) => serve(args),
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+/// Generates random statements and writes them to the output file.
+///
+/// Settings come from the command line first, then the config file, then the defaults.
+/// Output files that already exist are only replaced with `--force`, or appended to with
+/// `--append`.
+fn generate(args: GenerateArgs) -> Result<(), Box<dyn Error>> {
+    let config = load_config(&args)?;
+    let (mut generator, work) = prepare(&args, &config, Dialect::default())?;
+    if let Some(count) = args.preview {
+        return print_preview(&generator, count as usize);
+    }
+    if !args.quiet {
+        for table in generator.tables() {
+            for check in table.checks.iter().filter(|check| !check.is_supported()) {
+                eprintln!("warning: generated rows of `{}` may fail CHECK ({}), only comparisons of two columns are satisfied", table.name, check.condition);
+            }
+        }
+    }
+    let dialect = generator.dialect();
+    let params = args.params.or(config.params);
+    generator.set_param_style(params);
+    let dirty = args.dirty_rate.or(config.dirty_rate).is_some_and(|rate| rate > 0.0);
+    // Validation replays the statements of an identical generator once the output is written
+    #[cfg(feature = "exec")]
+    let validation = match &args.validate {
+        Some(url) => {
+            check_validation(url, dialect, args.format.or(config.format).unwrap_or_default(), params.is_some(), dirty)?;
+            Some((url.clone(), generator.clone(), work.share(0, 1)))
+        }
+        None => None,
+    };
+
+    let format = args.format.or(config.format).unwrap_or_default();
+    let path = args.output.or(config.output).unwrap_or_else(|| {
+        PathBuf::from(match format {
+            Format::Sql => "output.sql",
+            // The output names the directory holding one CSV file per table
+            Format::Csv | Format::Parquet => ".",
+            Format::Jsonl => "output.jsonl",
+            Format::Trace => "trace.log",
+            Format::TraceJson => "trace.jsonl",
+            Format::Bulk => "output.sql",
+            Format::CdcJson => "changes.jsonl",
+        })
+    });
+    if format.writes_table_files() && path == Path::new(STDOUT) {
+        return Err(format!("the {} format writes one file per table and cannot write to stdout", format).into());
+    }
+    if format == Format::Parquet && cfg!(not(feature = "arrow")) {
+        return Err("the parquet format needs fake-sql built with the `arrow` feature".into());
+    }
+    if format == Format::Bulk {
+        if !matches!(dialect, Dialect::Postgres | Dialect::MySql) {
+            return Err(format!("the bulk format writes Postgres COPY or MySQL LOAD DATA statements and cannot be used with {}", dialect).into());
+        }
+        if dialect == Dialect::MySql && path == Path::new(STDOUT) {
+            return Err("MySQL bulk loads read data files written next to the output and cannot write to stdout".into());
+        }
+    }
+    if args.stats_json.as_deref() == Some(Path::new(STDOUT)) && path == Path::new(STDOUT) {
+        return Err("--stats-json and --output cannot both write to stdout".into());
+    }
+    if let Some(min_size) = args.spill_blobs.or(config.spill_blobs) {
+        if format != Format::Sql {
+            return Err(format!("--spill-blobs writes SQL statements and cannot be used with the {} format", format).into());
+        }
+        if path == Path::new(STDOUT) {
+            return Err("--spill-blobs writes its files next to the output and cannot write to stdout".into());
+        }
+        let dir = blob::blob_dir(&path);
+        fs::create_dir_all(&dir)?;
+        // The database server reads the files, so the statements name them by absolute path
+        generator.set_blob_files(BlobFiles { min_size, dir: fs::canonicalize(&dir)? });
+    }
+    let output = Output {
+        format,
+        dialect,
+        transaction_size: args.transaction_size.or(config.transaction_size).map(|size| size as usize),
+        buffer_size: args.buffer_size.or(config.buffer_size).unwrap_or(DEFAULT_BUFFER_SIZE),
+        max_file_size: args.max_file_size.or(config.max_file_size).map(|size| size as u64),
+        append: !args.truncate && (args.append || config.append.unwrap_or(false)),
+        params: params.is_some(),
+        dirty,
+        rate: args.rate.or(config.rate).map(|rate| rate.with_poisson(args.poisson || config.poisson.unwrap_or(false))),
+        trace_start: args.date_range.or(config.date_range).unwrap_or_default().start.and_time(NaiveTime::MIN),
+        seed: args.seed.or(config.seed),
+        sessions: args.sessions.or(config.sessions),
+        progress: progress_bar(work.total(!format.writes_statements()), format, args.quiet),
+    };
+    let threads = args.threads.or(config.threads).unwrap_or(1).max(1) as usize;
+    let mut shards = args.shards.or(config.shards).unwrap_or(1).max(1) as usize;
+    if (format.writes_table_files() || args.split_output) && shards == 1 {
+        // CSV files start with a header and Parquet files end with a footer, so the outputs
+        // of several threads cannot be merged
+        shards = threads;
+    }
+    if args.ddl_only {
+        shards = 1;
+    }
+    let appendable = !format.writes_table_files() && shards == 1 && output.max_file_size.is_none();
+    if output.append && !appendable {
+        return Err("--append cannot be used with the csv or parquet format, --shards or --max-file-size".into());
+    }
+    if !output.append && !args.force {
+        let numbered = output.max_file_size.is_some();
+        if let Some(existing) = existing_output(&path, format, shards, numbered, output.params, output.dirty, generator.tables()) {
+            let hint = if appendable { " or --append to add to it" } else { "" };
+            return Err(format!("{} already exists; use --force to overwrite it{}", existing.display(), hint).into());
+        }
+    }
+
+    if args.ddl_only {
+        if format != Format::Sql {
+            return Err(format!("--ddl-only writes SQL statements and cannot be used with the {} format", format).into());
+        }
+        if args.stats_json.is_some() {
+            return Err("--stats-json reports generated statements and cannot be used with --ddl-only".into());
+        }
+        let mut writer = SqlWriter::new(open_output(&path, output.append, output.buffer_size)?, dialect);
+        for statement in generator.schema_statements() {
+            writer.write_statement(&statement)?;
+        }
+        writer.finish()?;
+    } else {
+        let start = Instant::now();
+        let mut stats = write_statements(&path, &output, generator, &work, threads, shards)?;
+        stats.elapsed = start.elapsed();
+        output.progress.finish_and_clear();
+        report(&stats, args.quiet, args.stats_json.as_deref())?;
+    }
+
+    #[cfg(feature = "exec")]
+    if let Some((url, generator, work)) = validation {
+        let parts = if shards == 1 && threads > 1 { threads } else { shards };
+        validate(&url, generator, work, parts, args.ddl_only)?;
+    }
+    Ok(())
+}
+
+/// Prints `count` example statements per statement type and table, each table headed by
+/// how its columns are filled, as SQL comments.
+fn print_preview(generator: &Generator, count: usize) -> Result<(), Box<dyn Error>> {
+    let mut out = io::stdout().lock();
+    for preview in generator.preview(count) {
+        writeln!(out, "-- {}", preview.table)?;
+        for (column, description) in &preview.columns {
+            writeln!(out, "--   {}: {}", column, description)?;
+        }
+        for (sql_type, statements) in &preview.statements {
+            writeln!(out, "\n-- {}: {}", preview.table, sql_type)?;
+            for statement in statements {
+                writeln!(out, "{}", statement)?;
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Writes the statements or rows of `work` to `path`, on `threads` threads and split into
+/// `shards` files.
+///
+/// # Returns
+///
+/// The statistics of every thread or shard combined.
+fn write_statements(
+    path: &Path,
+    output: &Output,
+    generator: Generator,
+    work: &Work,
+    threads: usize,
+    shards: usize,
+) -> Result<Stats, Box<dyn Error>> {
+    let format = output.format;
+
+    if output.params {
+        if format != Format::Sql {
+            return Err(format!("--params writes SQL statements and cannot be used with the {} format", format).into());
+        }
+        if output.max_file_size.is_some() || path == Path::new(STDOUT) {
+            return Err("--params writes its parameter file next to the output and needs a single output file".into());
+        }
+    }
+    if output.dirty {
+        if format != Format::Sql {
+            return Err(format!("--dirty-rate reports SQL statements and cannot be used with the {} format", format).into());
+        }
+        if output.max_file_size.is_some() || path == Path::new(STDOUT) {
+            return Err("--dirty-rate writes its report next to the output and needs a single output file".into());
+        }
+        if output.append {
+            return Err("--dirty-rate numbers the statements from the start of the output and cannot be used with --append".into());
+        }
+    }
+    if output.max_file_size.is_some() && format != Format::Sql {
+        return Err(format!("--max-file-size splits SQL statements and cannot be used with the {} format", format).into());
+    }
+    if (shards > 1 || output.max_file_size.is_some()) && path == Path::new(STDOUT) {
+        return Err("--shards and --max-file-size write numbered files and cannot write to stdout".into());
+    }
+    if output.rate.is_some() && (threads > 1 || shards > 1) {
+        return Err("--rate paces a single stream of statements and cannot be used with --threads or --shards".into());
+    }
+    if format == Format::CdcJson && (threads > 1 || shards > 1) {
+        return Err("change events update and delete the rows inserted before and cannot be written with --threads or --shards".into());
+    }
+    if matches!(format, Format::Trace | Format::TraceJson) {
+        if threads > 1 || shards > 1 {
+            return Err("a trace is a single timeline and cannot be written with --threads or --shards".into());
+        }
+        if output.transaction_size.is_some() {
+            return Err(format!("--transaction-size cannot be used with the {} format", format).into());
+        }
+    } else if output.sessions.is_some() {
+        return Err("--sessions simulates concurrent clients in a trace and needs --format trace or trace-json".into());
+    }
+    if format == Format::Parquet && output.rate.is_some() {
+        return Err("Parquet files are only readable once complete and cannot be paced with --rate".into());
+    }
+    if format == Format::Bulk {
+        if output.rate.is_some() {
+            return Err("the bulk format writes each table's rows once all are generated and cannot be paced with --rate".into());
+        }
+        if output.dialect == Dialect::MySql && output.append {
+            return Err("MySQL bulk loads replace their data files and cannot be used with --append".into());
+        }
+        if output.dialect == Dialect::MySql && threads > 1 && shards == 1 {
+            return Err("the data files of MySQL bulk loads cannot be merged across threads; use --shards".into());
+        }
+    }
+    if shards == 1 && threads == 1 {
+        return Ok(output.write(path, generator, work.share(0, 1), output.append)?);
+    }
+    if shards == 1 && output.max_file_size.is_some() {
+        return Err("--max-file-size cannot split the merged output of several threads; use --shards".into());
+    }
+
+    // Every shard, or every thread without shards, writes its share to a file of its own;
+    // the files of the threads are merged in thread order afterwards
+    let merge = shards == 1;
+    let parts = if merge { threads } else { shards };
+    let paths: Vec<PathBuf> = (0..parts).map(|index| part_path(path, format, index, merge)).collect();
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build()?;
+    let parts_stats = pool.install(|| {
+        generator
+            .split(parts)
+            .into_par_iter()
+            .zip(&paths)
+            .enumerate()
+            .map(|(index, (shard, path))| output.write(path, shard, work.share(index, parts), false))
+            .collect::<io::Result<Vec<Stats>>>()
+    })?;
+    if merge {
+        merge_parts(path, &paths, output.append, output.buffer_size)?;
+        if output.params {
+            let parts: Vec<PathBuf> = paths.iter().map(|part| params_path(part)).collect();
+            merge_parts(&params_path(path), &parts, output.append, output.buffer_size)?;
+        }
+        if output.dirty {
+            let parts: Vec<PathBuf> = paths.iter().map(|part| dirty::report_path(part)).collect();
+            let counts: Vec<u64> = parts_stats.iter().map(|part| output_lines(part.statements(), output.transaction_size, output.dialect)).collect();
+            merge_reports(&dirty::report_path(path), &parts, &counts, output.buffer_size)?;
+        }
+    }
+    let mut stats = Stats::default();
+    for part in parts_stats {
+        stats.merge(part);
+    }
+    Ok(stats)
+}
+
+/// Returns the progress bar of a run generating `total` statements, or rows for the row
+/// formats. It is drawn on stderr while it is a terminal, and hidden with `--quiet`.
+fn progress_bar(total: u64, format: Format, quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+    let unit = if format.writes_statements() { "statements" } else { "rows" };
+    let template = format!("[{{elapsed_precise}}] {{wide_bar}} {{human_pos}}/{{human_len}} {} ({{per_sec}}, {{eta}} left)", unit);
+    ProgressBar::new(total).with_style(ProgressStyle::with_template(&template).unwrap())
+}
+
+/// Prints the statistics of the run to stderr unless `quiet`, and writes them as JSON to
+/// `stats_json`, if given.
+fn report(stats: &Stats, quiet: bool, stats_json: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    if !quiet {
+        eprintln!("{}", stats);
+    }
+    if let Some(path) = stats_json {
+        let mut out = open_output(path, false, DEFAULT_BUFFER_SIZE)?;
+        serde_json::to_writer_pretty(&mut out, &stats.to_json())?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Checks that the statements written in `format` for `dialect` can be validated against
+/// the database at `url`.
+#[cfg(feature = "exec")]
+fn check_validation(url: &str, dialect: Dialect, format: Format, params: bool, dirty: bool) -> Result<(), Box<dyn Error>> {
+    if format != Format::Sql {
+        return Err(format!("--validate executes SQL statements and cannot be used with the {} format", format).into());
+    }
+    if params {
+        return Err("--validate cannot execute statements with bind placeholders; remove --params".into());
+    }
+    if dirty {
+        return Err("--validate expects every statement to succeed; remove --dirty-rate".into());
+    }
+    match Dialect::from_url(url) {
+        Some(database) if database == dialect => Ok(()),
+        Some(database) => Err(format!(
+            "--validate against `{}` needs {} statements, not {}; use --dialect {} or validate against a {} database",
+            url, database, dialect, database, dialect
+        )
+        .into()),
+        None => Err(format!("unsupported database URL `{}`, expected postgres://, mysql:// or sqlite:", url).into()),
+    }
+}
+
+/// Executes the statements `generator` writes for `work` against the database at `url`,
+/// after creating the schema unless the statements do, and reports the rejected ones.
+///
+/// # Arguments
+///
+/// * `url` - The database URL.
+/// * `generator` - A generator identical to the one whose output was written.
+/// * `work` - The work of the written output.
+/// * `parts` - Number of shards or threads the output was generated by, in order.
+/// * `ddl_only` - Whether the output is the schema.
+#[cfg(feature = "exec")]
+fn validate(url: &str, generator: Generator, work: Work, parts: usize, ddl_only: bool) -> Result<(), Box<dyn Error>> {
+    let schema = generator.schema_statements();
+    let (setup, statements): (Vec<String>, Box<dyn Iterator<Item = String>>) = if ddl_only {
+        (vec![], Box::new(schema.into_iter()))
+    } else if parts == 1 {
+        (schema, Box::new(work.into_statements(generator).map(|generated| generated.statement.text)))
+    } else {
+        let statements = generator
+            .split(parts)
+            .into_iter()
+            .enumerate()
+            .flat_map(move |(index, shard)| {
+                work.share(index, parts).into_statements(shard).map(|generated| generated.statement.text)
+            });
+        (schema, Box::new(statements))
+    };
+    let report = fake_sql::exec::validate(url, setup, statements).map_err(|err| err as Box<dyn Error>)?;
+    for failure in &report.failures {
+        eprintln!("statement {}: {}\n  {}", failure.position, failure.error, failure.sql);
+    }
+    if report.failed > report.failures.len() as u64 {
+        eprintln!("... and {} more", report.failed - report.failures.len() as u64);
+    }
+    eprintln!("{}", report);
+    match report.failed {
+        0 => Ok(()),
+        failed => Err(format!("{} statements failed validation against {}", failed, url).into()),
+    }
+}
+
+/// Generates random statements and executes them against the database at `--url`.
+///
+/// Every connection executes its own share of the work, like a shard of `generate`, so
+/// foreign keys only reference rows inserted through the same connection.
+#[cfg(feature = "exec")]
+fn exec(args: ExecArgs) -> Result<(), Box<dyn Error>> {
+    let config = load_config(&args.generate)?;
+    if let Some(target) = &args.target {
+        return load_target(target, &args, &config);
+    }
+    // Read here rather than by clap, so an exported DATABASE_URL does not conflict with --target
+    let url = args.url.clone().or_else(|| env::var("DATABASE_URL").ok()).ok_or("--url, DATABASE_URL or --target is required")?;
+    let url = url.as_str();
+    let dialect =
+        Dialect::from_url(url).ok_or_else(|| format!("unsupported database URL `{}`, expected postgres://, mysql:// or sqlite:", url))?;
+    let (generator, work) = prepare(&args.generate, &config, dialect)?;
+
+    let concurrency = args.concurrency.unwrap_or(1).max(1) as usize;
+    let sources: Vec<Box<dyn Iterator<Item = String> + Send>> = if args.generate.ddl_only {
+        vec![Box::new(generator.schema_statements().into_iter())]
+    } else if concurrency == 1 {
+        vec![Box::new(work.into_statements(generator).map(|generated| generated.statement.text))]
+    } else {
+        generator
+            .split(concurrency)
+            .into_iter()
+            .enumerate()
+            .map(|(index, shard)| {
+                let statements =
+                    work.share(index, concurrency).into_statements(shard).map(|generated| generated.statement.text);
+                Box::new(statements) as Box<dyn Iterator<Item = String> + Send>
+            })
+            .collect()
+    };
+    let rate = args.generate.rate.or(config.rate).map(|rate| rate.with_poisson(args.generate.poisson || config.poisson.unwrap_or(false)));
+    let report = fake_sql::exec::execute(url, sources, args.keep_going, rate).map_err(|err| err as Box<dyn Error>)?;
+    eprintln!("{}", report);
+    Ok(())
+}
+
+/// Runs `exec --target`: creates the schema in the database file of `target` and loads
+/// the generated statements into it on a single connection.
+#[cfg(feature = "exec")]
+fn load_target(target: &Target, args: &ExecArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    if args.concurrency.is_some_and(|concurrency| concurrency > 1) {
+        return Err(format!("--concurrency cannot be used with --target; {} is loaded on a single connection", target).into());
+    }
+    if let Some(dialect) = args.generate.dialect.or(config.dialect).filter(|dialect| *dialect != target.dialect()) {
+        return Err(format!("{} is loaded with {} statements and cannot use the {} dialect", target, target.dialect(), dialect).into());
+    }
+    let path = target.path();
+    if path.exists() {
+        if !args.generate.force {
+            return Err(format!("{} already exists; pass --force to replace it", path.display()).into());
+        }
+        fs::remove_file(path)?;
+    }
+    let (generator, work) = prepare(&args.generate, config, target.dialect())?;
+    let setup = generator.schema_statements();
+    let statements: Box<dyn Iterator<Item = String>> = if args.generate.ddl_only {
+        Box::new(std::iter::empty())
+    } else {
+        Box::new(work.into_statements(generator).map(|generated| generated.statement.text))
+    };
+    let rate = args.generate.rate.or(config.rate).map(|rate| rate.with_poisson(args.generate.poisson || config.poisson.unwrap_or(false)));
+    let report = fake_sql::target::load(target, setup, statements, args.keep_going, rate).map_err(|err| err as Box<dyn Error>)?;
+    eprintln!("{}", report);
+    Ok(())
+}
+
+/// Reads the tables of the database at `--url` and writes them as CREATE TABLE statements,
+/// ready to be passed to `generate --schema`.
+#[cfg(feature = "introspect")]
+fn introspect(args: IntrospectArgs) -> Result<(), Box<dyn Error>> {
+    let dialect = args.dialect.or_else(|| Dialect::from_url(&args.url)).unwrap_or_default();
+    let tables = fake_sql::introspect::introspect(&args.url, args.db_schema.as_deref()).map_err(|err| err as Box<dyn Error>)?;
+    let mut generator = Generator::new(tables);
+    generator.set_dialect(dialect);
+
+    let path = args.output.unwrap_or_else(|| PathBuf::from(STDOUT));
+    let mut writer = SqlWriter::new(open_output(&path, false, DEFAULT_BUFFER_SIZE)?, dialect);
+    for statement in generator.schema_statements() {
+        writer.write_statement(&statement)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Publishes the generated statements, or the rows with `--format jsonl`, to the Kafka topic
+/// at `--topic`, one message each.
+#[cfg(feature = "kafka")]
+fn kafka(args: KafkaArgs) -> Result<(), Box<dyn Error>> {
+    let config = load_config(&args.generate)?;
+    let format = args.generate.format.or(config.format).unwrap_or_default();
+    if !matches!(format, Format::Sql | Format::Jsonl) {
+        return Err(format!("kafka publishes statements (--format sql) or rows (--format jsonl), not the {} format", format).into());
+    }
+    let (mut generator, work) = prepare(&args.generate, &config, Dialect::default())?;
+    let mut sink = KafkaSink::connect(args.brokers, &args.topic)?;
+    sink.set_partitioning(args.partition_by.unwrap_or_default());
+    if let Some(sessions) = args.generate.sessions.or(config.sessions) {
+        sink.set_sessions(sessions);
+    }
+    let rate = args.generate.rate.or(config.rate).map(|rate| rate.with_poisson(args.generate.poisson || config.poisson.unwrap_or(false)));
+    let mut pacer = rate.map(Pacer::new);
+    let start = Instant::now();
+    // Paced messages go out one at a time: the previous one is sent before waiting for the next
+    let mut pace = |sink: &mut KafkaSink| -> io::Result<()> {
+        match &mut pacer {
+            Some(pacer) => {
+                sink.flush()?;
+                pacer.wait();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    };
+    if format == Format::Sql {
+        for generated in work.into_statements(generator) {
+            pace(&mut sink)?;
+            sink.send_statement(&generated.statement)?;
+        }
+    } else if work.rows.is_empty() {
+        for _ in 0..work.count {
+            pace(&mut sink)?;
+            let (table, row) = generator.next_row();
+            sink.write_row(table, &row)?;
+        }
+    } else {
+        for (table, count) in &work.rows {
+            for _ in 0..*count {
+                pace(&mut sink)?;
+                let (table, row) = generator.row_for(table).unwrap();
+                sink.write_row(table, &row)?;
+            }
+        }
+    }
+    sink.finish()?;
+    eprintln!("published {} messages to {} in {:.2} s", sink.messages(), args.topic, start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Parses every statement of a SQL file and prints the invalid ones with their line numbers.
+fn check(args: CheckArgs) -> Result<(), Box<dyn Error>> {
+    let (sql, name) = if args.file == Path::new(STDIN) {
+        (io::read_to_string(io::stdin())?, "stdin".to_string())
+    } else {
+        let sql = fs::read_to_string(&args.file).map_err(|err| format!("cannot read {}: {}", args.file.display(), err))?;
+        (sql, args.file.display().to_string())
+    };
+    let (count, invalid) = fake_sql::check::check(&sql, args.dialect.unwrap_or_default());
+    for statement in &invalid {
+        println!("{}\n  {}", statement, statement.sql);
+    }
+    eprintln!("checked {} statements, {} invalid", count, invalid.len());
+    match invalid.len() {
+        0 => Ok(()),
+        n => Err(format!("{} statements of {} are invalid", n, name).into()),
+    }
+}
+
+/// Runs the `anonymize` subcommand: rewrites the INSERT statements of a dump with fakes.
+fn anonymize(args: AnonymizeArgs) -> Result<(), Box<dyn Error>> {
+    let sql = if args.input == Path::new(STDIN) {
+        io::read_to_string(io::stdin())?
+    } else {
+        fs::read_to_string(&args.input).map_err(|err| format!("cannot read {}: {}", args.input.display(), err))?
+    };
+    let tables = match &args.schema {
+        Some(path) => load_schema(path).map_err(|err| format!("cannot load schema {}: {}", path.display(), err))?,
+        None => Vec::new(),
+    };
+    let dialect = args.dialect.unwrap_or_default();
+    let mut anonymizer = match args.seed {
+        Some(seed) => Anonymizer::with_seed(tables, dialect, seed),
+        None => Anonymizer::new(tables, dialect),
+    };
+    for (pattern, provider) in &args.columns {
+        anonymizer.add_column(pattern, *provider);
+    }
+    anonymizer.set_pii_free(args.pii_free);
+    let anonymized = anonymizer.anonymize(&sql)?;
+    let path = args.output.unwrap_or_else(|| PathBuf::from(STDOUT));
+    let mut writer = open_output(&path, false, DEFAULT_BUFFER_SIZE)?;
+    writer.write_all(anonymized.as_bytes())?;
+    writer.flush()?;
+    eprintln!("anonymized {} values in {} INSERT statements", anonymizer.values(), anonymizer.statements());
+    Ok(())
+}
+
+/// Writes a diagram of the tables and foreign keys of the schema, loaded like `generate`
+/// loads it.
+fn schema_dot(args: SchemaDotArgs) -> Result<(), Box<dyn Error>> {
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let tables = load_schema_tables(args.schema.as_deref(), args.preset, &config)?;
+    let dialect = args.dialect.or(config.dialect).unwrap_or_default();
+    let diagram = fake_sql::diagram::diagram(&tables, dialect, args.format.unwrap_or_default());
+    let path = args.output.unwrap_or_else(|| PathBuf::from(STDOUT));
+    let mut writer = open_output(&path, false, DEFAULT_BUFFER_SIZE)?;
+    writer.write_all(diagram.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the tables of the schema, loaded like `generate` loads it, as a config file.
+fn export_schema(args: ExportSchemaArgs) -> Result<(), Box<dyn Error>> {
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let tables = load_schema_tables(args.schema.as_deref(), args.preset, &config)?;
+    let path = args.output.unwrap_or_else(|| PathBuf::from(STDOUT));
+    let json = args.json || path.extension().is_some_and(|extension| extension == "json");
+    let document = fake_sql::config::export_tables(&tables, !json)?;
+    let mut writer = open_output(&path, false, DEFAULT_BUFFER_SIZE)?;
+    writer.write_all(document.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Serves generated statements over HTTP, starting with the tables of the schema loaded like
+/// `generate` loads it, until the process is stopped.
+#[cfg(feature = "serve")]
+fn serve(args: ServeArgs) -> Result<(), Box<dyn Error>> {
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let tables = load_schema_tables(args.schema.as_deref(), args.preset, &config)?;
+    // The binary registers no custom generators
+    GeneratorRegistry::new().check(&tables)?;
+    let dialect = args.dialect.or(config.dialect).unwrap_or_default();
+    let address = format!("{}:{}", args.host, args.port);
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    eprintln!("serving {} tables on http://{}", tables.len(), address);
+    fake_sql::serve::Server::new(tables, dialect).run(&address, workers)?;
+    Ok(())
+}
+
+/// Streams generated statements to gRPC clients, for the tables of the schema loaded like
+/// `generate` loads it unless a call sends its own, until the process is stopped.
+#[cfg(feature = "grpc")]
+fn grpc(args: GrpcArgs) -> Result<(), Box<dyn Error>> {
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let tables = load_schema_tables(args.schema.as_deref(), args.preset, &config)?;
+    // The binary registers no custom generators
+    GeneratorRegistry::new().check(&tables)?;
+    let dialect = args.dialect.or(config.dialect).unwrap_or_default();
+    let address = format!("{}:{}", args.host, args.port);
+    eprintln!("serving fake_sql.FakeSql with {} tables on {}", tables.len(), address);
+    fake_sql::grpc::GrpcService::new(tables, dialect).run(&address)?;
+    Ok(())
+}
+
+/// Loads the tables of the schema file or preset of a subcommand, or else of the config file,
+/// falling back to the config file's preset when neither is given.
+fn load_schema_tables(schema_path: Option<&Path>, preset: Option<Preset>, config: &Config) -> Result<Vec<Table>, Box<dyn Error>> {
+    let preset = match (schema_path, preset) {
+        (None, None) if config.schema.is_none() && config.ddl.is_none() && config.tables.is_empty() => config.preset,
+        (_, preset) => preset,
+    };
+    load_tables(schema_path, preset, config)
+}
+
+/// Reads the tables of a schema file, or of standard input for `-`, so a schema can be
+/// piped in, e.g. from `pg_dump --schema-only`.
+fn load_schema(path: &Path) -> io::Result<Vec<Table>> {
+    if path != Path::new(STDIN) {
+        return schema::load_schema(path);
+    }
+    let sql = io::read_to_string(io::stdin())?;
+    schema::parse_schema(&sql).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Loads the tables of the schema file, or else of the preset, the config file or the
+/// built-in schema, with the column settings of the config file applied.
+fn load_tables(schema_path: Option<&Path>, preset: Option<Preset>, config: &Config) -> Result<Vec<Table>, Box<dyn Error>> {
+    let mut tables = match (schema_path, preset) {
+        (Some(path), _) => load_schema(path).map_err(|err| format!("unable to read schema file {}: {}", path.display(), err))?,
+        (None, Some(preset)) => preset.tables(),
+        (None, None) => match config.tables()? {
+            Some(tables) => tables,
+            None => schema::parse_schema(DEFAULT_SCHEMA)?,
+        },
+    };
+    if tables.is_empty() {
+        return Err("the schema does not contain any CREATE TABLE statement".into());
+    }
+    config.apply_columns(&mut tables)?;
+    Ok(tables)
+}
+
+/// Loads the config file given with `--config`, or returns the default settings.
+fn load_config(args: &GenerateArgs) -> Result<Config, Box<dyn Error>> {
+    Ok(match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    })
+}
+
+/// Builds the generator and the work to do from the command line and the config file.
+///
+/// # Arguments
+///
+/// * `args` - The generation options of the command line.
+/// * `config` - The settings of the config file.
+/// * `default_dialect` - The dialect used when neither of them sets one.
+fn prepare(args: &GenerateArgs, config: &Config, default_dialect: Dialect) -> Result<(Generator, Work), Box<dyn Error>> {
+    // Initialize tables from the schema file, a preset, the config file, or the built-in schema
+    let schema_path = args.schema.as_ref().or(args.schema_file.as_ref());
+    let preset = match (schema_path, args.preset) {
+        (Some(_), _) => None,
+        (None, Some(preset)) => Some(preset),
+        (None, None) if config.schema.is_none() && config.ddl.is_none() && config.tables.is_empty() => config.preset,
+        (None, None) => None,
+    };
+    let tables = load_tables(schema_path.map(PathBuf::as_path), preset, config)?;
+
+    let scale = args.scale.or(config.scale);
+    if scale.is_some() && preset.is_none() {
+        return Err("--scale sizes the tables of a --preset and needs one".into());
+    }
+    // A preset fills its tables unless random statements are asked for with a count
+    let rows: Vec<(String, u64)> = if !args.rows.is_empty() {
+        args.rows.clone()
+    } else if !config.rows.is_empty() {
+        config.rows.iter().map(|(table, count)| (table.to_lowercase(), *count)).collect()
+    } else {
+        match preset {
+            Some(preset) if scale.is_some() || args.count.or(config.count).is_none() => preset.rows(scale.unwrap_or(1)),
+            _ => vec![],
+        }
+    };
+    if let Some((table, _)) = rows.iter().find(|(name, _)| tables.iter().all(|t| &t.name != name)) {
+        return Err(format!("table `{}` is not defined in the schema", table).into());
+    }
+    // Fill parents before the tables referencing them, otherwise keep the requested order
+    let names: Vec<String> = rows.iter().map(|(name, _)| name.clone()).collect();
+    let rows: Vec<(String, u64)> = schema::parents_first(&tables, &names).into_iter().map(|i| rows[i].clone()).collect();
+
+    let mut series = config.time_series(&tables)?;
+    for name in &args.time_series {
+        if series.iter().all(|(table, _)| table != name) {
+            let table = tables
+                .iter()
+                .find(|t| &t.name == name)
+                .ok_or_else(|| format!("time-series table `{}` is not defined in the schema", name))?;
+            series.push((name.clone(), TimeSeries::infer(table)?));
+        }
+    }
+    for (_, series) in &mut series {
+        series.interval = args.interval.unwrap_or(series.interval);
+        series.jitter = args.jitter.unwrap_or(series.jitter);
+    }
+    let mut dimensions = config.scd2(&tables)?;
+    for name in &args.scd2 {
+        if dimensions.iter().all(|(table, _)| table != name) {
+            let table = tables
+                .iter()
+                .find(|t| &t.name == name)
+                .ok_or_else(|| format!("dimension table `{}` is not defined in the schema", name))?;
+            dimensions.push((name.clone(), Scd2::infer(table)?));
+        }
+    }
+
+    let mut generator = match args.seed.or(config.seed) {
+        Some(seed) => Generator::with_seed(tables, seed),
+        None => Generator::new(tables),
+    };
+    // Use every SQL type unless a subset was requested
+    let mut mix = match args.mix.clone().or_else(|| config.mix.clone()) {
+        Some(mix) if args.sql_types.is_empty() => {
+            if args.dml_only && mix.weights().iter().any(|(sql_type, _)| sql_type.is_ddl()) {
+                return Err(format!("--dml-only cannot be combined with the DDL types of the mix `{}`", mix).into());
+            }
+            mix
+        }
+        _ if args.dml_only => {
+            if let Some(sql_type) = args.sql_types.iter().find(|t| t.is_ddl()) {
+                return Err(format!("--dml-only cannot be combined with the DDL type `{}`", sql_type).into());
+            }
+            let sql_types: Vec<SqlType> = if args.sql_types.is_empty() {
+                SqlType::ALL.iter().copied().filter(|t| !t.is_ddl()).collect()
+            } else {
+                args.sql_types.clone()
+            };
+            Mix::uniform(&sql_types)
+        }
+        _ => Mix::uniform(&args.sql_types),
+    };
+    let only = args.only.as_ref().or(config.only.as_ref());
+    let exclude = args.exclude.as_ref().or(config.exclude.as_ref());
+    let excluded: Vec<SqlType> = SqlType::ALL
+        .iter()
+        .copied()
+        .filter(|&sql_type| only.is_some_and(|only| !only.contains(sql_type)) || exclude.is_some_and(|exclude| exclude.contains(sql_type)))
+        .collect();
+    if only.is_some() || exclude.is_some() {
+        mix = mix.retain(|sql_type| !excluded.contains(&sql_type))?;
+    }
+    // A new version of a dimension is an INSERT followed by the UPDATE closing the old one
+    if !dimensions.is_empty() && excluded.contains(&SqlType::Insert) && mix.weights().iter().any(|(sql_type, _)| *sql_type == SqlType::Update) {
+        return Err("--scd2 writes the versions of dimensions as INSERTs and cannot generate UPDATEs without insert".into());
+    }
+    let safe = args.safe || config.safe.unwrap_or(false);
+    if safe {
+        mix = mix.retain(|sql_type| !sql_type.is_destructive())?;
+    }
+    generator.set_mix(mix);
+    generator.set_safe(safe);
+    generator.set_dialect(args.dialect.or(config.dialect).unwrap_or(default_dialect));
+    generator.set_key_strategy(args.keys.or(config.keys).unwrap_or_default());
+    generator.set_complexity(args.complexity.or(config.complexity).unwrap_or_default());
+    if let Some(subquery_rate) = args.subquery_rate.or(config.subquery_rate) {
+        generator.set_subquery_rate(fake_sql::config::check_rate("the subquery rate", subquery_rate)?);
+    }
+    if let Some(max_predicates) = args.max_predicates.or(config.max_predicates) {
+        generator.set_max_predicates(max_predicates as usize);
+    }
+    if let Some(or_rate) = args.or_rate.or(config.or_rate) {
+        generator.set_or_rate(fake_sql::config::check_rate("the OR rate", or_rate)?);
+    }
+    generator.set_delete_by_key(args.delete_by_key || config.delete_by_key.unwrap_or(false));
+    generator.set_evolve_every(args.evolve_every.or(config.evolve_every));
+    generator.set_excluded_types(excluded);
+
+    if let Some(null_rate) = args.null_rate.or(config.null_rate) {
+        generator.set_null_rate(fake_sql::config::check_rate("the null rate", null_rate)?);
+    }
+    if let Some(max_length_rate) = args.max_length_rate.or(config.max_length_rate) {
+        generator.set_max_length_rate(fake_sql::config::check_rate("the max length rate", max_length_rate)?);
+    }
+    if let Some(duplicates) = args.duplicates.or(config.duplicates) {
+        generator.set_duplicates(duplicates);
+    }
+    if let Some(dirty_rate) = args.dirty_rate.or(config.dirty_rate) {
+        generator.set_dirty_rate(fake_sql::config::check_rate("the dirty rate", dirty_rate)?);
+    }
+    if let Some(date_range) = args.date_range.or(config.date_range) {
+        generator.set_date_range(date_range);
+    }
+    if let Some(locale) = args.locale.or(config.locale) {
+        generator.set_locale(locale);
+    }
+    if let Some(timezones) = args.timezones.clone().or_else(|| config.timezones.clone()) {
+        generator.set_timezones(timezones);
+    }
+    if let Some(bounding_box) = args.bounding_box.or(config.bounding_box) {
+        generator.set_bounding_box(bounding_box);
+    }
+    if let Some(blob_size) = args.blob_size.or(config.blob_size) {
+        generator.set_blob_size(blob_size);
+    }
+    if let Some(blob_encoding) = args.blob_encoding.or(config.blob_encoding) {
+        generator.set_blob_encoding(blob_encoding);
+    }
+    if args.pii_free || config.pii_free.unwrap_or(false) {
+        generator.set_pii_free(true);
+    }
+    let batch_size = args.batch_size.or(config.batch_size).unwrap_or(1).max(1);
+    generator.set_batch_size(batch_size as usize);
+    for (table, series) in series {
+        generator.set_time_series(&table, series)?;
+    }
+    for (table, scd) in dimensions {
+        generator.set_scd2(&table, scd)?;
+    }
+    for (table, column, cardinality) in config.cardinalities()?.into_iter().chain(args.cardinality.clone()) {
+        generator.set_cardinality(&table, &column, cardinality)?;
+    }
+    // The binary registers no custom generators, so columns naming one are rejected
+    generator.set_registry(&GeneratorRegistry::new())?;
+
+    let count = args.count.or(config.count).unwrap_or(30);
+    let parents = generator.tables().iter().flat_map(|table| &table.columns).filter_map(|column| column.ref_table.as_ref()).map(|table| table.to_lowercase()).collect();
+    Ok((generator, Work { count, rows, parents, batch_size }))
+}
+
+/// The statements or rows a generator writes.
+struct Work {
+    /// Number of random statements or rows, used when `rows` is empty.
+    count: u64,
+    /// INSERT rows per table, in the order they are written.
+    rows: Vec<(String, u64)>,
+    /// Lowercase names of the tables referenced by a foreign key.
+    parents: HashSet<String>,
+    /// Number of rows per INSERT statement.
+    batch_size: u64,
+}
+
+impl Work {
+    /// Returns the part of the work done by thread `index` of `threads`; the first
+    /// threads take one more statement or row when the work does not divide evenly.
+    /// Shards only reference the parent rows they inserted, so every thread inserts at
+    /// least one row of a referenced table, even if that exceeds the requested rows.
+    fn share(&self, index: usize, threads: usize) -> Work {
+        let share = |total: u64| total / threads as u64 + u64::from((index as u64) < total % threads as u64);
+        Work {
+            count: share(self.count),
+            rows: self
+                .rows
+                .iter()
+                .map(|(table, count)| {
+                    let min = u64::from(*count > 0 && self.parents.contains(&table.to_lowercase()));
+                    (table.clone(), share(*count).max(min))
+                })
+                .collect(),
+            parents: self.parents.clone(),
+            batch_size: self.batch_size,
+        }
+    }
+
+    /// Returns the number of statements the work generates, or of rows for the row
+    /// formats when `rows_only`.
+    fn total(&self, rows_only: bool) -> u64 {
+        if self.rows.is_empty() {
+            return self.count;
+        }
+        let batch_size = if rows_only { 1 } else { self.batch_size };
+        self.rows.iter().map(|(_, count)| count.div_ceil(batch_size)).sum()
+    }
+
+    /// Returns the statements of the work, generated lazily by `generator`: the INSERT
+    /// batches of `rows`, or `count` random statements.
+    fn into_statements(self, mut generator: Generator) -> impl Iterator<Item = Generated> + Send + 'static {
+        let random = self.rows.is_empty();
+        let mut remaining = self.count;
+        let batch_size = self.batch_size;
+        let mut batches = self.rows.into_iter().flat_map(move |(table, count)| {
+            (0..count.div_ceil(batch_size)).map(move |batch| (table.clone(), (count - batch * batch_size).min(batch_size)))
+        });
+        std::iter::from_fn(move || {
+            let (statement, rows) = if random {
+                remaining = remaining.checked_sub(1)?;
+                let statement = generator.generate();
+                let rows = if statement.sql_type == SqlType::Insert { batch_size } else { 0 };
+                (statement, rows)
+            } else {
+                let (table, rows) = batches.next()?;
+                let sql = generator.insert_batch_into(&table, rows as usize).unwrap();
+                (Statement::new(SqlType::Insert, &table, sql), rows)
+            };
+            Some(Generated {
+                statement,
+                rows,
+                params: generator.take_params(),
+                dirt: generator.take_dirt(),
+                blobs: generator.take_blobs(),
+            })
+        })
+    }
+}
+
+/// A statement of the work.
+struct Generated {
+    statement: Statement,
+    /// Number of rows the statement inserts.
+    rows: u64,
+    /// The parameters bound by the placeholders of the statement, if it has any.
+    params: Option<serde_json::Value>,
+    /// The invalid value injected into the statement, if any.
+    dirt: Option<Dirt>,
+    /// The binary values the statement reads from files of their own.
+    blobs: Vec<Blob>,
+}
+
+/// How generated statements or rows are written.
+struct Output {
+    format: Format,
+    dialect: Dialect,
+    transaction_size: Option<usize>,
+    /// Size of the write buffer in bytes.
+    buffer_size: usize,
+    /// Size after which SQL output moves on to the next numbered file.
+    max_file_size: Option<u64>,
+    /// Whether an existing output file is appended to instead of replaced.
+    append: bool,
+    /// Whether the parameters of the statements are written next to SQL output.
+    params: bool,
+    /// Whether the statements with an invalid value are reported next to SQL output.
+    dirty: bool,
+    /// Pace the statements or rows are written at, each flushed as soon as it is written.
+    rate: Option<Rate>,
+    /// Timestamp of the first statement of a trace.
+    trace_start: NaiveDateTime,
+    /// Seed of the timestamps, sessions and durations of a trace.
+    seed: Option<u64>,
+    /// Number of concurrent sessions a trace simulates.
+    sessions: Option<u32>,
+    /// Progress of the statements or rows written by every thread.
+    progress: ProgressBar,
+}
+
+impl Output {
+    /// Generates `work` with `generator` and writes it to `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The output file, `-` for stdout, or the directory of CSV files.
+    /// * `generator` - The generator producing the statements or rows.
+    /// * `work` - What to generate.
+    /// * `append` - Whether statements or JSON lines are appended to an existing file;
+    ///   numbered files and CSV files are always replaced.
+    ///
+    /// # Returns
+    ///
+    /// The statistics of the statements or rows written, without the elapsed time.
+    fn write(&self, path: &Path, mut generator: Generator, work: Work, append: bool) -> io::Result<Stats> {
+        let mut stats = Stats::default();
+        let bytes = Arc::new(AtomicU64::new(0));
+        let counted = |out: Box<dyn Write>| -> Box<dyn Write> { Box::new(CountingWriter::new(out, bytes.clone())) };
+        if self.format == Format::Sql {
+            let mut writer = match self.max_file_size {
+                Some(max_size) => {
+                    let first = counted(open_output(&numbered_path(path, 1), false, self.buffer_size)?);
+                    let mut writer = SqlWriter::new(first, self.dialect);
+                    let (series, buffer_size, mut next) = (path.to_path_buf(), self.buffer_size, 1);
+                    let bytes = bytes.clone();
+                    writer.set_max_size(max_size, move || {
+                        next += 1;
+                        let out = open_output(&numbered_path(&series, next), false, buffer_size)?;
+                        Ok(Box::new(CountingWriter::new(out, bytes.clone())) as Box<dyn Write>)
+                    });
+                    writer
+                }
+                None => SqlWriter::new(counted(open_output(path, append, self.buffer_size)?), self.dialect),
+            };
+            writer.set_transaction_size(self.transaction_size);
+            let mut params_out =
+                if self.params { Some(counted(open_output(&params_path(path), append, self.buffer_size)?)) } else { None };
+            let mut dirty_out =
+                if self.dirty { Some(counted(open_output(&dirty::report_path(path), append, self.buffer_size)?)) } else { None };
+            let mut pacer = self.rate.map(Pacer::new);
+            // The parameter file has a line per line of the output, empty for BEGIN and COMMIT
+            let mut params_lines = 0;
+            for generated in work.into_statements(generator) {
+                if let Some(pacer) = &mut pacer {
+                    pacer.wait();
+                }
+                let statement = &generated.statement;
+                for blob in &generated.blobs {
+                    fs::write(&blob.path, &blob.bytes)?;
+                }
+                writer.write_statement(&statement.text)?;
+                let line = writer.statement_line();
+                stats.record(&statement.table, statement.sql_type, generated.rows);
+                self.progress.inc(1);
+                if let Some(out) = &mut params_out {
+                    for _ in params_lines + 1..line {
+                        out.write_all(b"\n")?;
+                    }
+                    params_lines = line;
+                    serde_json::to_writer(&mut *out, &generated.params.unwrap_or_default())?;
+                    out.write_all(b"\n")?;
+                    if pacer.is_some() {
+                        out.flush()?;
+                    }
+                }
+                if let (Some(out), Some(dirt)) = (&mut dirty_out, &generated.dirt) {
+                    serde_json::to_writer(&mut *out, &report_line(line, &statement.table, dirt))?;
+                    out.write_all(b"\n")?;
+                }
+                if pacer.is_some() {
+                    writer.flush()?;
+                }
+            }
+            writer.finish()?;
+            if let Some(out) = &mut params_out {
+                for _ in params_lines..writer.lines() {
+                    out.write_all(b"\n")?;
+                }
+                out.flush()?;
+            }
+            if let Some(out) = &mut dirty_out {
+                out.flush()?;
+            }
+            stats.bytes = bytes.load(Ordering::Relaxed);
+            return Ok(stats);
+        }
+        if matches!(self.format, Format::Trace | Format::TraceJson) {
+            let out = counted(open_output(path, append, self.buffer_size)?);
+            let mut writer = TraceWriter::new(out, self.format == Format::TraceJson, self.trace_start, self.seed);
+            writer.set_dialect(self.dialect);
+            if let Some(rate) = self.rate {
+                writer.set_rate(rate);
+            }
+            if let Some(sessions) = self.sessions {
+                writer.set_sessions(sessions);
+            }
+            let mut pacer = self.rate.map(Pacer::new);
+            for generated in work.into_statements(generator) {
+                if let Some(pacer) = &mut pacer {
+                    pacer.wait();
+                }
+                writer.write_statement(&generated.statement)?;
+                if pacer.is_some() {
+                    writer.flush()?;
+                }
+                stats.record(&generated.statement.table, generated.statement.sql_type, generated.rows);
+                self.progress.inc(1);
+            }
+            writer.finish()?;
+            stats.bytes = bytes.load(Ordering::Relaxed);
+            return Ok(stats);
+        }
+        if self.format == Format::CdcJson {
+            let mut writer = CdcWriter::new(counted(open_output(path, append, self.buffer_size)?), self.dialect, self.trace_start);
+            if let Some(rate) = self.rate {
+                writer.set_rate(rate);
+            }
+            let mut pacer = self.rate.map(Pacer::new);
+            // Rows of `--rows` are inserted, otherwise the mix picks inserts, updates and deletes
+            let indexes: Vec<(usize, u64)> =
+                work.rows.iter().map(|(table, count)| (generator.tables().iter().position(|t| &t.name == table).unwrap(), *count)).collect();
+            let mut inserts = indexes.into_iter().flat_map(|(index, count)| std::iter::repeat_n(index, count as usize));
+            let total = if work.rows.is_empty() { work.count } else { work.rows.iter().map(|(_, count)| count).sum() };
+            for _ in 0..total {
+                if let Some(pacer) = &mut pacer {
+                    pacer.wait();
+                }
+                let change = match inserts.next() {
+                    Some(index) => {
+                        let name = generator.tables()[index].name.clone();
+                        let (_, row) = generator.row_for(&name).unwrap();
+                        Change { op: Operation::Create, table: index, before: None, after: Some(row) }
+                    }
+                    None => generator.next_change(),
+                };
+                let table = &generator.tables()[change.table];
+                writer.write_change(table, &change)?;
+                if pacer.is_some() {
+                    writer.flush()?;
+                }
+                let sql_type = match change.op {
+                    Operation::Create => SqlType::Insert,
+                    Operation::Update => SqlType::Update,
+                    Operation::Delete => SqlType::Delete,
+                };
+                stats.record(&table.name, sql_type, 1);
+                self.progress.inc(1);
+            }
+            writer.flush()?;
+            stats.bytes = bytes.load(Ordering::Relaxed);
+            return Ok(stats);
+        }
+
+        let mut writer: Box<dyn RowWriter> = match self.format {
+            Format::Csv => {
+                let mut writer = CsvWriter::create(path)?;
+                writer.set_buffer_size(self.buffer_size);
+                Box::new(writer)
+            }
+            #[cfg(feature = "arrow")]
+            Format::Parquet => Box::new(fake_sql::arrow::ParquetWriter::create(path)?),
+            Format::Bulk => {
+                // Rows for stdout are spooled in the temporary directory
+                let dir = if path == Path::new(STDOUT) { data_dir(&env::temp_dir().join(format!("fake-sql-{}", process::id()))) } else { data_dir(path) };
+                let mut writer = BulkWriter::create(counted(open_output(path, append, self.buffer_size)?), self.dialect, dir)?;
+                writer.set_buffer_size(self.buffer_size);
+                Box::new(writer)
+            }
+            _ => Box::new(JsonlWriter::new(counted(open_output(path, append, self.buffer_size)?))),
+        };
+        let mut pacer = self.rate.map(Pacer::new);
+        let mut write_row = |table: &Table, row: &[fake_sql::SqlValue]| -> io::Result<()> {
+            if let Some(pacer) = &mut pacer {
+                pacer.wait();
+            }
+            writer.write_row(table, row)?;
+            if pacer.is_some() {
+                writer.flush()?;
+            }
+            stats.record(&table.name, SqlType::Insert, 1);
+            self.progress.inc(1);
+            Ok(())
+        };
+        if work.rows.is_empty() {
+            for _ in 0..work.count {
+                let (table, row) = if self.format == Format::Bulk { generator.next_row_parents_first() } else { generator.next_row() };
+                write_row(table, &row)?;
+            }
+        } else {
+            for (table, count) in &work.rows {
+                for _ in 0..*count {
+                    let (table, row) = generator.row_for(table).unwrap();
+                    write_row(table, &row)?;
+                }
+            }
+        }
+        writer.finish()?;
+        stats.bytes = bytes.load(Ordering::Relaxed);
+        if self.format == Format::Bulk && self.dialect == Dialect::MySql {
+            for table in stats.tables.keys() {
+                stats.bytes += fs::metadata(data_dir(path).join(format!("{}.tsv", table)))?.len();
+            }
+        }
+        if self.format.writes_table_files() {
+            // CSV and Parquet files are written by the writer itself, so their sizes are read afterwards
+            for table in stats.tables.keys() {
+                stats.bytes += fs::metadata(path.join(format!("{}.{}", table, self.format)))?.len();
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// Returns the output path of shard `index`, or of thread `index` when the outputs of
+/// the threads are merged afterwards.
+///
+/// Shards are numbered from 1: `out.sql` becomes `out_0001.sql`, and CSV or Parquet output
+/// goes to a `shard_0001` subdirectory. The files of threads are temporary parts next to the
+/// output, or in the temporary directory for stdout.
+fn part_path(path: &Path, format: Format, index: usize, merge: bool) -> PathBuf {
+    if format.writes_table_files() {
+        return path.join(format!("shard_{:04}", index + 1));
+    }
+    if !merge {
+        return numbered_path(path, index + 1);
+    }
+    if path == Path::new(STDOUT) {
+        return env::temp_dir().join(format!("fake-sql-{}.{}.part", process::id(), index));
+    }
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.part", index));
+    path.with_file_name(name)
+}
+
+/// Returns the first file the output would replace, if any exists already.
+///
+/// # Arguments
+///
+/// * `path` - The output file, `-` for stdout, or the directory of CSV or Parquet files.
+/// * `format` - The output format.
+/// * `shards` - The number of shards the output is split into.
+/// * `numbered` - Whether the output is split into numbered files by size.
+/// * `params` - Whether a parameter file is written next to the output.
+/// * `dirty` - Whether a report of the dirty statements is written next to the output.
+/// * `tables` - The tables, which name the CSV and Parquet files.
+fn existing_output(
+    path: &Path,
+    format: Format,
+    shards: usize,
+    numbered: bool,
+    params: bool,
+    dirty: bool,
+    tables: &[Table],
+) -> Option<PathBuf> {
+    if path == Path::new(STDOUT) {
+        return None;
+    }
+    let first = if shards > 1 { part_path(path, format, 0, false) } else { path.to_path_buf() };
+    if format.writes_table_files() {
+        return tables.iter().map(|table| first.join(format!("{}.{}", table.name, format))).find(|file| file.exists());
+    }
+    let first = if numbered { numbered_path(&first, 1) } else { first };
+    let params = params.then(|| params_path(&first));
+    let dirty = dirty.then(|| dirty::report_path(&first));
+    let data = (format == Format::Bulk).then(|| data_dir(&first));
+    Some(first).into_iter().chain(params).chain(dirty).chain(data).find(|file| file.exists())
+}
+
+/// Concatenates the files written by the threads into `path` in thread order and removes them.
+///
+/// # Arguments
+///
+/// * `path` - The output file, or `-` for stdout.
+/// * `parts` - The files of the threads, in thread order.
+/// * `append` - Whether an existing output file is appended to instead of replaced.
+/// * `buffer_size` - Size of the write buffer in bytes.
+fn merge_parts(path: &Path, parts: &[PathBuf], append: bool, buffer_size: usize) -> io::Result<()> {
+    let mut out = open_output(path, append, buffer_size)?;
+    for part in parts {
+        io::copy(&mut File::open(part)?, &mut out)?;
+        fs::remove_file(part)?;
+    }
+    out.flush()
+}
+
+/// Returns the number of lines of the SQL output of `statements` statements, including the
+/// `BEGIN` and `COMMIT` statements of every `transaction_size` statements.
+fn output_lines(statements: u64, transaction_size: Option<usize>, dialect: Dialect) -> u64 {
+    match transaction_size.filter(|size| *size > 0) {
+        Some(size) => statements + statements.div_ceil(size as u64) * (1 + u64::from(dialect.begin_transaction().is_some())),
+        None => statements,
+    }
+}
+
+/// Returns the line of the dirty report describing the invalid value of the statement on
+/// line `number` of the output, counted from 1, e.g. `{"statement": 12, "table": "orders", "row": 3, "column": "total",
+/// "kind": "out_of_range", "value": "-12345678901"}`.
+fn report_line(number: u64, table: &str, dirt: &Dirt) -> serde_json::Value {
+    serde_json::json!({
+        "statement": number,
+        "table": table,
+        "row": dirt.row,
+        "column": dirt.column,
+        "kind": dirt.kind.name(),
+        "value": dirt.value,
+    })
+}
+
+/// Concatenates the dirty reports of the threads into `path` in thread order and removes
+/// them, numbering the lines of each thread after those of the threads before it.
+///
+/// # Arguments
+///
+/// * `path` - The report of the merged output.
+/// * `parts` - The reports of the threads, in thread order.
+/// * `counts` - The number of lines each thread wrote.
+/// * `buffer_size` - Size of the write buffer in bytes.
+fn merge_reports(path: &Path, parts: &[PathBuf], counts: &[u64], buffer_size: usize) -> io::Result<()> {
+    let mut out = open_output(path, false, buffer_size)?;
+    let mut offset = 0;
+    for (part, count) in parts.iter().zip(counts) {
+        for line in BufReader::new(File::open(part)?).lines() {
+            let mut entry: serde_json::Value = serde_json::from_str(&line?)?;
+            entry["statement"] = (entry["statement"].as_u64().unwrap_or_default() + offset).into();
+            serde_json::to_writer(&mut out, &entry)?;
+            out.write_all(b"\n")?;
+        }
+        fs::remove_file(part)?;
+        offset += count;
+    }
+    out.flush()
+}
+
+/// Opens a buffered writer for `path`, or for standard output when `path` is `-`.
+///
+/// # Arguments
+///
+/// * `path` - The output file, or `-` for stdout.
+/// * `append` - Whether an existing file is appended to instead of replaced.
+/// * `buffer_size` - Size of the write buffer in bytes.
+fn open_output(path: &Path, append: bool, buffer_size: usize) -> io::Result<Box<dyn Write>> {
+    if path == Path::new(STDOUT) {
+        return Ok(Box::new(BufWriter::with_capacity(buffer_size, io::stdout().lock())));
+    }
+    let file = if append {
+        OpenOptions::new().append(true).create(true).open(path)?
+    } else {
+        File::create(path)?
+    };
+    Ok(Box::new(BufWriter::with_capacity(buffer_size, file)))
+}
@@ -0,0 +1,235 @@
+//! Weighted statement-type workload profiles.
+
+use crate::models::SqlType;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// A weighted mix of SQL types, e.g. `insert=70,select=20,update=8,delete=2`.
+///
+/// Each generated statement picks its SQL type with a probability proportional
+/// to the type's weight, so the output can resemble a real OLTP trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mix {
+    weights: Vec<(SqlType, u32)>,
+}
+
+impl Mix {
+    /// Initializes a new `Mix` from SQL types and their weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights` - The SQL types and their relative weights.
+    ///
+    /// # Returns
+    ///
+    /// A `Mix`, or an error if no weight is greater than zero or the weights add up to
+    /// more than `u32::MAX`, as [`Mix::choose`] could not pick from them.
+    pub fn new(weights: Vec<(SqlType, u32)>) -> Result<Mix, String> {
+        if weights.iter().all(|(_, weight)| *weight == 0) {
+            return Err("the statement mix needs at least one type with a weight above zero".to_string());
+        }
+        if weights.iter().try_fold(0u32, |total, (_, weight)| total.checked_add(*weight)).is_none() {
+            return Err(format!("the weights of the statement mix add up to more than {}", u32::MAX));
+        }
+        Ok(Mix { weights })
+    }
+
+    /// Initializes a new `Mix` picking every given SQL type with the same probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql_types` - The SQL types to generate. An empty slice selects every type.
+    pub fn uniform(sql_types: &[SqlType]) -> Mix {
+        let sql_types = if sql_types.is_empty() { &SqlType::ALL[..] } else { sql_types };
+        Mix {
+            weights: sql_types.iter().map(|sql_type| (*sql_type, 1)).collect(),
+        }
+    }
+
+    /// Returns the SQL types and their weights.
+    pub fn weights(&self) -> &[(SqlType, u32)] {
+        &self.weights
+    }
+
+    /// Returns the mix without the SQL types `keep` rejects.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - Whether a SQL type stays in the mix.
+    ///
+    /// # Returns
+    ///
+    /// The remaining mix, or an error if no type with a weight above zero remains.
+    pub fn retain(&self, keep: impl Fn(SqlType) -> bool) -> Result<Mix, String> {
+        let weights: Vec<(SqlType, u32)> = self.weights.iter().copied().filter(|(sql_type, _)| keep(*sql_type)).collect();
+        if weights.iter().all(|(_, weight)| *weight == 0) {
+            return Err(format!("filtering the statement types `{}` leaves none to generate", self));
+        }
+        Ok(Mix { weights })
+    }
+
+    /// Picks a random SQL type according to the weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator used for the pick.
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> SqlType {
+        self.weights.choose_weighted(rng, |(_, weight)| *weight).unwrap().0
+    }
+}
+
+impl Default for Mix {
+    fn default() -> Self {
+        Mix::uniform(&SqlType::ALL)
+    }
+}
+
+impl fmt::Display for Mix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.weights.iter().map(|(t, w)| format!("{}={}", t, w)).collect();
+        f.write_str(&parts.join(","))
+    }
+}
+
+impl FromStr for Mix {
+    type Err = String;
+
+    /// Parses a mix such as `insert=70,select=20,update=8,delete=2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = vec![];
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (name, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected `type=weight`, found `{}`", part))?;
+            let sql_type: SqlType = name.trim().parse()?;
+            let weight: u32 = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid weight `{}` for {}", weight.trim(), sql_type))?;
+            weights.push((sql_type, weight));
+        }
+        Mix::new(weights)
+    }
+}
+
+/// A set of SQL types named by their types or families, e.g. `drop,delete`, as `--only` and
+/// `--exclude` take it.
+///
+/// A name is a SQL type such as `insert` or `select-join`, a family of the types named after
+/// it such as `drop` (`drop-table` and `drop-index`) or `create`, or `ddl` or `dml` for the
+/// types that change the schema or the data.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::mix::TypeFilter;
+/// # use fake_sql::SqlType;
+/// let filter: TypeFilter = "drop,delete".parse().unwrap();
+/// assert_eq!(filter.types(), [SqlType::DropTable, SqlType::DropIndex, SqlType::Delete]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeFilter {
+    types: Vec<SqlType>,
+}
+
+impl TypeFilter {
+    /// Returns the SQL types of the set.
+    pub fn types(&self) -> &[SqlType] {
+        &self.types
+    }
+
+    /// Returns whether `sql_type` is in the set.
+    pub fn contains(&self, sql_type: SqlType) -> bool {
+        self.types.contains(&sql_type)
+    }
+}
+
+impl fmt::Display for TypeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.types.iter().map(SqlType::name).collect();
+        f.write_str(&names.join(","))
+    }
+}
+
+impl FromStr for TypeFilter {
+    type Err = String;
+
+    /// Parses a comma separated list of SQL types and families such as `drop,delete`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut types = vec![];
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let family = name.to_lowercase().replace('_', "-");
+            let matches: Vec<SqlType> = match (name.parse::<SqlType>(), family.as_str()) {
+                (Ok(sql_type), _) => vec![sql_type],
+                (Err(_), "ddl") => SqlType::ALL.iter().copied().filter(SqlType::is_ddl).collect(),
+                (Err(_), "dml") => SqlType::ALL.iter().copied().filter(|t| !t.is_ddl()).collect(),
+                (Err(err), _) => {
+                    let matches: Vec<SqlType> = SqlType::ALL.iter().copied().filter(|t| t.name().starts_with(&format!("{}-", family))).collect();
+                    if matches.is_empty() {
+                        return Err(err);
+                    }
+                    matches
+                }
+            };
+            for sql_type in matches {
+                if !types.contains(&sql_type) {
+                    types.push(sql_type);
+                }
+            }
+        }
+        if types.is_empty() {
+            return Err("expected a comma separated list of statement types, e.g. `insert,select`".to_string());
+        }
+        Ok(TypeFilter { types })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_parse_mix() {
+        let mix: Mix = "insert=70, select=20,update=8,delete=2".parse().unwrap();
+        assert_eq!(
+            mix.weights(),
+            [(SqlType::Insert, 70), (SqlType::Select, 20), (SqlType::Update, 8), (SqlType::Delete, 2)]
+        );
+        assert_eq!(mix.to_string().parse::<Mix>(), Ok(mix));
+        assert!("insert".parse::<Mix>().is_err());
+        assert!("insert=x".parse::<Mix>().is_err());
+        assert!("insert=0".parse::<Mix>().is_err());
+        assert!("insert=0,select=0".parse::<Mix>().is_err());
+        assert!(format!("insert={},select=1", u32::MAX).parse::<Mix>().is_err());
+    }
+
+    #[test]
+    fn test_type_filter() {
+        let filter: TypeFilter = "create, DELETE,create-table".parse().unwrap();
+        assert_eq!(filter.types(), [SqlType::CreateTable, SqlType::CreateIndex, SqlType::CreateView, SqlType::Delete]);
+        assert_eq!(filter.to_string().parse::<TypeFilter>(), Ok(filter));
+        assert_eq!("ddl".parse::<TypeFilter>().unwrap().types().len(), 7);
+        assert!(!"dml".parse::<TypeFilter>().unwrap().contains(SqlType::Truncate));
+        assert!("merge".parse::<TypeFilter>().is_err());
+        assert!(",".parse::<TypeFilter>().is_err());
+
+        let mix: Mix = "insert=7,drop-table=1,delete=2".parse().unwrap();
+        let drop: TypeFilter = "drop".parse().unwrap();
+        assert_eq!(mix.retain(|t| !drop.contains(t)).unwrap().to_string(), "insert=7,delete=2");
+        assert!(mix.retain(|t| t == SqlType::Select).is_err());
+    }
+
+    #[test]
+    fn test_choose_follows_weights() {
+        let mix: Mix = "insert=9,drop-table=1,select=0".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        let picks: Vec<SqlType> = (0..1000).map(|_| mix.choose(&mut rng)).collect();
+        let inserts = picks.iter().filter(|t| **t == SqlType::Insert).count();
+        assert!(picks.iter().all(|t| *t != SqlType::Select));
+        assert!((850..950).contains(&inserts), "{}", inserts);
+    }
+}
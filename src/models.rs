@@ -1,29 +1,200 @@
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use rand::Rng;
-use chrono::{NaiveDate, Duration};
-use regex::Regex;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use crate::blob::{BlobEncoding, BlobSize};
+use crate::config::{ColumnDocument, TableDocument};
+use crate::dialect::Dialect;
+use crate::distribution::Distribution;
+use crate::evolve::SchemaChange;
+use crate::geo::{self, BoundingBox};
+use crate::locale::Locale;
+use crate::params::{param_name, ParamStyle};
+use crate::payload::{self, Payload};
+use crate::pool::ValuePool;
+use crate::providers::Provider;
+use crate::query::{self, Complexity, Predicates};
+use crate::schema;
+use crate::statement::Statement;
+use crate::template::Template;
+use crate::timezone::TimeZones;
+use crate::value::SqlValue;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+
+/// Probability that an UPDATE given the primary key of a row targets that row instead of
+/// filtering on indexed columns.
+const KEY_TARGET_RATE: f64 = 0.8;
+
+/// Longest generated interval, 30 days, which MySQL's `time` columns hold as well.
+const MAX_INTERVAL_SECONDS: i64 = 30 * 86_400;
+
+/// Most elements of a generated array.
+const MAX_ARRAY_LENGTH: usize = 5;
+
+/// Most rows drawn for a table until one satisfies its [`TableCheck`]s.
+pub(crate) const MAX_CHECK_ATTEMPTS: usize = 100;
 
 /// Enum representing different types of SQL operations.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SqlType {
     CreateTable,
     AlterTable,
     DropTable,
+    /// `TRUNCATE TABLE`, or an unfiltered `DELETE` where the dialect has no TRUNCATE.
+    Truncate,
+    /// A unique or non-unique index over a random subset of columns.
+    CreateIndex,
+    DropIndex,
+    /// A view, or materialized view where supported, over a generated SELECT.
+    CreateView,
     Insert,
     Select,
+    /// A SELECT joining two or three tables along their foreign keys.
+    SelectJoin,
+    /// A SELECT with aggregate functions, GROUP BY and HAVING.
+    Aggregate,
     Update,
     Delete,
 }
 
+impl SqlType {
+    /// Every SQL statement type, in declaration order.
+    pub const ALL: [SqlType; 13] = [
+        SqlType::CreateTable,
+        SqlType::AlterTable,
+        SqlType::DropTable,
+        SqlType::Truncate,
+        SqlType::CreateIndex,
+        SqlType::DropIndex,
+        SqlType::CreateView,
+        SqlType::Insert,
+        SqlType::Select,
+        SqlType::SelectJoin,
+        SqlType::Aggregate,
+        SqlType::Update,
+        SqlType::Delete,
+    ];
+
+    /// Returns the kebab-case name of the SQL type, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SqlType::CreateTable => "create-table",
+            SqlType::AlterTable => "alter-table",
+            SqlType::DropTable => "drop-table",
+            SqlType::Truncate => "truncate",
+            SqlType::CreateIndex => "create-index",
+            SqlType::DropIndex => "drop-index",
+            SqlType::CreateView => "create-view",
+            SqlType::Insert => "insert",
+            SqlType::Select => "select",
+            SqlType::SelectJoin => "select-join",
+            SqlType::Aggregate => "aggregate",
+            SqlType::Update => "update",
+            SqlType::Delete => "delete",
+        }
+    }
+}
+
+impl fmt::Display for SqlType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SqlType {
+    type Err = String;
+
+    /// Parses a SQL type name such as `insert`, `create-table` or `CREATE_TABLE`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_lowercase();
+        SqlType::ALL
+            .iter()
+            .copied()
+            .find(|sql_type| sql_type.name().replace('-', "") == normalized)
+            .ok_or_else(|| format!("unknown SQL type `{}`", s))
+    }
+}
+
+impl SqlType {
+    /// Returns whether the type drops a table or an index or empties a table: `DROP TABLE`,
+    /// `DROP INDEX` and `TRUNCATE`.
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, SqlType::DropTable | SqlType::DropIndex | SqlType::Truncate)
+    }
+
+    /// Returns whether the type changes the schema rather than the data, e.g.
+    /// `CREATE TABLE`, `TRUNCATE` or `CREATE VIEW`.
+    pub fn is_ddl(&self) -> bool {
+        matches!(
+            self,
+            SqlType::CreateTable
+                | SqlType::AlterTable
+                | SqlType::DropTable
+                | SqlType::Truncate
+                | SqlType::CreateIndex
+                | SqlType::DropIndex
+                | SqlType::CreateView
+        )
+    }
+}
+
 /// Struct representing a database table.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "TableDocument", into = "TableDocument")]
 pub struct Table {
     pub name: String,
     pub columns: Vec<Column>,
     pub comment: Option<String>,
+    /// Names of the primary key columns, in key order. Holds several names for a
+    /// composite key declared with a table-level `PRIMARY KEY (a, b)` clause.
+    pub pkey_columns: Vec<String>,
+    /// Column names of the `UNIQUE (a, b)` constraints spanning several columns. Unique
+    /// single columns are flagged with [`Column::is_unique`] instead.
+    pub unique_keys: Vec<Vec<String>>,
+    /// The `CHECK (...)` constraints spanning several columns. Checks of a single column
+    /// are kept in [`Column::check_expr`] instead.
+    pub checks: Vec<TableCheck>,
+}
+
+/// A `CHECK (...)` constraint spanning several columns of a table, e.g.
+/// `CHECK (ends_at > starts_at)`.
+///
+/// Generated rows satisfy conditions that compare two columns, joined by `AND`: values
+/// in the wrong order are swapped, and rows are drawn again while a comparison fails.
+/// Other conditions are kept in the `CREATE TABLE` statement only, see
+/// [`TableCheck::is_supported`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableCheck {
+    /// The condition as a SQL expression, e.g. `ends_at > starts_at`.
+    pub condition: String,
+    /// The columns the condition refers to.
+    pub columns: Vec<String>,
+    /// The comparisons of two columns the condition consists of: the left column, the
+    /// orderings of its value to the value of the right column that satisfy the
+    /// comparison, and the right column, e.g. `("ends_at", [Greater], "starts_at")`.
+    pub comparisons: Vec<(String, Vec<Ordering>, String)>,
+}
+
+impl TableCheck {
+    /// Returns whether generated rows satisfy the condition, i.e. whether it only
+    /// compares columns with `=`, `<>`, `<`, `<=`, `>` or `>=`.
+    pub fn is_supported(&self) -> bool {
+        !self.comparisons.is_empty()
+    }
 }
 
 /// Struct representing a column in a database table.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "ColumnDocument", into = "ColumnDocument")]
 pub struct Column {
     pub name: String,
     pub column_type: String,
@@ -31,8 +202,758 @@ pub struct Column {
     pub decimal_places: Option<i32>,
     pub is_nullable: bool,
     pub is_pkey: bool,
+    /// Whether no two rows may hold the same value, as declared by `UNIQUE`. Generated
+    /// values of the column are never repeated.
+    pub is_unique: bool,
     pub ref_table: Option<String>,
     pub ref_column: Option<String>,
+    /// The provider used for text values; inferred from the column name when `None`.
+    pub provider: Option<Provider>,
+    /// Probability (0.0 to 1.0) that a generated value of a nullable column is NULL.
+    pub null_rate: Option<f64>,
+    /// Whether the database generates the column's values (`serial`, `AUTO_INCREMENT`,
+    /// `GENERATED ... AS IDENTITY`). Such columns are left out of INSERT and UPDATE statements.
+    pub auto_increment: bool,
+    /// Smallest value generated for a numeric column, e.g. `0.5`. Defaults to 1.
+    pub min_value: Option<f64>,
+    /// Largest value generated for a numeric column. Defaults to the largest value the
+    /// declared precision holds, e.g. 9,999,999,999 for a `number(10)`.
+    pub max_value: Option<f64>,
+    /// Probability (0.0 to 1.0) that a text value is exactly as long as the declared
+    /// length, to test how the database handles values at the boundary.
+    pub max_length_rate: Option<f64>,
+    /// Dates and timestamps generated for the column. Defaults to `DateRange::default()`.
+    pub date_range: Option<DateRange>,
+    /// How numbers and foreign keys are spread over their range. Defaults to uniform.
+    pub distribution: Option<Distribution>,
+    /// The fixed values of a categorical column, e.g. order statuses. When set, every
+    /// generated value and predicate of the column uses the pool.
+    pub pool: Option<ValuePool>,
+    /// The locale of names, addresses and other provider text. Defaults to English.
+    pub locale: Option<Locale>,
+    /// The column comment, rendered as `COMMENT ON COLUMN` or an inline MySQL `COMMENT`.
+    pub comment: Option<String>,
+    /// The default of the column as a SQL expression, e.g. `0` or `CURRENT_TIMESTAMP`.
+    pub default_value: Option<String>,
+    /// The CHECK constraint of the column as a SQL expression, e.g. `price >= 0`. Parsed
+    /// schemas narrow `min_value`, `max_value` and `pool` to the values it accepts.
+    pub check_expr: Option<String>,
+    /// The format of the column's values, e.g. `ORD-{YYYY}{seq:06}`. See [`Template`].
+    pub template: Option<Template>,
+    /// Whether names, email addresses and phone numbers only use placeholder names, the
+    /// domains reserved for examples and fictional numbers, see [`Provider::generate_pii_free`].
+    pub pii_free: bool,
+    /// UTC offsets of `timestamptz` values, e.g. `+00:00,+05:30`. Defaults to any whole-hour
+    /// offset.
+    pub timezones: Option<TimeZones>,
+    /// The attributes of a column of a Postgres composite type, or of an array of one,
+    /// declared with `CREATE TYPE ... AS (...)`. Empty for other columns.
+    pub attributes: Vec<Column>,
+    /// The documents of a `json` or `jsonb` column, from a JSON schema or template.
+    /// Defaults to a small object with an id, a name, an email address and a flag.
+    pub payload: Option<Payload>,
+    /// The area the values of a `geometry` or `geography` column are drawn within.
+    /// Defaults to the whole world.
+    pub bounding_box: Option<BoundingBox>,
+    /// The members of an enum column, in declaration order; empty for other columns.
+    /// Postgres enums keep the name of their type as the column type, MySQL's inline
+    /// `ENUM(...)` columns have the type `enum`.
+    pub labels: Vec<String>,
+    /// The range of sizes of a binary column's values, spread by the column's distribution.
+    /// Defaults to 1 to 16 bytes, or up to the declared length of at most 64 bytes.
+    pub blob_size: Option<BlobSize>,
+    /// How a binary column's values are written into statements. Defaults to hex.
+    pub blob_encoding: Option<BlobEncoding>,
+    /// The name of the custom generator filling the column, registered in a
+    /// [`GeneratorRegistry`](crate::plugin::GeneratorRegistry).
+    pub custom_generator: Option<String>,
+}
+
+/// An inclusive range of dates values are drawn from, written `2021-01-01..2025-12-31`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// Creates a range from `start` to `end`, both included.
+    ///
+    /// # Returns
+    ///
+    /// The range, or an error if `start` is after `end`.
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Result<DateRange, String> {
+        if start > end {
+            return Err(format!("the date range starts on {} after it ends on {}", start, end));
+        }
+        Ok(DateRange { start, end })
+    }
+
+    /// Returns a random date within the range.
+    pub fn random_date<R: Rng + ?Sized>(&self, rng: &mut R) -> NaiveDate {
+        self.start + Duration::days(rng.gen_range(0..=(self.end - self.start).num_days()))
+    }
+
+    /// Returns a random timestamp within the range, at any second of the day.
+    pub fn random_timestamp<R: Rng + ?Sized>(&self, rng: &mut R) -> NaiveDateTime {
+        self.random_date(rng).and_hms_opt(0, 0, 0).unwrap() + Duration::seconds(rng.gen_range(0..86_400))
+    }
+}
+
+impl Default for DateRange {
+    /// The range 2021-01-01 to 2025-12-31.
+    ///
+    /// Dates are drawn from a fixed range rather than relative to today so seeded
+    /// generation stays reproducible from one day to the next.
+    fn default() -> Self {
+        DateRange {
+            start: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+            end: NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+        }
+    }
+}
+
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+impl FromStr for DateRange {
+    type Err = String;
+
+    /// Parses a range such as `2020-01-01..2024-12-31`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once("..")
+            .ok_or_else(|| format!("expected a date range such as `2021-01-01..2025-12-31`, found `{}`", s))?;
+        let parse = |date: &str| {
+            NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| format!("invalid date `{}`", date.trim()))
+        };
+        DateRange::new(parse(start)?, parse(end)?)
+    }
+}
+
+impl Column {
+    /// Initializes a new nullable `Column` without key, reference or generation settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the column.
+    /// * `column_type` - The lowercase type name, e.g. `varchar` or `number`.
+    /// * `length` - The declared length or precision, e.g. `Some(20)` for `varchar(20)`.
+    /// * `decimal_places` - The declared scale, e.g. `Some(2)` for `number(10,2)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Column, Dialect};
+    /// let column = Column::new("price", "number", Some(10), Some(2));
+    /// assert_eq!(column.definition(Dialect::Postgres), "price numeric(10,2)");
+    /// ```
+    pub fn new(name: &str, column_type: &str, length: Option<i32>, decimal_places: Option<i32>) -> Column {
+        Column {
+            name: name.to_string(),
+            column_type: column_type.to_string(),
+            length,
+            decimal_places,
+            is_nullable: true,
+            is_pkey: false,
+            is_unique: false,
+            ref_table: None,
+            ref_column: None,
+            provider: None,
+            null_rate: None,
+            auto_increment: false,
+            min_value: None,
+            max_value: None,
+            max_length_rate: None,
+            date_range: None,
+            distribution: None,
+            pool: None,
+            locale: None,
+            comment: None,
+            default_value: None,
+            check_expr: None,
+            template: None,
+            pii_free: false,
+            timezones: None,
+            attributes: vec![],
+            payload: None,
+            bounding_box: None,
+            labels: vec![],
+            blob_size: None,
+            blob_encoding: None,
+            custom_generator: None,
+        }
+    }
+
+    /// Renders the column definition used in CREATE TABLE and ALTER TABLE statements.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the type name and identifier are rendered for.
+    ///
+    /// # Returns
+    ///
+    /// A string such as `id number(10) NOT NULL PRIMARY KEY`.
+    pub fn definition(&self, dialect: Dialect) -> String {
+        self.definition_with(dialect, self.is_pkey)
+    }
+
+    /// Renders the column definition, with an inline `PRIMARY KEY` only if `inline_pkey` is set.
+    fn definition_with(&self, dialect: Dialect, inline_pkey: bool) -> String {
+        let identity = match (self.auto_increment, dialect) {
+            (false, _) => "",
+            (true, Dialect::Oracle | Dialect::Postgres) => " GENERATED BY DEFAULT AS IDENTITY",
+            (true, Dialect::SqlServer) => " IDENTITY(1,1)",
+            (true, Dialect::MySql | Dialect::Sqlite) => "",
+        };
+        let default = match &self.default_value {
+            Some(default) => format!(" DEFAULT {}", default),
+            None => String::new(),
+        };
+        // Composites are Postgres types; other dialects store them as JSON
+        let column_type = match dialect {
+            Dialect::Postgres => &self.column_type,
+            _ if !self.attributes.is_empty() && !self.column_type.ends_with("[]") => "json",
+            _ => &self.column_type,
+        };
+        // Enums are Postgres types and MySQL column types; other dialects check the members
+        let labels: Vec<String> = self.labels.iter().map(|label| dialect.string_literal(label)).collect();
+        let enum_check = !labels.is_empty() && !self.column_type.ends_with("[]") && !self.has_enum_type(dialect);
+        let type_name = match dialect {
+            _ if labels.is_empty() || self.column_type.ends_with("[]") => dialect.type_name(column_type, self.length, self.decimal_places),
+            Dialect::Postgres if self.has_enum_type(dialect) => self.column_type.clone(),
+            Dialect::MySql => format!("ENUM({})", labels.join(", ")),
+            _ => {
+                let longest = self.labels.iter().map(|label| label.chars().count()).max().unwrap_or(1);
+                dialect.type_name("varchar", Some(longest.max(1) as i32), None)
+            }
+        };
+        let mut definition = format!(
+            "{} {}{}{}{}",
+            dialect.identifier(&self.name),
+            type_name,
+            default,
+            identity,
+            if self.is_nullable { "" } else { " NOT NULL" },
+        );
+        if self.auto_increment && dialect == Dialect::MySql {
+            definition.push_str(" AUTO_INCREMENT");
+        }
+        if self.is_unique && !inline_pkey {
+            definition.push_str(" UNIQUE");
+        }
+        if inline_pkey {
+            definition.push_str(" PRIMARY KEY");
+            // SQLite only accepts AUTOINCREMENT on an INTEGER PRIMARY KEY
+            if self.auto_increment && dialect == Dialect::Sqlite {
+                definition.push_str(" AUTOINCREMENT");
+            }
+        }
+        // Other dialects comment columns in separate statements, see `Table::schema_statements`
+        if let (Some(comment), Dialect::MySql) = (&self.comment, dialect) {
+            definition.push_str(&format!(" COMMENT {}", dialect.string_literal(comment)));
+        }
+        if let Some(check) = &self.check_expr {
+            definition.push_str(&format!(" CHECK ({})", check));
+        }
+        if enum_check {
+            definition.push_str(&format!(" CHECK ({} IN ({}))", dialect.identifier(&self.name), labels.join(", ")));
+        }
+        definition
+    }
+
+    /// Returns `true` if `dialect` declares the column's enum as a type of its own: a
+    /// Postgres enum created with `CREATE TYPE ... AS ENUM`, or any enum in MySQL.
+    fn has_enum_type(&self, dialect: Dialect) -> bool {
+        match dialect {
+            _ if self.labels.is_empty() => false,
+            Dialect::Postgres => self.column_type.trim_end_matches("[]") != "enum",
+            Dialect::MySql => true,
+            _ => false,
+        }
+    }
+
+    /// Renders the `CREATE TYPE` statements of the column's enum or composite type and of
+    /// the types of its attributes, in the order they have to run. Only Postgres has such
+    /// types, so other dialects get none.
+    pub fn type_statements(&self, dialect: Dialect) -> Vec<String> {
+        if dialect != Dialect::Postgres {
+            return vec![];
+        }
+        if self.has_enum_type(dialect) {
+            let labels: Vec<String> = self.labels.iter().map(|label| dialect.string_literal(label)).collect();
+            return vec![format!("CREATE TYPE {} AS ENUM ({});", self.column_type.trim_end_matches("[]"), labels.join(", "))];
+        }
+        if self.attributes.is_empty() {
+            return vec![];
+        }
+        let mut statements: Vec<String> = self.attributes.iter().flat_map(|a| a.type_statements(dialect)).collect();
+        let definitions: Vec<String> = self.attributes.iter().map(|a| a.definition(dialect)).collect();
+        let name = self.column_type.trim_end_matches("[]");
+        statements.push(format!("CREATE TYPE {} AS ({});", name, definitions.join(", ")));
+        statements
+    }
+
+    /// Returns the column each element of an array column is generated as, e.g. an `int`
+    /// column for an `int[]` column, or `None` if the column is not an array.
+    ///
+    /// Elements keep the column's length, range, provider and attributes, but are never
+    /// NULL and not drawn from the pool or template of the whole array.
+    pub fn element(&self) -> Option<Column> {
+        let element_type = self.column_type.strip_suffix("[]")?;
+        Some(Column {
+            column_type: element_type.to_string(),
+            is_nullable: false,
+            is_pkey: false,
+            is_unique: false,
+            ref_table: None,
+            ref_column: None,
+            null_rate: None,
+            pool: None,
+            template: None,
+            check_expr: None,
+            default_value: None,
+            custom_generator: None,
+            ..self.clone()
+        })
+    }
+
+    /// Returns `true` if `dialect` stores the column's values as JSON: `json` and `jsonb`
+    /// columns, and arrays and composites in dialects other than Postgres.
+    pub fn is_json(&self, dialect: Dialect) -> bool {
+        matches!(self.column_type.as_str(), "json" | "jsonb")
+            || (dialect != Dialect::Postgres && (self.element().is_some() || !self.attributes.is_empty()))
+    }
+
+    /// Returns `true` for `geometry` and `geography` columns, whose values cannot be
+    /// compared, sorted or grouped by.
+    pub fn is_spatial(&self) -> bool {
+        geo::spatial_type(&self.column_type).is_some()
+    }
+
+    /// Returns `true` for numeric column types such as `int` or `number(10,2)`.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.column_type.as_str(),
+            "int" | "integer" | "smallint" | "bigint" | "tinyint" | "number" | "numeric" | "decimal" | "float"
+        )
+    }
+
+    /// Returns the provider used for text values.
+    ///
+    /// Unless one was set explicitly, the provider is inferred from the column name.
+    /// Columns whose name gives no hint are filled with first names.
+    pub fn provider(&self) -> Provider {
+        self.inferred_provider().unwrap_or(Provider::FirstName)
+    }
+
+    /// Returns the provider set for the column, or the one inferred from its name.
+    fn inferred_provider(&self) -> Option<Provider> {
+        self.provider.or_else(|| Provider::infer(&self.name))
+    }
+
+    /// Describes how [`Column::generate_value`] fills the column, e.g.
+    /// `email (locale ja), up to 50 characters, 10% NULL`, so the resolved settings can be
+    /// checked before generating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::Column;
+    /// let mut column = Column::new("price", "number", Some(10), Some(2));
+    /// column.null_rate = Some(0.25);
+    /// assert_eq!(column.describe_values(), "numbers from 0.99 to 999.99, 25% NULL");
+    /// ```
+    pub fn describe_values(&self) -> String {
+        let mut description = if let Some(pool) = &self.pool {
+            format!("pool of {} values", pool.values().count())
+        } else if let Some(template) = &self.template {
+            format!("template `{}`", template)
+        } else if !self.labels.is_empty() && !self.column_type.ends_with("[]") {
+            format!("one of {}", self.labels.join(", "))
+        } else if let Some(element) = self.element() {
+            format!("arrays of up to {} {}", MAX_ARRAY_LENGTH, element.describe_values())
+        } else if !self.attributes.is_empty() {
+            let attributes: Vec<String> = self.attributes.iter().map(|a| format!("{}: {}", a.name, a.describe_values())).collect();
+            format!("composite of {}", attributes.join("; "))
+        } else if let Some((_, shape)) = geo::spatial_type(&self.column_type) {
+            format!("{} shapes within {}", shape.map_or("random", |shape| shape.name()), self.bounding_box.unwrap_or_default())
+        } else {
+            match self.column_type.as_str() {
+                "varchar" | "text" => {
+                    let mut text = self.provider().to_string();
+                    if let Some(locale) = self.locale.filter(|_| self.provider() != Provider::Timestamp) {
+                        text += &format!(" (locale {})", locale);
+                    }
+                    if self.pii_free {
+                        text += ", PII-free";
+                    }
+                    if let Some(length) = self.length {
+                        text += &format!(", up to {} characters", length);
+                    }
+                    text
+                }
+                "date" => format!("dates in {}", self.date_range()),
+                "datetime" | "timestamp" => format!("timestamps in {}", self.date_range()),
+                "timestamptz" => match self.timezones.clone().unwrap_or_default().to_string().as_str() {
+                    "" => format!("timestamps in {} at random UTC offsets", self.date_range()),
+                    offsets => format!("timestamps in {} at {}", self.date_range(), offsets),
+                },
+                "boolean" | "bool" | "bit" => "true or false".to_string(),
+                "uuid" | "uniqueidentifier" => "random UUIDs".to_string(),
+                "time" => "times of day".to_string(),
+                "interval" => "intervals of up to 30 days".to_string(),
+                "blob" | "bytea" | "binary" | "varbinary" | "raw" => match self.blob_size {
+                    Some(size) => format!("{} bytes, {}", size, self.blob_encoding.unwrap_or_default()),
+                    None => format!("random bytes, {}", self.blob_encoding.unwrap_or_default()),
+                },
+                "json" | "jsonb" if self.payload.is_some() => "JSON documents of the payload".to_string(),
+                "json" | "jsonb" => "JSON documents".to_string(),
+                _ => {
+                    let (min, max) = self.numeric_range();
+                    format!("numbers from {} to {}", self.number_value(min).as_text().unwrap(), self.number_value(max).as_text().unwrap())
+                }
+            }
+        };
+        if let Some(distribution) = self.distribution {
+            description += &format!(", {} distribution", distribution);
+        }
+        if let Some(rate) = self.null_rate.filter(|&rate| self.is_nullable && rate > 0.0) {
+            description += &format!(", {}% NULL", (rate * 100.0).round());
+        }
+        description
+    }
+
+    /// Generates a random value matching the column type.
+    ///
+    /// Nullable columns with a `null_rate` produce `SqlValue::Null` with that probability;
+    /// NOT NULL columns never do, whatever their `null_rate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator the value is drawn from.
+    ///
+    /// # Returns
+    ///
+    /// A `SqlValue` that can be rendered for any dialect.
+    pub fn generate_value<R: Rng + ?Sized>(&self, rng: &mut R) -> SqlValue {
+        if self.is_nullable && self.null_rate.is_some_and(|rate| rng.gen_bool(rate.clamp(0.0, 1.0))) {
+            return SqlValue::Null;
+        }
+        if let Some(pool) = &self.pool {
+            return pool.choose(self.distribution, rng).clone();
+        }
+        if let Some(template) = &self.template {
+            // Outside the generator's sequences, `{seq}` renders a random number
+            return self.render_template(template, rng.gen_range(1..1_000_000), rng);
+        }
+        if !self.labels.is_empty() && !self.column_type.ends_with("[]") {
+            return SqlValue::Text(self.labels.choose(rng).unwrap().clone());
+        }
+        if let Some(element) = self.element() {
+            let length = rng.gen_range(0..=MAX_ARRAY_LENGTH);
+            return SqlValue::Array((0..length).map(|_| element.generate_value(rng)).collect());
+        }
+        if !self.attributes.is_empty() {
+            return SqlValue::Composite(self.attributes.iter().map(|a| (a.name.clone(), a.generate_value(rng))).collect());
+        }
+        if let Some((geography, shape)) = geo::spatial_type(&self.column_type) {
+            return SqlValue::Geometry(geo::random_wkt(shape, self.bounding_box.unwrap_or_default(), rng), geography);
+        }
+        match self.column_type.as_str() {
+            "varchar" | "text" => {
+                let text = self.random_text(rng);
+                match self.length {
+                    Some(length) if self.max_length_rate.is_some_and(|rate| rate > 0.0 && rng.gen_bool(rate.min(1.0))) => {
+                        // Repeat the value until it fills the column
+                        SqlValue::Text(text.chars().cycle().take(length.max(1) as usize).collect())
+                    }
+                    _ => SqlValue::Text(text),
+                }
+            }
+            "date" => SqlValue::Date(self.date_range().random_date(rng)),
+            "datetime" => SqlValue::Timestamp(self.date_range().random_timestamp(rng)),
+            "boolean" | "bool" | "bit" => SqlValue::Boolean(rng.gen()),
+            "uuid" | "uniqueidentifier" => SqlValue::Uuid(random_uuid(rng)),
+            "timestamp" => SqlValue::Timestamp(self.date_range().random_timestamp(rng)),
+            "timestamptz" => SqlValue::TimestampTz(self.random_timestamp_tz(rng)),
+            "time" => SqlValue::Time(random_time(rng)),
+            "interval" => SqlValue::Interval(rng.gen_range(0..=MAX_INTERVAL_SECONDS)),
+            "blob" | "bytea" | "binary" | "varbinary" | "raw" => {
+                let len = match self.blob_size {
+                    // A declared length such as `varbinary(255)` still caps the size
+                    Some(size) => size.sample(self.distribution.unwrap_or_default(), rng).min(self.length.map_or(usize::MAX, |length| length.max(0) as usize)),
+                    None => rng.gen_range(1..=self.length.unwrap_or(16).clamp(1, 64)) as usize,
+                };
+                let bytes = (0..len).map(|_| rng.gen()).collect();
+                match self.blob_encoding.unwrap_or_default() {
+                    BlobEncoding::Hex => SqlValue::Bytes(bytes),
+                    BlobEncoding::Base64 => SqlValue::Base64(bytes),
+                }
+            }
+            "json" | "jsonb" => SqlValue::Json(self.random_document(rng)),
+            _ => self.random_number(rng),
+        }
+    }
+
+    /// Generates a value of the column from its template, with `seq` as the sequence
+    /// number, or NULL as often as the null rate says.
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if the column has no template.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::{Column, SqlValue};
+    /// let mut column = Column::new("order_code", "varchar", Some(10), None);
+    /// column.template = Some("ORD-{seq:04}".parse().unwrap());
+    /// let value = column.template_value(12, &mut rand::thread_rng());
+    /// assert_eq!(value, Some(SqlValue::Text("ORD-0012".to_string())));
+    /// ```
+    pub fn template_value<R: Rng + ?Sized>(&self, seq: i64, rng: &mut R) -> Option<SqlValue> {
+        let template = self.template.as_ref()?;
+        if self.is_nullable && self.null_rate.is_some_and(|rate| rng.gen_bool(rate.clamp(0.0, 1.0))) {
+            return Some(SqlValue::Null);
+        }
+        Some(self.render_template(template, seq, rng))
+    }
+
+    /// Renders `template` as a value of the column: a number for numeric columns when the
+    /// text is one, otherwise text cut to the declared length.
+    fn render_template<R: Rng + ?Sized>(&self, template: &Template, seq: i64, rng: &mut R) -> SqlValue {
+        let text = template.render(self.date_range(), self.locale.unwrap_or_default(), self.pii_free, seq, rng);
+        match text.parse() {
+            Ok(n) if self.is_numeric() => SqlValue::Integer(n),
+            _ => match self.length {
+                Some(length) if !self.is_numeric() => SqlValue::Text(text.chars().take(length.max(1) as usize).collect()),
+                _ => SqlValue::Text(text),
+            },
+        }
+    }
+
+    /// Returns the range dates and timestamps are drawn from.
+    pub fn date_range(&self) -> DateRange {
+        self.date_range.unwrap_or_default()
+    }
+
+    /// Returns a random timestamp within the date range with one of the column's UTC offsets.
+    fn random_timestamp_tz<R: Rng + ?Sized>(&self, rng: &mut R) -> DateTime<FixedOffset> {
+        self.timezones.clone().unwrap_or_default().random_timestamp(self.date_range(), rng)
+    }
+
+    /// Generates a JSON document from the column's payload, or the default object.
+    fn random_document<R: Rng + ?Sized>(&self, rng: &mut R) -> serde_json::Value {
+        if let Some(payload) = &self.payload {
+            return payload.generate(self.date_range(), self.locale.unwrap_or_default(), self.pii_free, rng);
+        }
+        serde_json::json!({
+            "id": rng.gen_range(1..1000),
+            "name": if self.pii_free { Provider::FullName.generate_pii_free(Locale::En, rng) } else { Provider::FullName.generate(rng) },
+            "email": if self.pii_free { Provider::Email.generate_pii_free(Locale::En, rng) } else { Provider::Email.generate(rng) },
+            "active": rng.gen::<bool>(),
+        })
+    }
+
+    /// Generates a text value with the column's provider, cut to the declared length.
+    fn random_text<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let text = match self.provider() {
+            Provider::Timestamp => self.date_range().random_timestamp(rng).format("%Y-%m-%d %H:%M:%S").to_string(),
+            provider if self.pii_free => provider.generate_pii_free(self.locale.unwrap_or_default(), rng),
+            provider => provider.generate_in(self.locale.unwrap_or_default(), rng),
+        };
+        match self.length {
+            Some(length) => text.chars().take(length.max(1) as usize).collect(),
+            None => text,
+        }
+    }
+
+    /// Returns the range of values generated for a numeric column, in units of its scale,
+    /// e.g. cents for a `number(10, 2)`.
+    ///
+    /// The range runs from `min_value` (default 1, or the smallest unit for a scale with
+    /// no integer digits such as `number(3, 3)`) to `max_value`, and never exceeds what the
+    /// declared precision or the integer type can hold: a `number(10, 2)` holds 8 digits
+    /// before the decimal point and 2 after it. Columns without a declared precision hold up
+    /// to 9 digits, integers at most 18 and decimals, which are exact, up to 38. Price
+    /// columns such as `unit_price` default to 0.99 to 999.99 instead.
+    pub fn numeric_range(&self) -> (i128, i128) {
+        let factor = 10i128.pow(self.decimal_places.unwrap_or(0).clamp(0, 38) as u32);
+        let limit = self.numeric_limit();
+        let (default_min, default_max) = match self.inferred_provider() {
+            Some(Provider::Price) => ((0.99 * factor as f64).round().max(1.0) as i128, (999.99 * factor as f64).round() as i128),
+            _ if factor <= limit => (factor, limit),
+            _ => (1, limit),
+        };
+        // `as` saturates, so huge overrides end up at the limit
+        let min = self.min_value.map_or(default_min, |min| (min * factor as f64).round() as i128).clamp(-limit, limit);
+        let max = self.max_value.map_or(default_max, |max| (max * factor as f64).round() as i128).clamp(-limit, limit);
+        (min, max.max(min))
+    }
+
+    /// Returns the largest number of units of the column's scale its type holds, e.g.
+    /// 32,767 for a `smallint` or 99,999 for a `number(5, 2)`.
+    pub(crate) fn numeric_limit(&self) -> i128 {
+        // Integers are drawn as `i64`, decimals as exact units
+        let max_digits = if self.decimal_places.unwrap_or(0) > 0 { 38 } else { 18 };
+        let digits = self.length.unwrap_or(9).clamp(1, max_digits) as u32;
+        match self.column_type.as_str() {
+            "tinyint" => i8::MAX as i128,
+            "smallint" => i16::MAX as i128,
+            "int" | "integer" | "mediumint" if self.length.is_none() => i32::MAX as i128,
+            "int" | "integer" | "mediumint" => (10i128.pow(digits) - 1).min(i32::MAX as i128),
+            "bigint" if self.length.is_none() => i64::MAX as i128,
+            _ => 10i128.pow(digits) - 1,
+        }
+    }
+
+    /// Draws a number within `numeric_range`, as a decimal when the column has a scale.
+    fn random_number<R: Rng + ?Sized>(&self, rng: &mut R) -> SqlValue {
+        let (min, max) = self.numeric_range();
+        let units = match self.distribution {
+            Some(distribution) => distribution.sample(min, max, rng),
+            None => rng.gen_range(min..=max),
+        };
+        self.number_value(units)
+    }
+
+    /// Returns the value of `units` of the column's scale, e.g. 12.34 for 1234 units of a
+    /// `number(10, 2)`.
+    pub(crate) fn number_value(&self, units: i128) -> SqlValue {
+        match self.decimal_places {
+            Some(decimal_places) if decimal_places > 0 => SqlValue::Decimal(units, decimal_places as u32),
+            _ => SqlValue::Integer(units.clamp(i64::MIN as i128, i64::MAX as i128) as i64),
+        }
+    }
+
+    /// Returns whether [`Column::generate_condition`] generates predicates for the column.
+    pub fn is_filterable(&self) -> bool {
+        if let Some(element) = self.element() {
+            return element.is_filterable();
+        }
+        self.pool.is_some()
+            || !self.labels.is_empty()
+            || self.is_numeric()
+            || matches!(
+                self.column_type.as_str(),
+                "varchar" | "text" | "date" | "timestamp" | "datetime" | "timestamptz" | "time" | "interval" | "boolean" | "bool" | "bit" | "json" | "jsonb"
+            )
+    }
+
+    /// Generates a random predicate on the column that `value` satisfies, e.g. `price >= 42`
+    /// or `name IN ('Alice', 'Bob')` for the values 42 and `Alice`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - The rendered column reference, e.g. `price` or `p.price`.
+    /// * `value` - A value of the column, such as one of an inserted row.
+    /// * `dialect` - The dialect literals are rendered for.
+    /// * `rng` - The random number generator used for operators and other values.
+    pub fn matching_condition<R: Rng + ?Sized>(&self, reference: &str, value: &SqlValue, dialect: Dialect, rng: &mut R) -> String {
+        let literal = dialect.literal(value);
+        match value {
+            SqlValue::Null => format!("{} IS NULL", reference),
+            SqlValue::Array(values) => {
+                let elements: Vec<&SqlValue> = values.iter().filter(|value| **value != SqlValue::Null).collect();
+                contains_condition(reference, elements.choose(rng).copied(), dialect)
+            }
+            SqlValue::Json(document) => json_condition(reference, document, dialect, rng),
+            // Enums refuse other text than their members
+            SqlValue::Text(_) if self.pool.is_none() && self.labels.is_empty() && rng.gen_bool(0.5) => {
+                let mut values: Vec<String> = (0..rng.gen_range(1..5))
+                    .map(|_| dialect.string_literal(&self.random_text(rng)))
+                    .collect();
+                values.insert(rng.gen_range(0..=values.len()), literal);
+                format!("{} IN ({})", reference, values.join(", "))
+            }
+            SqlValue::Integer(_)
+            | SqlValue::Decimal(..)
+            | SqlValue::Date(_)
+            | SqlValue::Timestamp(_)
+            | SqlValue::TimestampTz(_)
+            | SqlValue::Time(_)
+            | SqlValue::Interval(_) => {
+                format!("{} {} {}", reference, ["=", ">=", "<="].choose(rng).unwrap(), literal)
+            }
+            _ => format!("{} = {}", reference, literal),
+        }
+    }
+
+    /// Generates a random predicate on the column, e.g. `price > 42` or `name IN ('a', 'b')`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - The rendered column reference, e.g. `price` or `p.price`.
+    /// * `dialect` - The dialect literals are rendered for.
+    /// * `rng` - The random number generator used for operators and values.
+    ///
+    /// # Returns
+    ///
+    /// The predicate, or `None` for column types no predicate is generated for.
+    pub fn generate_condition<R: Rng + ?Sized>(&self, reference: &str, dialect: Dialect, rng: &mut R) -> Option<String> {
+        if let Some(pool) = &self.pool {
+            let values: Vec<String> = pool.choose_many(rng.gen_range(1..=3), rng).into_iter().map(|v| dialect.literal(v)).collect();
+            return Some(match values.as_slice() {
+                [value] => format!("{} = {}", reference, value),
+                _ => format!("{} IN ({})", reference, values.join(", ")),
+            });
+        }
+        if let Some(element) = self.element() {
+            return Some(contains_condition(reference, Some(&element.generate_value(rng)), dialect));
+        }
+        if !self.labels.is_empty() {
+            let count = rng.gen_range(1..=self.labels.len().min(3));
+            let labels: Vec<String> = self.labels.choose_multiple(rng, count).map(|label| dialect.string_literal(label)).collect();
+            return Some(match labels.as_slice() {
+                [label] => format!("{} = {}", reference, label),
+                _ => format!("{} IN ({})", reference, labels.join(", ")),
+            });
+        }
+        let condition = match self.column_type.as_str() {
+            _ if self.is_numeric() => {
+                let operator = ["=", ">", "<", ">=", "<="].choose(rng).unwrap();
+                format!("{} {} {}", reference, operator, dialect.literal(&self.random_number(rng)))
+            }
+            "varchar" | "text" => {
+                let values: Vec<String> = (0..rng.gen_range(2..11))
+                    .map(|_| dialect.string_literal(&self.random_text(rng)))
+                    .collect();
+                format!("{} IN ({})", reference, values.join(", "))
+            }
+            "date" => {
+                let (start, end) = (self.date_range().random_date(rng), self.date_range().random_date(rng));
+                let (start, end) = (start.min(end), start.max(end));
+                format!("{} BETWEEN {} AND {}", reference, dialect.date_literal(start), dialect.date_literal(end))
+            }
+            "timestamp" | "datetime" => {
+                let (start, end) = (self.date_range().random_timestamp(rng), self.date_range().random_timestamp(rng));
+                let (start, end) = (start.min(end), start.max(end));
+                format!("{} BETWEEN {} AND {}", reference, dialect.timestamp_literal(start), dialect.timestamp_literal(end))
+            }
+            "timestamptz" => {
+                let (start, end) = (self.random_timestamp_tz(rng), self.random_timestamp_tz(rng));
+                let (start, end) = (start.min(end), start.max(end));
+                format!("{} BETWEEN {} AND {}", reference, dialect.timestamp_tz_literal(start), dialect.timestamp_tz_literal(end))
+            }
+            "time" => {
+                let (start, end) = (random_time(rng), random_time(rng));
+                let (start, end) = (start.min(end), start.max(end));
+                format!("{} BETWEEN {} AND {}", reference, dialect.time_literal(start), dialect.time_literal(end))
+            }
+            "interval" => {
+                let operator = [">", "<", ">=", "<="].choose(rng).unwrap();
+                format!("{} {} {}", reference, operator, dialect.interval_literal(rng.gen_range(0..=MAX_INTERVAL_SECONDS)))
+            }
+            "boolean" | "bool" | "bit" => format!("{} = {}", reference, dialect.boolean_literal(rng.gen())),
+            "json" | "jsonb" => json_condition(reference, &self.random_document(rng), dialect, rng),
+            _ => return None,
+        };
+        Some(condition)
+    }
 }
 
 impl Table {
@@ -50,6 +971,7 @@ impl Table {
     /// # Example
     ///
     /// ```
+    /// # use fake_sql::{Column, Table};
     /// let columns = vec![
     ///     Column {
     ///         name: "id".to_string(),
@@ -58,8 +980,32 @@ impl Table {
     ///         decimal_places: None,
     ///         is_nullable: false,
     ///         is_pkey: true,
+    ///         is_unique: false,
     ///         ref_table: None,
     ///         ref_column: None,
+    ///         provider: None,
+    ///         null_rate: None,
+    ///         auto_increment: false,
+    ///         min_value: None,
+    ///         max_value: None,
+    ///         max_length_rate: None,
+    ///         date_range: None,
+    ///         distribution: None,
+    ///         pool: None,
+    ///         locale: None,
+    ///         comment: None,
+    ///         default_value: None,
+    ///         check_expr: None,
+    ///         template: None,
+    ///         pii_free: false,
+    ///         timezones: None,
+    ///         attributes: vec![],
+    ///         payload: None,
+    ///         bounding_box: None,
+    ///         labels: vec![],
+    ///         blob_size: None,
+    ///         blob_encoding: None,
+    ///         custom_generator: None,
     ///     },
     ///     Column {
     ///         name: "name".to_string(),
@@ -68,8 +1014,32 @@ impl Table {
     ///         decimal_places: None,
     ///         is_nullable: true,
     ///         is_pkey: false,
+    ///         is_unique: false,
     ///         ref_table: None,
     ///         ref_column: None,
+    ///         provider: None,
+    ///         null_rate: None,
+    ///         auto_increment: false,
+    ///         min_value: None,
+    ///         max_value: None,
+    ///         max_length_rate: None,
+    ///         date_range: None,
+    ///         distribution: None,
+    ///         pool: None,
+    ///         locale: None,
+    ///         comment: None,
+    ///         default_value: None,
+    ///         check_expr: None,
+    ///         template: None,
+    ///         pii_free: false,
+    ///         timezones: None,
+    ///         attributes: vec![],
+    ///         payload: None,
+    ///         bounding_box: None,
+    ///         labels: vec![],
+    ///         blob_size: None,
+    ///         blob_encoding: None,
+    ///         custom_generator: None,
     ///     },
     /// ];
     /// let table = Table::init("test_table".to_string(), columns);
@@ -77,15 +1047,22 @@ impl Table {
     /// assert_eq!(table.columns.len(), 2);
     /// ```
     pub fn init(name: String, columns: Vec<Column>) -> Table {
+        let pkey_columns = columns.iter().filter(|c| c.is_pkey).map(|c| c.name.clone()).collect();
         Table {
             name,
             columns,
             comment: None,
+            pkey_columns,
+            unique_keys: Vec::new(),
+            checks: Vec::new(),
         }
     }
 
     /// Initializes a new `Table` from a SQL create table string.
     ///
+    /// Use [`schema::parse_create_table`](crate::schema::parse_create_table) to handle
+    /// invalid statements without panicking.
+    ///
     /// # Arguments
     ///
     /// * `create_table_string` - A string slice that holds the SQL create table statement.
@@ -94,9 +1071,14 @@ impl Table {
     ///
     /// A `Table` struct.
     ///
+    /// # Panics
+    ///
+    /// Panics if the string is not a single valid `CREATE TABLE` statement.
+    ///
     /// # Example
     ///
     /// ```
+    /// # use fake_sql::Table;
     /// let sql = "create table test_table (id number(10) primary key, name varchar(255))";
     /// let table = Table::init_via_sql(sql);
     /// assert_eq!(table.name, "test_table");
@@ -105,114 +1087,389 @@ impl Table {
     /// assert_eq!(table.columns[1].name, "name");
     /// ```
     pub fn init_via_sql(create_table_string: &str) -> Table {
-        let create_table_string = create_table_string.to_lowercase().trim().to_string();
-        let comment = None;
-        let parts: Vec<&str> = create_table_string
-            .trim_start_matches("create table ")
-            .splitn(2, '(')
+        schema::parse_create_table(create_table_string).unwrap_or_else(|err| panic!("invalid CREATE TABLE statement: {}", err))
+    }
+
+    /// Generates a SQL WHERE clause on some of the table's columns.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the SQL WHERE clause.
+    pub fn generate_where_clause(&self) -> String {
+        self.generate_where_clause_with(Dialect::default(), &mut thread_rng())
+    }
+
+    /// Generates a SQL WHERE clause for `dialect` with the default [`Predicates`], drawing
+    /// random values from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect literals and identifiers are rendered for.
+    /// * `rng` - The random number generator used for operators and values.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the SQL WHERE clause.
+    pub fn generate_where_clause_with<R: Rng + ?Sized>(&self, dialect: Dialect, rng: &mut R) -> String {
+        self.where_clause(dialect, None, Predicates::default(), rng)
+    }
+
+    /// Generates a SQL WHERE clause on a random subset of the table's columns.
+    ///
+    /// Primary key columns are picked four times and foreign key columns twice as often
+    /// as the others. The predicates keep the column order of the table. Given a row, every
+    /// predicate holds for its values, so the clause selects at least that row.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect literals and identifiers are rendered for.
+    /// * `row` - A row of the table the clause matches, one value per column.
+    /// * `predicates` - How many predicates the clause has and how they are combined.
+    /// * `rng` - The random number generator used for the columns, operators and values.
+    ///
+    /// # Returns
+    ///
+    /// The clause without the `WHERE` keyword, empty if no column can be filtered on.
+    pub fn where_clause<R: Rng + ?Sized>(
+        &self,
+        dialect: Dialect,
+        row: Option<&[SqlValue]>,
+        predicates: Predicates,
+        rng: &mut R,
+    ) -> String {
+        let candidates: Vec<usize> = (0..self.columns.len()).filter(|&i| self.columns[i].is_filterable()).collect();
+        self.conditions_on(&candidates, row, dialect, predicates, rng)
+    }
+
+    /// Generates a random value for every column of the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator the values are drawn from.
+    ///
+    /// # Returns
+    ///
+    /// The values in column order.
+    pub fn generate_row<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<SqlValue> {
+        let mut row: Vec<SqlValue> = self.columns.iter().map(|c| c.generate_value(rng)).collect();
+        for _ in 1..MAX_CHECK_ATTEMPTS {
+            if self.satisfy_checks(&mut row) {
+                break;
+            }
+            row = self.columns.iter().map(|c| c.generate_value(rng)).collect();
+        }
+        row
+    }
+
+    /// Makes `row` satisfy the comparisons of the table's [`TableCheck`]s where swapping
+    /// the values of two columns of the same type, or copying one for `=`, does. Values
+    /// of key and foreign key columns are left in place.
+    ///
+    /// # Returns
+    ///
+    /// Whether every comparison holds, or involves a NULL, so the row has to be drawn again
+    /// if not.
+    pub(crate) fn satisfy_checks(&self, row: &mut [SqlValue]) -> bool {
+        let position = |name: &String| self.columns.iter().position(|c| &c.name == name);
+        let comparisons: Vec<(usize, &[Ordering], usize)> = self
+            .checks
+            .iter()
+            .flat_map(|check| &check.comparisons)
+            .filter_map(|(left, orderings, right)| Some((position(left)?, orderings.as_slice(), position(right)?)))
             .collect();
-        let table_name = parts[0].trim().to_string();
-
-        let trimmed_columns = parts[1].rsplitn(2, ')').collect::<Vec<&str>>()[1].trim();
-        let re = Regex::new(r"(\d+)\s*,\s*(\d+)").unwrap();
-        let cleaned_columns = re.replace_all(trimmed_columns, "$1.$2").to_string();
-        let split_column_strings: Vec<&str> = cleaned_columns.split(',').collect();
-
-        let mut columns = vec![];
-
-        for column_str in split_column_strings {
-            let column_parts: Vec<&str> = column_str.trim().split_whitespace().collect();
-            let name = column_parts[0];
-            let column_type_str = column_parts[1];
-            let re = Regex::new(r"([a-zA-Z]+)|(\d+)").unwrap();
-            let col_parts = re.find_iter(column_type_str).map(|m| m.as_str()).collect::<Vec<&str>>();
-
-            let mut column_type = "";
-            let mut length = None;
-            let mut decimal_places = None;
-
-            for (i, part) in col_parts.iter().enumerate() {
-                match i {
-                    0 => column_type = part,
-                    1 => length = part.parse().ok(),
-                    2 => decimal_places = part.parse().ok(),
-                    _ => (),
-                }
+        let fixed = |i: usize| {
+            let column = &self.columns[i];
+            column.is_pkey || self.is_unique_column(column) || column.ref_table.is_some() || column.auto_increment
+        };
+        for &(left, orderings, right) in &comparisons {
+            let Some(ordering) = compare(&row[left], &row[right]) else { continue };
+            if orderings.contains(&ordering) || fixed(left) || fixed(right) || mem::discriminant(&row[left]) != mem::discriminant(&row[right]) {
+                continue;
+            }
+            if orderings.contains(&ordering.reverse()) {
+                row.swap(left, right);
+            } else if orderings == [Ordering::Equal] {
+                row[right] = row[left].clone();
             }
+        }
+        // A swap for one comparison can break another
+        comparisons.iter().all(|&(left, orderings, right)| compare(&row[left], &row[right]).is_none_or(|ordering| orderings.contains(&ordering)))
+    }
 
-            let is_pkey = column_parts.contains(&"primary") && column_parts.contains(&"key");
-            let (ref_table, ref_column) = Table::parse_references(&column_parts);
+    /// Renders an INSERT statement for a row of values.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `row` - One value per column, in column order.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the INSERT statement.
+    pub fn insert_statement(&self, dialect: Dialect, row: &[SqlValue]) -> String {
+        self.insert_statement_rows(dialect, &[row.to_vec()])
+    }
 
-            columns.push(Column {
-                name: name.to_string(),
-                column_type: column_type.to_string(),
-                length,
-                decimal_places,
-                is_nullable: !is_pkey, // Assume non-primary key columns are nullable
-                is_pkey,
-                ref_table,
-                ref_column,
-            });
+    /// Renders a single INSERT statement for several rows of values.
+    ///
+    /// Most dialects get `INSERT INTO t (...) VALUES (...), (...);`. Oracle, which has
+    /// no multi-row VALUES clause, gets `INSERT ALL INTO t (...) VALUES (...) ... SELECT 1 FROM DUAL;`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `rows` - The rows, each with one value per column in column order.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the INSERT statement.
+    pub fn insert_statement_rows(&self, dialect: Dialect, rows: &[Vec<SqlValue>]) -> String {
+        self.render_insert(dialect, rows, |_, _, value| dialect.literal(value))
+    }
+
+    /// Returns the values an INSERT of `rows` renders, in the order they appear in the
+    /// statement: row after row, leaving out auto-increment columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The rows, each with one value per column in column order.
+    pub fn inserted_values(&self, rows: &[Vec<SqlValue>]) -> Vec<SqlValue> {
+        rows.iter()
+            .flat_map(|row| row.iter().zip(&self.columns).filter(|(_, column)| !column.auto_increment))
+            .map(|(value, _)| value.clone())
+            .collect()
+    }
+
+    /// Renders an INSERT statement for several rows with bind placeholders in place of
+    /// the values, together with the parameter document binding them. Named placeholders
+    /// of columns whose names differ only in case or punctuation, such as `Order Date`
+    /// and `order_date`, are made distinct with a suffix `_2`, `_3`, ...
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `style` - Whether placeholders are positional or named after the columns.
+    /// * `rows` - The rows, each with one value per column in column order.
+    ///
+    /// # Returns
+    ///
+    /// The INSERT statement and its parameters: a JSON array for positional placeholders,
+    /// a JSON object for named ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::params::ParamStyle;
+    /// # use fake_sql::{Dialect, SqlValue, Table};
+    /// let table = Table::init_via_sql("create table t (id int, name varchar(10))");
+    /// let row = vec![SqlValue::Integer(1), SqlValue::Text("Ann".to_string())];
+    /// let (sql, params) = table.insert_statement_params(Dialect::Postgres, ParamStyle::Positional, &[row]);
+    /// assert_eq!(sql, "INSERT INTO t (id, name) VALUES ($1, $2);");
+    /// assert_eq!(params, serde_json::json!([1, "Ann"]));
+    /// ```
+    pub fn insert_statement_params(
+        &self,
+        dialect: Dialect,
+        style: ParamStyle,
+        rows: &[Vec<SqlValue>],
+    ) -> (String, serde_json::Value) {
+        let mut params = Vec::new();
+        let mut names = HashSet::new();
+        let sql = self.render_insert(dialect, rows, |row, column, value| {
+            let mut name = param_name(&self.columns[column].name, (rows.len() > 1).then_some(row + 1));
+            if names.contains(&name) {
+                name = (2..).map(|n| format!("{}_{}", name, n)).find(|candidate| !names.contains(candidate)).unwrap();
+            }
+            names.insert(name.clone());
+            let placeholder = style.placeholder(dialect, params.len() + 1, &name);
+            params.push((name, value));
+            placeholder
+        });
+        (sql, style.document(params))
+    }
+
+    /// Renders an INSERT statement, turning the value in `column` of the `row`-th row
+    /// into SQL with `render`.
+    fn render_insert<'a>(
+        &self,
+        dialect: Dialect,
+        rows: &'a [Vec<SqlValue>],
+        mut render: impl FnMut(usize, usize, &'a SqlValue) -> String,
+    ) -> String {
+        let table_name = dialect.identifier(&self.name);
+        // Auto-increment columns are left for the database to fill
+        let inserted: Vec<usize> = (0..self.columns.len()).filter(|&i| !self.columns[i].auto_increment).collect();
+        let column_names: Vec<String> = inserted.iter().map(|&i| dialect.identifier(&self.columns[i].name)).collect();
+        let tuples: Vec<String> = rows
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                let values: Vec<String> = inserted.iter().map(|&i| render(r, i, &row[i])).collect();
+                format!("({})", values.join(", "))
+            })
+            .collect();
+        if dialect == Dialect::Oracle && rows.len() > 1 {
+            let targets: Vec<String> = tuples
+                .iter()
+                .map(|tuple| format!("INTO {} ({}) VALUES {}", table_name, column_names.join(", "), tuple))
+                .collect();
+            format!("INSERT ALL {} SELECT 1 FROM DUAL;", targets.join(" "))
+        } else {
+            format!("INSERT INTO {} ({}) VALUES {};", table_name, column_names.join(", "), tuples.join(", "))
         }
+    }
 
-        Table {
-            name: table_name,
-            columns,
-            comment,
+    /// Returns the column called `name`, if the table has one.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Returns the primary key column of the table, if it has one.
+    ///
+    /// For a composite key this is the first column of the key.
+    pub fn primary_key(&self) -> Option<&Column> {
+        match self.pkey_columns.first() {
+            Some(name) => self.columns.iter().find(|c| &c.name == name),
+            None => self.columns.iter().find(|c| c.is_pkey),
+        }
+    }
+
+    /// Sets the primary key of the table, e.g. from a `PRIMARY KEY (a, b)` clause.
+    ///
+    /// The key columns become NOT NULL; columns that are not part of the key lose their
+    /// primary key flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The names of the key columns, in key order.
+    pub fn set_primary_key(&mut self, columns: Vec<String>) {
+        for column in &mut self.columns {
+            column.is_pkey = columns.contains(&column.name);
+            if column.is_pkey {
+                column.is_nullable = false;
+            }
+        }
+        self.pkey_columns = columns;
+    }
+
+    /// Returns `true` if `column` is unique or part of a unique key.
+    fn is_unique_column(&self, column: &Column) -> bool {
+        column.is_unique || self.unique_keys.iter().any(|key| key.contains(&column.name))
+    }
+
+    /// Adds a unique constraint, e.g. from a `UNIQUE (a, b)` clause. A constraint on a
+    /// single column flags the column as unique.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The names of the columns whose combination is unique.
+    pub fn add_unique_key(&mut self, columns: Vec<String>) {
+        match columns.as_slice() {
+            [name] => {
+                if let Some(column) = self.columns.iter_mut().find(|c| &c.name == name) {
+                    column.is_unique = true;
+                }
+            }
+            [] => {}
+            _ => self.unique_keys.push(columns),
         }
     }
 
-    /// Parses references from column parts.
+    /// Generates the statements creating the table with its foreign keys: a `CREATE TABLE`
+    /// statement with a `FOREIGN KEY` constraint per referencing column, followed by a
+    /// `CREATE INDEX` on each foreign key column that is not the primary key.
     ///
     /// # Arguments
     ///
-    /// * `column_parts` - A slice of string slices representing parts of a column definition.
+    /// * `dialect` - The dialect the statements are rendered for.
     ///
     /// # Returns
     ///
-    /// A tuple containing the referenced table and column, if any.
-    pub fn parse_references(column_parts: &[&str]) -> (Option<String>, Option<String>) {
-        if let Some(pos) = column_parts.iter().position(|&s| s == "references") {
-            let ref_table = column_parts.get(pos + 1).map(|s| s.to_string());
-            let ref_column = column_parts.get(pos + 2).map(|s| s.trim_matches(|c| c == '(' || c == ')').to_string());
-            (ref_table, ref_column)
-        } else {
-            (None, None)
+    /// The statements, in the order they have to run.
+    pub fn schema_statements(&self, dialect: Dialect) -> Vec<String> {
+        let table_name = dialect.identifier(&self.name);
+        let mut definitions = self.definitions(dialect);
+        let references: Vec<&Column> = self.columns.iter().filter(|c| c.ref_table.is_some()).collect();
+        for column in &references {
+            let parent = dialect.identifier(column.ref_table.as_ref().unwrap());
+            let parent_column = match &column.ref_column {
+                Some(name) => format!(" ({})", dialect.identifier(name)),
+                None => String::new(),
+            };
+            definitions.push(format!(
+                "FOREIGN KEY ({}) REFERENCES {}{}",
+                dialect.identifier(&column.name),
+                parent,
+                parent_column
+            ));
         }
-    }
 
-    /// Generates a SQL WHERE clause based on the table's columns.
-    ///
-    /// # Returns
-    ///
-    /// A string representing the SQL WHERE clause.
-    pub fn generate_where_clause(&self) -> String {
-        let mut rng = thread_rng();
-        let mut conditions = vec![];
+        let mut statements = vec![format!(
+            "CREATE TABLE {} ({}){};",
+            table_name,
+            definitions.join(", "),
+            self.table_options(dialect)
+        )];
+        statements.extend(self.comment_statements(dialect));
+        for column in references {
+            if column.is_pkey && self.pkey_columns.len() <= 1 {
+                continue;
+            }
+            let name = format!("idx_{}_{}", self.name, column.name);
+            statements.push(format!(
+                "CREATE INDEX {} ON {} ({});",
+                dialect.identifier(&name),
+                table_name,
+                dialect.identifier(&column.name)
+            ));
+        }
+        statements
+    }
 
-        for column in &self.columns {
-            let condition = match column.column_type.as_str() {
-                "int" | "number" => {
-                    let operator = ["=", ">", "<", ">=", "<="].choose(&mut rng).unwrap();
-                    format!("{} {} {}", column.name, operator, rng.gen_range(1..100))
-                }
-                "varchar" | "text" => {
-                    let values: Vec<String> = (0..rng.gen_range(2..11))
-                        .map(|_| format!("'{}'", ["Alice", "Bob", "Charlie", "David"].choose(&mut rng).unwrap()))
-                        .collect();
-                    format!("{} IN ({})", column.name, values.join(", "))
-                }
-                "date" | "datetime" => {
-                    let start_date = NaiveDate::from_ymd(2021, 1, 1) + Duration::days(rng.gen_range(0..3));
-                    let end_date = chrono::Local::today().naive_local();
-                    format!("{} BETWEEN to_date('{}','YYYY-MM-DD') AND to_date('{}','YYYY-MM-DD')", column.name, start_date, end_date)
-                }
-                _ => continue,
-            };
-            conditions.push(condition);
+    /// Renders the options following the column definitions of a CREATE TABLE statement:
+    /// the table comment in MySQL.
+    fn table_options(&self, dialect: Dialect) -> String {
+        match (&self.comment, dialect) {
+            (Some(comment), Dialect::MySql) => format!(" COMMENT={}", dialect.string_literal(comment)),
+            _ => String::new(),
         }
+    }
 
-        conditions.join(" AND ")
+    /// Renders the statements commenting the table and its columns in dialects without
+    /// inline comments.
+    fn comment_statements(&self, dialect: Dialect) -> Vec<String> {
+        let table = self.comment.as_ref().map(|comment| (None, comment));
+        let columns = self.columns.iter().filter_map(|c| c.comment.as_ref().map(|comment| (Some(c.name.as_str()), comment)));
+        table
+            .into_iter()
+            .chain(columns)
+            .filter_map(|(column, comment)| dialect.comment_on(&self.name, column, comment))
+            .map(|statement| format!("{};", statement))
+            .collect()
+    }
+
+    /// Renders the column definitions of a CREATE TABLE statement, followed by a
+    /// `PRIMARY KEY (...)` clause when the key spans several columns, a `UNIQUE (...)`
+    /// clause per unique key and a `CHECK (...)` clause per table check.
+    fn definitions(&self, dialect: Dialect) -> Vec<String> {
+        let composite = self.pkey_columns.len() > 1;
+        let mut definitions: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| c.definition_with(dialect, c.is_pkey && !composite))
+            .collect();
+        if composite {
+            let names: Vec<String> = self.pkey_columns.iter().map(|name| dialect.identifier(name)).collect();
+            definitions.push(format!("PRIMARY KEY ({})", names.join(", ")));
+        }
+        for key in &self.unique_keys {
+            let names: Vec<String> = key.iter().map(|name| dialect.identifier(name)).collect();
+            definitions.push(format!("UNIQUE ({})", names.join(", ")));
+        }
+        for check in &self.checks {
+            definitions.push(format!("CHECK ({})", check.condition));
+        }
+        definitions
     }
 
     /// Generates a SQL statement based on the table and SQL type.
@@ -223,11 +1480,12 @@ impl Table {
     ///
     /// # Returns
     ///
-    /// A string representing the SQL statement.
+    /// The statement, with its values if it is an INSERT.
     ///
     /// # Example
     ///
     /// ```
+    /// # use fake_sql::{Column, SqlType, Table};
     /// let columns = vec![
     ///     Column {
     ///         name: "id".to_string(),
@@ -236,8 +1494,32 @@ impl Table {
     ///         decimal_places: None,
     ///         is_nullable: false,
     ///         is_pkey: true,
+    ///         is_unique: false,
     ///         ref_table: None,
     ///         ref_column: None,
+    ///         provider: None,
+    ///         null_rate: None,
+    ///         auto_increment: false,
+    ///         min_value: None,
+    ///         max_value: None,
+    ///         max_length_rate: None,
+    ///         date_range: None,
+    ///         distribution: None,
+    ///         pool: None,
+    ///         locale: None,
+    ///         comment: None,
+    ///         default_value: None,
+    ///         check_expr: None,
+    ///         template: None,
+    ///         pii_free: false,
+    ///         timezones: None,
+    ///         attributes: vec![],
+    ///         payload: None,
+    ///         bounding_box: None,
+    ///         labels: vec![],
+    ///         blob_size: None,
+    ///         blob_encoding: None,
+    ///         custom_generator: None,
     ///     },
     ///     Column {
     ///         name: "name".to_string(),
@@ -246,127 +1528,295 @@ impl Table {
     ///         decimal_places: None,
     ///         is_nullable: true,
     ///         is_pkey: false,
+    ///         is_unique: false,
     ///         ref_table: None,
     ///         ref_column: None,
+    ///         provider: None,
+    ///         null_rate: None,
+    ///         auto_increment: false,
+    ///         min_value: None,
+    ///         max_value: None,
+    ///         max_length_rate: None,
+    ///         date_range: None,
+    ///         distribution: None,
+    ///         pool: None,
+    ///         locale: None,
+    ///         comment: None,
+    ///         default_value: None,
+    ///         check_expr: None,
+    ///         template: None,
+    ///         pii_free: false,
+    ///         timezones: None,
+    ///         attributes: vec![],
+    ///         payload: None,
+    ///         bounding_box: None,
+    ///         labels: vec![],
+    ///         blob_size: None,
+    ///         blob_encoding: None,
+    ///         custom_generator: None,
     ///     },
     /// ];
     /// let table = Table::init("test_table".to_string(), columns);
-    /// let sql = table.generate(SqlType::CreateTable);
-    /// assert_eq!(sql, "CREATE TABLE test_table (id number(10) NOT NULL PRIMARY KEY, name varchar(255));");
+    /// let statement = table.generate(SqlType::CreateTable);
+    /// assert_eq!(statement.text, "CREATE TABLE test_table (id number(10) NOT NULL PRIMARY KEY, name varchar(255));");
     /// ```
-    pub fn generate(&self, sql_type: SqlType) -> String {
-        match sql_type {
+    pub fn generate(&self, sql_type: SqlType) -> Statement {
+        self.generate_with(sql_type, Dialect::default(), &mut thread_rng())
+    }
+
+    /// Generates a SQL statement for `dialect`, drawing random values from `rng`.
+    ///
+    /// Using a seeded `rng` makes the generated statement reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql_type` - The type of SQL statement to generate.
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `rng` - The random number generator used for values and predicates.
+    ///
+    /// # Returns
+    ///
+    /// The statement, with its values if it is an INSERT.
+    pub fn generate_with<R: Rng + ?Sized>(&self, sql_type: SqlType, dialect: Dialect, rng: &mut R) -> Statement {
+        let table_name = dialect.identifier(&self.name);
+        let text = match sql_type {
             SqlType::CreateTable => {
-                let mut sql = format!("CREATE TABLE {} (", self.name);
-                for column in &self.columns {
-                    sql.push_str(&format!(
-                        "{} {}{}{}{}{}",
-                        column.name,
-                        column.column_type,
-                        if let Some(length) = column.length {
-                            if let Some(decimal_places) = column.decimal_places {
-                                format!("({},{})", length, decimal_places)
-                            } else {
-                                format!("({})", length)
-                            }
-                        } else {
-                            "".to_string()
-                        },
-                        if column.is_nullable { "" } else { " NOT NULL" },
-                        if column.is_pkey { " PRIMARY KEY" } else { "" },
-                        if self.columns.last().unwrap().name != column.name { ", " } else { "" }
-                        
-                    ));
-                }
-                sql.push_str(");");
-                sql
-            }
-            SqlType::AlterTable => {
-                let mut sql = format!("ALTER TABLE {} ", self.name);
-                for column in &self.columns {
-                    sql.push_str(&format!(
-                        "ADD COLUMN {} {}{}{}{}{}",
-                        column.name,
-                        column.column_type,
-                        if let Some(length) = column.length {
-                            if let Some(decimal_places) = column.decimal_places {
-                                format!("({},{})", length, decimal_places)
-                            } else {
-                                format!("({})", length)
-                            }
-                        } else {
-                            "".to_string()
-                        },
-                        if column.is_nullable { "" } else { " NOT NULL" },
-                        if column.is_pkey { " PRIMARY KEY" } else { "" },
-                        if self.columns.last().unwrap().name != column.name { ", " } else { "" }
-                    ));
-                }
-                sql.trim_end_matches(", ").to_string() + ";"
-            }
-            SqlType::DropTable => format!("DROP TABLE {};", self.name),
-            SqlType::Insert => {
-                let mut rng = thread_rng();
-                let column_names: Vec<String> = self.columns.iter().map(|c| c.name.clone()).collect();
-                let values: Vec<String> = self.columns.iter().map(|c| {
-                    match c.column_type.as_str() {
-                        "varchar" | "text" => format!("'{}'", ["Alice", "Bob", "Charlie", "David"].choose(&mut rng).unwrap()),
-                        "date" | "datetime" => {
-                            let today = chrono::Local::today().naive_local();
-                            format!("to_date('{}','YYYY-MM-DD')", today)
-                        },
-                        "number" if c.decimal_places.is_some() => {
-                            let factor = 10f64.powi(c.decimal_places.unwrap());
-                            let value = rng.gen_range(1..100) as f64 / factor;
-                            format!("{:.1$}", value, c.decimal_places.unwrap() as usize)
-                        }
-                        _ => rng.gen_range(1..100).to_string(),
-                    }
-                }).collect();
                 format!(
-                    "INSERT INTO {} ({}) VALUES ({});",
-                    self.name,
-                    column_names.join(", "),
-                    values.join(", ")
+                    "CREATE TABLE {} ({}){};",
+                    table_name,
+                    self.definitions(dialect).join(", "),
+                    self.table_options(dialect)
                 )
             }
-            SqlType::Select => {
-                let column_names: Vec<String> = self.columns.iter().map(|c| c.name.clone()).collect();
-                format!(
-                    "SELECT {} FROM {} WHERE {};",
-                    column_names.join(", "),
-                    self.name,
-                    self.generate_where_clause()
-                )
+            SqlType::AlterTable => SchemaChange::random_alter(self, dialect, rng).statement(self, dialect),
+            SqlType::DropTable => format!("DROP TABLE {};", table_name),
+            SqlType::Truncate => format!("{};", dialect.truncate_table(&table_name)),
+            // A table of spatial columns only has nothing to index, so it is queried instead
+            SqlType::CreateIndex | SqlType::DropIndex if self.columns.iter().all(Column::is_spatial) => {
+                return self.generate_with(SqlType::Select, dialect, rng);
+            }
+            SqlType::CreateIndex => {
+                let (name, columns, unique) = self.random_index(rng);
+                let columns: Vec<String> = columns.iter().map(|c| dialect.identifier(c)).collect();
+                let unique = if unique { "UNIQUE " } else { "" };
+                format!("CREATE {}INDEX {} ON {} ({});", unique, dialect.identifier(&name), table_name, columns.join(", "))
             }
+            SqlType::DropIndex => {
+                let (name, _, _) = self.random_index(rng);
+                format!("{};", dialect.drop_index(&dialect.identifier(&name), &table_name))
+            }
+            SqlType::Insert => {
+                let rows = [self.generate_row(rng)];
+                let text = self.insert_statement_rows(dialect, &rows);
+                return Statement::new(sql_type, &self.name, text).with_values(self.inserted_values(&rows));
+            }
+            // A single table has nothing to join; `Generator` renders joins across its tables
+            SqlType::Select | SqlType::SelectJoin => {
+                query::select(self, None, None, dialect, Complexity::Simple, Predicates::default(), rng)
+            }
+            SqlType::Aggregate => query::select_aggregate(self, dialect, Complexity::Simple, rng),
+            SqlType::CreateView => query::create_view(std::slice::from_ref(self), 0, dialect, rng),
             SqlType::Update => {
-                let mut rng = thread_rng();
-                let column_values: Vec<String> = self.columns.iter().map(|c| {
-                    match c.column_type.as_str() {
-                        "varchar" | "text" => format!("{} = '{}'", c.name, ["Alice", "Bob", "Charlie", "David"].choose(&mut rng).unwrap()),
-                        "date" | "datetime" => {
-                            let today = chrono::Local::today().naive_local();
-                            format!("{} = to_date('{}','YYYY-MM-DD')", c.name, today)
-                        },
-                        "number" if c.decimal_places.is_some() => {
-                            let factor = 10f64.powi(c.decimal_places.unwrap());
-                            let value = rng.gen_range(1..100) as f64 / factor;
-                            format!("{} = {:.precision$}", c.name, value, precision = c.decimal_places.unwrap() as usize)
-                        }
-                        _ => format!("{} = {}", c.name, rng.gen_range(1..100)),
-                    }
-                }).collect();
-                format!(
-                    "UPDATE {} SET {} WHERE {};",
-                    self.name,
-                    column_values.join(", "),
-                    self.generate_where_clause()
-                )
+                let row = self.generate_row(rng);
+                self.update_statement(dialect, Some(&row), Predicates::default(), rng)
             }
-            SqlType::Delete => format!("DELETE FROM {} WHERE {};", self.name, self.generate_where_clause()),
-        }
+            SqlType::Delete => self.delete_statement(dialect, None, Predicates::default(), rng),
+        };
+        Statement::new(sql_type, &self.name, text)
     }
     
+    /// Returns the indexes of the columns an UPDATE sets, see [`Table::update_statement`]:
+    /// those outside the primary key and unique keys, and not foreign keys unless the table
+    /// has no other columns, nor auto-increment.
+    pub fn updatable_columns(&self) -> Vec<usize> {
+        let settable = |foreign: bool| -> Vec<usize> {
+            (0..self.columns.len())
+                .filter(|&i| {
+                    let c = &self.columns[i];
+                    !c.is_pkey && !c.auto_increment && !self.is_unique_column(c) && (foreign || c.ref_table.is_none())
+                })
+                .collect()
+        };
+        let mut candidates = settable(false);
+        if candidates.is_empty() {
+            candidates = settable(true);
+        }
+        if candidates.is_empty() {
+            candidates = (0..self.columns.len()).filter(|&i| !self.columns[i].auto_increment).collect();
+        }
+        candidates
+    }
+
+    /// Generates an UPDATE of one to three columns of a row, e.g.
+    /// `UPDATE orders SET status = 'paid' WHERE order_id = 42;`.
+    ///
+    /// Primary key, auto-increment and foreign key columns are not set, so the row keeps
+    /// its identity and its parents, nor are unique columns, whose value the statement
+    /// could set on several rows; only tables without other columns get their foreign
+    /// keys updated. Most statements target `row` by its primary key; the others, and those
+    /// of tables without a primary key, filter on the indexed primary and foreign key columns
+    /// with predicates `row` satisfies.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `row` - The row to update, one value per column; random predicates without it.
+    /// * `predicates` - How many filters the statement has and how they are combined.
+    /// * `rng` - The random number generator used for the columns, values and predicates.
+    pub fn update_statement<R: Rng + ?Sized>(
+        &self,
+        dialect: Dialect,
+        row: Option<&[SqlValue]>,
+        predicates: Predicates,
+        rng: &mut R,
+    ) -> String {
+        let candidates: Vec<&Column> = self.updatable_columns().into_iter().map(|i| &self.columns[i]).collect();
+        let count = rng.gen_range(1..=candidates.len().clamp(1, 3)).min(candidates.len());
+        let mut picked = rand::seq::index::sample(rng, candidates.len(), count).into_vec();
+        picked.sort_unstable();
+        let column_values: Vec<String> = picked
+            .into_iter()
+            .map(|i| candidates[i])
+            .map(|c| format!("{} = {}", dialect.identifier(&c.name), dialect.literal(&c.generate_value(rng))))
+            .collect();
+
+        let condition = match row {
+            Some(row) if !self.pkey_columns.is_empty() && rng.gen_bool(KEY_TARGET_RATE) => self.key_condition(dialect, row),
+            _ => {
+                let indexed: Vec<usize> = (0..self.columns.len())
+                    .filter(|&i| (self.columns[i].is_pkey || self.columns[i].ref_table.is_some()) && self.columns[i].is_filterable())
+                    .collect();
+                if indexed.is_empty() {
+                    self.where_clause(dialect, row, predicates, rng)
+                } else {
+                    self.conditions_on(&indexed, row, dialect, predicates, rng)
+                }
+            }
+        };
+        format!("UPDATE {} SET {} WHERE {};", dialect.identifier(&self.name), column_values.join(", "), condition)
+    }
+
+    /// Generates a DELETE of a row by its primary key, e.g.
+    /// `DELETE FROM orders WHERE order_id = 42;`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `row` - The row to delete, one value per column.
+    pub fn delete_row_statement(&self, dialect: Dialect, row: &[SqlValue]) -> String {
+        format!("DELETE FROM {} WHERE {};", dialect.identifier(&self.name), self.key_condition(dialect, row))
+    }
+
+    /// Renders the condition selecting `row` by its primary key, e.g. `id = 42`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the values are rendered for.
+    /// * `row` - One value per column.
+    pub(crate) fn key_condition(&self, dialect: Dialect, row: &[SqlValue]) -> String {
+        let conditions: Vec<String> = self
+            .pkey_columns
+            .iter()
+            .filter_map(|name| self.columns.iter().position(|c| &c.name == name))
+            .map(|i| format!("{} = {}", dialect.identifier(&self.columns[i].name), dialect.literal(&row[i])))
+            .collect();
+        conditions.join(" AND ")
+    }
+
+    /// Generates a DELETE filtered on some of the columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `row` - A row the filters match, one value per column; random filters without it.
+    /// * `predicates` - How many filters the statement has and how they are combined.
+    /// * `rng` - The random number generator used for the predicates.
+    pub fn delete_statement<R: Rng + ?Sized>(
+        &self,
+        dialect: Dialect,
+        row: Option<&[SqlValue]>,
+        predicates: Predicates,
+        rng: &mut R,
+    ) -> String {
+        let condition = self.where_clause(dialect, row, predicates, rng);
+        format!("DELETE FROM {} WHERE {};", dialect.identifier(&self.name), condition)
+    }
+
+    /// Generates a WHERE clause on a random subset of the columns at the positions
+    /// `candidates`, which must be filterable.
+    ///
+    /// Primary key columns are picked four times and foreign key columns twice as often as
+    /// the others, and the predicates keep the order of `candidates`. Given a row, every
+    /// predicate holds for its values.
+    fn conditions_on<R: Rng + ?Sized>(
+        &self,
+        candidates: &[usize],
+        row: Option<&[SqlValue]>,
+        dialect: Dialect,
+        predicates: Predicates,
+        rng: &mut R,
+    ) -> String {
+        if candidates.is_empty() {
+            return String::new();
+        }
+        let count = rng.gen_range(1..=predicates.max.clamp(1, candidates.len()));
+        let weight = |column: &Column| match (column.is_pkey, column.ref_table.is_some()) {
+            (true, _) => 4.0,
+            (false, true) => 2.0,
+            (false, false) => 1.0,
+        };
+        let mut picked: Vec<usize> = candidates
+            .choose_multiple_weighted(rng, count, |&i| weight(&self.columns[i]))
+            .unwrap()
+            .copied()
+            .collect();
+        picked.sort_unstable();
+
+        // Predicates joined with OR form a group; the groups are joined with AND
+        let mut groups: Vec<Vec<String>> = vec![];
+        for i in picked {
+            let column = &self.columns[i];
+            let reference = dialect.identifier(&column.name);
+            let condition = match row {
+                Some(row) => column.matching_condition(&reference, &row[i], dialect, rng),
+                None => column.generate_condition(&reference, dialect, rng).unwrap(),
+            };
+            match groups.last_mut() {
+                Some(group) if rng.gen_bool(predicates.or_rate.clamp(0.0, 1.0)) => group.push(condition),
+                _ => groups.push(vec![condition]),
+            }
+        }
+        let nested = groups.len() > 1;
+        let groups: Vec<String> = groups
+            .into_iter()
+            .map(|group| match group.len() {
+                1 => group.join(""),
+                _ if nested => format!("({})", group.join(" OR ")),
+                _ => group.join(" OR "),
+            })
+            .collect();
+        groups.join(" AND ")
+    }
+
+    /// Picks a random index: one to three columns other than spatial ones, in table order,
+    /// unique about a third of the time. The table needs a column that is not spatial.
+    ///
+    /// # Returns
+    ///
+    /// The index name, `ux_<table>_<columns>` for unique indexes and `idx_<table>_<columns>`
+    /// otherwise, the indexed column names and whether the index is unique.
+    fn random_index<R: Rng + ?Sized>(&self, rng: &mut R) -> (String, Vec<String>, bool) {
+        // Spatial columns need spatial indexes of their own
+        let indexable: Vec<usize> = (0..self.columns.len()).filter(|&i| !self.columns[i].is_spatial()).collect();
+        let count = rng.gen_range(1..=indexable.len().clamp(1, 3)).min(indexable.len());
+        let mut picked: Vec<usize> = indexable.choose_multiple(rng, count).copied().collect();
+        picked.sort_unstable();
+        let columns: Vec<String> = picked.iter().map(|&i| self.columns[i].name.clone()).collect();
+        let unique = rng.gen_bool(1.0 / 3.0);
+        let prefix = if unique { "ux" } else { "idx" };
+        (format!("{}_{}_{}", prefix, self.name, columns.join("_")), columns, unique)
+    }
+
     /// Sets a comment for the table.
     ///
     /// # Arguments
@@ -377,9 +1827,70 @@ impl Table {
     }
 }
 
+/// Orders two values of the same kind, or two numbers, as a CHECK condition compares them.
+///
+/// # Returns
+///
+/// `None` if either value is NULL or the values cannot be compared.
+fn compare(left: &SqlValue, right: &SqlValue) -> Option<Ordering> {
+    match (left, right) {
+        (SqlValue::Integer(a), SqlValue::Integer(b)) | (SqlValue::Interval(a), SqlValue::Interval(b)) => Some(a.cmp(b)),
+        (SqlValue::Decimal(a, scale), SqlValue::Decimal(b, other)) if scale == other => Some(a.cmp(b)),
+        (SqlValue::Text(a), SqlValue::Text(b)) => Some(a.cmp(b)),
+        (SqlValue::Date(a), SqlValue::Date(b)) => Some(a.cmp(b)),
+        (SqlValue::Timestamp(a), SqlValue::Timestamp(b)) => Some(a.cmp(b)),
+        (SqlValue::TimestampTz(a), SqlValue::TimestampTz(b)) => Some(a.cmp(b)),
+        (SqlValue::Time(a), SqlValue::Time(b)) => Some(a.cmp(b)),
+        (SqlValue::Date(a), SqlValue::Timestamp(b)) => Some(a.and_time(NaiveTime::MIN).cmp(b)),
+        (SqlValue::Timestamp(a), SqlValue::Date(b)) => Some(a.cmp(&b.and_time(NaiveTime::MIN))),
+        _ => left.as_f64()?.partial_cmp(&right.as_f64()?),
+    }
+}
+
+/// Renders a predicate on an array column that holds if the array contains `element`, e.g.
+/// `42 = ANY(scores)`, or if it is empty when there is no element. Other dialects than
+/// Postgres store arrays as JSON, so the predicate matches the JSON text instead, e.g.
+/// `scores LIKE '%42%'`.
+fn contains_condition(reference: &str, element: Option<&SqlValue>, dialect: Dialect) -> String {
+    match (element, dialect) {
+        (Some(element), Dialect::Postgres) => format!("{} = ANY({})", dialect.literal(element), reference),
+        (None, Dialect::Postgres) => format!("cardinality({}) = 0", reference),
+        (Some(element), _) => format!("{} LIKE {}", reference, dialect.string_literal(&format!("%{}%", element.to_json()))),
+        (None, _) => format!("{} LIKE '[]'", reference),
+    }
+}
+
+/// Renders a predicate on a JSON column that holds for `document`, comparing one of its
+/// strings or numbers, e.g. `payload->'user'->>'name' = 'Ann'` or
+/// `JSON_EXTRACT(payload, '$.user.name') = 'Ann'`, or `IS NOT NULL` if it has none.
+fn json_condition<R: Rng + ?Sized>(reference: &str, document: &serde_json::Value, dialect: Dialect, rng: &mut R) -> String {
+    let paths = payload::scalar_paths(document);
+    let Some((path, value)) = paths.choose(rng) else { return format!("{} IS NOT NULL", reference) };
+    // MySQL and SQLite extract typed values, the others text
+    let literal = match (value, dialect) {
+        (serde_json::Value::String(text), _) => dialect.string_literal(text),
+        (number, Dialect::MySql | Dialect::Sqlite) => number.to_string(),
+        (number, _) => dialect.string_literal(&number.to_string()),
+    };
+    format!("{} = {}", dialect.json_value(reference, path), literal)
+}
+
+/// Returns a random time of day, to the second.
+fn random_time<R: Rng + ?Sized>(rng: &mut R) -> NaiveTime {
+    NaiveTime::from_num_seconds_from_midnight_opt(rng.gen_range(0..86_400), 0).unwrap()
+}
+
+/// Returns a random version 4 UUID.
+pub(crate) fn random_uuid<R: Rng + ?Sized>(rng: &mut R) -> u128 {
+    let uuid: u128 = rng.gen();
+    // Set the version (4) and the RFC 4122 variant bits
+    (uuid & !(0xf << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn test_table_init() {
@@ -391,8 +1902,32 @@ mod tests {
                 decimal_places: None,
                 is_nullable: false,
                 is_pkey: true,
+                is_unique: false,
                 ref_table: None,
                 ref_column: None,
+                provider: None,
+                null_rate: None,
+                auto_increment: false,
+                min_value: None,
+                max_value: None,
+                max_length_rate: None,
+                date_range: None,
+                distribution: None,
+                pool: None,
+                locale: None,
+                comment: None,
+                default_value: None,
+                check_expr: None,
+                template: None,
+                pii_free: false,
+                timezones: None,
+                attributes: vec![],
+                payload: None,
+                bounding_box: None,
+                labels: vec![],
+                blob_size: None,
+                blob_encoding: None,
+                custom_generator: None,
             },
             Column {
                 name: "name".to_string(),
@@ -401,8 +1936,32 @@ mod tests {
                 decimal_places: None,
                 is_nullable: true,
                 is_pkey: false,
+                is_unique: false,
                 ref_table: None,
                 ref_column: None,
+                provider: None,
+                null_rate: None,
+                auto_increment: false,
+                min_value: None,
+                max_value: None,
+                max_length_rate: None,
+                date_range: None,
+                distribution: None,
+                pool: None,
+                locale: None,
+                comment: None,
+                default_value: None,
+                check_expr: None,
+                template: None,
+                pii_free: false,
+                timezones: None,
+                attributes: vec![],
+                payload: None,
+                bounding_box: None,
+                labels: vec![],
+                blob_size: None,
+                blob_encoding: None,
+                custom_generator: None,
             },
         ];
         let table = Table::init("test_table".to_string(), columns);
@@ -430,8 +1989,32 @@ mod tests {
                 decimal_places: None,
                 is_nullable: false,
                 is_pkey: true,
+                is_unique: false,
                 ref_table: None,
                 ref_column: None,
+                provider: None,
+                null_rate: None,
+                auto_increment: false,
+                min_value: None,
+                max_value: None,
+                max_length_rate: None,
+                date_range: None,
+                distribution: None,
+                pool: None,
+                locale: None,
+                comment: None,
+                default_value: None,
+                check_expr: None,
+                template: None,
+                pii_free: false,
+                timezones: None,
+                attributes: vec![],
+                payload: None,
+                bounding_box: None,
+                labels: vec![],
+                blob_size: None,
+                blob_encoding: None,
+                custom_generator: None,
             },
             Column {
                 name: "name".to_string(),
@@ -440,12 +2023,565 @@ mod tests {
                 decimal_places: None,
                 is_nullable: true,
                 is_pkey: false,
+                is_unique: false,
                 ref_table: None,
                 ref_column: None,
+                provider: None,
+                null_rate: None,
+                auto_increment: false,
+                min_value: None,
+                max_value: None,
+                max_length_rate: None,
+                date_range: None,
+                distribution: None,
+                pool: None,
+                locale: None,
+                comment: None,
+                default_value: None,
+                check_expr: None,
+                template: None,
+                pii_free: false,
+                timezones: None,
+                attributes: vec![],
+                payload: None,
+                bounding_box: None,
+                labels: vec![],
+                blob_size: None,
+                blob_encoding: None,
+                custom_generator: None,
             },
         ];
         let table = Table::init("test_table".to_string(), columns);
-        let sql = table.generate(SqlType::CreateTable);
+        let sql = table.generate(SqlType::CreateTable).text;
         assert_eq!(sql, "CREATE TABLE test_table (id number(10) NOT NULL PRIMARY KEY, name varchar(255));");
     }
+
+    #[test]
+    fn test_generate_for_dialect() {
+        let table = Table::init_via_sql("create table t (id number(10) primary key, price number(10,2), created date)");
+        let mut rng = thread_rng();
+        assert_eq!(
+            table.generate_with(SqlType::CreateTable, Dialect::Postgres, &mut rng).text,
+            "CREATE TABLE t (id numeric(10) NOT NULL PRIMARY KEY, price numeric(10,2), created date);"
+        );
+        let insert = table.generate_with(SqlType::Insert, Dialect::Postgres, &mut rng).text;
+        assert!(insert.contains("DATE '"), "{}", insert);
+        assert!(!insert.contains("to_date"), "{}", insert);
+        for dialect in Dialect::ALL {
+            let alter = table.generate_with(SqlType::AlterTable, dialect, &mut rng).text;
+            assert!(alter.starts_with("ALTER TABLE t ") && !alter.contains("PRIMARY KEY") && !alter.contains(", "), "{}", alter);
+        }
+    }
+
+    #[test]
+    fn test_generate_statement() {
+        let table = Table::init_via_sql("create table t (id serial primary key, code int, name varchar(20) not null)");
+        let insert = table.generate_with(SqlType::Insert, Dialect::Postgres, &mut thread_rng());
+        assert_eq!((insert.sql_type, insert.table.as_str()), (SqlType::Insert, "t"));
+        // The auto-increment id is not inserted
+        assert_eq!(insert.values.len(), 2);
+        assert!(insert.text.ends_with(&format!("{});", Dialect::Postgres.literal(&insert.values[1]))), "{}", insert);
+
+        let drop = table.generate(SqlType::DropTable);
+        assert_eq!((drop.sql_type, drop.table.as_str(), drop.to_string()), (SqlType::DropTable, "t", "DROP TABLE t;".to_string()));
+        assert!(drop.values.is_empty());
+    }
+
+    #[test]
+    fn test_where_clause() {
+        let table = Table::init_via_sql("create table t (id int primary key, a varchar(10), b date, c int, payload blob)");
+        let mut rng = thread_rng();
+        let predicates = Predicates { max: 2, or_rate: 0.0 };
+        let mut with_id = 0;
+        for _ in 0..200 {
+            let clause = table.where_clause(Dialect::Postgres, None, predicates, &mut rng);
+            assert!(!clause.is_empty() && !clause.contains(" OR ") && !clause.contains("payload"), "{}", clause);
+            assert!(clause.matches(" AND ").count() <= 1 || clause.contains("BETWEEN"), "{}", clause);
+            with_id += usize::from(clause.starts_with("id "));
+        }
+        // The primary key is weighted four times as much as the other columns
+        assert!(with_id > 100, "{}", with_id);
+
+        let predicates = Predicates { max: 4, or_rate: 1.0 };
+        let clause = Table::init_via_sql("create table t (a int, b int)").where_clause(Dialect::Postgres, None, predicates, &mut rng);
+        assert!(!clause.contains(" AND ") && !clause.starts_with('('), "{}", clause);
+        assert_eq!(Table::init_via_sql("create table t (d blob)").where_clause(Dialect::Postgres, None, predicates, &mut rng), "");
+    }
+
+    #[test]
+    fn test_where_clause_matching_row() {
+        let table = Table::init_via_sql("create table t (id int primary key, a varchar(10), b int)");
+        let row = [SqlValue::Integer(7), SqlValue::Text("abc".to_string()), SqlValue::Null];
+        let mut rng = thread_rng();
+        let predicates = Predicates { max: 3, or_rate: 0.0 };
+        for _ in 0..50 {
+            let clause = table.where_clause(Dialect::Postgres, Some(&row), predicates, &mut rng);
+            for predicate in clause.split(" AND ") {
+                let matches = ["id = 7", "id >= 7", "id <= 7", "a = 'abc'", "b IS NULL"].contains(&predicate)
+                    || predicate.starts_with("a IN (") && predicate.contains("'abc'");
+                assert!(matches, "{}", clause);
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_statement() {
+        let table = Table::init_via_sql(
+            "create table t (id int primary key, parent_id int references p(id), a varchar(10), b int, c date, d int)",
+        );
+        let mut rng = thread_rng();
+        let row = table.generate_row(&mut rng);
+        let mut by_key = 0;
+        for _ in 0..100 {
+            let sql = table.update_statement(Dialect::Postgres, Some(&row), Predicates::default(), &mut rng);
+            let set = &sql[sql.find(" SET ").unwrap()..sql.find(" WHERE ").unwrap()];
+            assert!((1..=3).contains(&set.matches(" = ").count()), "{}", sql);
+            assert!(!set.contains("id ="), "{}", sql);
+            let filter = &sql[sql.find(" WHERE ").unwrap()..];
+            assert!(filter.contains("id "), "{}", sql);
+            by_key += usize::from(filter == format!(" WHERE id = {};", row[0].as_text().unwrap()));
+        }
+        assert!(by_key > 50, "{}", by_key);
+    }
+
+    #[test]
+    fn test_index_statements() {
+        let table = Table::init_via_sql("create table t (id int primary key, a varchar(10), b date)");
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            let sql = table.generate_with(SqlType::CreateIndex, Dialect::Postgres, &mut rng).text;
+            assert!(sql.starts_with("CREATE INDEX idx_t_") || sql.starts_with("CREATE UNIQUE INDEX ux_t_"), "{}", sql);
+            assert!(sql.contains(" ON t ("), "{}", sql);
+            let sql = table.generate_with(SqlType::DropIndex, Dialect::MySql, &mut rng).text;
+            assert!(sql.starts_with("DROP INDEX ") && sql.ends_with(" ON t;"), "{}", sql);
+        }
+        assert_eq!(table.generate_with(SqlType::Truncate, Dialect::Oracle, &mut rng).text, "TRUNCATE TABLE t;");
+        assert_eq!(table.generate_with(SqlType::Truncate, Dialect::Sqlite, &mut rng).text, "DELETE FROM t;");
+    }
+
+    #[test]
+    fn test_schema_statements() {
+        let table = Table::init_via_sql(
+            "create table orders (order_id number(10) primary key, customer_id number(10) references customers(customer_id))",
+        );
+        assert_eq!(
+            table.schema_statements(Dialect::Postgres),
+            [
+                "CREATE TABLE orders (order_id numeric(10) NOT NULL PRIMARY KEY, customer_id numeric(10), \
+                 FOREIGN KEY (customer_id) REFERENCES customers (customer_id));",
+                "CREATE INDEX idx_orders_customer_id ON orders (customer_id);",
+            ]
+        );
+        assert!(SqlType::Truncate.is_ddl());
+        assert!(!SqlType::Aggregate.is_ddl());
+    }
+
+    #[test]
+    fn test_comments() {
+        let table = Table::init_via_sql("create table t (id int primary key comment 'the key', name varchar(20)) comment='people'");
+        assert_eq!(table.comment.as_deref(), Some("people"));
+        assert_eq!(
+            table.schema_statements(Dialect::MySql),
+            ["CREATE TABLE t (id int NOT NULL PRIMARY KEY COMMENT 'the key', name varchar(20)) COMMENT='people';"]
+        );
+        assert_eq!(
+            table.schema_statements(Dialect::Oracle),
+            [
+                "CREATE TABLE t (id int NOT NULL PRIMARY KEY, name varchar(20));",
+                "COMMENT ON TABLE t IS 'people';",
+                "COMMENT ON COLUMN t.id IS 'the key';",
+            ]
+        );
+        assert_eq!(table.schema_statements(Dialect::Sqlite).len(), 1);
+    }
+
+    #[test]
+    fn test_unique_constraints() {
+        let table = Table::init_via_sql("create table t (id int primary key, a int unique, b int, c int, unique (b, c))");
+        assert_eq!(
+            table.generate_with(SqlType::CreateTable, Dialect::Postgres, &mut thread_rng()).text,
+            "CREATE TABLE t (id integer NOT NULL PRIMARY KEY, a integer UNIQUE, b integer, c integer, UNIQUE (b, c));"
+        );
+    }
+
+    #[test]
+    fn test_checks_and_defaults() {
+        let table = Table::init_via_sql("create table t (qty int default 1 not null check (qty > 0), note varchar(20) default 'none')");
+        assert_eq!(
+            table.generate_with(SqlType::CreateTable, Dialect::Oracle, &mut thread_rng()).text,
+            "CREATE TABLE t (qty int DEFAULT 1 NOT NULL CHECK (qty > 0), note varchar(20) DEFAULT 'none');"
+        );
+    }
+
+    #[test]
+    fn test_auto_increment() {
+        let table = Table::init_via_sql("create table t (id serial primary key, name varchar(20) not null)");
+        assert!(table.columns[0].auto_increment);
+        let mut rng = thread_rng();
+        let create = |dialect| table.generate_with(SqlType::CreateTable, dialect, &mut thread_rng()).text;
+        assert_eq!(create(Dialect::Postgres), "CREATE TABLE t (id integer GENERATED BY DEFAULT AS IDENTITY NOT NULL PRIMARY KEY, name varchar(20) NOT NULL);");
+        assert_eq!(create(Dialect::MySql), "CREATE TABLE t (id int NOT NULL AUTO_INCREMENT PRIMARY KEY, name varchar(20) NOT NULL);");
+        assert_eq!(create(Dialect::Sqlite), "CREATE TABLE t (id integer NOT NULL PRIMARY KEY AUTOINCREMENT, name varchar(20) NOT NULL);");
+        assert_eq!(create(Dialect::SqlServer), "CREATE TABLE t (id int IDENTITY(1,1) NOT NULL PRIMARY KEY, name varchar(20) NOT NULL);");
+
+        let insert = table.generate_with(SqlType::Insert, Dialect::Postgres, &mut rng).text;
+        assert!(insert.starts_with("INSERT INTO t (name) VALUES ('"), "{}", insert);
+        let update = table.generate_with(SqlType::Update, Dialect::Postgres, &mut rng).text;
+        assert!(update.starts_with("UPDATE t SET name = '"), "{}", update);
+    }
+
+    #[test]
+    fn test_create_table_with_composite_key() {
+        let table = Table::init_via_sql("create table order_items (order_id int, product_id int, primary key (order_id, product_id))");
+        assert_eq!(
+            table.generate_with(SqlType::CreateTable, Dialect::Postgres, &mut thread_rng()).text,
+            "CREATE TABLE order_items (order_id integer NOT NULL, product_id integer NOT NULL, PRIMARY KEY (order_id, product_id));"
+        );
+    }
+
+    #[test]
+    fn test_insert_statement_rows() {
+        let table = Table::init_via_sql("create table t (id number(10) primary key, name varchar(20))");
+        let rows = vec![
+            vec![SqlValue::Integer(1), SqlValue::Text("Bob".to_string())],
+            vec![SqlValue::Integer(2), SqlValue::Null],
+        ];
+        assert_eq!(
+            table.insert_statement_rows(Dialect::Postgres, &rows),
+            "INSERT INTO t (id, name) VALUES (1, 'Bob'), (2, NULL);"
+        );
+        assert_eq!(
+            table.insert_statement_rows(Dialect::Oracle, &rows),
+            "INSERT ALL INTO t (id, name) VALUES (1, 'Bob') INTO t (id, name) VALUES (2, NULL) SELECT 1 FROM DUAL;"
+        );
+        assert_eq!(
+            table.insert_statement_rows(Dialect::Oracle, &rows[..1]),
+            "INSERT INTO t (id, name) VALUES (1, 'Bob');"
+        );
+    }
+
+    #[test]
+    fn test_insert_statement_params() {
+        let table = Table::init_via_sql("create table t (id serial primary key, code int, name varchar(20))");
+        let rows = vec![
+            vec![SqlValue::Integer(1), SqlValue::Integer(10), SqlValue::Text("Bob".to_string())],
+            vec![SqlValue::Integer(2), SqlValue::Integer(20), SqlValue::Null],
+        ];
+        let (sql, params) = table.insert_statement_params(Dialect::MySql, ParamStyle::Positional, &rows);
+        assert_eq!(sql, "INSERT INTO t (code, name) VALUES (?, ?), (?, ?);");
+        assert_eq!(params, serde_json::json!([10, "Bob", 20, null]));
+
+        let (sql, params) = table.insert_statement_params(Dialect::Oracle, ParamStyle::Named, &rows);
+        assert_eq!(
+            sql,
+            "INSERT ALL INTO t (code, name) VALUES (:code_1, :name_1) INTO t (code, name) VALUES (:code_2, :name_2) SELECT 1 FROM DUAL;"
+        );
+        assert_eq!(params, serde_json::json!({"code_1": 10, "name_1": "Bob", "code_2": 20, "name_2": null}));
+
+        let (sql, _) = table.insert_statement_params(Dialect::SqlServer, ParamStyle::Named, &rows[..1]);
+        assert_eq!(sql, "INSERT INTO t (code, name) VALUES (@code, @name);");
+
+        let table = Table::init_via_sql(r#"create table t ("Order Date" int, order_date int, order_date_2 int)"#);
+        let row = vec![SqlValue::Integer(1), SqlValue::Integer(2), SqlValue::Integer(3)];
+        let (sql, params) = table.insert_statement_params(Dialect::Postgres, ParamStyle::Named, &[row]);
+        assert!(sql.ends_with("VALUES (:order_date, :order_date_2, :order_date_2_2);"), "{}", sql);
+        assert_eq!(params, serde_json::json!({"order_date": 1, "order_date_2": 2, "order_date_2_2": 3}));
+    }
+
+    #[test]
+    fn test_null_rate() {
+        let mut table = Table::init_via_sql("create table t (id number(10) primary key, a varchar(20), b varchar(20) not null)");
+        assert!(table.columns[1].is_nullable);
+        assert!(!table.columns[2].is_nullable);
+        for column in &mut table.columns {
+            column.null_rate = Some(1.0);
+        }
+        let row = table.generate_row(&mut thread_rng());
+        assert_ne!(row[0], SqlValue::Null);
+        assert_eq!(row[1], SqlValue::Null);
+        assert_ne!(row[2], SqlValue::Null);
+    }
+
+    #[test]
+    fn test_generate_value_for_types() {
+        let table = Table::init_via_sql(
+            "create table t (a boolean, b uuid, c timestamp, d timestamptz, e bytea, f jsonb, g blob(4))",
+        );
+        let row = table.generate_row(&mut thread_rng());
+        assert!(matches!(row[0], SqlValue::Boolean(_)));
+        let SqlValue::Uuid(uuid) = row[1] else { panic!("expected a uuid, found {:?}", row[1]) };
+        assert_eq!(row[1].as_text().unwrap().chars().nth(14), Some('4'));
+        assert_eq!((uuid >> 62) & 0x3, 0x2);
+        assert!(matches!(row[2], SqlValue::Timestamp(_)));
+        assert!(matches!(row[3], SqlValue::TimestampTz(_)));
+        assert!(matches!(&row[4], SqlValue::Bytes(bytes) if !bytes.is_empty()));
+        assert!(matches!(&row[5], SqlValue::Json(json) if json.is_object()));
+        assert!(matches!(&row[6], SqlValue::Bytes(bytes) if bytes.len() <= 4));
+        assert!(table.insert_statement(Dialect::Postgres, &row).starts_with("INSERT INTO t (a, b, c, d, e, f, g) VALUES ("));
+    }
+
+    #[test]
+    fn test_time_interval_and_timestamptz() {
+        let mut table = Table::init_via_sql("create table t (a time, b interval day to second, c timestamp with time zone)");
+        table.columns[2].timezones = Some("+05:30".parse().unwrap());
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            let row = table.generate_row(&mut rng);
+            assert!(matches!(row[0], SqlValue::Time(_)));
+            assert!(matches!(row[1], SqlValue::Interval(seconds) if (0..=MAX_INTERVAL_SECONDS).contains(&seconds)));
+            let SqlValue::TimestampTz(timestamp) = row[2] else { panic!("expected a timestamptz, found {:?}", row[2]) };
+            assert_eq!(timestamp.offset().local_minus_utc(), 19_800);
+            for (column, value) in table.columns.iter().zip(&row) {
+                assert!(column.matching_condition("x", value, Dialect::Postgres, &mut rng).starts_with("x "));
+            }
+        }
+        let conditions: Vec<String> = table.columns.iter().map(|c| c.generate_condition("x", Dialect::Postgres, &mut rng).unwrap()).collect();
+        assert!(conditions[0].starts_with("x BETWEEN TIME '"), "{}", conditions[0]);
+        assert!(conditions[1].contains(" INTERVAL '"), "{}", conditions[1]);
+        assert!(conditions[2].starts_with("x BETWEEN TIMESTAMPTZ '") && conditions[2].ends_with("+05:30'"), "{}", conditions[2]);
+        assert_eq!(table.columns[1].definition(Dialect::MySql), "b time");
+    }
+
+    #[test]
+    fn test_json_conditions() {
+        let mut column = Column::new("doc", "jsonb", None, None);
+        column.payload = Some(Payload::from_template(&serde_json::json!({"user": {"name": "{first_name}"}, "n": 7, "ok": true})).unwrap());
+        let mut rng = thread_rng();
+        let value = column.generate_value(&mut rng);
+        let SqlValue::Json(document) = &value else { panic!("expected a document, found {:?}", value) };
+        let name = document["user"]["name"].as_str().unwrap();
+        let conditions: Vec<String> = (0..20).map(|_| column.matching_condition("doc", &value, Dialect::Postgres, &mut rng)).collect();
+        assert!(conditions.contains(&format!("doc->'user'->>'name' = '{}'", name)));
+        assert!(conditions.iter().all(|c| c.starts_with("doc->'user'->>'name' = ") || c == "doc->>'n' = '7'"), "{:?}", conditions);
+        let conditions: Vec<String> = (0..20).map(|_| column.generate_condition("doc", Dialect::MySql, &mut rng).unwrap()).collect();
+        assert!(conditions.contains(&"JSON_EXTRACT(doc, '$.n') = 7".to_string()), "{:?}", conditions);
+        assert!(column.is_filterable() && column.is_json(Dialect::Postgres));
+        assert!(Column::new("tags", "int[]", None, None).is_json(Dialect::Sqlite));
+    }
+
+    #[test]
+    fn test_enums() {
+        let mut table = Table::init_via_sql("create table t (status mood not null, moods mood[])");
+        for column in &mut table.columns {
+            column.labels = vec!["sad".to_string(), "ok".to_string()];
+        }
+        assert_eq!(table.columns[0].type_statements(Dialect::Postgres), ["CREATE TYPE mood AS ENUM ('sad', 'ok');"]);
+        assert!(table.columns[0].type_statements(Dialect::MySql).is_empty());
+        assert_eq!(table.columns[0].definition(Dialect::MySql), "status ENUM('sad', 'ok') NOT NULL");
+        assert_eq!(table.columns[0].definition(Dialect::Sqlite), "status varchar(3) NOT NULL CHECK (status IN ('sad', 'ok'))");
+        assert_eq!(table.columns[1].definition(Dialect::Postgres), "moods mood[]");
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            let row = table.generate_row(&mut rng);
+            assert!(matches!(&row[0], SqlValue::Text(label) if table.columns[0].labels.contains(label)), "{:?}", row[0]);
+            let SqlValue::Array(moods) = &row[1] else { panic!("expected an array, found {:?}", row[1]) };
+            assert!(moods.iter().all(|mood| matches!(mood, SqlValue::Text(label) if label == "sad" || label == "ok")));
+            let condition = table.columns[0].generate_condition("status", Dialect::Postgres, &mut rng).unwrap();
+            assert!(["status = 'sad'", "status = 'ok'", "status IN ('sad', 'ok')", "status IN ('ok', 'sad')"].contains(&condition.as_str()), "{}", condition);
+            assert!(!table.columns[0].matching_condition("status", &row[0], Dialect::Postgres, &mut rng).contains(" IN "));
+        }
+    }
+
+    #[test]
+    fn test_spatial_values() {
+        let mut table = Table::init_via_sql("create table stores (id int primary key, location geography(point), area geometry)");
+        table.columns[1].bounding_box = Some("13.0,52.3,13.8,52.7".parse().unwrap());
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            let row = table.generate_row(&mut rng);
+            assert!(matches!(&row[1], SqlValue::Geometry(wkt, true) if wkt.starts_with("POINT(13.")), "{:?}", row[1]);
+            assert!(matches!(&row[2], SqlValue::Geometry(_, false)), "{:?}", row[2]);
+        }
+        assert!(!table.columns[1].is_filterable());
+        assert_eq!(table.schema_statements(Dialect::Postgres)[0], "CREATE TABLE stores (id integer NOT NULL PRIMARY KEY, location geography(Point,4326), area geometry(Geometry,4326));");
+        assert!((0..20).all(|_| table.random_index(&mut rng).1 == ["id"]));
+        let shapes = Table::init_via_sql("create table shapes (location geography(point), area geometry)");
+        for sql_type in [SqlType::CreateIndex, SqlType::DropIndex] {
+            let statement = shapes.generate_with(sql_type, Dialect::Postgres, &mut rng);
+            assert_eq!(statement.sql_type, SqlType::Select, "{}", statement);
+        }
+    }
+
+    #[test]
+    fn test_blob_sizes_and_encodings() {
+        let mut table = Table::init_via_sql("create table files (id int primary key, data blob, thumbnail varbinary(8))");
+        table.columns[1].blob_size = Some("1K..2K".parse().unwrap());
+        table.columns[1].blob_encoding = Some(BlobEncoding::Base64);
+        table.columns[2].blob_size = Some("4..64".parse().unwrap());
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            let row = table.generate_row(&mut rng);
+            assert!(matches!(&row[1], SqlValue::Base64(bytes) if (1024..=2048).contains(&bytes.len())), "{:?}", row[1]);
+            // The declared length caps the size
+            assert!(matches!(&row[2], SqlValue::Bytes(bytes) if (4..=8).contains(&bytes.len())), "{:?}", row[2]);
+        }
+    }
+
+    #[test]
+    fn test_arrays_and_composites() {
+        let mut table = Table::init_via_sql("create table t (tags varchar(5)[], home address, homes address[])");
+        let attributes = vec![Column::new("street", "text", None, None), Column::new("zip", "int", None, None)];
+        table.columns[1].attributes = attributes.clone();
+        table.columns[2].attributes = attributes;
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            let row = table.generate_row(&mut rng);
+            let SqlValue::Array(tags) = &row[0] else { panic!("expected an array, found {:?}", row[0]) };
+            assert!(tags.len() <= MAX_ARRAY_LENGTH);
+            assert!(tags.iter().all(|tag| matches!(tag, SqlValue::Text(text) if text.chars().count() <= 5)));
+            let SqlValue::Composite(fields) = &row[1] else { panic!("expected a composite, found {:?}", row[1]) };
+            assert!(matches!(fields.as_slice(), [(street, SqlValue::Text(_)), (zip, SqlValue::Integer(_))] if street == "street" && zip == "zip"));
+            assert!(matches!(&row[2], SqlValue::Array(homes) if homes.iter().all(|home| matches!(home, SqlValue::Composite(_)))));
+            let condition = table.columns[0].matching_condition("x", &row[0], Dialect::Postgres, &mut rng);
+            assert!(condition.ends_with(" = ANY(x)") || condition == "cardinality(x) = 0", "{}", condition);
+        }
+        assert!(table.columns[0].is_filterable() && !table.columns[1].is_filterable());
+        let condition = table.columns[0].generate_condition("x", Dialect::MySql, &mut rng).unwrap();
+        assert!(condition.starts_with("x LIKE '%\"") && condition.ends_with("\"%'"), "{}", condition);
+
+        assert_eq!(table.columns[0].definition(Dialect::Postgres), "tags varchar(5)[]");
+        assert_eq!(table.columns[1].definition(Dialect::SqlServer), "home nvarchar(max)");
+        assert_eq!(table.columns[2].definition(Dialect::Postgres), "homes address[]");
+        assert_eq!(table.columns[2].type_statements(Dialect::Postgres), ["CREATE TYPE address AS (street text, zip integer);"]);
+        assert!(table.columns[2].type_statements(Dialect::MySql).is_empty());
+    }
+
+    #[test]
+    fn test_numeric_range() {
+        let table = Table::init_via_sql("create table t (a number(10), b number(5, 2), c smallint, d int, e number(3))");
+        assert_eq!(table.columns[0].numeric_range(), (1, 9_999_999_999));
+        assert_eq!(table.columns[1].numeric_range(), (100, 99_999));
+        assert_eq!(table.columns[2].numeric_range(), (1, 32_767));
+        assert_eq!(table.columns[3].numeric_range(), (1, 2_147_483_647));
+
+        let mut column = table.columns[1].clone();
+        column.min_value = Some(9.5);
+        column.max_value = Some(10.0);
+        assert_eq!(column.numeric_range(), (950, 1000));
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let SqlValue::Decimal(value, 2) = column.generate_value(&mut rng) else { panic!("expected a decimal") };
+            assert!((950..=1000).contains(&value));
+        }
+        let mut column = table.columns[4].clone();
+        column.max_value = Some(1e9);
+        assert_eq!(column.numeric_range(), (1, 999));
+
+        // Decimals are at least 1 unless the scale leaves no integer digits, and stay exact
+        // over their whole precision
+        let decimals = Table::init_via_sql("create table d (a number(3, 3), b numeric(38, 10), c number(10, 2))");
+        assert_eq!(decimals.columns[0].numeric_range(), (1, 999));
+        assert_eq!(decimals.columns[1].numeric_range(), (10_000_000_000, 10i128.pow(38) - 1));
+        let mut long = 0;
+        for _ in 0..100 {
+            let value = decimals.columns[2].generate_value(&mut rng);
+            let SqlValue::Decimal(n, 2) = value else { panic!("expected a decimal") };
+            assert!((100..=9_999_999_999).contains(&n), "{}", n);
+            let text = decimals.columns[1].generate_value(&mut rng).as_text().unwrap();
+            let (whole, fraction) = text.split_once('.').unwrap();
+            assert!(whole.len() <= 28 && fraction.len() == 10, "{}", text);
+            long += usize::from(whole.len() > 20);
+        }
+        assert!(long > 90);
+
+        let prices = Table::init_via_sql("create table p (unit_price number(10, 2), price int)");
+        assert_eq!(prices.columns[0].numeric_range(), (99, 99_999));
+        assert_eq!(prices.columns[1].numeric_range(), (1, 1000));
+    }
+
+    #[test]
+    fn test_text_respects_length() {
+        let table = Table::init_via_sql("create table t (name varchar(5), email varchar(8))");
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            for (column, value) in table.columns.iter().zip(table.generate_row(&mut rng)) {
+                let SqlValue::Text(text) = value else { panic!("expected text") };
+                assert!((1..=column.length.unwrap() as usize).contains(&text.chars().count()), "{}", text);
+            }
+        }
+
+        let mut column = table.columns[1].clone();
+        column.max_length_rate = Some(1.0);
+        let SqlValue::Text(text) = column.generate_value(&mut rng) else { panic!("expected text") };
+        assert_eq!(text.chars().count(), 8);
+
+        // Multibyte text is cut by characters, not bytes
+        let mut column = table.columns[0].clone();
+        column.locale = Some(Locale::Ja);
+        column.provider = Some(Provider::Address);
+        column.max_length_rate = Some(1.0);
+        let SqlValue::Text(text) = column.generate_value(&mut rng) else { panic!("expected text") };
+        assert_eq!(text.chars().count(), 5);
+        assert!(text.len() > 5);
+    }
+
+    #[test]
+    fn test_date_range() {
+        let range: DateRange = "2020-02-01..2020-02-03".parse().unwrap();
+        assert_eq!(range.to_string(), "2020-02-01..2020-02-03");
+        assert!("2020-02-03..2020-02-01".parse::<DateRange>().is_err());
+        assert!("2020-02-01".parse::<DateRange>().is_err());
+
+        let mut table = Table::init_via_sql("create table t (d date, dt datetime)");
+        for column in &mut table.columns {
+            column.date_range = Some(range);
+        }
+        let mut rng = thread_rng();
+        let mut times = HashSet::new();
+        for _ in 0..50 {
+            let row = table.generate_row(&mut rng);
+            let SqlValue::Date(date) = row[0] else { panic!("expected a date") };
+            assert!((range.start..=range.end).contains(&date));
+            let SqlValue::Timestamp(timestamp) = row[1] else { panic!("expected a timestamp") };
+            assert!((range.start..=range.end).contains(&timestamp.date()));
+            times.insert(timestamp.time());
+        }
+        assert!(times.len() > 1);
+    }
+
+    #[test]
+    fn test_distribution() {
+        let mut column = Table::init_via_sql("create table t (n number(3))").columns.remove(0);
+        column.distribution = Some(Distribution::Zipfian);
+        let mut rng = thread_rng();
+        let small = (0..1000)
+            .filter(|_| matches!(column.generate_value(&mut rng), SqlValue::Integer(n) if n <= 100))
+            .count();
+        assert!(small > 500, "{}", small);
+    }
+
+    #[test]
+    fn test_value_pool() {
+        let mut table = Table::init_via_sql("create table t (status varchar(10))");
+        let statuses = ["new", "paid", "shipped"].map(|s| SqlValue::Text(s.to_string()));
+        table.columns[0].pool = Some(ValuePool::uniform(statuses.to_vec()).unwrap());
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            assert!(statuses.contains(&table.generate_row(&mut rng)[0]));
+            let condition = table.columns[0].generate_condition("status", Dialect::Postgres, &mut rng).unwrap();
+            assert!(condition.contains("'new'") || condition.contains("'paid'") || condition.contains("'shipped'"), "{}", condition);
+        }
+    }
+
+    #[test]
+    fn test_parse_references() {
+        let table = Table::init_via_sql(
+            "create table orders (order_id number(10) primary key, customer_id number(10) references customers(customer_id), product_id number(10) references products (product_id))",
+        );
+        assert_eq!(table.columns[1].ref_table.as_deref(), Some("customers"));
+        assert_eq!(table.columns[1].ref_column.as_deref(), Some("customer_id"));
+        assert_eq!(table.columns[2].ref_table.as_deref(), Some("products"));
+        assert_eq!(table.columns[2].ref_column.as_deref(), Some("product_id"));
+    }
+
+    #[test]
+    fn test_sql_type_from_str() {
+        assert_eq!("insert".parse::<SqlType>(), Ok(SqlType::Insert));
+        assert_eq!("create-table".parse::<SqlType>(), Ok(SqlType::CreateTable));
+        assert_eq!("DROP_TABLE".parse::<SqlType>(), Ok(SqlType::DropTable));
+        assert!("merge".parse::<SqlType>().is_err());
+        for sql_type in SqlType::ALL {
+            assert_eq!(sql_type.to_string().parse::<SqlType>(), Ok(sql_type));
+        }
+    }
 }
\ No newline at end of file
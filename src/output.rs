@@ -0,0 +1,1097 @@
+//! Output formats other than plain SQL scripts.
+
+use crate::dialect::Dialect;
+use crate::models::{SqlType, Table};
+use crate::rate::Rate;
+use crate::schema;
+use crate::statement::Statement;
+use crate::value::{self, SqlValue};
+use chrono::{NaiveDateTime, TimeDelta};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Size of the write buffer of output files unless another size is configured.
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Parses a size in bytes, optionally with a `K`, `KiB`, `M`, `MiB`, `G` or `GiB` suffix.
+///
+/// # Arguments
+///
+/// * `s` - The size, e.g. `65536`, `64K`, `1MiB` or `2G`.
+///
+/// # Returns
+///
+/// The size in bytes, or an error if `s` is not a positive size.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::output::parse_size;
+/// assert_eq!(parse_size("64K"), Ok(65536));
+/// assert_eq!(parse_size("1MiB"), Ok(1 << 20));
+/// assert!(parse_size("0").is_err());
+/// ```
+pub fn parse_size(s: &str) -> Result<usize, String> {
+    let upper = s.trim().to_uppercase();
+    let (digits, factor) = if let Some(digits) = upper.strip_suffix("GIB").or_else(|| upper.strip_suffix('G')) {
+        (digits, 1 << 30)
+    } else if let Some(digits) = upper.strip_suffix("MIB").or_else(|| upper.strip_suffix('M')) {
+        (digits, 1 << 20)
+    } else if let Some(digits) = upper.strip_suffix("KIB").or_else(|| upper.strip_suffix('K')) {
+        (digits, 1 << 10)
+    } else {
+        (upper.strip_suffix('B').unwrap_or(&upper), 1)
+    };
+    match digits.trim().parse::<usize>().ok().and_then(|n| n.checked_mul(factor)) {
+        Some(size) if size > 0 => Ok(size),
+        _ => Err(format!("invalid size `{}`", s)),
+    }
+}
+
+/// Enum representing the format generated data is written in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// A SQL script with DDL and DML statements.
+    #[default]
+    Sql,
+    /// One CSV file per table holding only the generated rows.
+    Csv,
+    /// One JSON object per generated row and line, keyed by table name.
+    Jsonl,
+    /// SQL statements prefixed with a synthetic timestamp, session id and duration, like
+    /// the lines of an audit or slow-query log.
+    Trace,
+    /// The lines of [`Format::Trace`] as JSON objects.
+    TraceJson,
+    /// A bulk load of the generated rows per table: Postgres `COPY ... FROM STDIN` blocks
+    /// or MySQL `LOAD DATA LOCAL INFILE` statements, see [`BulkWriter`].
+    Bulk,
+    /// One Parquet file per table holding only the generated rows; needs the `arrow`
+    /// feature.
+    Parquet,
+    /// Inserts, updates and deletes of rows as Debezium change events, one JSON object per
+    /// line, see [`CdcWriter`](crate::cdc::CdcWriter).
+    CdcJson,
+}
+
+impl Format {
+    /// Returns the lowercase name of the format, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Sql => "sql",
+            Format::Csv => "csv",
+            Format::Jsonl => "jsonl",
+            Format::Trace => "trace",
+            Format::TraceJson => "trace-json",
+            Format::Bulk => "bulk",
+            Format::Parquet => "parquet",
+            Format::CdcJson => "cdc-json",
+        }
+    }
+
+    /// Returns whether the format writes statements rather than only the generated rows.
+    pub fn writes_statements(&self) -> bool {
+        matches!(self, Format::Sql | Format::Trace | Format::TraceJson)
+    }
+
+    /// Returns whether the format writes a file per table, named after the table with the
+    /// name of the format as extension, into the output directory.
+    pub fn writes_table_files(&self) -> bool {
+        matches!(self, Format::Csv | Format::Parquet)
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sql" => Ok(Format::Sql),
+            "csv" => Ok(Format::Csv),
+            "jsonl" | "ndjson" | "json" => Ok(Format::Jsonl),
+            "trace" | "log" => Ok(Format::Trace),
+            "trace-json" | "trace-jsonl" => Ok(Format::TraceJson),
+            "bulk" | "copy" | "load-data" => Ok(Format::Bulk),
+            "parquet" => Ok(Format::Parquet),
+            "cdc-json" | "cdc" | "debezium" => Ok(Format::CdcJson),
+            _ => Err(format!("unknown output format `{}`", s)),
+        }
+    }
+}
+
+/// Writes SQL statements, one per line, optionally wrapped in transactions.
+///
+/// With a transaction size of `N`, every `N` statements are enclosed in the
+/// dialect's `BEGIN`/`COMMIT` statements so the script loads atomically and faster.
+///
+/// With a maximum size, the writer moves on to a new destination once the current one
+/// is full, e.g. from `output_0001.sql` to `output_0002.sql`.
+pub struct SqlWriter<W: Write> {
+    out: W,
+    dialect: Dialect,
+    transaction_size: Option<usize>,
+    /// Statements written in the currently open transaction.
+    in_transaction: usize,
+    /// Lines written to all destinations, transaction statements included.
+    lines: u64,
+    /// Line of the last statement written, counting from 1 across destinations.
+    statement_line: u64,
+    /// Bytes written to the current destination.
+    written: u64,
+    rotation: Option<Rotation<W>>,
+}
+
+/// When and how a [`SqlWriter`] switches to a new destination.
+struct Rotation<W> {
+    max_size: u64,
+    open_next: Box<dyn FnMut() -> io::Result<W>>,
+}
+
+/// Returns the `n`-th file of a numbered series of outputs: `output.sql` becomes
+/// `output_0001.sql` for `n = 1`.
+///
+/// # Arguments
+///
+/// * `path` - The output path the series is named after.
+/// * `n` - The number of the file, starting at 1.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::output::numbered_path;
+/// # use std::path::Path;
+/// assert_eq!(numbered_path(Path::new("out/data.sql"), 12), Path::new("out/data_0012.sql"));
+/// ```
+pub fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(extension) => path.with_file_name(format!("{}_{:04}.{}", stem, n, extension.to_string_lossy())),
+        None => path.with_file_name(format!("{}_{:04}", stem, n)),
+    }
+}
+
+impl<W: Write> SqlWriter<W> {
+    /// Initializes a new `SqlWriter` writing to `out`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The destination of the statements.
+    /// * `dialect` - The dialect used for the transaction statements.
+    pub fn new(out: W, dialect: Dialect) -> SqlWriter<W> {
+        SqlWriter {
+            out,
+            dialect,
+            transaction_size: None,
+            in_transaction: 0,
+            lines: 0,
+            statement_line: 0,
+            written: 0,
+            rotation: None,
+        }
+    }
+
+    /// Moves on to a new destination once the current one holds at least `max_size`
+    /// bytes.
+    ///
+    /// The switch only happens between statements, and never inside a transaction,
+    /// so a destination can exceed `max_size` by a statement or a transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_size` - Size in bytes after which the writer switches destinations.
+    /// * `open_next` - Opens the next destination, e.g. the next numbered file.
+    pub fn set_max_size<F>(&mut self, max_size: u64, open_next: F)
+    where
+        F: FnMut() -> io::Result<W> + 'static,
+    {
+        self.rotation = Some(Rotation {
+            max_size,
+            open_next: Box::new(open_next),
+        });
+    }
+
+    /// Wraps every `size` statements in a transaction; `None` disables wrapping.
+    pub fn set_transaction_size(&mut self, size: Option<usize>) {
+        self.transaction_size = size.filter(|size| *size > 0);
+    }
+
+    /// Writes a statement, opening or committing a transaction as needed.
+    pub fn write_statement(&mut self, sql: &str) -> io::Result<()> {
+        if self.in_transaction == 0 {
+            if let Some(rotation) = self.rotation.as_mut().filter(|rotation| self.written >= rotation.max_size) {
+                self.out.flush()?;
+                self.out = (rotation.open_next)()?;
+                self.written = 0;
+            }
+        }
+        if let Some(size) = self.transaction_size {
+            if self.in_transaction == 0 {
+                if let Some(begin) = self.dialect.begin_transaction() {
+                    self.write_line(begin)?;
+                }
+            }
+            self.write_line(sql)?;
+            self.statement_line = self.lines;
+            self.in_transaction += 1;
+            if self.in_transaction == size {
+                self.commit()?;
+            }
+            Ok(())
+        } else {
+            self.write_line(sql)?;
+            self.statement_line = self.lines;
+            Ok(())
+        }
+    }
+
+    /// Returns the number of lines written so far to all destinations, the `BEGIN` and
+    /// `COMMIT` statements of transactions included.
+    pub fn lines(&self) -> u64 {
+        self.lines
+    }
+
+    /// Returns the line of the last statement written, counting from 1 across
+    /// destinations, or 0 before the first one.
+    pub fn statement_line(&self) -> u64 {
+        self.statement_line
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        self.written += line.len() as u64 + 1;
+        self.lines += 1;
+        writeln!(self.out, "{}", line)
+    }
+
+    /// Flushes the output without committing the open transaction, so the statements
+    /// written so far reach readers of a live stream.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Commits the open transaction, if any, and flushes the output.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.in_transaction > 0 {
+            self.commit()?;
+        }
+        self.out.flush()
+    }
+
+    fn commit(&mut self) -> io::Result<()> {
+        self.in_transaction = 0;
+        self.write_line(self.dialect.commit())
+    }
+}
+
+/// Counts the bytes written to the inner writer.
+///
+/// The count is shared, so the writers of a series of files, e.g. the numbered files of
+/// a [`SqlWriter`] with a maximum size, add up to the bytes of the whole output.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::output::CountingWriter;
+/// # use std::io::Write;
+/// # use std::sync::atomic::{AtomicU64, Ordering};
+/// # use std::sync::Arc;
+/// let count = Arc::new(AtomicU64::new(0));
+/// let mut out = CountingWriter::new(Vec::new(), count.clone());
+/// writeln!(out, "SELECT 1;").unwrap();
+/// assert_eq!(count.load(Ordering::Relaxed), 10);
+/// ```
+pub struct CountingWriter<W: Write> {
+    inner: W,
+    count: Arc<AtomicU64>,
+}
+
+impl<W: Write> CountingWriter<W> {
+    /// Initializes a new `CountingWriter` adding the bytes written to `inner` to `count`.
+    pub fn new(inner: W, count: Arc<AtomicU64>) -> CountingWriter<W> {
+        CountingWriter { inner, count }
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count.fetch_add(written as u64, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Number of sessions a trace spreads its statements over by default.
+pub const DEFAULT_TRACE_SESSIONS: u32 = 8;
+
+/// Probability that a session opens a transaction before a statement of a trace.
+const TRANSACTION_RATE: f64 = 0.25;
+
+/// Maximum number of statements of a transaction in a trace.
+const MAX_TRANSACTION_STATEMENTS: u32 = 5;
+
+/// Probability that a transaction of a trace is rolled back instead of committed.
+const ROLLBACK_RATE: f64 = 0.1;
+
+/// Average time a `BEGIN`, `COMMIT` or `ROLLBACK` takes in a trace, in milliseconds.
+const TRANSACTION_STATEMENT_MS: f64 = 0.2;
+
+/// Writes statements as a workload trace, simulating the log of a database serving
+/// concurrent sessions.
+///
+/// Every statement gets a timestamp, a session id and a duration. Sessions behave like
+/// clients: each runs one statement at a time, then waits for a think time drawn from an
+/// exponential distribution before its next one, and the statements go to the session
+/// that is ready first, so the lines stay in timestamp order. Starting at the given time,
+/// statements arrive 100 per second on average over all sessions unless another rate is
+/// set, or less often when long statements keep the sessions busy. Durations are drawn from an exponential distribution whose mean depends on the
+/// statement type, e.g. longer for joins and index builds than for single-row inserts.
+///
+/// Sessions now and then wrap a few DML statements or queries in a transaction, opened
+/// with the dialect's `BEGIN` and closed with `COMMIT` or, sometimes, `ROLLBACK` on their
+/// next turn. [`TraceWriter::finish`] closes the transactions still open.
+///
+/// Text lines look like
+/// `2021-01-01T00:00:00.012345 session=3 duration_ms=1.207 INSERT INTO ...;`, JSON lines
+/// like `{"timestamp":"2021-01-01T00:00:00.012345","session":3,"duration_ms":1.207,
+/// "type":"insert","table":"orders","statement":"INSERT INTO ...;"}`, where transaction
+/// statements have the type `begin`, `commit` or `rollback` and an empty table.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::output::TraceWriter;
+/// # use fake_sql::{Dialect, SqlType, Statement};
+/// # use chrono::NaiveDate;
+/// let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+/// let mut writer = TraceWriter::new(Vec::new(), false, start, Some(7));
+/// writer.set_sessions(1);
+/// writer.set_dialect(Dialect::Postgres);
+/// writer.write_statement(&Statement::new(SqlType::Select, "orders", "SELECT 1;".to_string())).unwrap();
+/// writer.finish().unwrap();
+/// let trace = String::from_utf8(writer.into_inner()).unwrap();
+/// assert!(trace.starts_with("2024-01-01T00:00:00.") && trace.contains(" session=1 duration_ms="));
+/// assert!(trace.contains(" SELECT 1;\n"));
+/// ```
+pub struct TraceWriter<W: Write> {
+    out: W,
+    json: bool,
+    dialect: Dialect,
+    start: NaiveDateTime,
+    /// Mean time between two statements over all sessions, in seconds.
+    gap: f64,
+    /// Number of sessions; they are started with the first statement.
+    session_count: u32,
+    sessions: Vec<Session>,
+    rng: StdRng,
+}
+
+/// A simulated client of a trace.
+struct Session {
+    /// When the session issues its next statement.
+    ready: NaiveDateTime,
+    /// Number of statements left in the open transaction, if any.
+    transaction: Option<u32>,
+    /// Whether the transaction is complete and is committed or rolled back on the next turn.
+    closing: bool,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Initializes a new `TraceWriter` writing to `out`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The destination of the trace.
+    /// * `json` - Whether the lines are JSON objects instead of prefixed statements.
+    /// * `start` - The time the sessions start at.
+    /// * `seed` - Seed for timestamps, sessions and durations, or `None` for random ones.
+    pub fn new(out: W, json: bool, start: NaiveDateTime, seed: Option<u64>) -> TraceWriter<W> {
+        TraceWriter {
+            out,
+            json,
+            dialect: Dialect::default(),
+            start,
+            gap: 0.01,
+            session_count: DEFAULT_TRACE_SESSIONS,
+            sessions: Vec::new(),
+            rng: seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+        }
+    }
+
+    /// Sets the dialect of the transaction statements.
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    /// Sets the average rate statements arrive at over all sessions.
+    pub fn set_rate(&mut self, rate: Rate) {
+        self.gap = 1.0 / rate.per_second();
+    }
+
+    /// Sets the number of concurrent sessions, at least 1.
+    pub fn set_sessions(&mut self, sessions: u32) {
+        self.session_count = sessions.max(1);
+    }
+
+    /// Writes a statement in the session that is ready first, with the transaction
+    /// statements that session issues before it.
+    pub fn write_statement(&mut self, statement: &Statement) -> io::Result<()> {
+        if self.sessions.is_empty() {
+            self.start_sessions();
+        }
+        loop {
+            let index = self.next_session();
+            let session = &mut self.sessions[index];
+            if session.closing {
+                session.closing = false;
+                self.close_transaction(index)?;
+            } else if session.transaction.is_none() && !statement.sql_type.is_ddl() && self.rng.gen_bool(TRANSACTION_RATE) {
+                session.transaction = Some(self.rng.gen_range(1..=MAX_TRANSACTION_STATEMENTS));
+                if let Some(begin) = self.dialect.begin_transaction() {
+                    self.write_line(index, "begin", "", begin, TRANSACTION_STATEMENT_MS, false)?;
+                }
+            } else {
+                break;
+            }
+        }
+        let index = self.next_session();
+        let session = &mut self.sessions[index];
+        if let Some(left) = session.transaction {
+            session.transaction = (left > 1).then_some(left - 1);
+            session.closing = left == 1;
+        }
+        let mean_ms = mean_duration_ms(statement.sql_type);
+        self.write_line(index, statement.sql_type.name(), &statement.table, &statement.text, mean_ms, true)
+    }
+
+    /// Closes the open transactions, in the order their sessions get to them, and flushes
+    /// the output.
+    pub fn finish(&mut self) -> io::Result<()> {
+        let mut open: Vec<usize> = (0..self.sessions.len())
+            .filter(|&i| self.sessions[i].closing || self.sessions[i].transaction.is_some())
+            .collect();
+        open.sort_by_key(|&i| self.sessions[i].ready);
+        for index in open {
+            self.sessions[index].transaction = None;
+            self.sessions[index].closing = false;
+            self.close_transaction(index)?;
+        }
+        self.flush()
+    }
+
+    /// Flushes the output.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Returns the destination of the trace.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Starts the sessions, each after a think time of its own so they do not all issue
+    /// their first statement at the start.
+    fn start_sessions(&mut self) {
+        let think = self.think_time();
+        self.sessions = (0..self.session_count)
+            .map(|_| Session {
+                ready: self.start + TimeDelta::microseconds((self.exponential(think) * 1e6) as i64),
+                transaction: None,
+                closing: false,
+            })
+            .collect();
+    }
+
+    /// Returns the index of the session that is ready first.
+    fn next_session(&self) -> usize {
+        (0..self.sessions.len()).min_by_key(|&i| self.sessions[i].ready).unwrap_or(0)
+    }
+
+    /// Returns the mean think time of a session, so that all sessions together issue a
+    /// statement every `gap` seconds on average.
+    fn think_time(&self) -> f64 {
+        self.gap * self.sessions.len().max(self.session_count as usize) as f64
+    }
+
+    /// Writes the `COMMIT`, or now and then the `ROLLBACK`, of the session at `index`.
+    fn close_transaction(&mut self, index: usize) -> io::Result<()> {
+        if self.rng.gen_bool(ROLLBACK_RATE) {
+            self.write_line(index, "rollback", "", self.dialect.rollback(), TRANSACTION_STATEMENT_MS, true)
+        } else {
+            self.write_line(index, "commit", "", self.dialect.commit(), TRANSACTION_STATEMENT_MS, true)
+        }
+    }
+
+    /// Writes a line of the session at `index` at the time it is ready, and advances the
+    /// session past the statement and, if `think` is set, a think time.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the session.
+    /// * `sql_type` - The name of the statement type.
+    /// * `table` - The table of the statement, or an empty string.
+    /// * `text` - The SQL text.
+    /// * `mean_ms` - The average duration of such statements in milliseconds.
+    /// * `think` - Whether the session waits for a think time after the statement.
+    fn write_line(&mut self, index: usize, sql_type: &str, table: &str, text: &str, mean_ms: f64, think: bool) -> io::Result<()> {
+        let timestamp = self.sessions[index].ready.format("%Y-%m-%dT%H:%M:%S%.6f").to_string();
+        let duration_ms = (self.exponential(mean_ms / 1000.0) * 1e6).round() / 1000.0;
+        let think_time = if think { self.exponential(self.think_time()) } else { 0.0 };
+        let session = &mut self.sessions[index];
+        session.ready += TimeDelta::microseconds((duration_ms * 1000.0) as i64).max(TimeDelta::microseconds(1));
+        session.ready += TimeDelta::microseconds((think_time * 1e6) as i64);
+        let session = index + 1;
+        if self.json {
+            let line = serde_json::json!({
+                "timestamp": timestamp,
+                "session": session,
+                "duration_ms": duration_ms,
+                "type": sql_type,
+                "table": table,
+                "statement": text,
+            });
+            writeln!(self.out, "{}", line)
+        } else {
+            writeln!(self.out, "{} session={} duration_ms={:.3} {}", timestamp, session, duration_ms, text)
+        }
+    }
+
+    /// Draws from an exponential distribution with the given mean, in seconds.
+    fn exponential(&mut self, mean: f64) -> f64 {
+        let uniform: f64 = self.rng.gen();
+        -(1.0 - uniform).ln() * mean
+    }
+}
+
+/// Returns the average time a statement of `sql_type` takes in a trace, in milliseconds.
+fn mean_duration_ms(sql_type: SqlType) -> f64 {
+    match sql_type {
+        SqlType::Insert | SqlType::Update | SqlType::Delete => 1.5,
+        SqlType::Select => 2.0,
+        SqlType::SelectJoin => 12.0,
+        SqlType::Aggregate => 25.0,
+        SqlType::DropTable | SqlType::DropIndex | SqlType::Truncate => 8.0,
+        SqlType::CreateTable | SqlType::CreateView => 15.0,
+        SqlType::AlterTable => 40.0,
+        SqlType::CreateIndex => 120.0,
+    }
+}
+
+/// A destination for generated rows, used by the row-oriented formats.
+pub trait RowWriter {
+    /// Writes a row of `table`.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table the row belongs to.
+    /// * `row` - One value per column, in column order.
+    fn write_row(&mut self, table: &Table, row: &[SqlValue]) -> io::Result<()>;
+
+    /// Flushes buffered rows.
+    fn flush(&mut self) -> io::Result<()>;
+
+    /// Writes what is left once every row is written and flushes the output.
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+/// Writes generated rows to one CSV file per table, suitable for `COPY`/`LOAD DATA`.
+///
+/// Files are named `<table>.csv`, start with a header row of column names and are
+/// created when the first row of their table is written. NULL is written as an
+/// empty field.
+pub struct CsvWriter {
+    dir: PathBuf,
+    files: HashMap<String, BufWriter<File>>,
+    /// Size of the write buffer of every file.
+    buffer_size: usize,
+}
+
+impl CsvWriter {
+    /// Initializes a new `CsvWriter` writing into `dir`, creating the directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory the CSV files are written to.
+    pub fn create<P: AsRef<Path>>(dir: P) -> io::Result<CsvWriter> {
+        fs::create_dir_all(&dir)?;
+        Ok(CsvWriter {
+            dir: dir.as_ref().to_path_buf(),
+            files: HashMap::new(),
+            buffer_size: DEFAULT_BUFFER_SIZE,
+        })
+    }
+
+    /// Sets the size of the write buffer of the CSV files opened from now on.
+    pub fn set_buffer_size(&mut self, buffer_size: usize) {
+        self.buffer_size = buffer_size.max(1);
+    }
+
+}
+
+impl RowWriter for CsvWriter {
+    fn write_row(&mut self, table: &Table, row: &[SqlValue]) -> io::Result<()> {
+        if !self.files.contains_key(&table.name) {
+            let path = self.dir.join(format!("{}.csv", table.name));
+            let mut file = BufWriter::with_capacity(self.buffer_size, File::create(path)?);
+            let header: Vec<String> = table.columns.iter().map(|c| csv_field(&c.name)).collect();
+            writeln!(file, "{}", header.join(","))?;
+            self.files.insert(table.name.clone(), file);
+        }
+        let file = self.files.get_mut(&table.name).unwrap();
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| value.as_text().map(|text| csv_field(&text)).unwrap_or_default())
+            .collect();
+        writeln!(file, "{}", fields.join(","))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.files.values_mut().try_for_each(|file| file.flush())
+    }
+}
+
+/// Writes generated rows as newline-delimited JSON.
+///
+/// Every line is an object with the table name as its only key, e.g.
+/// `{"orders":{"order_id":1,"order_date":"2021-03-04","customer_id":7}}`, so the
+/// same data can feed document stores and streaming pipelines.
+pub struct JsonlWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonlWriter<W> {
+    /// Initializes a new `JsonlWriter` writing to `out`.
+    pub fn new(out: W) -> JsonlWriter<W> {
+        JsonlWriter { out }
+    }
+}
+
+impl<W: Write> RowWriter for JsonlWriter<W> {
+    fn write_row(&mut self, table: &Table, row: &[SqlValue]) -> io::Result<()> {
+        writeln!(self.out, "{}", row_json(table, row))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Returns a row of `table` as a JSON object with the table name as its only key, as
+/// [`JsonlWriter`] writes it.
+pub fn row_json(table: &Table, row: &[SqlValue]) -> serde_json::Value {
+    let values: serde_json::Map<String, serde_json::Value> =
+        table.columns.iter().zip(row).map(|(column, value)| (column.name.clone(), value.to_json())).collect();
+    let mut object = serde_json::Map::new();
+    object.insert(table.name.clone(), values.into());
+    serde_json::Value::Object(object)
+}
+
+/// Returns the directory the data files of the bulk loads in `output` are written to,
+/// e.g. `out.sql.data` for `out.sql`.
+pub fn data_dir(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_os_string();
+    name.push(".data");
+    PathBuf::from(name)
+}
+
+/// Writes generated rows as bulk loads, one per table: a Postgres `COPY t (...) FROM STDIN;`
+/// block holding the rows as tab-separated text up to its `\.` line, or a MySQL
+/// `LOAD DATA LOCAL INFILE` statement reading them from a data file.
+///
+/// The rows are spooled to a `<table>.tsv` file per table in the data directory while they
+/// are generated, and [`RowWriter::finish`] writes the loads, parents before the tables
+/// referencing them so foreign keys hold. Postgres blocks copy the rows into the output and
+/// the spool files are removed; MySQL statements name the files by absolute path, so they
+/// are kept for the server to read. Auto-increment columns are left out, as in INSERTs.
+///
+/// Fields are in the text format both databases read by default: `\N` for NULL, and
+/// backslashes, tabs and line breaks escaped with a backslash. MySQL binary and spatial
+/// values are read into variables and converted with `UNHEX` and `ST_GeomFromText`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::output::{BulkWriter, RowWriter};
+/// # use fake_sql::{Dialect, SqlValue, Table};
+/// let dir = std::env::temp_dir().join(format!("fake-sql-bulk-doc-{}", std::process::id()));
+/// let table = Table::init_via_sql("create table t (id int primary key, name varchar(20))");
+/// let mut writer = BulkWriter::create(Vec::new(), Dialect::Postgres, &dir).unwrap();
+/// writer.write_row(&table, &[SqlValue::Integer(1), SqlValue::Text("a\tb".to_string())]).unwrap();
+/// writer.write_row(&table, &[SqlValue::Integer(2), SqlValue::Null]).unwrap();
+/// writer.finish().unwrap();
+/// let sql = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(sql, "COPY t (id, name) FROM STDIN;\n1\ta\\tb\n2\t\\N\n\\.\n");
+/// ```
+pub struct BulkWriter<W: Write> {
+    out: W,
+    dialect: Dialect,
+    /// The directory of the spool or data files, as an absolute path.
+    dir: PathBuf,
+    /// The tables with rows, in the order of their first row.
+    tables: Vec<Table>,
+    files: HashMap<String, BufWriter<File>>,
+    /// Size of the write buffer of every file.
+    buffer_size: usize,
+}
+
+impl<W: Write> BulkWriter<W> {
+    /// Initializes a new `BulkWriter` writing the loads to `out` and their rows into `dir`,
+    /// creating the directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The destination of the `COPY` blocks or `LOAD DATA` statements.
+    /// * `dialect` - Postgres or MySQL; other dialects get MySQL's statements.
+    /// * `dir` - The directory the rows are spooled to.
+    pub fn create<P: AsRef<Path>>(out: W, dialect: Dialect, dir: P) -> io::Result<BulkWriter<W>> {
+        fs::create_dir_all(&dir)?;
+        Ok(BulkWriter {
+            out,
+            dialect,
+            // The database server reads MySQL data files, so the statements name them by absolute path
+            dir: fs::canonicalize(dir)?,
+            tables: Vec::new(),
+            files: HashMap::new(),
+            buffer_size: DEFAULT_BUFFER_SIZE,
+        })
+    }
+
+    /// Sets the size of the write buffer of the files opened from now on.
+    pub fn set_buffer_size(&mut self, buffer_size: usize) {
+        self.buffer_size = buffer_size.max(1);
+    }
+
+    /// Returns the output the loads were written to.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Renders the `LOAD DATA` statement of the rows of `table` in the file at `path`.
+    fn load_data(&self, table: &Table, path: &Path) -> String {
+        let mut targets = vec![];
+        let mut conversions = vec![];
+        for (i, column) in table.columns.iter().enumerate().filter(|(_, c)| !c.auto_increment) {
+            let name = self.dialect.identifier(&column.name);
+            let conversion = if matches!(column.column_type.as_str(), "blob" | "bytea" | "binary" | "varbinary" | "raw") {
+                Some(format!("UNHEX(@v{})", i))
+            } else if column.is_spatial() {
+                Some(format!("ST_GeomFromText(@v{}, {}, 'axis-order=long-lat')", i, crate::geo::SRID))
+            } else {
+                None
+            };
+            match conversion {
+                Some(conversion) => {
+                    targets.push(format!("@v{}", i));
+                    conversions.push(format!("{} = {}", name, conversion));
+                }
+                None => targets.push(name),
+            }
+        }
+        let set = if conversions.is_empty() { String::new() } else { format!(" SET {}", conversions.join(", ")) };
+        format!(
+            "LOAD DATA LOCAL INFILE {} INTO TABLE {} CHARACTER SET utf8mb4 FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\' LINES TERMINATED BY '\\n' ({}){};",
+            self.dialect.string_literal(&path.to_string_lossy()),
+            self.dialect.identifier(&table.name),
+            targets.join(", "),
+            set
+        )
+    }
+}
+
+impl<W: Write> RowWriter for BulkWriter<W> {
+    fn write_row(&mut self, table: &Table, row: &[SqlValue]) -> io::Result<()> {
+        if !self.files.contains_key(&table.name) {
+            let file = File::create(self.dir.join(format!("{}.tsv", table.name)))?;
+            self.files.insert(table.name.clone(), BufWriter::with_capacity(self.buffer_size, file));
+            self.tables.push(table.clone());
+        }
+        let file = self.files.get_mut(&table.name).unwrap();
+        let fields: Vec<String> = row
+            .iter()
+            .zip(&table.columns)
+            .filter(|(_, column)| !column.auto_increment)
+            .map(|(value, _)| bulk_field(self.dialect, value))
+            .collect();
+        writeln!(file, "{}", fields.join("\t"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.files.values_mut().try_for_each(|file| file.flush())?;
+        self.out.flush()
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.files.values_mut().try_for_each(|file| file.flush())?;
+        self.files.clear();
+        for i in schema::dependency_order(&self.tables) {
+            let table = &self.tables[i];
+            let path = self.dir.join(format!("{}.tsv", table.name));
+            if self.dialect != Dialect::Postgres {
+                writeln!(self.out, "{}", self.load_data(table, &path))?;
+                continue;
+            }
+            let columns: Vec<String> =
+                table.columns.iter().filter(|c| !c.auto_increment).map(|c| self.dialect.identifier(&c.name)).collect();
+            writeln!(self.out, "COPY {} ({}) FROM STDIN;", self.dialect.identifier(&table.name), columns.join(", "))?;
+            io::copy(&mut File::open(&path)?, &mut self.out)?;
+            writeln!(self.out, "\\.")?;
+            fs::remove_file(&path)?;
+        }
+        if self.dialect == Dialect::Postgres {
+            // Left in place if it holds files of others
+            fs::remove_dir(&self.dir).ok();
+        }
+        self.out.flush()
+    }
+}
+
+/// Renders a value as a field of tab-separated bulk load data: `\N` for NULL, and the text
+/// of the value with backslashes, tabs and line breaks escaped with a backslash.
+///
+/// Postgres gets `bytea` values as `\x` followed by hex digits, and MySQL gets booleans as
+/// `1` and `0`, binary values as hex digits, spatial values as Well-Known Text, intervals
+/// as times and arrays and composites as JSON, as its statements would store them.
+fn bulk_field(dialect: Dialect, value: &SqlValue) -> String {
+    let mysql = dialect != Dialect::Postgres;
+    let text = match value {
+        SqlValue::Null => return "\\N".to_string(),
+        SqlValue::Bytes(bytes) | SqlValue::Base64(bytes) if mysql => value::hex(bytes),
+        SqlValue::Bytes(bytes) | SqlValue::Base64(bytes) => format!("\\x{}", value::hex(bytes)),
+        SqlValue::Boolean(b) if mysql => (*b as u8).to_string(),
+        SqlValue::Interval(seconds) if mysql => dialect.interval_literal(*seconds).trim_matches('\'').to_string(),
+        SqlValue::Geometry(wkt, _) if mysql => wkt.clone(),
+        SqlValue::Array(_) | SqlValue::Composite(_) if mysql => value.to_json().to_string(),
+        _ => value.as_text().unwrap(),
+    };
+    let mut field = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => field.push_str("\\\\"),
+            '\t' => field.push_str("\\t"),
+            '\n' => field.push_str("\\n"),
+            '\r' => field.push_str("\\r"),
+            _ => field.push(c),
+        }
+    }
+    field
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size("8kib"), Ok(8192));
+        assert_eq!(parse_size("2M"), Ok(2 << 20));
+        assert_eq!(parse_size("1g"), Ok(1 << 30));
+        assert_eq!(parse_size("512B"), Ok(512));
+        assert!(parse_size("K").is_err());
+        assert!(parse_size("-1").is_err());
+    }
+
+    #[test]
+    fn test_sql_writer_transactions() {
+        let mut writer = SqlWriter::new(Vec::new(), Dialect::Postgres);
+        writer.set_transaction_size(Some(2));
+        let lines: Vec<u64> = ["a;", "b;", "c;"]
+            .into_iter()
+            .map(|sql| {
+                writer.write_statement(sql).unwrap();
+                writer.statement_line()
+            })
+            .collect();
+        writer.finish().unwrap();
+        assert_eq!((lines, writer.lines()), (vec![2, 3, 6], 7));
+        assert_eq!(String::from_utf8(writer.out).unwrap(), "BEGIN;\na;\nb;\nCOMMIT;\nBEGIN;\nc;\nCOMMIT;\n");
+
+        let mut writer = SqlWriter::new(Vec::new(), Dialect::Oracle);
+        writer.set_transaction_size(Some(5));
+        writer.write_statement("a;").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(String::from_utf8(writer.out).unwrap(), "a;\nCOMMIT;\n");
+    }
+
+    #[test]
+    fn test_sql_writer_rotation() {
+        let dir = std::env::temp_dir().join(format!("fake-sql-rotation-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.sql");
+        let mut writer = SqlWriter::new(File::create(numbered_path(&path, 1)).unwrap(), Dialect::Postgres);
+        writer.set_transaction_size(Some(2));
+        let mut next = 1;
+        let series = path.clone();
+        writer.set_max_size(10, move || {
+            next += 1;
+            File::create(numbered_path(&series, next))
+        });
+        for sql in ["a;", "b;", "c;", "d;", "e;"] {
+            writer.write_statement(sql).unwrap();
+        }
+        writer.finish().unwrap();
+        let files: Vec<String> = (1..=3).map(|n| fs::read_to_string(numbered_path(&path, n)).unwrap()).collect();
+        assert_eq!(files, ["BEGIN;\na;\nb;\nCOMMIT;\n", "BEGIN;\nc;\nd;\nCOMMIT;\n", "BEGIN;\ne;\nCOMMIT;\n"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_csv_writer() {
+        let dir = std::env::temp_dir().join(format!("fake-sql-csv-{}", std::process::id()));
+        let table = Table::init_via_sql("create table t (id number(10) primary key, name varchar(20), created date)");
+        let mut writer = CsvWriter::create(&dir).unwrap();
+        let date = NaiveDate::from_ymd_opt(2022, 5, 6).unwrap();
+        writer
+            .write_row(&table, &[SqlValue::Integer(1), SqlValue::Text("Smith, Bob".to_string()), SqlValue::Date(date)])
+            .unwrap();
+        writer.write_row(&table, &[SqlValue::Integer(2), SqlValue::Null, SqlValue::Date(date)]).unwrap();
+        writer.flush().unwrap();
+
+        let content = fs::read_to_string(dir.join("t.csv")).unwrap();
+        assert_eq!(content, "id,name,created\n1,\"Smith, Bob\",2022-05-06\n2,,2022-05-06\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_bulk_writer() {
+        let dir = std::env::temp_dir().join(format!("fake-sql-bulk-{}", std::process::id()));
+        let parent = Table::init_via_sql("create table users (id int primary key, active boolean, avatar bytea)");
+        let child = Table::init_via_sql("create table visits (id serial primary key, user_id int references users(id), place geometry, note text)");
+        let write = |dialect: Dialect| {
+            let mut writer = BulkWriter::create(Vec::new(), dialect, &dir).unwrap();
+            let place = SqlValue::Geometry("POINT(13.4 52.5)".to_string(), false);
+            writer.write_row(&child, &[SqlValue::Integer(1), SqlValue::Integer(7), place, SqlValue::Text("a\\b\nc".to_string())]).unwrap();
+            writer.write_row(&parent, &[SqlValue::Integer(7), SqlValue::Boolean(true), SqlValue::Bytes(vec![10, 255])]).unwrap();
+            writer.finish().unwrap();
+            String::from_utf8(writer.into_inner()).unwrap()
+        };
+
+        // Parents are loaded first, whatever the order of the rows
+        assert_eq!(
+            write(Dialect::Postgres),
+            "COPY users (id, active, avatar) FROM STDIN;\n7\ttrue\t\\\\x0aff\n\\.\n\
+             COPY visits (user_id, place, note) FROM STDIN;\n7\tSRID=4326;POINT(13.4 52.5)\ta\\\\b\\nc\n\\.\n"
+        );
+        assert!(!dir.exists());
+
+        let sql = write(Dialect::MySql);
+        let dir = fs::canonicalize(&dir).unwrap();
+        let lines: Vec<&str> = sql.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "LOAD DATA LOCAL INFILE '{}' INTO TABLE users CHARACTER SET utf8mb4 FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\' LINES TERMINATED BY '\\n' (id, active, @v2) SET avatar = UNHEX(@v2);",
+                dir.join("users.tsv").display()
+            )
+        );
+        assert!(lines[1].ends_with("(user_id, @v2, note) SET place = ST_GeomFromText(@v2, 4326, 'axis-order=long-lat');"), "{}", lines[1]);
+        assert_eq!(fs::read_to_string(dir.join("users.tsv")).unwrap(), "7\t1\t0aff\n");
+        assert_eq!(fs::read_to_string(dir.join("visits.tsv")).unwrap(), "7\tPOINT(13.4 52.5)\ta\\\\b\\nc\n");
+        fs::remove_dir_all(dir).unwrap();
+        assert_eq!("copy".parse(), Ok(Format::Bulk));
+    }
+
+    #[test]
+    fn test_trace_writer() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let statements: Vec<Statement> = (0..500)
+            .map(|i| Statement::new(if i % 2 == 0 { SqlType::Insert } else { SqlType::Aggregate }, "t", format!("SELECT {};", i)))
+            .collect();
+        let trace = |json: bool| {
+            let mut writer = TraceWriter::new(Vec::new(), json, start, Some(3));
+            writer.set_sessions(4);
+            writer.set_rate(Rate::new(1000.0));
+            writer.set_dialect(Dialect::Postgres);
+            statements.iter().for_each(|statement| writer.write_statement(statement).unwrap());
+            writer.finish().unwrap();
+            String::from_utf8(writer.into_inner()).unwrap()
+        };
+        let text = trace(false);
+        assert_eq!(text, trace(false));
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("2024-01-01T00:00:00.00"));
+        assert!(lines.iter().all(|line| matches!(line.split(' ').nth(1), Some("session=1" | "session=2" | "session=3" | "session=4"))));
+        // 4 sessions busy with 13ms statements on average fall short of 1000 per second
+        assert!(lines.last().unwrap().starts_with("2024-01-01T00:00:0"));
+        let timestamps: Vec<&str> = lines.iter().map(|line| line.split(' ').next().unwrap()).collect();
+        assert!(timestamps.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // Every session closes each transaction it opens before opening the next one
+        let mut open = HashMap::new();
+        let mut statement_count = 0;
+        for line in &lines {
+            let mut fields = line.splitn(4, ' ');
+            let session = fields.nth(1).unwrap();
+            match fields.nth(1).unwrap() {
+                "BEGIN;" => assert_eq!(open.insert(session, true), None, "{}", line),
+                "COMMIT;" | "ROLLBACK;" => assert_eq!(open.remove(session), Some(true), "{}", line),
+                _ => statement_count += 1,
+            }
+        }
+        assert!(open.is_empty());
+        assert_eq!(statement_count, 500);
+        assert!(text.contains(" COMMIT;\n") && text.contains(" ROLLBACK;\n"));
+
+        let json = trace(true);
+        let durations: Vec<(String, f64)> = json
+            .lines()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line).unwrap();
+                (value["type"].as_str().unwrap().to_string(), value["duration_ms"].as_f64().unwrap())
+            })
+            .collect();
+        let mean = |sql_type: &str| {
+            let durations: Vec<f64> = durations.iter().filter(|(t, _)| t == sql_type).map(|(_, d)| *d).collect();
+            durations.iter().sum::<f64>() / durations.len() as f64
+        };
+        assert!(mean("aggregate") > 5.0 * mean("insert"));
+    }
+
+    #[test]
+    fn test_jsonl_writer() {
+        let table = Table::init_via_sql("create table t (id number(10) primary key, price number(10,2), name varchar(20))");
+        let mut writer = JsonlWriter::new(Vec::new());
+        writer
+            .write_row(&table, &[SqlValue::Integer(1), SqlValue::Decimal(950, 2), SqlValue::Null])
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(writer.out).unwrap(),
+            "{\"t\":{\"id\":1,\"price\":9.5,\"name\":null}}\n"
+        );
+    }
+}
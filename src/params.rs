@@ -0,0 +1,152 @@
+//! Bind parameter placeholders for prepared statement output.
+
+use crate::dialect::Dialect;
+use crate::value::SqlValue;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Enum representing how the values of a prepared statement are bound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParamStyle {
+    /// Numbered or anonymous placeholders (`$1`, `?`, `:1`, `@p1`) bound from a JSON array.
+    Positional,
+    /// Placeholders named after the columns (`:name`, `@name`) bound from a JSON object.
+    Named,
+}
+
+impl ParamStyle {
+    /// Every supported parameter style.
+    pub const ALL: [ParamStyle; 2] = [ParamStyle::Positional, ParamStyle::Named];
+
+    /// Returns the lowercase name of the style, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParamStyle::Positional => "positional",
+            ParamStyle::Named => "named",
+        }
+    }
+
+    /// Returns the placeholder binding one value in the dialect's syntax.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The dialect the statement is rendered for.
+    /// * `position` - The position of the parameter in the statement, starting at 1.
+    /// * `name` - The name of the parameter, as returned by [`param_name`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::params::ParamStyle;
+    /// # use fake_sql::Dialect;
+    /// assert_eq!(ParamStyle::Positional.placeholder(Dialect::Postgres, 2, "id"), "$2");
+    /// assert_eq!(ParamStyle::Positional.placeholder(Dialect::MySql, 2, "id"), "?");
+    /// assert_eq!(ParamStyle::Named.placeholder(Dialect::Oracle, 2, "id"), ":id");
+    /// assert_eq!(ParamStyle::Named.placeholder(Dialect::SqlServer, 2, "id"), "@id");
+    /// ```
+    pub fn placeholder(&self, dialect: Dialect, position: usize, name: &str) -> String {
+        match (self, dialect) {
+            (ParamStyle::Positional, Dialect::Postgres) => format!("${}", position),
+            (ParamStyle::Positional, Dialect::Oracle) => format!(":{}", position),
+            (ParamStyle::Positional, Dialect::SqlServer) => format!("@p{}", position),
+            (ParamStyle::Positional, Dialect::MySql | Dialect::Sqlite) => "?".to_string(),
+            (ParamStyle::Named, Dialect::SqlServer) => format!("@{}", name),
+            (ParamStyle::Named, _) => format!(":{}", name),
+        }
+    }
+
+    /// Collects the bound values into the parameter document of a statement: a JSON
+    /// array in placeholder order, or a JSON object keyed by parameter name.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameter names and values, in placeholder order.
+    pub fn document(&self, params: Vec<(String, &SqlValue)>) -> serde_json::Value {
+        match self {
+            ParamStyle::Positional => params.into_iter().map(|(_, value)| value.to_json()).collect(),
+            ParamStyle::Named => params.into_iter().map(|(name, value)| (name, value.to_json())).collect(),
+        }
+    }
+}
+
+impl fmt::Display for ParamStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ParamStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "positional" | "numbered" => Ok(ParamStyle::Positional),
+            "named" => Ok(ParamStyle::Named),
+            _ => Err(format!("unknown parameter style `{}`", s)),
+        }
+    }
+}
+
+/// Returns the name of the parameter binding a column: the column name reduced to
+/// letters, digits and underscores, suffixed with the row number in multi-row statements.
+///
+/// # Arguments
+///
+/// * `column` - The name of the column.
+/// * `row` - The number of the row in a multi-row statement starting at 1, or `None`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::params::param_name;
+/// assert_eq!(param_name("order_id", None), "order_id");
+/// assert_eq!(param_name("Order Date", Some(2)), "order_date_2");
+/// ```
+pub fn param_name(column: &str, row: Option<usize>) -> String {
+    let name: String = column
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    match row {
+        Some(row) => format!("{}_{}", name, row),
+        None => name,
+    }
+}
+
+/// Returns the file the parameters of the statements written to `path` go to: `output.sql`
+/// becomes `output.params.jsonl`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::params::params_path;
+/// # use std::path::Path;
+/// assert_eq!(params_path(Path::new("out/data.sql")), Path::new("out/data.params.jsonl"));
+/// ```
+pub fn params_path(path: &Path) -> PathBuf {
+    path.with_extension("params.jsonl")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_style_from_str() {
+        for style in ParamStyle::ALL {
+            assert_eq!(style.name().parse::<ParamStyle>(), Ok(style));
+        }
+        assert_eq!("Numbered".parse::<ParamStyle>(), Ok(ParamStyle::Positional));
+        assert!("anonymous".parse::<ParamStyle>().is_err());
+    }
+
+    #[test]
+    fn test_document() {
+        let id = SqlValue::Integer(7);
+        let name = SqlValue::Text("Ann".to_string());
+        let params = || vec![("id".to_string(), &id), ("name".to_string(), &name)];
+        assert_eq!(ParamStyle::Positional.document(params()), serde_json::json!([7, "Ann"]));
+        assert_eq!(ParamStyle::Named.document(params()), serde_json::json!({"id": 7, "name": "Ann"}));
+    }
+}
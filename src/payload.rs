@@ -0,0 +1,408 @@
+//! Structured documents of JSON columns, described by a JSON schema or a template.
+
+use crate::locale::Locale;
+use crate::models::{random_uuid, DateRange};
+use crate::providers::{Provider, LOREM_WORDS};
+use crate::template::Template;
+use crate::value;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde_json::{Map, Value};
+
+/// The documents generated for a `json` or `jsonb` column, read from either
+///
+/// - a JSON schema, of which `type` (or a list of types, one picked per value), `properties`
+///   and `required`, `additionalProperties` with `minProperties` and `maxProperties` for
+///   objects with random keys, `items` with `minItems` and `maxItems`, `minimum` and
+///   `maximum`, `maxLength`, `enum` and `const` are used. The `format` of a string is
+///   `date`, `date-time`, `time`, `uuid` or a provider such as `email` or `city`. Properties
+///   that are not `required` are left out half of the time; or
+/// - a template: a JSON document whose strings are [`Template`]s such as `{first_name}`,
+///   while other values are copied as they are.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::payload::Payload;
+/// # use fake_sql::locale::Locale;
+/// let schema = serde_json::json!({
+///     "type": "object",
+///     "properties": {
+///         "email": {"type": "string", "format": "email"},
+///         "tags": {"type": "array", "items": {"enum": ["new", "vip"]}, "maxItems": 2}
+///     },
+///     "required": ["email", "tags"]
+/// });
+/// let payload = Payload::from_schema(&schema).unwrap();
+/// let document = payload.generate(Default::default(), Locale::En, false, &mut rand::thread_rng());
+/// assert!(document["email"].as_str().unwrap().contains('@'));
+/// assert!(document["tags"].as_array().unwrap().len() <= 2);
+///
+/// let template = serde_json::json!({"user": {"name": "{first_name}", "code": "A-{digits:3}"}, "version": 2});
+/// let payload = Payload::from_template(&template).unwrap();
+/// let document = payload.generate(Default::default(), Locale::En, false, &mut rand::thread_rng());
+/// assert_eq!(document["user"]["code"].as_str().unwrap().len(), 5);
+/// assert_eq!(document["version"], 2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Payload {
+    root: Node,
+    source: PayloadSource,
+}
+
+/// The document a [`Payload`] was read from, kept so the payload can be written back out.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PayloadSource {
+    /// A JSON schema.
+    Schema(Value),
+    /// A template document.
+    Template(Value),
+}
+
+/// A part of a document.
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    /// An object with fixed keys, and whether each of them is required.
+    Object(Vec<(String, Node, bool)>),
+    /// An object with between `min` and `max` random keys.
+    Map { values: Box<Node>, min: usize, max: usize },
+    /// An array of between `min` and `max` items.
+    Array { items: Box<Node>, min: usize, max: usize },
+    /// An array with one item per node, as written in a template.
+    Tuple(Vec<Node>),
+    /// A string rendered from a template, cut to the length.
+    Text(Template, Option<usize>),
+    Uuid,
+    Integer(i64, i64),
+    /// A number with two decimal places.
+    Number(f64, f64),
+    Boolean,
+    /// One of the nodes, e.g. of a schema with several types.
+    Choice(Vec<Node>),
+    /// One of fixed values, e.g. of an `enum`.
+    Fixed(Vec<Value>),
+}
+
+/// A step of the path to a value within a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// The member of an object.
+    Key(String),
+    /// The item of an array, starting at 0.
+    Index(usize),
+}
+
+impl Payload {
+    /// Reads the payload from a JSON schema.
+    ///
+    /// # Returns
+    ///
+    /// The payload, or a message naming the part of the schema that is not supported.
+    pub fn from_schema(schema: &Value) -> Result<Payload, String> {
+        Ok(Payload { root: schema_node(schema)?, source: PayloadSource::Schema(schema.clone()) })
+    }
+
+    /// Reads the payload from a template document.
+    ///
+    /// # Returns
+    ///
+    /// The payload, or a message describing the first invalid string template.
+    pub fn from_template(template: &Value) -> Result<Payload, String> {
+        Ok(Payload { root: template_node(template)?, source: PayloadSource::Template(template.clone()) })
+    }
+
+    /// Returns the JSON schema or template the payload was read from.
+    pub fn source(&self) -> &PayloadSource {
+        &self.source
+    }
+
+    /// Generates a document.
+    ///
+    /// # Arguments
+    ///
+    /// * `date_range` - The range dates and timestamps are drawn from.
+    /// * `locale` - The locale of provider text.
+    /// * `pii_free` - Whether provider text only uses values that cannot belong to a real person.
+    /// * `rng` - The random number generator the values are drawn from.
+    pub fn generate<R: Rng + ?Sized>(&self, date_range: DateRange, locale: Locale, pii_free: bool, rng: &mut R) -> Value {
+        self.root.generate(&Context { date_range, locale, pii_free }, rng)
+    }
+}
+
+/// The settings text placeholders are rendered with.
+struct Context {
+    date_range: DateRange,
+    locale: Locale,
+    pii_free: bool,
+}
+
+impl Node {
+    /// Generates a value of the node.
+    fn generate<R: Rng + ?Sized>(&self, context: &Context, rng: &mut R) -> Value {
+        match self {
+            Node::Object(properties) => {
+                let mut object = Map::new();
+                for (name, node, required) in properties {
+                    if *required || rng.gen_bool(0.5) {
+                        object.insert(name.clone(), node.generate(context, rng));
+                    }
+                }
+                Value::Object(object)
+            }
+            Node::Map { values, min, max } => {
+                let mut object = Map::new();
+                let count = rng.gen_range(*min..=*max);
+                // Stop drawing keys once the words run out
+                while object.len() < count.min(LOREM_WORDS.len()) {
+                    let key = LOREM_WORDS.choose(rng).unwrap().to_string();
+                    if !object.contains_key(&key) {
+                        object.insert(key, values.generate(context, rng));
+                    }
+                }
+                Value::Object(object)
+            }
+            Node::Array { items, min, max } => (0..rng.gen_range(*min..=*max)).map(|_| items.generate(context, rng)).collect(),
+            Node::Tuple(items) => items.iter().map(|item| item.generate(context, rng)).collect(),
+            Node::Text(template, max_length) => {
+                // Outside the generator's sequences, `{seq}` renders a random number
+                let text = template.render(context.date_range, context.locale, context.pii_free, rng.gen_range(1..1_000_000), rng);
+                Value::String(match max_length {
+                    Some(length) => text.chars().take(*length).collect(),
+                    None => text,
+                })
+            }
+            Node::Uuid => Value::String(value::format_uuid(random_uuid(rng))),
+            Node::Integer(min, max) => rng.gen_range(*min..=*max).into(),
+            Node::Number(min, max) => ((rng.gen_range(*min..=*max) * 100.0).round() / 100.0).into(),
+            Node::Boolean => rng.gen::<bool>().into(),
+            Node::Choice(nodes) => nodes.choose(rng).unwrap().generate(context, rng),
+            Node::Fixed(values) => values.choose(rng).cloned().unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// Returns the paths of the strings and numbers within `document`, with their values.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::payload::{scalar_paths, PathSegment};
+/// let document = serde_json::json!({"user": {"name": "Ann", "active": true}, "tags": ["a"]});
+/// let paths = scalar_paths(&document);
+/// assert_eq!(paths.len(), 2);
+/// assert_eq!(paths[1].0, [PathSegment::Key("tags".to_string()), PathSegment::Index(0)]);
+/// ```
+pub fn scalar_paths(document: &Value) -> Vec<(Vec<PathSegment>, &Value)> {
+    let mut paths = vec![];
+    add_scalar_paths(document, &mut vec![], &mut paths);
+    paths
+}
+
+/// Adds the paths of the strings and numbers within `value`, which is at `path`, to `paths`.
+fn add_scalar_paths<'a>(value: &'a Value, path: &mut Vec<PathSegment>, paths: &mut Vec<(Vec<PathSegment>, &'a Value)>) {
+    match value {
+        Value::String(_) | Value::Number(_) => paths.push((path.clone(), value)),
+        Value::Object(object) => {
+            for (key, value) in object {
+                path.push(PathSegment::Key(key.clone()));
+                add_scalar_paths(value, path, paths);
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                add_scalar_paths(item, path, paths);
+                path.pop();
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+}
+
+/// Converts a JSON schema into a node.
+fn schema_node(schema: &Value) -> Result<Node, String> {
+    let Value::Object(schema) = schema else {
+        return Err(format!("expected a JSON schema object, found `{}`", schema));
+    };
+    if let Some(value) = schema.get("const") {
+        return Ok(Node::Fixed(vec![value.clone()]));
+    }
+    if let Some(values) = schema.get("enum") {
+        return match values {
+            Value::Array(values) if !values.is_empty() => Ok(Node::Fixed(values.clone())),
+            _ => Err(format!("the enum of a JSON schema must be a list of values, found `{}`", values)),
+        };
+    }
+    let schema_type = match schema.get("type") {
+        Some(Value::String(schema_type)) => schema_type.as_str(),
+        Some(Value::Array(types)) => {
+            let nodes = types
+                .iter()
+                .map(|schema_type| {
+                    let mut schema = schema.clone();
+                    schema.insert("type".to_string(), schema_type.clone());
+                    schema_node(&Value::Object(schema))
+                })
+                .collect::<Result<Vec<Node>, String>>()?;
+            return match nodes.is_empty() {
+                true => Err("the type list of a JSON schema is empty".to_string()),
+                false => Ok(Node::Choice(nodes)),
+            };
+        }
+        Some(other) => return Err(format!("the type of a JSON schema must be a name, found `{}`", other)),
+        None if schema.contains_key("properties") || schema.contains_key("additionalProperties") => "object",
+        None if schema.contains_key("items") => "array",
+        None => return Err(format!("JSON schema `{}` has no type", Value::Object(schema.clone()))),
+    };
+    let count = |key: &str| -> Result<Option<usize>, String> {
+        schema
+            .get(key)
+            .map(|value| value.as_u64().map(|n| n as usize).ok_or_else(|| format!("`{}` of a JSON schema must be a count, found `{}`", key, value)))
+            .transpose()
+    };
+    let bound = |key: &str| -> Result<Option<f64>, String> {
+        schema
+            .get(key)
+            .map(|value| value.as_f64().ok_or_else(|| format!("`{}` of a JSON schema must be a number, found `{}`", key, value)))
+            .transpose()
+    };
+    let range = |min: Option<usize>, max: Option<usize>, default: usize| -> Result<(usize, usize), String> {
+        let min = min.unwrap_or(0);
+        let max = max.unwrap_or_else(|| min.max(default));
+        match min <= max {
+            true => Ok((min, max)),
+            false => Err(format!("a JSON schema allows at least {} but at most {} entries", min, max)),
+        }
+    };
+    let node = match schema_type {
+        "object" => {
+            let required: Vec<&str> = schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect();
+            match (schema.get("properties"), schema.get("additionalProperties")) {
+                (Some(Value::Object(properties)), _) => Node::Object(
+                    properties
+                        .iter()
+                        .map(|(name, property)| Ok((name.clone(), schema_node(property)?, required.contains(&name.as_str()))))
+                        .collect::<Result<_, String>>()?,
+                ),
+                (None, Some(values @ Value::Object(_))) => {
+                    let (min, max) = range(count("minProperties")?, count("maxProperties")?, 3)?;
+                    Node::Map { values: Box::new(schema_node(values)?), min: min.max(1).min(max), max }
+                }
+                (Some(other), _) => return Err(format!("the properties of a JSON schema must be an object, found `{}`", other)),
+                (None, _) => Node::Object(vec![]),
+            }
+        }
+        "array" => {
+            let items = match schema.get("items") {
+                Some(items) => schema_node(items)?,
+                None => Node::Text("{lorem}".parse()?, None),
+            };
+            let (min, max) = range(count("minItems")?, count("maxItems")?, 3)?;
+            Node::Array { items: Box::new(items), min, max }
+        }
+        "string" => {
+            let max_length = count("maxLength")?;
+            let template = match schema.get("format").and_then(Value::as_str) {
+                Some("uuid") => return Ok(Node::Uuid),
+                Some("date") => "{YYYY}-{MM}-{DD}".to_string(),
+                Some("date-time") => "{YYYY}-{MM}-{DD}T{hh}:{mm}:{ss}Z".to_string(),
+                Some("time") => "{hh}:{mm}:{ss}".to_string(),
+                Some(format) => {
+                    let provider: Provider = format.parse().map_err(|_| format!("unknown string format `{}` in a JSON schema", format))?;
+                    format!("{{{}}}", provider.name())
+                }
+                None => "{lorem}".to_string(),
+            };
+            Node::Text(template.parse()?, max_length)
+        }
+        "integer" => {
+            let min = bound("minimum")?.map_or(0, |min| min.ceil() as i64);
+            let max = bound("maximum")?.map_or(min.saturating_add(1000), |max| max.floor() as i64);
+            if min > max {
+                return Err(format!("a JSON schema allows integers from {} to {}", min, max));
+            }
+            Node::Integer(min, max)
+        }
+        "number" => {
+            let min = bound("minimum")?.unwrap_or(0.0);
+            let max = bound("maximum")?.unwrap_or(min + 1000.0);
+            if min > max {
+                return Err(format!("a JSON schema allows numbers from {} to {}", min, max));
+            }
+            Node::Number(min, max)
+        }
+        "boolean" => Node::Boolean,
+        "null" => Node::Fixed(vec![Value::Null]),
+        other => return Err(format!("unsupported JSON schema type `{}`", other)),
+    };
+    Ok(node)
+}
+
+/// Converts a template document into a node.
+fn template_node(template: &Value) -> Result<Node, String> {
+    let node = match template {
+        Value::Object(object) => Node::Object(
+            object
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), template_node(value)?, true)))
+                .collect::<Result<_, String>>()?,
+        ),
+        Value::Array(items) => Node::Tuple(items.iter().map(template_node).collect::<Result<_, _>>()?),
+        Value::String(text) => Node::Text(text.parse()?, None),
+        value => Node::Fixed(vec![value.clone()]),
+    };
+    Ok(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer", "minimum": 5, "maximum": 9},
+                "score": {"type": ["number", "null"], "maximum": 1},
+                "created": {"type": "string", "format": "date-time"},
+                "name": {"type": "string", "format": "first_name", "maxLength": 3},
+                "labels": {"additionalProperties": {"type": "boolean"}, "maxProperties": 2},
+                "note": {"type": "string"}
+            },
+            "required": ["id", "score", "created", "name", "labels"]
+        });
+        let payload = Payload::from_schema(&schema).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut notes = 0;
+        for _ in 0..50 {
+            let document = payload.generate("2024-01-01..2024-01-31".parse().unwrap(), Locale::En, false, &mut rng);
+            assert!((5..=9).contains(&document["id"].as_i64().unwrap()));
+            assert!(document["score"].is_null() || document["score"].as_f64().unwrap() <= 1.0);
+            assert!(document["created"].as_str().unwrap().starts_with("2024-01-"));
+            assert!(document["name"].as_str().unwrap().chars().count() <= 3);
+            let labels = document["labels"].as_object().unwrap();
+            assert!((1..=2).contains(&labels.len()) && labels.values().all(Value::is_boolean));
+            notes += document.get("note").is_some() as usize;
+        }
+        assert!(notes > 0 && notes < 50);
+
+        for invalid in [json!({"type": "date"}), json!({"minimum": 1}), json!({"type": "string", "format": "isbn"}), json!({"type": "array", "minItems": 3, "maxItems": 1})] {
+            assert!(Payload::from_schema(&invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_template() {
+        let payload = Payload::from_template(&json!({"id": "{seq}", "tags": ["fixed", "{letters:2}"], "ok": true})).unwrap();
+        let document = payload.generate(DateRange::default(), Locale::En, false, &mut StdRng::seed_from_u64(2));
+        assert_eq!(document["tags"][0], "fixed");
+        assert_eq!(document["tags"][1].as_str().unwrap().len(), 2);
+        assert_eq!(document["ok"], true);
+        assert!(Payload::from_template(&json!({"name": "{nickname}"})).is_err());
+    }
+}
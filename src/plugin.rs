@@ -0,0 +1,214 @@
+//! Custom value generators, so crates embedding the generator can fill columns with domain
+//! values the built-in providers do not know, such as IBANs, ICD codes or SKUs.
+//!
+//! A [`ValueGenerator`] is registered by name in a [`GeneratorRegistry`], and columns name
+//! it in the config file like a built-in provider, e.g. `generator = "sku"`:
+//!
+//! ```
+//! use fake_sql::plugin::{GeneratorRegistry, RowContext, ValueGenerator};
+//! use fake_sql::{Config, Generator, SqlValue, Table};
+//! use rand::Rng;
+//!
+//! #[derive(Clone)]
+//! struct Sku;
+//!
+//! impl ValueGenerator for Sku {
+//!     fn generate(&mut self, ctx: &RowContext) -> SqlValue {
+//!         SqlValue::Text(format!("SKU-{:05}", ctx.rng().gen_range(0..100_000)))
+//!     }
+//! }
+//!
+//! let config = Config::from_toml("[columns.\"products.sku\"]\ngenerator = \"sku\"").unwrap();
+//! let mut tables = vec![Table::init_via_sql("create table products (id int primary key, sku varchar(9))")];
+//! config.apply_columns(&mut tables).unwrap();
+//! let mut registry = GeneratorRegistry::new();
+//! registry.register("sku", Sku).unwrap();
+//! let mut generator = Generator::with_seed(tables, 1);
+//! generator.set_registry(&registry).unwrap();
+//! assert!(generator.insert_into("products").unwrap().contains(", 'SKU-"));
+//! ```
+//!
+//! Custom generators fill the values of inserted rows. UPDATE statements and WHERE clauses
+//! fall back to the values of the column's type.
+
+use crate::models::{Column, Table};
+use crate::providers::Provider;
+use crate::value::SqlValue;
+use rand::RngCore;
+use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
+
+/// Generates the values of a column, as registered in a [`GeneratorRegistry`].
+///
+/// Every column using a generator gets a copy of its own, so state such as a counter is
+/// kept per column. [`Generator::split`](crate::Generator::split) copies the generators
+/// into every shard as they are, so the shards repeat the same sequence of such values.
+/// NULL values are drawn by the column's `null_rate` before the generator is asked. Key
+/// and foreign key columns cannot use a custom generator, as their values have to be
+/// unique or reference a parent row.
+pub trait ValueGenerator: ValueGeneratorClone + Send {
+    /// Generates the next value of the column of `ctx`.
+    fn generate(&mut self, ctx: &RowContext) -> SqlValue;
+}
+
+/// Copies boxed generators; implemented for every [`ValueGenerator`] that is `Clone`.
+pub trait ValueGeneratorClone {
+    /// Returns a boxed copy of the generator.
+    fn clone_box(&self) -> Box<dyn ValueGenerator>;
+}
+
+impl<T: ValueGenerator + Clone + 'static> ValueGeneratorClone for T {
+    fn clone_box(&self) -> Box<dyn ValueGenerator> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn ValueGenerator> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// The row a [`ValueGenerator`] generates a value for.
+pub struct RowContext<'a> {
+    /// The table of the row.
+    pub table: &'a Table,
+    /// The column the value is generated for.
+    pub column: &'a Column,
+    /// The values of the columns before `column`, in column order.
+    pub row: &'a [SqlValue],
+    rng: RefCell<&'a mut dyn RngCore>,
+}
+
+impl<'a> RowContext<'a> {
+    /// Initializes a new `RowContext`.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table of the row.
+    /// * `column` - The column the value is generated for.
+    /// * `row` - The values of the columns before `column`.
+    /// * `rng` - The random number generator values are drawn from.
+    pub fn new(table: &'a Table, column: &'a Column, row: &'a [SqlValue], rng: &'a mut dyn RngCore) -> RowContext<'a> {
+        RowContext { table, column, row, rng: RefCell::new(rng) }
+    }
+
+    /// Returns the random number generator of the statement generator, so the values are
+    /// the same for the same seed.
+    pub fn rng(&self) -> RefMut<'_, dyn RngCore + 'a> {
+        RefMut::map(self.rng.borrow_mut(), |rng| &mut **rng)
+    }
+}
+
+/// Custom value generators by name, which columns refer to with `generator = "name"`.
+#[derive(Clone, Default)]
+pub struct GeneratorRegistry {
+    generators: HashMap<String, Box<dyn ValueGenerator>>,
+}
+
+impl GeneratorRegistry {
+    /// Initializes a new, empty `GeneratorRegistry`.
+    pub fn new() -> GeneratorRegistry {
+        GeneratorRegistry::default()
+    }
+
+    /// Registers `generator` under `name`, replacing the generator registered before under
+    /// the same name. Names are case-insensitive.
+    ///
+    /// # Returns
+    ///
+    /// An error if `name` is empty or the name of a built-in provider such as `email`,
+    /// which columns naming it keep using.
+    pub fn register(&mut self, name: &str, generator: impl ValueGenerator + 'static) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("the name of a generator cannot be empty".to_string());
+        }
+        if name.parse::<Provider>().is_ok() {
+            return Err(format!("`{}` is the name of a built-in generator", name));
+        }
+        self.generators.insert(name.trim().to_lowercase(), Box::new(generator));
+        Ok(())
+    }
+
+    /// Returns a copy of the generator registered under `name`, or `None` if there is none.
+    pub fn get(&self, name: &str) -> Option<Box<dyn ValueGenerator>> {
+        self.generators.get(&name.trim().to_lowercase()).cloned()
+    }
+
+    /// Checks that the generator of every column of `tables` naming one is registered, and
+    /// that no such column is part of a primary key, a unique key or a foreign key.
+    ///
+    /// # Returns
+    ///
+    /// An error naming the first column whose generator is not registered or is not allowed.
+    pub fn check(&self, tables: &[Table]) -> Result<(), String> {
+        for table in tables {
+            for column in &table.columns {
+                let Some(name) = column.custom_generator.as_ref() else { continue };
+                if self.get(name).is_none() {
+                    return Err(format!("unknown generator `{}` of column `{}.{}`", name, table.name, column.name));
+                }
+                let is_key = column.is_pkey
+                    || column.is_unique
+                    || table.pkey_columns.contains(&column.name)
+                    || table.unique_keys.iter().any(|key| key.contains(&column.name));
+                if is_key || column.ref_table.is_some() {
+                    return Err(format!(
+                        "column `{}.{}` is a {}, so it cannot use the custom generator `{}`",
+                        table.name,
+                        column.name,
+                        if is_key { "key" } else { "foreign key" },
+                        name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[derive(Clone)]
+    struct Counter(i64);
+
+    impl ValueGenerator for Counter {
+        fn generate(&mut self, ctx: &RowContext) -> SqlValue {
+            self.0 += ctx.rng().gen_range(1..=2);
+            SqlValue::Integer(self.0)
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = GeneratorRegistry::new();
+        registry.register("Counter", Counter(10)).unwrap();
+        assert!(registry.register("email", Counter(0)).is_err());
+        assert!(registry.register(" ", Counter(0)).is_err());
+
+        let mut table = Table::init_via_sql("create table t (id int primary key, n int)");
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut generator = registry.get("counter").unwrap();
+        let value = generator.generate(&RowContext::new(&table, &table.columns[1], &[SqlValue::Integer(1)], &mut rng));
+        assert!(matches!(value, SqlValue::Integer(11 | 12)));
+        // Copies start from the registered state
+        let mut copy = registry.get("counter").unwrap();
+        assert!(matches!(copy.generate(&RowContext::new(&table, &table.columns[1], &[], &mut rng)), SqlValue::Integer(11 | 12)));
+
+        assert!(registry.check(std::slice::from_ref(&table)).is_ok());
+        table.columns[1].custom_generator = Some("sku".to_string());
+        assert_eq!(registry.check(std::slice::from_ref(&table)), Err("unknown generator `sku` of column `t.n`".to_string()));
+        table.columns[0].custom_generator = Some("counter".to_string());
+        table.columns[1].custom_generator = None;
+        assert_eq!(registry.check(std::slice::from_ref(&table)), Err("column `t.id` is a key, so it cannot use the custom generator `counter`".to_string()));
+
+        let parent = Table::init_via_sql("create table p (id int primary key)");
+        let mut child = Table::init_via_sql("create table c (id int primary key, p_id int not null references p(id))");
+        child.columns[1].custom_generator = Some("counter".to_string());
+        assert_eq!(registry.check(&[parent, child]), Err("column `c.p_id` is a foreign key, so it cannot use the custom generator `counter`".to_string()));
+    }
+}
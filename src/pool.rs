@@ -0,0 +1,178 @@
+//! Fixed pools of values for categorical columns.
+
+use crate::distribution::Distribution;
+use crate::value::SqlValue;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A fixed set of values a categorical column is filled with, e.g. the statuses
+/// `new`, `paid`, `shipped` and `cancelled`, each with a relative weight.
+///
+/// INSERT and UPDATE statements only use values of the pool, and WHERE clauses
+/// filter on them, so generated predicates match generated rows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValuePool {
+    values: Vec<(SqlValue, u32)>,
+}
+
+impl ValuePool {
+    /// Initializes a new `ValuePool` from values and their weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values and their relative weights.
+    ///
+    /// # Returns
+    ///
+    /// A `ValuePool`, or an error if no weight is greater than zero or the weights add up
+    /// to more than `u32::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::pool::ValuePool;
+    /// # use fake_sql::SqlValue;
+    /// let pool = ValuePool::new(vec![(SqlValue::Text("new".to_string()), 9), (SqlValue::Text("cancelled".to_string()), 1)]).unwrap();
+    /// assert_eq!(pool.values().count(), 2);
+    /// assert!(ValuePool::new(vec![]).is_err());
+    /// ```
+    pub fn new(values: Vec<(SqlValue, u32)>) -> Result<ValuePool, String> {
+        if values.iter().all(|(_, weight)| *weight == 0) {
+            return Err("the value pool needs at least one value with a weight above zero".to_string());
+        }
+        if values.iter().try_fold(0u32, |total, (_, weight)| total.checked_add(*weight)).is_none() {
+            return Err(format!("the weights of the value pool add up to more than {}", u32::MAX));
+        }
+        Ok(ValuePool { values })
+    }
+
+    /// Initializes a new `ValuePool` picking every value with the same probability.
+    ///
+    /// # Returns
+    ///
+    /// A `ValuePool`, or an error if `values` is empty.
+    pub fn uniform(values: Vec<SqlValue>) -> Result<ValuePool, String> {
+        ValuePool::new(values.into_iter().map(|value| (value, 1)).collect())
+    }
+
+    /// Reads a dictionary file with one text value per line, e.g. a list of city names.
+    ///
+    /// Surrounding whitespace is trimmed, and empty lines and lines starting with `#`
+    /// are skipped. Every value is equally likely.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the newline-delimited file.
+    ///
+    /// # Returns
+    ///
+    /// The pool, or an error if the file cannot be read or holds no value.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<ValuePool> {
+        let content = fs::read_to_string(path)?;
+        let values: Vec<SqlValue> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| SqlValue::Text(line.to_string()))
+            .collect();
+        ValuePool::uniform(values).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "the file holds no value"))
+    }
+
+    /// Returns the values of the pool, in the order they were defined.
+    pub fn values(&self) -> impl Iterator<Item = &SqlValue> {
+        self.values.iter().map(|(value, _)| value)
+    }
+
+    /// Returns the relative weights of the values, in the order of [`ValuePool::values`].
+    pub fn weights(&self) -> impl Iterator<Item = u32> + '_ {
+        self.values.iter().map(|(_, weight)| *weight)
+    }
+
+    /// Picks a random value according to the weights.
+    ///
+    /// With a `distribution`, the weights are ignored and the values are picked by their
+    /// position instead, so a zipfian pool favors the first values.
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution` - How values are spread over the pool, if not by weight.
+    /// * `rng` - The random number generator used for the pick.
+    pub fn choose<R: Rng + ?Sized>(&self, distribution: Option<Distribution>, rng: &mut R) -> &SqlValue {
+        match distribution {
+            Some(distribution) => &self.values[distribution.sample_index(self.values.len(), rng)].0,
+            None => &self.values.choose_weighted(rng, |(_, weight)| *weight).unwrap().0,
+        }
+    }
+
+    /// Picks up to `count` different values according to the weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of values wanted.
+    /// * `rng` - The random number generator used for the pick.
+    pub fn choose_many<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Vec<&SqlValue> {
+        // Values with a zero weight would still fill up the picks, so leave them out
+        let weighted: Vec<&(SqlValue, u32)> = self.values.iter().filter(|(_, weight)| *weight > 0).collect();
+        weighted
+            .choose_multiple_weighted(rng, count, |(_, weight)| *weight as f64)
+            .unwrap()
+            .map(|(value, _)| value)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn statuses() -> ValuePool {
+        let values = [("new", 90), ("paid", 10), ("never", 0)];
+        ValuePool::new(values.iter().map(|(value, weight)| (SqlValue::Text(value.to_string()), *weight)).collect())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_choose_by_weight() {
+        let pool = statuses();
+        let mut rng = StdRng::seed_from_u64(4);
+        let picks: Vec<&SqlValue> = (0..1000).map(|_| pool.choose(None, &mut rng)).collect();
+        let new = picks.iter().filter(|value| value.as_text().as_deref() == Some("new")).count();
+        assert!((800..980).contains(&new), "{}", new);
+        assert!(picks.iter().all(|value| value.as_text().as_deref() != Some("never")));
+        let text = |value: &str| SqlValue::Text(value.to_string());
+        assert!(ValuePool::new(vec![(text("new"), 0), (text("paid"), 0)]).is_err());
+        assert!(ValuePool::new(vec![(text("new"), u32::MAX), (text("paid"), 1)]).is_err());
+    }
+
+    #[test]
+    fn test_choose_many() {
+        let pool = statuses();
+        let picks = pool.choose_many(5, &mut StdRng::seed_from_u64(1));
+        assert_eq!(picks.len(), 2);
+        assert_ne!(picks[0], picks[1]);
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("fake-sql-pool-{}.txt", std::process::id()));
+        fs::write(&path, "# cities\nTaipei\n\n  Kaohsiung  \n").unwrap();
+        let pool = ValuePool::from_file(&path).unwrap();
+        let values: Vec<String> = pool.values().filter_map(|v| v.as_text()).collect();
+        assert_eq!(values, ["Taipei", "Kaohsiung"]);
+
+        fs::write(&path, "# nothing\n").unwrap();
+        assert_eq!(ValuePool::from_file(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_zero_weights() {
+        assert!(ValuePool::new(vec![(SqlValue::Integer(1), 0)]).is_err());
+        assert!(ValuePool::uniform(vec![SqlValue::Integer(1)]).is_ok());
+    }
+}
@@ -0,0 +1,492 @@
+//! Built-in schemas modeled on standard benchmarks and common applications.
+
+use crate::models::Table;
+use crate::schema;
+use std::fmt;
+use std::str::FromStr;
+
+/// A TPC-C-like order-entry schema: warehouses with their districts, customers, orders
+/// and stock.
+const TPCC_SCHEMA: &str = "
+create table warehouse (
+    w_id number(10) primary key,
+    w_name varchar(10) not null,
+    w_street_1 varchar(20),
+    w_city varchar(20),
+    w_state varchar(2),
+    w_zip varchar(9),
+    w_tax number(4, 4) check (w_tax between 0 and 0.2),
+    w_ytd number(12, 2)
+);
+create table district (
+    d_id number(10) primary key,
+    d_w_id number(10) not null references warehouse (w_id),
+    d_name varchar(10) not null,
+    d_street_1 varchar(20),
+    d_city varchar(20),
+    d_state varchar(2),
+    d_zip varchar(9),
+    d_tax number(4, 4) check (d_tax between 0 and 0.2),
+    d_ytd number(12, 2),
+    d_next_o_id number(10)
+);
+create table customer (
+    c_id number(10) primary key,
+    c_d_id number(10) not null references district (d_id),
+    c_first_name varchar(16) not null,
+    c_middle varchar(2),
+    c_last_name varchar(16) not null,
+    c_street_1 varchar(20),
+    c_city varchar(20),
+    c_state varchar(2),
+    c_zip varchar(9),
+    c_phone varchar(16),
+    c_since timestamp,
+    c_credit varchar(2) check (c_credit in ('GC', 'BC')),
+    c_credit_lim number(12, 2),
+    c_discount number(4, 4) check (c_discount between 0 and 0.5),
+    c_balance number(12, 2),
+    c_ytd_payment number(12, 2),
+    c_payment_cnt number(4),
+    c_delivery_cnt number(4),
+    c_data varchar(500)
+);
+create table history (
+    h_id number(10) primary key,
+    h_c_id number(10) not null references customer (c_id),
+    h_d_id number(10) not null references district (d_id),
+    h_date timestamp,
+    h_amount number(6, 2),
+    h_data varchar(24)
+);
+create table item (
+    i_id number(10) primary key,
+    i_im_id number(10),
+    i_name varchar(24) not null,
+    i_price number(5, 2) check (i_price between 1 and 100),
+    i_data varchar(50)
+);
+create table stock (
+    s_id number(10) primary key,
+    s_i_id number(10) not null references item (i_id),
+    s_w_id number(10) not null references warehouse (w_id),
+    s_quantity number(4) check (s_quantity between 10 and 100),
+    s_ytd number(8),
+    s_order_cnt number(4),
+    s_remote_cnt number(4),
+    s_data varchar(50)
+);
+create table orders (
+    o_id number(10) primary key,
+    o_c_id number(10) not null references customer (c_id),
+    o_d_id number(10) not null references district (d_id),
+    o_entry_d timestamp not null,
+    o_carrier_id number(2) check (o_carrier_id between 1 and 10),
+    o_ol_cnt number(2) check (o_ol_cnt between 5 and 15),
+    o_all_local number(1) check (o_all_local in (0, 1))
+);
+create table new_order (
+    no_id number(10) primary key,
+    no_o_id number(10) not null references orders (o_id)
+);
+create table order_line (
+    ol_id number(10) primary key,
+    ol_o_id number(10) not null references orders (o_id),
+    ol_i_id number(10) not null references item (i_id),
+    ol_supply_w_id number(10) not null references warehouse (w_id),
+    ol_number number(2) check (ol_number between 1 and 15),
+    ol_delivery_d timestamp,
+    ol_quantity number(2) check (ol_quantity between 1 and 10),
+    ol_amount number(6, 2),
+    ol_dist_info varchar(24)
+);
+";
+
+/// A TPC-H-like decision support schema: parts and their suppliers, customers, orders
+/// and line items, within nations and regions.
+const TPCH_SCHEMA: &str = "
+create table region (
+    r_regionkey number(10) primary key,
+    r_name varchar(25) not null,
+    r_comment varchar(152)
+);
+create table nation (
+    n_nationkey number(10) primary key,
+    n_name varchar(25) not null,
+    n_regionkey number(10) not null references region (r_regionkey),
+    n_comment varchar(152)
+);
+create table supplier (
+    s_suppkey number(10) primary key,
+    s_name varchar(25) not null,
+    s_address varchar(40) not null,
+    s_nationkey number(10) not null references nation (n_nationkey),
+    s_phone varchar(15) not null,
+    s_acctbal number(15, 2) not null,
+    s_comment varchar(101)
+);
+create table part (
+    p_partkey number(10) primary key,
+    p_name varchar(55) not null,
+    p_mfgr varchar(25) check (p_mfgr in ('Manufacturer#1', 'Manufacturer#2', 'Manufacturer#3', 'Manufacturer#4', 'Manufacturer#5')),
+    p_brand varchar(10),
+    p_type varchar(25),
+    p_size number(10) check (p_size between 1 and 50),
+    p_container varchar(10) check (p_container in ('SM CASE', 'SM BOX', 'MED BAG', 'MED PACK', 'LG CASE', 'LG BOX', 'JUMBO PKG', 'WRAP DRUM')),
+    p_retailprice number(15, 2) not null,
+    p_comment varchar(23)
+);
+create table partsupp (
+    ps_partkey number(10) not null references part (p_partkey),
+    ps_suppkey number(10) not null references supplier (s_suppkey),
+    ps_availqty number(10) check (ps_availqty between 1 and 9999),
+    ps_supplycost number(15, 2) not null,
+    ps_comment varchar(199),
+    primary key (ps_partkey, ps_suppkey)
+);
+create table customer (
+    c_custkey number(10) primary key,
+    c_name varchar(25) not null,
+    c_address varchar(40) not null,
+    c_nationkey number(10) not null references nation (n_nationkey),
+    c_phone varchar(15) not null,
+    c_acctbal number(15, 2) not null,
+    c_mktsegment varchar(10) check (c_mktsegment in ('AUTOMOBILE', 'BUILDING', 'FURNITURE', 'MACHINERY', 'HOUSEHOLD')),
+    c_comment varchar(117)
+);
+create table orders (
+    o_orderkey number(10) primary key,
+    o_custkey number(10) not null references customer (c_custkey),
+    o_orderstatus varchar(1) check (o_orderstatus in ('F', 'O', 'P')),
+    o_totalprice number(15, 2) not null,
+    o_orderdate date not null,
+    o_orderpriority varchar(15) check (o_orderpriority in ('1-URGENT', '2-HIGH', '3-MEDIUM', '4-NOT SPECIFIED', '5-LOW')),
+    o_clerk varchar(15),
+    o_shippriority number(10),
+    o_comment varchar(79)
+);
+create table lineitem (
+    l_orderkey number(10) not null references orders (o_orderkey),
+    l_linenumber number(10) not null,
+    l_partkey number(10) not null references part (p_partkey),
+    l_suppkey number(10) not null references supplier (s_suppkey),
+    l_quantity number(15, 2) check (l_quantity between 1 and 50),
+    l_extendedprice number(15, 2) not null,
+    l_discount number(15, 2) check (l_discount between 0 and 0.1),
+    l_tax number(15, 2) check (l_tax between 0 and 0.08),
+    l_returnflag varchar(1) check (l_returnflag in ('A', 'N', 'R')),
+    l_linestatus varchar(1) check (l_linestatus in ('F', 'O')),
+    l_shipdate date,
+    l_commitdate date,
+    l_receiptdate date,
+    l_shipinstruct varchar(25) check (l_shipinstruct in ('DELIVER IN PERSON', 'COLLECT COD', 'NONE', 'TAKE BACK RETURN')),
+    l_shipmode varchar(10) check (l_shipmode in ('REG AIR', 'AIR', 'RAIL', 'SHIP', 'TRUCK', 'MAIL', 'FOB')),
+    l_comment varchar(44),
+    primary key (l_orderkey, l_linenumber)
+);
+";
+
+/// An online shop: customers with addresses, a product catalog, orders with their items
+/// and payments, and product reviews.
+const ECOMMERCE_SCHEMA: &str = "
+create table customers (
+    customer_id number(10) primary key,
+    first_name varchar(50) not null,
+    last_name varchar(50) not null,
+    email varchar(255) not null unique,
+    phone varchar(30),
+    created_at timestamp not null
+);
+create table addresses (
+    address_id number(10) primary key,
+    customer_id number(10) not null references customers (customer_id),
+    street_address varchar(255) not null,
+    city varchar(100) not null,
+    zip_code varchar(10),
+    country varchar(100) not null
+);
+create table categories (
+    category_id number(10) primary key,
+    category_name varchar(100) not null unique,
+    parent_id number(10) references categories (category_id)
+);
+create table products (
+    product_id number(10) primary key,
+    category_id number(10) not null references categories (category_id),
+    sku varchar(20) not null unique,
+    product_name varchar(255) not null,
+    description varchar(1000),
+    price number(10, 2) not null check (price > 0),
+    stock_quantity number(10) check (stock_quantity >= 0)
+);
+create table orders (
+    order_id number(10) primary key,
+    customer_id number(10) not null references customers (customer_id),
+    shipping_address_id number(10) references addresses (address_id),
+    order_date date not null,
+    status varchar(20) check (status in ('pending', 'paid', 'shipped', 'delivered', 'cancelled', 'returned')),
+    total_amount number(12, 2) not null
+);
+create table order_items (
+    order_item_id number(10) primary key,
+    order_id number(10) not null references orders (order_id),
+    product_id number(10) not null references products (product_id),
+    quantity number(5) check (quantity between 1 and 10),
+    unit_price number(10, 2) not null
+);
+create table payments (
+    payment_id number(10) primary key,
+    order_id number(10) not null references orders (order_id),
+    method varchar(20) check (method in ('card', 'paypal', 'bank_transfer', 'gift_card')),
+    amount number(12, 2) not null,
+    paid_at timestamp
+);
+create table reviews (
+    review_id number(10) primary key,
+    product_id number(10) not null references products (product_id),
+    customer_id number(10) not null references customers (customer_id),
+    rating number(1) check (rating between 1 and 5),
+    review_text varchar(2000),
+    created_at timestamp not null
+);
+";
+
+/// A retail bank: branches, customers with accounts and cards, transactions and transfers
+/// between accounts, and loans with their payments.
+const FINANCE_SCHEMA: &str = "
+create table branches (
+    branch_id number(10) primary key,
+    branch_name varchar(100) not null,
+    street_address varchar(255),
+    city varchar(100),
+    country varchar(100)
+);
+create table customers (
+    customer_id number(10) primary key,
+    branch_id number(10) not null references branches (branch_id),
+    first_name varchar(50) not null,
+    last_name varchar(50) not null,
+    email varchar(255) unique,
+    phone varchar(30),
+    date_of_birth date,
+    risk_rating varchar(10) check (risk_rating in ('low', 'medium', 'high'))
+);
+create table accounts (
+    account_id number(10) primary key,
+    customer_id number(10) not null references customers (customer_id),
+    iban varchar(34) not null unique,
+    account_type varchar(20) check (account_type in ('checking', 'savings', 'brokerage')),
+    currency varchar(3) check (currency in ('USD', 'EUR', 'GBP', 'JPY')),
+    balance number(15, 2) not null,
+    opened_on date not null
+);
+create table cards (
+    card_id number(10) primary key,
+    account_id number(10) not null references accounts (account_id),
+    card_number varchar(16) not null unique,
+    card_type varchar(10) check (card_type in ('debit', 'credit')),
+    expires_on date,
+    credit_limit number(12, 2)
+);
+create table transactions (
+    transaction_id number(12) primary key,
+    account_id number(10) not null references accounts (account_id),
+    card_id number(10) references cards (card_id),
+    amount number(12, 2) not null,
+    transaction_type varchar(20) check (transaction_type in ('purchase', 'withdrawal', 'deposit', 'fee', 'interest')),
+    merchant_name varchar(100),
+    booked_at timestamp not null
+);
+create table transfers (
+    transfer_id number(10) primary key,
+    from_account_id number(10) not null references accounts (account_id),
+    to_account_id number(10) not null references accounts (account_id),
+    amount number(12, 2) not null check (amount > 0),
+    status varchar(10) check (status in ('pending', 'completed', 'failed')),
+    created_at timestamp not null
+);
+create table loans (
+    loan_id number(10) primary key,
+    customer_id number(10) not null references customers (customer_id),
+    principal_amount number(12, 2) not null,
+    interest_rate number(5, 3) check (interest_rate between 0.5 and 15),
+    term_months number(3) check (term_months between 6 and 360),
+    start_date date not null
+);
+create table loan_payments (
+    payment_id number(10) primary key,
+    loan_id number(10) not null references loans (loan_id),
+    amount number(12, 2) not null,
+    due_date date not null,
+    paid_on date
+);
+";
+
+/// Enum representing the built-in schemas users can generate data for by name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// TPC-C-like order entry, scaled by warehouses.
+    Tpcc,
+    /// TPC-H-like decision support, scaled in steps of scale factor 0.001.
+    Tpch,
+    /// An online shop.
+    Ecommerce,
+    /// A retail bank.
+    Finance,
+}
+
+impl Preset {
+    /// Every preset.
+    pub const ALL: [Preset; 4] = [Preset::Tpcc, Preset::Tpch, Preset::Ecommerce, Preset::Finance];
+
+    /// Returns the lowercase name of the preset, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Tpcc => "tpcc",
+            Preset::Tpch => "tpch",
+            Preset::Ecommerce => "ecommerce",
+            Preset::Finance => "finance",
+        }
+    }
+
+    /// Returns the `CREATE TABLE` statements of the preset.
+    pub fn ddl(&self) -> &'static str {
+        match self {
+            Preset::Tpcc => TPCC_SCHEMA,
+            Preset::Tpch => TPCH_SCHEMA,
+            Preset::Ecommerce => ECOMMERCE_SCHEMA,
+            Preset::Finance => FINANCE_SCHEMA,
+        }
+    }
+
+    /// Returns the tables of the preset.
+    pub fn tables(&self) -> Vec<Table> {
+        schema::parse_schema(self.ddl()).expect("the built-in presets are valid")
+    }
+
+    /// Returns the number of rows of every table at scale 1, and whether the table grows
+    /// with the scale. Tables such as TPC-H's regions and nations have a fixed size.
+    ///
+    /// The ratios follow the benchmarks: a TPC-C warehouse has 10 districts with 300
+    /// customers each (a tenth of the specification), about 10 lines per order and a
+    /// third of the orders still new; a TPC-H scale step has 4 suppliers per part and
+    /// about 4 line items per order.
+    fn cardinalities(&self) -> &'static [(&'static str, u64, bool)] {
+        match self {
+            Preset::Tpcc => &[
+                ("warehouse", 1, true),
+                ("district", 10, true),
+                ("customer", 3_000, true),
+                ("history", 3_000, true),
+                ("item", 10_000, false),
+                ("stock", 10_000, true),
+                ("orders", 3_000, true),
+                ("new_order", 900, true),
+                ("order_line", 30_000, true),
+            ],
+            Preset::Tpch => &[
+                ("region", 5, false),
+                ("nation", 25, false),
+                ("supplier", 10, true),
+                ("part", 200, true),
+                ("partsupp", 800, true),
+                ("customer", 150, true),
+                ("orders", 1_500, true),
+                ("lineitem", 6_000, true),
+            ],
+            Preset::Ecommerce => &[
+                ("customers", 1_000, true),
+                ("addresses", 1_500, true),
+                ("categories", 25, false),
+                ("products", 500, true),
+                ("orders", 3_000, true),
+                ("order_items", 9_000, true),
+                ("payments", 3_000, true),
+                ("reviews", 1_200, true),
+            ],
+            Preset::Finance => &[
+                ("branches", 10, true),
+                ("customers", 1_000, true),
+                ("accounts", 1_500, true),
+                ("cards", 1_200, true),
+                ("transactions", 20_000, true),
+                ("transfers", 3_000, true),
+                ("loans", 200, true),
+                ("loan_payments", 2_400, true),
+            ],
+        }
+    }
+
+    /// Returns the number of INSERT rows of every table at `scale`, in the order the
+    /// tables are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The multiple of the base size, e.g. the number of TPC-C warehouses.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::preset::Preset;
+    /// let rows = Preset::Tpcc.rows(2);
+    /// assert_eq!(rows[0], ("warehouse".to_string(), 2));
+    /// assert!(rows.contains(&("item".to_string(), 10_000)));
+    /// ```
+    pub fn rows(&self, scale: u64) -> Vec<(String, u64)> {
+        self.cardinalities()
+            .iter()
+            .map(|&(table, rows, scales)| (table.to_string(), if scales { rows * scale } else { rows }))
+            .collect()
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "tpcc" => Ok(Preset::Tpcc),
+            "tpch" => Ok(Preset::Tpch),
+            "ecommerce" | "shop" => Ok(Preset::Ecommerce),
+            "finance" | "bank" => Ok(Preset::Finance),
+            _ => Err(format!("unknown preset `{}`; expected tpcc, tpch, ecommerce or finance", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for preset in Preset::ALL {
+            assert_eq!(preset.name().parse(), Ok(preset));
+            let tables = preset.tables();
+            let names: Vec<&str> = tables.iter().map(|table| table.name.as_str()).collect();
+            let rows = preset.rows(1);
+            assert_eq!(rows.iter().map(|(table, _)| table.as_str()).collect::<Vec<_>>(), names, "{}", preset);
+            // Every foreign key references a table of the preset
+            for column in tables.iter().flat_map(|table| &table.columns) {
+                if let Some(ref_table) = &column.ref_table {
+                    assert!(names.contains(&ref_table.as_str()), "{}.{}", preset, column.name);
+                }
+            }
+        }
+        assert_eq!("TPC-H".parse(), Ok(Preset::Tpch));
+        assert!("tpcd".parse::<Preset>().is_err());
+    }
+
+    #[test]
+    fn test_rows() {
+        let rows = Preset::Tpch.rows(10);
+        assert_eq!(rows[0], ("region".to_string(), 5));
+        assert_eq!(rows[7], ("lineitem".to_string(), 60_000));
+    }
+}
@@ -0,0 +1,443 @@
+//! Faker-style providers producing realistic text values.
+
+use crate::locale::Locale;
+use crate::models::DateRange;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+const STREET_SUFFIXES: &[&str] = &["St", "Ave", "Rd", "Blvd", "Ln", "Dr", "Way", "Ct"];
+
+pub(crate) const EMAIL_DOMAINS: &[&str] = &["example.com", "mail.com", "inbox.net", "post.org", "webmail.io"];
+
+/// Domains reserved for examples by RFC 2606, the only ones of PII-free email addresses.
+pub(crate) const RESERVED_EMAIL_DOMAINS: &[&str] = &["example.com", "example.net", "example.org"];
+
+pub(crate) const LOREM_WORDS: &[&str] = &[
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do",
+    "eiusmod", "tempor", "incididunt", "ut", "labore", "et", "dolore", "magna", "aliqua", "enim",
+    "ad", "minim", "veniam", "quis", "nostrud", "exercitation", "ullamco", "laboris", "nisi",
+];
+
+/// Enum representing the kinds of realistic text a column can be filled with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Provider {
+    FirstName,
+    LastName,
+    FullName,
+    Email,
+    Phone,
+    Address,
+    City,
+    Country,
+    Company,
+    Lorem,
+    /// Five digit postal codes such as `90210`.
+    ZipCode,
+    /// Amounts between 0.99 and 999.99; numeric columns get the same range.
+    Price,
+    /// Timestamps such as `2023-04-05 13:45:00`, for text columns like `created_at`.
+    Timestamp,
+}
+
+/// Column name patterns and the provider they select, tried in order.
+///
+/// A `*` matches any run of characters, so `*_at` matches `created_at` and `*email*`
+/// matches `customer_email_address`. More specific patterns come first.
+const INFERENCE_RULES: &[(&str, Provider)] = &[
+    ("*email*", Provider::Email),
+    ("mail", Provider::Email),
+    ("*_mail", Provider::Email),
+    ("*phone*", Provider::Phone),
+    ("*mobile*", Provider::Phone),
+    ("*fax*", Provider::Phone),
+    ("tel", Provider::Phone),
+    ("*_tel", Provider::Phone),
+    ("tel_*", Provider::Phone),
+    ("*zip*", Provider::ZipCode),
+    ("*postal_code*", Provider::ZipCode),
+    ("*postcode*", Provider::ZipCode),
+    ("*address*", Provider::Address),
+    ("*street*", Provider::Address),
+    ("*city*", Provider::City),
+    ("*town*", Provider::City),
+    ("*country*", Provider::Country),
+    ("*company*", Provider::Company),
+    ("*organization*", Provider::Company),
+    ("*employer*", Provider::Company),
+    ("*first_name*", Provider::FirstName),
+    ("*firstname*", Provider::FirstName),
+    ("*given_name*", Provider::FirstName),
+    ("*last_name*", Provider::LastName),
+    ("*lastname*", Provider::LastName),
+    ("*surname*", Provider::LastName),
+    ("*name*", Provider::FullName),
+    ("*price*", Provider::Price),
+    ("*cost*", Provider::Price),
+    ("*amount*", Provider::Price),
+    ("*_at", Provider::Timestamp),
+    ("*description*", Provider::Lorem),
+    ("*comment*", Provider::Lorem),
+    ("*note*", Provider::Lorem),
+    ("*text*", Provider::Lorem),
+    ("*body*", Provider::Lorem),
+    ("*summary*", Provider::Lorem),
+    ("*remark*", Provider::Lorem),
+];
+
+impl Provider {
+    /// Every provider.
+    pub const ALL: [Provider; 13] = [
+        Provider::FirstName,
+        Provider::LastName,
+        Provider::FullName,
+        Provider::Email,
+        Provider::Phone,
+        Provider::Address,
+        Provider::City,
+        Provider::Country,
+        Provider::Company,
+        Provider::Lorem,
+        Provider::ZipCode,
+        Provider::Price,
+        Provider::Timestamp,
+    ];
+
+    /// Returns the snake_case name of the provider, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::FirstName => "first_name",
+            Provider::LastName => "last_name",
+            Provider::FullName => "full_name",
+            Provider::Email => "email",
+            Provider::Phone => "phone",
+            Provider::Address => "address",
+            Provider::City => "city",
+            Provider::Country => "country",
+            Provider::Company => "company",
+            Provider::Lorem => "lorem",
+            Provider::ZipCode => "zip_code",
+            Provider::Price => "price",
+            Provider::Timestamp => "timestamp",
+        }
+    }
+
+    /// Infers the provider from a column name, e.g. `customer_email` → `Provider::Email`.
+    ///
+    /// The name is matched case-insensitively against `INFERENCE_RULES`, and the first
+    /// matching pattern wins, so the same name always selects the same provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `column_name` - The name of the column.
+    ///
+    /// # Returns
+    ///
+    /// The matching provider, or `None` if the name gives no hint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::providers::Provider;
+    /// assert_eq!(Provider::infer("customer_email"), Some(Provider::Email));
+    /// assert_eq!(Provider::infer("customer_name"), Some(Provider::FullName));
+    /// assert_eq!(Provider::infer("created_at"), Some(Provider::Timestamp));
+    /// assert_eq!(Provider::infer("sku"), None);
+    /// ```
+    pub fn infer(column_name: &str) -> Option<Provider> {
+        let name = column_name.to_lowercase();
+        INFERENCE_RULES
+            .iter()
+            .find(|(pattern, _)| matches_pattern(pattern, &name))
+            .map(|&(_, provider)| provider)
+    }
+
+    /// Generates a random English value.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator the value is drawn from.
+    ///
+    /// # Returns
+    ///
+    /// A string such as `alice.smith@example.com` for `Provider::Email`.
+    pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.generate_in(Locale::En, rng)
+    }
+
+    /// Generates a random value for `locale`.
+    ///
+    /// Names, addresses, cities, countries and companies use the words of the locale,
+    /// and phone numbers and postal codes its formats. Email addresses stay ASCII.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale of the value.
+    /// * `rng` - The random number generator the value is drawn from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::locale::Locale;
+    /// # use fake_sql::providers::Provider;
+    /// let phone = Provider::Phone.generate_in(Locale::Ja, &mut rand::thread_rng());
+    /// assert!(phone.starts_with("090-"));
+    /// ```
+    pub fn generate_in<R: Rng + ?Sized>(&self, locale: Locale, rng: &mut R) -> String {
+        let words = locale.words();
+        match self {
+            Provider::FirstName => pick(words.first_names, rng).to_string(),
+            Provider::LastName => pick(words.last_names, rng).to_string(),
+            Provider::FullName if locale.family_name_first() => {
+                let first = pick(words.first_names, rng);
+                format!("{}{}", pick(words.last_names, rng), first)
+            }
+            Provider::FullName => format!("{} {}", pick(words.first_names, rng), pick(words.last_names, rng)),
+            Provider::Email => {
+                let english = Locale::En.words();
+                format!(
+                    "{}.{}{}@{}",
+                    pick(english.first_names, rng).to_lowercase(),
+                    pick(english.last_names, rng).to_lowercase(),
+                    rng.gen_range(1..100),
+                    pick(EMAIL_DOMAINS, rng)
+                )
+            }
+            Provider::Phone => match locale {
+                Locale::En => format!(
+                    "{:03}-{:03}-{:04}",
+                    rng.gen_range(200..1000),
+                    rng.gen_range(200..1000),
+                    rng.gen_range(0..10000)
+                ),
+                Locale::ZhTw => format!("09{:02}-{:03}-{:03}", rng.gen_range(0..100), rng.gen_range(0..1000), rng.gen_range(0..1000)),
+                Locale::Ja => format!("090-{:04}-{:04}", rng.gen_range(0..10000), rng.gen_range(0..10000)),
+                Locale::De => format!("+49 {} {:07}", rng.gen_range(30..90), rng.gen_range(0..10_000_000)),
+            },
+            Provider::Address => match locale {
+                Locale::En => format!(
+                    "{} {} {}",
+                    rng.gen_range(1..10000),
+                    pick(words.streets, rng),
+                    pick(STREET_SUFFIXES, rng)
+                ),
+                Locale::ZhTw => format!("{}{}{}號", pick(words.cities, rng), pick(words.streets, rng), rng.gen_range(1..500)),
+                Locale::Ja => format!(
+                    "{}{}{}-{}-{}",
+                    pick(words.cities, rng),
+                    pick(words.streets, rng),
+                    rng.gen_range(1..10),
+                    rng.gen_range(1..30),
+                    rng.gen_range(1..20)
+                ),
+                Locale::De => format!("{} {}", pick(words.streets, rng), rng.gen_range(1..200)),
+            },
+            Provider::City => pick(words.cities, rng).to_string(),
+            Provider::Country => pick(words.countries, rng).to_string(),
+            Provider::Company => {
+                let name = pick(words.companies, rng);
+                let separator = if locale.family_name_first() { "" } else { " " };
+                format!("{}{}{}", name, separator, pick(words.company_suffixes, rng))
+            }
+            Provider::ZipCode => match locale {
+                Locale::En => format!("{:05}", rng.gen_range(501..100_000)),
+                Locale::ZhTw => format!("{:03}", rng.gen_range(100..1000)),
+                Locale::Ja => format!("{:03}-{:04}", rng.gen_range(0..1000), rng.gen_range(0..10000)),
+                Locale::De => format!("{:05}", rng.gen_range(1067..100_000)),
+            },
+            Provider::Price => {
+                let cents = rng.gen_range(99..=99_999);
+                format!("{}.{:02}", cents / 100, cents % 100)
+            }
+            Provider::Timestamp => DateRange::default().random_timestamp(rng).format("%Y-%m-%d %H:%M:%S").to_string(),
+            Provider::Lorem => {
+                let words: Vec<&str> = (0..rng.gen_range(3..12)).map(|_| pick(LOREM_WORDS, rng)).collect();
+                let sentence = words.join(" ");
+                let mut chars = sentence.chars();
+                match chars.next() {
+                    Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+                    None => sentence,
+                }
+            }
+        }
+    }
+
+    /// Generates a random value for `locale` that cannot belong to a real person, so the
+    /// data is safe to share: email addresses at the domains reserved for examples
+    /// (`example.com`, `example.net` and `example.org`), phone numbers in the fictional
+    /// `555-0100` to `555-0199` range, and the placeholder family names of the locale, such
+    /// as `Doe` or `Mustermann`. Other providers generate as [`Provider::generate_in`] does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::locale::Locale;
+    /// # use fake_sql::providers::Provider;
+    /// let mut rng = rand::thread_rng();
+    /// assert!(Provider::Email.generate_pii_free(Locale::En, &mut rng).contains("@example."));
+    /// assert_eq!(&Provider::Phone.generate_pii_free(Locale::De, &mut rng)[3..10], "-555-01");
+    /// assert!(["テスト", "見本", "サンプル"].contains(&Provider::LastName.generate_pii_free(Locale::Ja, &mut rng).as_str()));
+    /// ```
+    pub fn generate_pii_free<R: Rng + ?Sized>(&self, locale: Locale, rng: &mut R) -> String {
+        let words = locale.words();
+        match self {
+            Provider::LastName => pick(words.placeholder_last_names, rng).to_string(),
+            Provider::FullName if locale.family_name_first() => {
+                let first = pick(words.first_names, rng);
+                format!("{}{}", pick(words.placeholder_last_names, rng), first)
+            }
+            Provider::FullName => format!("{} {}", pick(words.first_names, rng), pick(words.placeholder_last_names, rng)),
+            Provider::Email => {
+                let english = Locale::En.words();
+                format!(
+                    "{}.{}{}@{}",
+                    pick(english.first_names, rng).to_lowercase(),
+                    pick(english.placeholder_last_names, rng).to_lowercase(),
+                    rng.gen_range(1..100),
+                    pick(RESERVED_EMAIL_DOMAINS, rng)
+                )
+            }
+            Provider::Phone => format!("{:03}-555-01{:02}", rng.gen_range(200..1000), rng.gen_range(0..100)),
+            _ => self.generate_in(locale, rng),
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Provider {
+    type Err = String;
+
+    /// Parses a provider name such as `email`, `full_name` or `first-name`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase().replace('-', "_");
+        match normalized.as_str() {
+            "name" => Ok(Provider::FullName),
+            "text" => Ok(Provider::Lorem),
+            _ => Provider::ALL
+                .iter()
+                .copied()
+                .find(|provider| provider.name() == normalized)
+                .ok_or_else(|| format!("unknown generator `{}`", s)),
+        }
+    }
+}
+
+/// Returns whether `name` matches `pattern`, where `*` stands for any run of characters.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::providers::matches_pattern;
+/// assert!(matches_pattern("*_at", "created_at"));
+/// assert!(matches_pattern("customers.*", "customers.email"));
+/// assert!(!matches_pattern("*_at", "format"));
+/// ```
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // The text before the first `*` is a prefix, the text after the last one a suffix
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else { return false };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else { return rest.is_empty() };
+    for part in parts {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Picks a random entry of a non-empty word list.
+fn pick<'a, R: Rng + ?Sized>(words: &[&'a str], rng: &mut R) -> &'a str {
+    words.choose(rng).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_infer() {
+        assert_eq!(Provider::infer("CUSTOMER_EMAIL"), Some(Provider::Email));
+        assert_eq!(Provider::infer("phone_number"), Some(Provider::Phone));
+        assert_eq!(Provider::infer("first_name"), Some(Provider::FirstName));
+        assert_eq!(Provider::infer("product_description"), Some(Provider::Lorem));
+        assert_eq!(Provider::infer("status"), None);
+        assert_eq!(Provider::infer("zip"), Some(Provider::ZipCode));
+        assert_eq!(Provider::infer("unit_price"), Some(Provider::Price));
+        assert_eq!(Provider::infer("updated_at"), Some(Provider::Timestamp));
+        assert_eq!(Provider::infer("hotel"), None);
+        assert_eq!(Provider::infer("company_name"), Some(Provider::Company));
+        assert_eq!(Provider::infer("email_address"), Some(Provider::Email));
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("email", "email"));
+        assert!(!matches_pattern("email", "emails"));
+        assert!(matches_pattern("*", ""));
+        assert!(matches_pattern("*mail*", "mail"));
+        assert!(matches_pattern("a*b*c", "axxbyyc"));
+        assert!(!matches_pattern("a*b*c", "acb"));
+        assert!(!matches_pattern("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_from_str() {
+        for provider in Provider::ALL {
+            assert_eq!(provider.name().parse::<Provider>(), Ok(provider));
+        }
+        assert_eq!("First-Name".parse::<Provider>(), Ok(Provider::FirstName));
+        assert!("iban".parse::<Provider>().is_err());
+    }
+
+    #[test]
+    fn test_generate_in_locale() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for locale in [Locale::ZhTw, Locale::Ja] {
+            let name = Provider::FullName.generate_in(locale, &mut rng);
+            assert!(!name.is_ascii() && !name.contains(' '), "{}", name);
+            assert!(!Provider::Address.generate_in(locale, &mut rng).is_ascii());
+        }
+        assert!(Provider::Email.generate_in(Locale::Ja, &mut rng).is_ascii());
+        assert!(Provider::Phone.generate_in(Locale::De, &mut rng).starts_with("+49 "));
+        let zip = Provider::ZipCode.generate_in(Locale::Ja, &mut rng);
+        assert_eq!((zip.len(), &zip[3..4]), (8, "-"));
+    }
+
+    #[test]
+    fn test_generate_pii_free() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for locale in [Locale::En, Locale::ZhTw, Locale::Ja, Locale::De] {
+            for _ in 0..50 {
+                let email = Provider::Email.generate_pii_free(locale, &mut rng);
+                assert!(RESERVED_EMAIL_DOMAINS.contains(&email.split_once('@').unwrap().1), "{}", email);
+                let phone = Provider::Phone.generate_pii_free(locale, &mut rng);
+                assert!(phone.len() == 12 && phone[3..].starts_with("-555-01"), "{}", phone);
+                let name = Provider::FullName.generate_pii_free(locale, &mut rng);
+                assert!(locale.words().placeholder_last_names.iter().any(|last| name.contains(last)), "{}", name);
+            }
+        }
+        assert_eq!(Provider::City.generate_pii_free(Locale::En, &mut StdRng::seed_from_u64(1)), Provider::City.generate(&mut StdRng::seed_from_u64(1)));
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let email = Provider::Email.generate(&mut rng);
+        assert!(email.contains('@') && email.contains('.'), "{}", email);
+        let phone = Provider::Phone.generate(&mut rng);
+        assert_eq!(phone.len(), 12, "{}", phone);
+        let lorem = Provider::Lorem.generate(&mut rng);
+        assert!(lorem.ends_with('.'), "{}", lorem);
+    }
+}
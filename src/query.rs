@@ -0,0 +1,718 @@
+//! Generation of SELECT queries: single-table, joined and aggregate.
+
+use crate::dialect::Dialect;
+use crate::models::{Column, Table};
+use crate::value::SqlValue;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Enum representing how elaborate generated queries are.
+///
+/// The complexity controls how often queries get optional clauses such as
+/// `ORDER BY` and pagination (`LIMIT`/`OFFSET`, `FETCH FIRST` or `TOP`).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Complexity {
+    /// Filters only, no sorting or pagination.
+    Simple,
+    /// Sorted about half of the time and sometimes paginated.
+    #[default]
+    Moderate,
+    /// Nearly always sorted and usually paginated.
+    Complex,
+}
+
+impl Complexity {
+    /// Every complexity level, from the simplest.
+    pub const ALL: [Complexity; 3] = [Complexity::Simple, Complexity::Moderate, Complexity::Complex];
+
+    /// Returns the lowercase name of the level, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Complexity::Simple => "simple",
+            Complexity::Moderate => "moderate",
+            Complexity::Complex => "complex",
+        }
+    }
+
+    /// Returns the probability that a query gets an `ORDER BY` clause.
+    fn order_by_rate(&self) -> f64 {
+        match self {
+            Complexity::Simple => 0.0,
+            Complexity::Moderate => 0.5,
+            Complexity::Complex => 0.9,
+        }
+    }
+
+    /// Returns the probability that a query is paginated.
+    fn pagination_rate(&self) -> f64 {
+        match self {
+            Complexity::Simple => 0.0,
+            Complexity::Moderate => 0.3,
+            Complexity::Complex => 0.7,
+        }
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Complexity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "simple" | "low" => Ok(Complexity::Simple),
+            "moderate" | "medium" => Ok(Complexity::Moderate),
+            "complex" | "high" => Ok(Complexity::Complex),
+            _ => Err(format!("unknown query complexity `{}`", s)),
+        }
+    }
+}
+
+/// How WHERE clauses are built: how many predicates they have and how they are combined.
+///
+/// A clause filters on one to `max` columns picked at random, preferring primary key and
+/// foreign key columns, which real queries filter on most and which are indexed. The
+/// predicates are joined with `AND`, except that each one is joined to the previous one
+/// with `OR` at `or_rate`, e.g. `id = 4 AND (status = 'new' OR total > 10)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Predicates {
+    /// Maximum number of predicates per clause; values below 1 are treated as 1.
+    pub max: usize,
+    /// Probability (0.0 to 1.0) that a predicate is joined to the previous one with `OR`.
+    pub or_rate: f64,
+}
+
+impl Default for Predicates {
+    fn default() -> Self {
+        Predicates { max: 3, or_rate: 0.2 }
+    }
+}
+
+/// A foreign key between two tables: `child.child_column` references `parent.parent_column`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relationship {
+    /// Index of the referencing table.
+    pub child: usize,
+    pub child_column: String,
+    /// Index of the referenced table.
+    pub parent: usize,
+    pub parent_column: String,
+}
+
+/// Returns every foreign key between `tables`.
+///
+/// References that do not name a column resolve to the parent's primary key;
+/// references to unknown tables or tables without a primary key are skipped.
+///
+/// # Arguments
+///
+/// * `tables` - The tables to look for foreign keys in.
+pub fn relationships(tables: &[Table]) -> Vec<Relationship> {
+    let mut relationships = vec![];
+    for (child, table) in tables.iter().enumerate() {
+        for column in &table.columns {
+            let Some(ref_table) = &column.ref_table else { continue };
+            let Some(parent) = tables.iter().position(|t| &t.name == ref_table) else { continue };
+            let parent_column = match &column.ref_column {
+                Some(name) => name.clone(),
+                None => match tables[parent].primary_key() {
+                    Some(key) => key.name.clone(),
+                    None => continue,
+                },
+            };
+            relationships.push(Relationship {
+                child,
+                child_column: column.name.clone(),
+                parent,
+                parent_column,
+            });
+        }
+    }
+    relationships
+}
+
+/// Generates a SELECT joining two or three related tables along their foreign keys,
+/// e.g. `SELECT o.order_id, c.customer_name FROM orders o JOIN customers c ON o.customer_id = c.customer_id`.
+///
+/// The query starts from a random table taking part in a foreign key, projects a few
+/// columns of every joined table and filters on one of their columns.
+///
+/// # Arguments
+///
+/// * `tables` - The tables of the schema.
+/// * `dialect` - The dialect the statement is rendered for.
+/// * `complexity` - How often the query is sorted and paginated.
+/// * `rng` - The random number generator used for the tables, columns and predicate.
+///
+/// # Returns
+///
+/// The SELECT statement, or `None` if no table references another one.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::{query, Dialect};
+/// # use fake_sql::query::Complexity;
+/// # use fake_sql::schema::parse_schema;
+/// let tables = parse_schema(
+///     "create table customers (customer_id int primary key, customer_name varchar(50));
+///      create table orders (order_id int primary key, customer_id int references customers (customer_id));",
+/// )
+/// .unwrap();
+/// let sql = query::select_join(&tables, Dialect::Postgres, Complexity::Simple, &mut rand::thread_rng()).unwrap();
+/// assert!(sql.contains(" JOIN "));
+/// assert!(sql.contains("o.customer_id = c.customer_id"));
+/// ```
+pub fn select_join<R: Rng + ?Sized>(
+    tables: &[Table],
+    dialect: Dialect,
+    complexity: Complexity,
+    rng: &mut R,
+) -> Option<String> {
+    select_join_from(tables, None, dialect, complexity, rng)
+}
+
+/// Generates a SELECT joining related tables, starting from `tables[start]` if given.
+///
+/// Returns `None` if no table references another one, or `start` takes part in no foreign key.
+fn select_join_from<R: Rng + ?Sized>(
+    tables: &[Table],
+    start: Option<usize>,
+    dialect: Dialect,
+    complexity: Complexity,
+    rng: &mut R,
+) -> Option<String> {
+    let relationships = relationships(tables);
+    let start = match start {
+        Some(start) if relationships.iter().any(|r| r.child == start || r.parent == start) => start,
+        Some(_) => return None,
+        None => {
+            let first = relationships.choose(rng)?;
+            if rng.gen_bool(0.5) { first.child } else { first.parent }
+        }
+    };
+    let mut joined = vec![start];
+    let mut joins = vec![];
+
+    let target = rng.gen_range(2..=3);
+    while joined.len() < target {
+        // Relationships connecting a joined table to one that is not joined yet
+        let candidates: Vec<&Relationship> = relationships
+            .iter()
+            .filter(|r| joined.contains(&r.child) != joined.contains(&r.parent))
+            .collect();
+        let Some(relationship) = candidates.choose(rng) else { break };
+        let table = if joined.contains(&relationship.child) { relationship.parent } else { relationship.child };
+        joined.push(table);
+        joins.push(*relationship);
+    }
+
+    let aliases = aliases(tables, &joined);
+    let alias = |table: usize| &aliases[joined.iter().position(|&t| t == table).unwrap()];
+    let column_ref = |table: usize, column: &str| format!("{}.{}", alias(table), dialect.identifier(column));
+
+    let mut selected = vec![];
+    for &table in &joined {
+        let columns = &tables[table].columns;
+        let count = rng.gen_range(1..=columns.len().clamp(1, 3));
+        let mut picked: Vec<usize> = rand::seq::index::sample(rng, columns.len(), count.min(columns.len())).into_vec();
+        picked.sort_unstable();
+        selected.extend(picked.iter().map(|&i| (table, columns[i].name.as_str())));
+    }
+    // Spatial values have no order, so they are never sorted or grouped by
+    let sort_keys: Vec<String> = selected
+        .iter()
+        .filter(|&&(table, column)| !tables[table].column(column).is_some_and(Column::is_spatial))
+        .map(|&(table, column)| column_ref(table, column))
+        .collect();
+    // Columns selected from several tables get an alias so every result column has a distinct name
+    let projection: Vec<String> = selected
+        .iter()
+        .map(|&(table, column)| {
+            if selected.iter().filter(|&&(_, other)| other == column).count() > 1 {
+                let name = format!("{}_{}", alias(table), column);
+                format!("{} AS {}", column_ref(table, column), dialect.identifier(&name))
+            } else {
+                column_ref(table, column)
+            }
+        })
+        .collect();
+
+    let mut from = format!("{} {}", dialect.identifier(&tables[joined[0]].name), alias(joined[0]));
+    for (relationship, &table) in joins.iter().zip(&joined[1..]) {
+        let join = if rng.gen_bool(0.25) { "LEFT JOIN" } else { "JOIN" };
+        from.push_str(&format!(
+            " {} {} {} ON {} = {}",
+            join,
+            dialect.identifier(&tables[table].name),
+            alias(table),
+            column_ref(relationship.child, &relationship.child_column),
+            column_ref(relationship.parent, &relationship.parent_column),
+        ));
+    }
+
+    let table = *joined.choose(rng).unwrap();
+    let condition = tables[table]
+        .columns
+        .choose(rng)
+        .and_then(|column| column.generate_condition(&column_ref(table, &column.name), dialect, rng));
+    let sql = match condition {
+        Some(condition) => format!("SELECT {} FROM {} WHERE {}", projection.join(", "), from, condition),
+        None => format!("SELECT {} FROM {}", projection.join(", "), from),
+    };
+    Some(finish(sql, &sort_keys, dialect, complexity, rng))
+}
+
+/// Generates an aggregate query over `table`, e.g.
+/// `SELECT customer_id, COUNT(*) AS row_count, SUM(amount) AS sum_amount FROM orders GROUP BY customer_id HAVING COUNT(*) > 3`.
+///
+/// Non-numeric columns are grouped by and numeric columns are aggregated with
+/// `SUM`, `AVG`, `MIN` or `MAX`; every query also counts its rows. Primary keys are
+/// neither grouped by nor aggregated. A WHERE and a HAVING clause are added at random.
+///
+/// # Arguments
+///
+/// * `table` - The table to query.
+/// * `dialect` - The dialect the statement is rendered for.
+/// * `complexity` - How often the query is sorted and paginated.
+/// * `rng` - The random number generator used for the columns, functions and predicates.
+///
+/// # Returns
+///
+/// The SELECT statement.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::{query, Dialect, Table};
+/// # use fake_sql::query::Complexity;
+/// let table = Table::init_via_sql("create table orders (order_id int primary key, status varchar(10), amount number(10,2))");
+/// let sql = query::select_aggregate(&table, Dialect::Postgres, Complexity::Simple, &mut rand::thread_rng());
+/// assert!(sql.starts_with("SELECT status, COUNT(*) AS row_count"));
+/// assert!(sql.contains(" GROUP BY status"));
+/// ```
+pub fn select_aggregate<R: Rng + ?Sized>(table: &Table, dialect: Dialect, complexity: Complexity, rng: &mut R) -> String {
+    let (numeric, other): (Vec<&Column>, Vec<&Column>) =
+        table.columns.iter().filter(|c| !c.is_pkey && !c.is_spatial()).partition(|c| c.is_numeric());
+
+    let count = if other.is_empty() { 0 } else { rng.gen_range(1..=other.len().min(2)) };
+    let mut groups: Vec<&Column> = other.choose_multiple(rng, count).copied().collect();
+    groups.sort_by_key(|group| table.columns.iter().position(|c| c.name == group.name));
+    let groups: Vec<String> = groups.iter().map(|c| dialect.identifier(&c.name)).collect();
+
+    let mut projection = groups.clone();
+    let mut aggregates = vec!["COUNT(*)".to_string()];
+    projection.push("COUNT(*) AS row_count".to_string());
+    let count = rng.gen_range(0..=numeric.len().min(2));
+    for column in numeric.choose_multiple(rng, count) {
+        let function = ["SUM", "AVG", "MIN", "MAX"].choose(rng).unwrap();
+        let aggregate = format!("{}({})", function, dialect.identifier(&column.name));
+        projection.push(format!("{} AS {}", aggregate, dialect.identifier(&format!("{}_{}", function.to_lowercase(), column.name))));
+        aggregates.push(aggregate);
+    }
+
+    let mut sql = format!("SELECT {} FROM {}", projection.join(", "), dialect.identifier(&table.name));
+    if rng.gen_bool(0.5) {
+        let condition = table
+            .columns
+            .choose(rng)
+            .and_then(|column| column.generate_condition(&dialect.identifier(&column.name), dialect, rng));
+        if let Some(condition) = condition {
+            sql.push_str(&format!(" WHERE {}", condition));
+        }
+    }
+    if !groups.is_empty() {
+        sql.push_str(&format!(" GROUP BY {}", groups.join(", ")));
+        if rng.gen_bool(0.5) {
+            let operator = [">", ">=", "<"].choose(rng).unwrap();
+            sql.push_str(&format!(" HAVING {} {} {}", aggregates.choose(rng).unwrap(), operator, rng.gen_range(1..100)));
+        }
+    }
+    let mut sort_keys = groups;
+    sort_keys.extend(aggregates);
+    finish(sql, &sort_keys, dialect, complexity, rng)
+}
+
+/// Generates a SELECT of every column of `table` filtered on some of its columns, e.g.
+/// `SELECT id, name FROM t WHERE id > 42 AND name IN ('Alice', 'Bob')`.
+///
+/// Given a row of the table, such as one inserted before, the filters match it, so the
+/// query returns at least that row.
+///
+/// # Arguments
+///
+/// * `table` - The table to query.
+/// * `row` - A row the filters match, one value per column; random filters without it.
+/// * `extra_condition` - A predicate added to the generated filters, such as a
+///   subquery from [`subquery_condition`].
+/// * `dialect` - The dialect the statement is rendered for.
+/// * `complexity` - How often the query is sorted and paginated.
+/// * `predicates` - How many filters the query has and how they are combined.
+/// * `rng` - The random number generator used for the predicates.
+///
+/// # Returns
+///
+/// The SELECT statement.
+pub fn select<R: Rng + ?Sized>(
+    table: &Table,
+    row: Option<&[SqlValue]>,
+    extra_condition: Option<&str>,
+    dialect: Dialect,
+    complexity: Complexity,
+    predicates: Predicates,
+    rng: &mut R,
+) -> String {
+    let column_names: Vec<String> = table.columns.iter().map(|c| dialect.identifier(&c.name)).collect();
+    let mut conditions = table.where_clause(dialect, row, predicates, rng);
+    if let Some(condition) = extra_condition {
+        if conditions.contains(" OR ") {
+            conditions = format!("({})", conditions);
+        }
+        if !conditions.is_empty() {
+            conditions.push_str(" AND ");
+        }
+        conditions.push_str(condition);
+    }
+    let mut sql = format!("SELECT {} FROM {}", column_names.join(", "), dialect.identifier(&table.name));
+    if !conditions.is_empty() {
+        sql.push_str(&format!(" WHERE {}", conditions));
+    }
+    let sort_keys: Vec<String> = table.columns.iter().filter(|c| !c.is_spatial()).map(|c| dialect.identifier(&c.name)).collect();
+    finish(sql, &sort_keys, dialect, complexity, rng)
+}
+
+/// Generates a subquery predicate on `tables[table_index]` following one of its foreign keys.
+///
+/// A table referencing a parent gets an uncorrelated `IN` subquery, e.g.
+/// `customer_id IN (SELECT customer_id FROM customers WHERE ...)`; a table referenced
+/// by a child gets a correlated `EXISTS` subquery, e.g.
+/// `EXISTS (SELECT 1 FROM orders sub WHERE sub.customer_id = customers.customer_id)`.
+/// Either may be negated.
+///
+/// # Arguments
+///
+/// * `tables` - The tables of the schema.
+/// * `table_index` - The position of the table the predicate filters.
+/// * `dialect` - The dialect the predicate is rendered for.
+/// * `rng` - The random number generator used for the relationship and filters.
+///
+/// # Returns
+///
+/// The predicate, or `None` if the table takes part in no foreign key.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::{query, Dialect};
+/// # use fake_sql::schema::parse_schema;
+/// let tables = parse_schema(
+///     "create table customers (customer_id int primary key);
+///      create table orders (order_id int primary key, customer_id int references customers (customer_id));",
+/// )
+/// .unwrap();
+/// let condition = query::subquery_condition(&tables, 0, Dialect::Postgres, &mut rand::thread_rng()).unwrap();
+/// assert!(condition.contains("EXISTS (SELECT 1 FROM orders sub WHERE sub.customer_id = customers.customer_id"));
+/// ```
+pub fn subquery_condition<R: Rng + ?Sized>(tables: &[Table], table_index: usize, dialect: Dialect, rng: &mut R) -> Option<String> {
+    let candidates: Vec<Relationship> = relationships(tables)
+        .into_iter()
+        .filter(|r| (r.child == table_index) != (r.parent == table_index))
+        .collect();
+    let relationship = candidates.choose(rng)?;
+    let negation = if rng.gen_bool(0.2) { "NOT " } else { "" };
+
+    if relationship.child == table_index {
+        let parent = &tables[relationship.parent];
+        let filter = parent
+            .columns
+            .choose(rng)
+            .and_then(|column| column.generate_condition(&dialect.identifier(&column.name), dialect, rng))
+            .map(|condition| format!(" WHERE {}", condition))
+            .unwrap_or_default();
+        Some(format!(
+            "{} {}IN (SELECT {} FROM {}{})",
+            dialect.identifier(&relationship.child_column),
+            negation,
+            dialect.identifier(&relationship.parent_column),
+            dialect.identifier(&parent.name),
+            filter
+        ))
+    } else {
+        let child = &tables[relationship.child];
+        let filter = child
+            .columns
+            .choose(rng)
+            .and_then(|column| column.generate_condition(&format!("sub.{}", dialect.identifier(&column.name)), dialect, rng))
+            .map(|condition| format!(" AND {}", condition))
+            .unwrap_or_default();
+        Some(format!(
+            "{}EXISTS (SELECT 1 FROM {} sub WHERE sub.{} = {}.{}{})",
+            negation,
+            dialect.identifier(&child.name),
+            dialect.identifier(&relationship.child_column),
+            dialect.identifier(&tables[table_index].name),
+            dialect.identifier(&relationship.parent_column),
+            filter
+        ))
+    }
+}
+
+/// Appends a random `ORDER BY` and pagination clause to a query, as often as
+/// `complexity` asks for, and terminates it with a semicolon.
+///
+/// # Arguments
+///
+/// * `sql` - The query, without a trailing semicolon.
+/// * `sort_keys` - The expressions the query may be sorted by.
+/// * `dialect` - The dialect the pagination syntax is rendered for.
+/// * `complexity` - How often the clauses are added.
+/// * `rng` - The random number generator.
+fn finish<R: Rng + ?Sized>(mut sql: String, sort_keys: &[String], dialect: Dialect, complexity: Complexity, rng: &mut R) -> String {
+    let ordered = !sort_keys.is_empty() && rng.gen_bool(complexity.order_by_rate());
+    if ordered {
+        let count = rng.gen_range(1..=sort_keys.len().min(2));
+        let keys: Vec<String> = sort_keys
+            .choose_multiple(rng, count)
+            .map(|key| format!("{} {}", key, ["ASC", "DESC"].choose(rng).unwrap()))
+            .collect();
+        sql.push_str(&format!(" ORDER BY {}", keys.join(", ")));
+    }
+
+    if rng.gen_bool(complexity.pagination_rate()) {
+        let limit = [10, 20, 50, 100].choose(rng).unwrap();
+        let offset = if rng.gen_bool(0.5) { Some(rng.gen_range(1..10) * limit) } else { None };
+        match (dialect, offset) {
+            (Dialect::Postgres | Dialect::MySql | Dialect::Sqlite, None) => sql.push_str(&format!(" LIMIT {}", limit)),
+            (Dialect::Postgres | Dialect::MySql | Dialect::Sqlite, Some(offset)) => {
+                sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset))
+            }
+            (Dialect::Oracle, None) => sql.push_str(&format!(" FETCH FIRST {} ROWS ONLY", limit)),
+            (Dialect::Oracle, Some(offset)) => sql.push_str(&format!(" OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit)),
+            // SQL Server only accepts OFFSET/FETCH after an ORDER BY
+            (Dialect::SqlServer, Some(offset)) if ordered => {
+                sql.push_str(&format!(" OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit))
+            }
+            (Dialect::SqlServer, _) => sql = sql.replacen("SELECT ", &format!("SELECT TOP {} ", limit), 1),
+        }
+    }
+    sql.push(';');
+    sql
+}
+
+/// Generates a `CREATE VIEW` over a generated query: a join along the foreign keys,
+/// an aggregate over `tables[table_index]` or a filtered SELECT of it. Dialects with
+/// materialized views (Oracle and PostgreSQL) sometimes get `CREATE MATERIALIZED VIEW`.
+///
+/// # Arguments
+///
+/// * `tables` - The tables of the schema.
+/// * `table_index` - The position of the table the view is named after and selects from
+///   when it is not a join.
+/// * `dialect` - The dialect the statement is rendered for.
+/// * `rng` - The random number generator used for the query and view name.
+///
+/// # Returns
+///
+/// The statement, e.g. `CREATE VIEW v_orders_17 AS SELECT ... FROM orders o JOIN customers c ON ...;`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::{query, Dialect, Table};
+/// let tables = vec![Table::init_via_sql("create table orders (order_id int primary key, status varchar(10))")];
+/// let sql = query::create_view(&tables, 0, Dialect::MySql, &mut rand::thread_rng());
+/// assert!(sql.starts_with("CREATE VIEW v_orders_"));
+/// assert!(sql.contains(" AS SELECT "));
+/// ```
+pub fn create_view<R: Rng + ?Sized>(tables: &[Table], table_index: usize, dialect: Dialect, rng: &mut R) -> String {
+    let table = &tables[table_index];
+    let query = match rng.gen_range(0..3) {
+        0 => select_join_from(tables, Some(table_index), dialect, Complexity::Simple, rng),
+        1 => Some(select_aggregate(table, dialect, Complexity::Simple, rng)),
+        _ => None,
+    }
+    .unwrap_or_else(|| select(table, None, None, dialect, Complexity::Simple, Predicates::default(), rng));
+    let materialized = dialect.supports_materialized_views() && rng.gen_bool(0.3);
+    let name = format!("v_{}_{}", table.name, rng.gen_range(1..1000));
+    format!(
+        "CREATE {}VIEW {} AS {}",
+        if materialized { "MATERIALIZED " } else { "" },
+        dialect.identifier(&name),
+        query
+    )
+}
+
+/// Returns a short alias per joined table: its initial, followed by a number when
+/// several tables share an initial, e.g. `o`, `c` or `p1`, `p2`.
+fn aliases(tables: &[Table], joined: &[usize]) -> Vec<String> {
+    let initials: Vec<char> = joined
+        .iter()
+        .map(|&t| {
+            tables[t]
+                .name
+                .chars()
+                .find(char::is_ascii_alphabetic)
+                .map_or('t', |c| c.to_ascii_lowercase())
+        })
+        .collect();
+    initials
+        .iter()
+        .enumerate()
+        .map(|(i, initial)| {
+            if initials.iter().filter(|&c| c == initial).count() > 1 {
+                let n = initials[..i].iter().filter(|&c| c == initial).count() + 1;
+                format!("{}{}", initial, n)
+            } else {
+                initial.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::parse_schema;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    fn schema() -> Vec<Table> {
+        parse_schema(
+            "create table customers (customer_id int primary key, customer_name varchar(50));
+             create table products (product_id int primary key, price number(10,2));
+             create table purchases (purchase_id int primary key, customer_id int references customers,
+                                     product_id int references products (product_id));",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_relationships() {
+        let relationships = relationships(&schema());
+        assert_eq!(relationships.len(), 2);
+        assert_eq!(relationships[0].child, 2);
+        assert_eq!(relationships[0].parent, 0);
+        assert_eq!(relationships[0].parent_column, "customer_id");
+        assert_eq!(relationships[1].parent_column, "product_id");
+    }
+
+    #[test]
+    fn test_select_join() {
+        let tables = schema();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let sql = select_join(&tables, Dialect::Oracle, Complexity::Simple, &mut rng).unwrap();
+            assert!(sql.starts_with("SELECT "), "{}", sql);
+            assert!(sql.contains("JOIN") && sql.contains(" ON "), "{}", sql);
+            assert!(sql.contains(".customer_id = c.customer_id") || sql.contains(".product_id = p"), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_select_join_without_relationships() {
+        let tables = parse_schema("create table a (id int primary key); create table b (id int primary key);").unwrap();
+        assert!(select_join(&tables, Dialect::Oracle, Complexity::Simple, &mut rand::thread_rng()).is_none());
+    }
+
+    #[test]
+    fn test_select_aggregate() {
+        let table = &schema()[1];
+        let mut rng = StdRng::seed_from_u64(8);
+        let queries: Vec<String> = (0..50).map(|_| select_aggregate(table, Dialect::Oracle, Complexity::Simple, &mut rng)).collect();
+        assert!(queries.iter().all(|sql| sql.starts_with("SELECT COUNT(*) AS row_count") && sql.ends_with(';')));
+        assert!(queries.iter().all(|sql| !sql.contains("GROUP BY") && !sql.contains("(product_id)")));
+        assert!(queries.iter().any(|sql| sql.contains("(price) AS ")));
+
+        let table = Table::init_via_sql("create table t (id int primary key, kind varchar(5), n int)");
+        let queries: Vec<String> = (0..50).map(|_| select_aggregate(&table, Dialect::Oracle, Complexity::Simple, &mut rng)).collect();
+        assert!(queries.iter().any(|sql| sql.contains(" GROUP BY kind HAVING ")));
+    }
+
+    #[test]
+    fn test_order_by_and_pagination() {
+        let table = &schema()[0];
+        let mut rng = StdRng::seed_from_u64(1);
+        let simple: Vec<String> = (0..50).map(|_| select(table, None, None, Dialect::Postgres, Complexity::Simple, Predicates::default(), &mut rng)).collect();
+        assert!(simple.iter().all(|sql| !sql.contains("ORDER BY") && !sql.contains("LIMIT")));
+
+        let queries = |dialect| -> Vec<String> {
+            let mut rng = StdRng::seed_from_u64(2);
+            (0..100).map(|_| select(table, None, None, dialect, Complexity::Complex, Predicates::default(), &mut rng)).collect()
+        };
+        let postgres = queries(Dialect::Postgres);
+        assert!(postgres.iter().any(|sql| sql.contains(" ORDER BY ")));
+        assert!(postgres.iter().any(|sql| sql.contains(" LIMIT ") && sql.contains(" OFFSET ")));
+        assert!(queries(Dialect::Oracle).iter().any(|sql| sql.ends_with(" ROWS ONLY;")));
+        let sql_server = queries(Dialect::SqlServer);
+        assert!(sql_server.iter().any(|sql| sql.starts_with("SELECT TOP ")));
+        assert!(sql_server.iter().filter(|sql| sql.contains(" OFFSET ")).all(|sql| sql.contains(" ORDER BY ")));
+    }
+
+    #[test]
+    fn test_subquery_condition() {
+        let tables = schema();
+        let mut rng = StdRng::seed_from_u64(4);
+        let conditions: Vec<String> = (0..50).map(|_| subquery_condition(&tables, 2, Dialect::Oracle, &mut rng).unwrap()).collect();
+        assert!(conditions.iter().any(|c| c.starts_with("customer_id IN (SELECT customer_id FROM customers")));
+        assert!(conditions.iter().any(|c| c.starts_with("product_id NOT IN (SELECT product_id FROM products")));
+
+        let condition = subquery_condition(&tables, 1, Dialect::Oracle, &mut rng).unwrap();
+        assert!(condition.contains("EXISTS (SELECT 1 FROM purchases sub WHERE sub.product_id = products.product_id"), "{}", condition);
+
+        let sql = select(&tables[1], None, Some(&condition), Dialect::Oracle, Complexity::Simple, Predicates::default(), &mut rng);
+        assert!(sql.contains(&format!(" AND {}", condition)), "{}", sql);
+    }
+
+    #[test]
+    fn test_create_view() {
+        let tables = schema();
+        let mut rng = StdRng::seed_from_u64(6);
+        let views: Vec<String> = (0..50).map(|_| create_view(&tables, 2, Dialect::Postgres, &mut rng)).collect();
+        assert!(views.iter().all(|sql| sql.ends_with(';') && sql.contains(" AS SELECT ")));
+        assert!(views.iter().any(|sql| sql.starts_with("CREATE MATERIALIZED VIEW v_purchases_")));
+        assert!(views.iter().any(|sql| sql.contains(" JOIN ")));
+        assert!(views.iter().any(|sql| sql.contains(" GROUP BY ") || sql.contains("COUNT(*)")));
+
+        let views: Vec<String> = (0..50).map(|_| create_view(&tables, 2, Dialect::MySql, &mut rng)).collect();
+        assert!(views.iter().all(|sql| sql.starts_with("CREATE VIEW ")));
+    }
+
+    #[test]
+    fn test_select_join_aliases_duplicate_columns() {
+        let tables = schema();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let sql = select_join(&tables, Dialect::Postgres, Complexity::Complex, &mut rng).unwrap();
+            let projection = &sql["SELECT ".len()..sql.find(" FROM ").unwrap()];
+            let names: Vec<&str> = projection
+                .split(", ")
+                .map(|column| column.rsplit(" AS ").next().unwrap().rsplit('.').next().unwrap())
+                .collect();
+            let unique: HashSet<&str> = names.iter().copied().collect();
+            assert_eq!(unique.len(), names.len(), "{}", sql);
+            assert!(!sql.split(" ORDER BY ").nth(1).is_some_and(|order| order.contains(" AS ")), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_complexity_from_str() {
+        for complexity in Complexity::ALL {
+            assert_eq!(complexity.name().parse::<Complexity>(), Ok(complexity));
+        }
+        assert!("extreme".parse::<Complexity>().is_err());
+    }
+
+    #[test]
+    fn test_aliases() {
+        let tables = schema();
+        assert_eq!(aliases(&tables, &[2, 0, 1]), ["p1", "c", "p2"]);
+        assert_eq!(aliases(&tables, &[0, 2]), ["c", "p"]);
+    }
+}
@@ -0,0 +1,212 @@
+//! Pacing statements at a fixed rate, for live workloads.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Longest gap between two statements, a year, so the schedule stays within the range
+/// of an `Instant`.
+const MAX_GAP: Duration = Duration::from_secs(365 * 86_400);
+
+/// The pace statements are emitted at, e.g. `500/s`.
+///
+/// Statements are spaced evenly, or with exponentially distributed gaps of the same
+/// average with [`Rate::with_poisson`], as arrivals of a Poisson process would be.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::rate::Rate;
+/// let rate: Rate = "30/min".parse().unwrap();
+/// assert_eq!(rate.per_second(), 0.5);
+/// assert_eq!(rate.to_string(), "0.5/s");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rate {
+    per_second: f64,
+    /// Whether the gaps between statements are drawn from an exponential distribution.
+    poisson: bool,
+}
+
+impl Rate {
+    /// Initializes a new `Rate` of `per_second` evenly spaced statements per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `per_second` is not a positive number.
+    pub fn new(per_second: f64) -> Rate {
+        assert!(per_second > 0.0 && per_second.is_finite(), "rate must be positive");
+        Rate {
+            per_second,
+            poisson: false,
+        }
+    }
+
+    /// Returns the rate with exponentially distributed gaps between statements if
+    /// `poisson` is set, or evenly spaced statements otherwise.
+    pub fn with_poisson(mut self, poisson: bool) -> Rate {
+        self.poisson = poisson;
+        self
+    }
+
+    /// Returns the number of statements per second.
+    pub fn per_second(&self) -> f64 {
+        self.per_second
+    }
+
+    /// Returns whether the gaps between statements follow a Poisson process.
+    pub fn is_poisson(&self) -> bool {
+        self.poisson
+    }
+
+    /// Returns the rate of each of `parts` streams sharing this rate, e.g. connections.
+    pub fn split(&self, parts: usize) -> Rate {
+        Rate {
+            per_second: self.per_second / parts.max(1) as f64,
+            poisson: self.poisson,
+        }
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/s", self.per_second)
+    }
+}
+
+impl FromStr for Rate {
+    type Err = String;
+
+    /// Parses a number of statements per second (`500`, `500/s`), per minute (`30/min`,
+    /// `30/m`) or per hour (`100/h`), of at least one statement a year.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid rate `{}`; expected e.g. `500/s`, `30/min` or `100/h`", s);
+        let (count, unit) = s.trim().split_once('/').unwrap_or((s.trim(), "s"));
+        let seconds = match unit.trim().to_lowercase().as_str() {
+            "s" | "sec" | "second" => 1.0,
+            "m" | "min" | "minute" => 60.0,
+            "h" | "hour" => 3600.0,
+            _ => return Err(invalid()),
+        };
+        match count.trim().parse::<f64>() {
+            Ok(count) if count > 0.0 && count.is_finite() && seconds / count <= MAX_GAP.as_secs_f64() => Ok(Rate::new(count / seconds)),
+            Ok(count) if count > 0.0 && count.is_finite() => Err(format!("invalid rate `{}`; expected at least one statement a year", s)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Schedules statements so they are emitted at a [`Rate`].
+///
+/// Every statement is due a gap after the previous one was due, so the rate holds on
+/// average even when writing a statement takes time. After a stall, statements are
+/// emitted without waiting until the schedule is caught up.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::rate::{Pacer, Rate};
+/// let mut pacer = Pacer::new(Rate::new(1000.0));
+/// let start = std::time::Instant::now();
+/// for _ in 0..11 {
+///     pacer.wait();
+/// }
+/// assert!(start.elapsed().as_millis() >= 10);
+/// ```
+pub struct Pacer {
+    rate: Rate,
+    /// When the next statement is due; `None` before the first one.
+    next: Option<Instant>,
+    rng: StdRng,
+}
+
+impl Pacer {
+    /// Initializes a new `Pacer` whose first statement is due immediately.
+    pub fn new(rate: Rate) -> Pacer {
+        Pacer {
+            rate,
+            next: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Returns how long to wait until the next statement is due, and schedules the one
+    /// after it. Async callers sleep for the returned time themselves.
+    pub fn delay(&mut self) -> Duration {
+        let now = Instant::now();
+        let due = self.next.unwrap_or(now);
+        self.next = Some(due + self.gap());
+        due.saturating_duration_since(now)
+    }
+
+    /// Blocks until the next statement is due.
+    pub fn wait(&mut self) {
+        let delay = self.delay();
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+
+    /// Draws the time between two statements.
+    fn gap(&mut self) -> Duration {
+        let mean = 1.0 / self.rate.per_second;
+        let seconds = if self.rate.poisson {
+            // Inverse transform sampling of the exponential distribution
+            let uniform: f64 = self.rng.gen();
+            -(1.0 - uniform).ln() * mean
+        } else {
+            mean
+        };
+        // Rates split between many streams can be slower than any `Duration` holds
+        Duration::try_from_secs_f64(seconds).map_or(MAX_GAP, |gap| gap.min(MAX_GAP))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_from_str() {
+        assert_eq!("500/s".parse(), Ok(Rate::new(500.0)));
+        assert_eq!("500".parse(), Ok(Rate::new(500.0)));
+        assert_eq!("120/min".parse(), Ok(Rate::new(2.0)));
+        assert_eq!("7200/h".parse(), Ok(Rate::new(2.0)));
+        assert!("0/s".parse::<Rate>().is_err());
+        assert!("500/day".parse::<Rate>().is_err());
+        assert!("fast".parse::<Rate>().is_err());
+        assert!("1e-300/s".parse::<Rate>().unwrap_err().contains("at least one statement a year"));
+        assert!("1/h".parse::<Rate>().is_ok());
+        assert_eq!(Rate::new(500.0).split(4).per_second(), 125.0);
+        assert!(Rate::new(500.0).with_poisson(true).split(4).is_poisson());
+    }
+
+    #[test]
+    fn test_gaps() {
+        let mut even = Pacer::new(Rate::new(100.0));
+        assert!((0..10).all(|_| even.gap() == Duration::from_millis(10)));
+
+        let mut poisson = Pacer::new(Rate::new(100.0).with_poisson(true));
+        let gaps: Vec<Duration> = (0..10_000).map(|_| poisson.gap()).collect();
+        let mean = gaps.iter().sum::<Duration>().as_secs_f64() / gaps.len() as f64;
+        assert!((mean - 0.01).abs() < 0.001, "mean gap {}", mean);
+        assert!(gaps.iter().any(|gap| *gap < Duration::from_millis(2)) && gaps.iter().any(|gap| *gap > Duration::from_millis(30)));
+
+        for rate in [Rate::new(1e-300), Rate::new(1e-300).with_poisson(true), Rate::new(1.0).split(usize::MAX)] {
+            let mut pacer = Pacer::new(rate);
+            assert!(pacer.delay().is_zero());
+            assert_eq!(pacer.gap(), MAX_GAP);
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let mut pacer = Pacer::new(Rate::new(10.0));
+        assert!(pacer.delay().is_zero());
+        let delay = pacer.delay();
+        assert!(delay > Duration::from_millis(90) && delay <= Duration::from_millis(100));
+    }
+}
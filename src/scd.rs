@@ -0,0 +1,388 @@
+//! Slowly changing dimensions of type 2, which keep every version of a business key.
+
+use crate::data_pool::DataPool;
+use crate::dialect::Dialect;
+use crate::models::{Column, Table};
+use crate::value::SqlValue;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use rand::seq::index;
+use rand::Rng;
+
+/// Number of versions a business key gets at most when its history is inserted, unless
+/// configured otherwise.
+pub const DEFAULT_MAX_VERSIONS: u32 = 3;
+
+/// Column names recognized as the start of a version's validity.
+const VALID_FROM_NAMES: [&str; 5] = ["valid_from", "effective_from", "effective_date", "start_date", "valid_start"];
+
+/// Column names recognized as the end of a version's validity.
+const VALID_TO_NAMES: [&str; 5] = ["valid_to", "effective_to", "expiry_date", "end_date", "valid_end"];
+
+/// Column names recognized as the flag of the current version.
+const IS_CURRENT_NAMES: [&str; 4] = ["is_current", "current_flag", "is_active", "current"];
+
+/// Column name suffixes recognized as business keys.
+const BUSINESS_KEY_SUFFIXES: [&str; 5] = ["_id", "_key", "_code", "_number", "_no"];
+
+/// How the rows of a type 2 slowly changing dimension are generated, e.g. a customer
+/// dimension of a data warehouse.
+///
+/// Every row is a version of a business key, valid from `valid_from` until `valid_to`.
+/// INSERTs write the whole history of a new business key, one to `max_versions` versions
+/// in a row: each version changes one or two attributes of the previous one and is valid
+/// until the next one starts, and the last version is open (`valid_to` is NULL, or
+/// 9999-12-31 for NOT NULL columns) and flagged as current in `is_current`. UPDATEs of the
+/// table change a current version as an ELT job would: they insert a new current version
+/// and close the previous one.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::scd::Scd2;
+/// # use fake_sql::Table;
+/// let table = Table::init_via_sql(
+///     "create table dim_customer (customer_sk int primary key, customer_id int, city varchar(30), valid_from date, valid_to date, is_current boolean)",
+/// );
+/// let scd = Scd2::infer(&table).unwrap();
+/// assert_eq!((scd.business_key.as_str(), scd.is_current.as_deref()), ("customer_id", Some("is_current")));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scd2 {
+    /// The natural key the versions share, e.g. `customer_id`.
+    pub business_key: String,
+    /// The date or timestamp column a version is valid from.
+    pub valid_from: String,
+    /// The date or timestamp column a version is valid until.
+    pub valid_to: String,
+    /// The column flagging the current version: a boolean, `1`/`0` for numeric columns or
+    /// `Y`/`N` for text columns.
+    pub is_current: Option<String>,
+    /// Largest number of versions the history of a new business key has.
+    pub max_versions: u32,
+}
+
+impl Scd2 {
+    /// Infers the columns of a dimension from their names: `valid_from` (or `effective_from`,
+    /// `start_date`, ...), `valid_to` (or `effective_to`, `end_date`, ...), `is_current` (or
+    /// `current_flag`, `is_active`) and, as the business key, the first other column that is
+    /// not a primary key and whose name ends in `_id`, `_key`, `_code`, `_number` or `_no`.
+    ///
+    /// # Returns
+    ///
+    /// The dimension, or an error naming the column that could not be found.
+    pub fn infer(table: &Table) -> Result<Scd2, String> {
+        let named = |names: &[&str]| names.iter().find_map(|name| table.column(name)).map(|c| c.name.clone());
+        let valid_from = named(&VALID_FROM_NAMES).ok_or_else(|| format!("table `{}` has no valid_from column", table.name))?;
+        let valid_to = named(&VALID_TO_NAMES).ok_or_else(|| format!("table `{}` has no valid_to column", table.name))?;
+        let is_current = named(&IS_CURRENT_NAMES);
+        let business_key = table
+            .columns
+            .iter()
+            .filter(|c| !c.is_pkey && !c.auto_increment && c.name != valid_from && c.name != valid_to)
+            .find(|c| BUSINESS_KEY_SUFFIXES.iter().any(|suffix| c.name.ends_with(suffix)))
+            .ok_or_else(|| format!("table `{}` has no business key column", table.name))?;
+        Ok(Scd2 {
+            business_key: business_key.name.clone(),
+            valid_from,
+            valid_to,
+            is_current,
+            max_versions: DEFAULT_MAX_VERSIONS,
+        })
+    }
+
+    /// Checks that the columns of the dimension exist in `table` and have a suitable type.
+    pub fn check(&self, table: &Table) -> Result<(), String> {
+        let column = |name: &str| {
+            table
+                .column(name)
+                .ok_or_else(|| format!("column `{}.{}` of the dimension is not defined in the schema", table.name, name))
+        };
+        let business_key = column(&self.business_key)?;
+        if business_key.is_unique || (business_key.is_pkey && table.pkey_columns.len() <= 1) {
+            return Err(format!("the business key `{}.{}` repeats across versions and cannot be unique", table.name, business_key.name));
+        }
+        for name in [&self.valid_from, &self.valid_to] {
+            if !matches!(column(name)?.column_type.as_str(), "date" | "datetime" | "timestamp" | "timestamptz") {
+                return Err(format!("the validity column `{}.{}` must be a date or timestamp", table.name, name));
+            }
+        }
+        if let Some(name) = &self.is_current {
+            let flag = column(name)?;
+            if !flag.is_numeric() && !matches!(flag.column_type.as_str(), "boolean" | "bool" | "bit" | "varchar" | "text") {
+                return Err(format!("the current flag `{}.{}` must be a boolean, number or text", table.name, name));
+            }
+        }
+        if self.max_versions == 0 {
+            return Err("a dimension needs at least one version per business key".to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns the names of the columns the dimension sets.
+    pub fn columns(&self) -> Vec<String> {
+        let mut columns = vec![self.business_key.clone(), self.valid_from.clone(), self.valid_to.clone()];
+        columns.extend(self.is_current.clone());
+        columns
+    }
+}
+
+/// The versions written for a dimension: the rest of the history being inserted, and a
+/// sample of the current versions later changes replace.
+#[derive(Clone, Debug)]
+pub(crate) struct History {
+    settings: Scd2,
+    /// The business key whose history is being inserted, with the start of its remaining versions.
+    key: Option<(SqlValue, Vec<NaiveDateTime>)>,
+    /// The last version written of the business key being inserted.
+    previous: Option<Vec<SqlValue>>,
+    /// The current version the next row replaces, see [`History::start_change`].
+    change: Option<Vec<SqlValue>>,
+    /// A sample of the current versions, keyed by table name.
+    current: DataPool,
+}
+
+impl History {
+    pub(crate) fn new(settings: Scd2) -> History {
+        History {
+            settings,
+            key: None,
+            previous: None,
+            change: None,
+            current: DataPool::default(),
+        }
+    }
+
+    /// Returns the settings of the dimension.
+    pub(crate) fn settings(&self) -> &Scd2 {
+        &self.settings
+    }
+
+    /// Returns the sample of current versions, which follows the schema changes of the table.
+    pub(crate) fn current_mut(&mut self) -> &mut DataPool {
+        &mut self.current
+    }
+
+    /// Returns whether the next row starts the history of a new business key.
+    pub(crate) fn needs_key(&self) -> bool {
+        self.change.is_none() && self.key.is_none()
+    }
+
+    /// Picks a current version of the table called `table` for the next row to replace.
+    ///
+    /// # Returns
+    ///
+    /// The version, or `None` while no current version is remembered.
+    pub(crate) fn start_change<R: Rng + ?Sized>(&mut self, table: &str, rng: &mut R) -> Option<Vec<SqlValue>> {
+        let version = self.current.take(table, rng)?;
+        self.change = Some(version.clone());
+        Some(version)
+    }
+
+    /// Turns the drawn `row` of `table` into the next version: the next one of the history
+    /// being inserted, or the one replacing the version picked by [`History::start_change`].
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The dimension table.
+    /// * `row` - The drawn row, one value per column.
+    /// * `new_key` - The business key of a new history, given when [`History::needs_key`].
+    /// * `rng` - The random number generator used for the validity and the changed attributes.
+    pub(crate) fn apply<R: Rng + ?Sized>(&mut self, table: &Table, row: &mut [SqlValue], new_key: Option<SqlValue>, rng: &mut R) {
+        let position = |name: &str| table.columns.iter().position(|c| c.name == name);
+        let Some(from_index) = position(&self.settings.valid_from) else { return };
+        let (key, previous, from, to) = match self.change.take() {
+            Some(old) => {
+                let started = time_of(&old[from_index]).unwrap_or_else(|| start_of(&table.columns[from_index]));
+                let from = started + Duration::days(rng.gen_range(1..=30)) + Duration::seconds(rng.gen_range(0..86_400));
+                let key = position(&self.settings.business_key).map_or(SqlValue::Null, |i| old[i].clone());
+                (key, Some(old), from, None)
+            }
+            None => {
+                if self.key.is_none() {
+                    let column = &table.columns[from_index];
+                    let versions = rng.gen_range(1..=self.settings.max_versions.max(1));
+                    let mut starts: Vec<NaiveDateTime> = (0..versions).map(|_| random_time(column, rng)).collect();
+                    starts.sort_unstable();
+                    starts.dedup();
+                    self.key = Some((new_key.unwrap_or(SqlValue::Null), starts));
+                    self.previous = None;
+                }
+                let (key, starts) = self.key.as_mut().unwrap();
+                let key = key.clone();
+                let from = starts.remove(0);
+                let to = starts.first().copied();
+                if starts.is_empty() {
+                    self.key = None;
+                }
+                (key, self.previous.take(), from, to)
+            }
+        };
+
+        if let Some(previous) = previous {
+            // Keep the attributes of the previous version but one or two
+            let fixed = self.settings.columns();
+            let attributes: Vec<usize> = (0..table.columns.len())
+                .filter(|&i| {
+                    let column = &table.columns[i];
+                    !column.is_pkey && !column.auto_increment && !column.is_unique && !fixed.contains(&column.name)
+                })
+                .collect();
+            let changed = if attributes.is_empty() {
+                vec![]
+            } else {
+                let count = rng.gen_range(1..=attributes.len().min(2));
+                index::sample(rng, attributes.len(), count).into_vec()
+            };
+            for (n, &i) in attributes.iter().enumerate() {
+                let unique = table.unique_keys.iter().any(|key| key.contains(&table.columns[i].name));
+                if !changed.contains(&n) && !unique {
+                    row[i] = previous[i].clone();
+                }
+            }
+        }
+        if let Some(i) = position(&self.settings.business_key) {
+            row[i] = key;
+        }
+        row[from_index] = time_value(&table.columns[from_index], from);
+        if let Some(i) = position(&self.settings.valid_to) {
+            let column = &table.columns[i];
+            row[i] = match to {
+                Some(to) => time_value(column, to),
+                None if column.is_nullable => SqlValue::Null,
+                None => time_value(column, NaiveDate::from_ymd_opt(9999, 12, 31).unwrap().and_hms_opt(23, 59, 59).unwrap()),
+            };
+        }
+        if let Some(i) = self.settings.is_current.as_deref().and_then(position) {
+            row[i] = flag_value(&table.columns[i], to.is_none());
+        }
+        if to.is_none() {
+            self.current.insert(&table.name, row.to_vec(), rng);
+        } else {
+            self.previous = Some(row.to_vec());
+        }
+    }
+
+    /// Renders the UPDATE closing the version `old` of `table` when `new` replaces it, e.g.
+    /// `UPDATE dim SET valid_to = DATE '2024-03-01', is_current = FALSE WHERE dim_sk = 4;`.
+    pub(crate) fn close_statement(&self, table: &Table, old: &[SqlValue], new: &[SqlValue], dialect: Dialect) -> String {
+        let position = |name: &str| table.columns.iter().position(|c| c.name == name);
+        let mut assignments = Vec::new();
+        if let (Some(to), Some(from)) = (position(&self.settings.valid_to), position(&self.settings.valid_from)) {
+            let value = time_of(&new[from]).map_or(SqlValue::Null, |from| time_value(&table.columns[to], from));
+            assignments.push(format!("{} = {}", dialect.identifier(&self.settings.valid_to), dialect.literal(&value)));
+        }
+        if let Some(i) = self.settings.is_current.as_deref().and_then(position) {
+            let flag = flag_value(&table.columns[i], false);
+            assignments.push(format!("{} = {}", dialect.identifier(&table.columns[i].name), dialect.literal(&flag)));
+        }
+        let condition = if table.pkey_columns.is_empty() {
+            // Without a surrogate key, a version is identified by its business key and start
+            [&self.settings.business_key, &self.settings.valid_from]
+                .into_iter()
+                .filter_map(|name| position(name))
+                .map(|i| format!("{} = {}", dialect.identifier(&table.columns[i].name), dialect.literal(&old[i])))
+                .collect::<Vec<String>>()
+                .join(" AND ")
+        } else {
+            table.key_condition(dialect, old)
+        };
+        format!("UPDATE {} SET {} WHERE {};", dialect.identifier(&table.name), assignments.join(", "), condition)
+    }
+}
+
+/// Returns the start of the date range of `column`.
+fn start_of(column: &Column) -> NaiveDateTime {
+    column.date_range().start.and_time(NaiveTime::MIN)
+}
+
+/// Returns a random time within the date range of `column`, at midnight for date columns.
+fn random_time<R: Rng + ?Sized>(column: &Column, rng: &mut R) -> NaiveDateTime {
+    match column.column_type.as_str() {
+        "date" => column.date_range().random_date(rng).and_time(NaiveTime::MIN),
+        _ => column.date_range().random_timestamp(rng),
+    }
+}
+
+/// Returns the time of a date or timestamp value.
+fn time_of(value: &SqlValue) -> Option<NaiveDateTime> {
+    match value {
+        SqlValue::Date(date) => Some(date.and_time(NaiveTime::MIN)),
+        SqlValue::Timestamp(timestamp) => Some(*timestamp),
+        SqlValue::TimestampTz(timestamp) => Some(timestamp.naive_utc()),
+        _ => None,
+    }
+}
+
+/// Returns the value of the date or timestamp `column` for `time`.
+fn time_value(column: &Column, time: NaiveDateTime) -> SqlValue {
+    match column.column_type.as_str() {
+        "date" => SqlValue::Date(time.date()),
+        "timestamptz" => SqlValue::TimestampTz(time.and_utc().fixed_offset()),
+        _ => SqlValue::Timestamp(time),
+    }
+}
+
+/// Returns the value of the current flag `column`.
+fn flag_value(column: &Column, current: bool) -> SqlValue {
+    match column.column_type.as_str() {
+        "varchar" | "text" => SqlValue::Text(if current { "Y" } else { "N" }.to_string()),
+        _ if column.is_numeric() => SqlValue::Integer(current as i64),
+        _ => SqlValue::Boolean(current),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const DIMENSION: &str =
+        "create table dim (dim_sk int primary key, customer_code varchar(10), city varchar(20), tier varchar(10), valid_from date not null, valid_to date, is_current char(1))";
+
+    #[test]
+    fn test_infer() {
+        let table = Table::init_via_sql(DIMENSION);
+        let scd = Scd2::infer(&table).unwrap();
+        assert_eq!(scd.columns(), ["customer_code", "valid_from", "valid_to", "is_current"]);
+        assert!(scd.check(&table).is_err(), "char is no flag type");
+        let table = Table::init_via_sql("create table dim (id int primary key, valid_from date, valid_to date)");
+        assert!(Scd2::infer(&table).is_err());
+        let scd = Scd2 { business_key: "id".to_string(), valid_from: "valid_from".to_string(), valid_to: "valid_to".to_string(), is_current: None, max_versions: 1 };
+        assert!(scd.check(&table).is_err());
+    }
+
+    #[test]
+    fn test_history() {
+        let table = Table::init_via_sql(&DIMENSION.replace("char(1)", "varchar(1)"));
+        let mut history = History::new(Scd2::infer(&table).unwrap());
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut rows = Vec::new();
+        for n in 1..=50 {
+            let key = history.needs_key().then(|| SqlValue::Integer(n));
+            let mut row = table.generate_row(&mut rng);
+            history.apply(&table, &mut row, key, &mut rng);
+            rows.push(row);
+        }
+        // Every version but the last of a key is closed by the start of the next one
+        for pair in rows.windows(2) {
+            if pair[0][1] == pair[1][1] {
+                assert_eq!((&pair[0][5], &pair[0][6]), (&pair[1][4], &SqlValue::Text("N".to_string())));
+            } else {
+                assert_eq!((&pair[0][5], &pair[0][6]), (&SqlValue::Null, &SqlValue::Text("Y".to_string())));
+            }
+        }
+        assert!(rows.windows(2).any(|pair| pair[0][1] == pair[1][1]));
+
+        let old = history.start_change("dim", &mut rng).unwrap();
+        let mut row = table.generate_row(&mut rng);
+        history.apply(&table, &mut row, None, &mut rng);
+        assert_eq!((&row[1], &row[5], &row[6]), (&old[1], &SqlValue::Null, &SqlValue::Text("Y".to_string())));
+        assert!(time_of(&row[4]) > time_of(&old[4]));
+        let SqlValue::Date(from) = row[4] else { panic!() };
+        assert_eq!(
+            history.close_statement(&table, &old, &row, Dialect::Postgres),
+            format!("UPDATE dim SET valid_to = DATE '{}', is_current = 'N' WHERE dim_sk = {};", from, Dialect::Postgres.literal(&old[0]))
+        );
+    }
+}
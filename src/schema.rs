@@ -0,0 +1,1161 @@
+//! Loading table definitions from DDL scripts.
+
+use crate::check::split_statements;
+use crate::geo;
+use crate::dialect::Dialect;
+use crate::models::{Column, Table, TableCheck};
+use crate::pool::ValuePool;
+use crate::value::SqlValue;
+use sqlparser::ast::{
+    AlterTableOperation, BinaryOperator, ColumnDef, ColumnOption, CommentDef, CommentObject, CreateTable, CreateTableOptions, DataType, EnumMember, Expr, Ident, IndexColumn,
+    ObjectName, SqlOption, Statement, TableConstraint, UnaryOperator, UserDefinedTypeRepresentation, Value,
+};
+use sqlparser::dialect::{GenericDialect, MsSqlDialect, MySqlDialect};
+use sqlparser::parser::{Parser, ParserError};
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The schema used when no schema file is given: `orders`, `customers` and `products`.
+pub const DEFAULT_SCHEMA: &str = "
+create table orders(order_id number(10) primary key, order_date date, customer_id number(10) references customers(customer_id));
+create table customers(customer_id number(10) primary key, customer_name varchar(255), customer_email varchar(255));
+create table products(product_id number(10) primary key, product_name varchar(255), product_price number(10, 2));
+";
+
+/// Parses every `CREATE TABLE` statement of a SQL script into a `Table`.
+///
+/// The script is parsed with `sqlparser`, so quoted identifiers, comments and nested
+/// parentheses are handled. `COMMENT ON TABLE` and `COMMENT ON COLUMN` statements set the
+/// comments of the tables defined before them; other statements are ignored. When the
+/// script contains statements `sqlparser` does not understand, such as the `SET` and
+/// `CREATE FUNCTION` statements of a `pg_dump --schema-only` script, it is split into
+/// statements on the semicolons outside of quotes, comments and dollar-quoted function
+/// bodies, and only the statements that fail to parse and are not `CREATE TABLE` are skipped.
+///
+/// # Arguments
+///
+/// * `sql` - A string slice that holds the SQL script.
+///
+/// # Returns
+///
+/// The tables in the order they are defined in the script, or a message describing
+/// the `CREATE TABLE` statement that could not be parsed.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::schema::parse_schema;
+/// let tables = parse_schema("
+///     create table a (id number(10) primary key);
+///     -- not a table
+///     create index a_idx on a (id);
+///     create table b (id number(10) primary key, a_id number(10) references a (id));
+/// ").unwrap();
+/// assert_eq!(tables.len(), 2);
+/// assert_eq!(tables[1].name, "b");
+/// ```
+pub fn parse_schema(sql: &str) -> Result<Vec<Table>, String> {
+    if let Ok(statements) = parse_sql(sql) {
+        return Ok(tables_from_ast(&statements));
+    }
+
+    // Fall back to one statement at a time so unsupported statements can be skipped
+    // MySQL dumps quote names with backticks and escape quotes in strings with backslashes
+    let dialect = if sql.contains('`') { Dialect::MySql } else { Dialect::Postgres };
+    let mut statements = vec![];
+    for (_, statement) in split_statements(sql, dialect) {
+        match parse_sql(statement) {
+            Ok(parsed) => statements.extend(parsed),
+            Err(err) if is_create_table(statement) => {
+                return Err(format!("{} in `{}`", err, statement));
+            }
+            Err(_) => {}
+        }
+    }
+    Ok(tables_from_ast(&statements))
+}
+
+/// Orders `tables` so that every table comes after the tables it references.
+///
+/// Tables keep their relative order where the foreign keys allow it. References to
+/// unknown tables and to the table itself are ignored, and tables caught in a cycle of
+/// references are appended in their original order.
+///
+/// # Arguments
+///
+/// * `tables` - The tables of the schema.
+///
+/// # Returns
+///
+/// The positions of the tables in `tables`, parents first.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::schema::{dependency_order, parse_schema, DEFAULT_SCHEMA};
+/// let tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+/// let names: Vec<&str> = dependency_order(&tables).iter().map(|&i| tables[i].name.as_str()).collect();
+/// assert_eq!(names, ["customers", "orders", "products"]);
+/// ```
+pub fn dependency_order(tables: &[Table]) -> Vec<usize> {
+    let parents: Vec<Vec<usize>> = tables
+        .iter()
+        .map(|table| {
+            table
+                .columns
+                .iter()
+                .filter_map(|c| c.ref_table.as_ref())
+                .filter_map(|parent| tables.iter().position(|t| &t.name == parent))
+                .filter(|&parent| tables[parent].name != table.name)
+                .collect()
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(tables.len());
+    let mut placed = vec![false; tables.len()];
+    while order.len() < tables.len() {
+        // Place the first table whose parents are all placed, or break a cycle
+        let next = (0..tables.len())
+            .find(|&i| !placed[i] && parents[i].iter().all(|&p| placed[p]))
+            .or_else(|| (0..tables.len()).find(|&i| !placed[i]))
+            .unwrap();
+        placed[next] = true;
+        order.push(next);
+    }
+    order
+}
+
+/// Orders the tables called `names` so every table comes after the tables it references
+/// among them, moving a parent just ahead of its first child and otherwise keeping the
+/// given order. Names of tables that are not in `tables` keep their place.
+///
+/// # Returns
+///
+/// The positions of the names in `names`, parents first.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::schema::{parents_first, parse_schema, DEFAULT_SCHEMA};
+/// let tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+/// let names = ["products".to_string(), "orders".to_string(), "customers".to_string()];
+/// assert_eq!(parents_first(&tables, &names), [0, 2, 1]);
+/// ```
+pub fn parents_first(tables: &[Table], names: &[String]) -> Vec<usize> {
+    let references = |child: &str, parent: &str| {
+        child != parent && tables.iter().find(|t| t.name == child).is_some_and(|t| t.columns.iter().any(|c| c.ref_table.as_deref() == Some(parent)))
+    };
+    // 0: not visited, 1: being placed, 2: placed; a table being placed breaks a cycle
+    fn visit(i: usize, names: &[String], references: &dyn Fn(&str, &str) -> bool, state: &mut [u8], order: &mut Vec<usize>) {
+        if state[i] != 0 {
+            return;
+        }
+        state[i] = 1;
+        for parent in 0..names.len() {
+            if references(&names[i], &names[parent]) {
+                visit(parent, names, references, state, order);
+            }
+        }
+        state[i] = 2;
+        order.push(i);
+    }
+    let mut state = vec![0; names.len()];
+    let mut order = Vec::with_capacity(names.len());
+    for i in 0..names.len() {
+        visit(i, names, &references, &mut state, &mut order);
+    }
+    order
+}
+
+/// Parses a single `CREATE TABLE` statement.
+///
+/// # Arguments
+///
+/// * `sql` - A string slice that holds the `CREATE TABLE` statement.
+///
+/// # Returns
+///
+/// The table, or a message describing why the statement could not be parsed.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::schema::parse_create_table;
+/// let table = parse_create_table(r#"CREATE TABLE "Order Items" (id int PRIMARY KEY, note varchar(20))"#).unwrap();
+/// assert_eq!(table.name, "Order Items");
+/// assert!(parse_create_table("create index i on t (id)").is_err());
+/// ```
+pub fn parse_create_table(sql: &str) -> Result<Table, String> {
+    let statements = parse_sql(sql).map_err(|err| err.to_string())?;
+    match statements.as_slice() {
+        [statement] => create_table(statement)
+            .map(table_from_ast)
+            .ok_or_else(|| format!("expected a CREATE TABLE statement, found `{}`", sql.trim())),
+        _ => Err(format!("expected a single CREATE TABLE statement, found {}", statements.len())),
+    }
+}
+
+/// Reads a SQL script from `path` and parses its `CREATE TABLE` statements.
+///
+/// # Arguments
+///
+/// * `path` - Path of the file holding the SQL script.
+///
+/// # Returns
+///
+/// The tables defined in the file, or the I/O error raised while reading it.
+pub fn load_schema<P: AsRef<Path>>(path: P) -> io::Result<Vec<Table>> {
+    parse_schema(&fs::read_to_string(path)?).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Parses a SQL script, falling back to the SQL Server dialect for `[quoted]` identifiers
+/// and to the MySQL dialect for the `'it\'s'` strings of `mysqldump` scripts.
+///
+/// The generic dialect knows `"quoted"` and `` `quoted` `` identifiers but not brackets
+/// or backslash escapes. When every dialect fails, the error of the generic dialect is
+/// returned.
+fn parse_sql(sql: &str) -> Result<Vec<Statement>, ParserError> {
+    Parser::parse_sql(&GenericDialect {}, sql)
+        .or_else(|err| Parser::parse_sql(&MsSqlDialect {}, sql).map_err(|_| err))
+        .or_else(|err| Parser::parse_sql(&MySqlDialect {}, sql).map_err(|_| err))
+}
+
+/// Returns the `CREATE TABLE` of a parsed statement, if it is one.
+fn create_table(statement: &Statement) -> Option<&CreateTable> {
+    match statement {
+        Statement::CreateTable(create_table) => Some(create_table),
+        _ => None,
+    }
+}
+
+/// Converts the parsed `CREATE TABLE` statements of a script into tables and applies the
+/// `COMMENT ON` and `ALTER TABLE ... ADD CONSTRAINT` statements to them.
+fn tables_from_ast(statements: &[Statement]) -> Vec<Table> {
+    let mut tables: Vec<Table> = vec![];
+    // Composite types by name, with their attributes, and enum types with their members
+    let mut types: Vec<(String, Vec<Column>)> = vec![];
+    let mut enums: Vec<(String, Vec<String>)> = vec![];
+    for statement in statements {
+        match statement {
+            Statement::CreateTable(create_table) => tables.push(table_from_ast(create_table)),
+            Statement::AlterTable(_) => alter_table(&mut tables, statement),
+            Statement::CreateType {
+                name,
+                representation: Some(UserDefinedTypeRepresentation::Composite { attributes }),
+            } => {
+                let mut attributes: Vec<Column> = attributes
+                    .iter()
+                    .map(|attribute| {
+                        let (column_type, length, decimal_places) = split_data_type(&attribute.data_type.to_string());
+                        Column::new(&identifier(&attribute.name), &column_type, length, decimal_places)
+                    })
+                    .collect();
+                for attribute in &mut attributes {
+                    resolve_type(attribute, &types, &enums);
+                }
+                types.push((object_name(name), attributes));
+            }
+            Statement::CreateType {
+                name,
+                representation: Some(UserDefinedTypeRepresentation::Enum { labels }),
+            } => enums.push((object_name(name), labels.iter().map(|label| label.value.clone()).collect())),
+            Statement::Comment {
+                object_type,
+                object_name,
+                comment,
+                ..
+            } => {
+                // Column names are qualified by their table, e.g. `orders.order_id`
+                let parts: Vec<String> = object_name.0.iter().filter_map(|part| part.as_ident()).map(identifier).collect();
+                let (table_name, column_name) = match (object_type, parts.as_slice()) {
+                    (CommentObject::Table, [.., table]) => (table, None),
+                    (CommentObject::Column, [.., table, column]) => (table, Some(column)),
+                    _ => continue,
+                };
+                let Some(table) = tables.iter_mut().find(|t| &t.name == table_name) else {
+                    continue;
+                };
+                match column_name {
+                    Some(name) => {
+                        if let Some(column) = table.columns.iter_mut().find(|c| &c.name == name) {
+                            column.comment = comment.clone();
+                        }
+                    }
+                    None => table.set_comment(comment.clone()),
+                }
+            }
+            _ => {}
+        }
+    }
+    for column in tables.iter_mut().flat_map(|table| &mut table.columns) {
+        resolve_type(column, &types, &enums);
+    }
+    tables
+}
+
+/// Sets the attributes of a column of one of the composite `types` and the members of a
+/// column of one of the `enums`, or of an array of one.
+fn resolve_type(column: &mut Column, types: &[(String, Vec<Column>)], enums: &[(String, Vec<String>)]) {
+    let name = column.column_type.trim_end_matches("[]");
+    if let Some((_, attributes)) = types.iter().find(|(type_name, _)| type_name == name) {
+        column.attributes = attributes.clone();
+    }
+    if let Some((_, labels)) = enums.iter().find(|(type_name, _)| type_name == name) {
+        column.labels = labels.clone();
+    }
+}
+
+/// Converts a parsed `CREATE TABLE` statement into a `Table`.
+///
+/// Table-level `PRIMARY KEY (...)`, `UNIQUE (...)` and `FOREIGN KEY (...) REFERENCES ...`
+/// clauses are applied to the columns they name, as are `CHECK (...)` clauses on a single
+/// column; other table constraints are ignored.
+fn table_from_ast(create_table: &CreateTable) -> Table {
+    let columns = create_table.columns.iter().map(column_from_ast).collect();
+    let mut table = Table::init(object_name(&create_table.name), columns);
+    // MySQL's `COMMENT='...'` is a table option
+    let options = match &create_table.table_options {
+        CreateTableOptions::Plain(options) | CreateTableOptions::With(options) => options.as_slice(),
+        _ => &[],
+    };
+    let comment = create_table.comment.iter().chain(options.iter().filter_map(|option| match option {
+        SqlOption::Comment(comment) => Some(comment),
+        _ => None,
+    }));
+    table.set_comment(comment.map(|comment| match comment {
+        CommentDef::WithEq(text) | CommentDef::WithoutEq(text) => text.clone(),
+    }).next());
+    for constraint in &create_table.constraints {
+        add_constraint(&mut table, constraint);
+    }
+    table
+}
+
+/// Applies the `ADD CONSTRAINT` clauses of a parsed `ALTER TABLE` statement to the table
+/// it names, as `pg_dump` declares keys after the data; other statements and clauses are
+/// ignored.
+pub(crate) fn alter_table(tables: &mut [Table], statement: &Statement) {
+    let Statement::AlterTable(alter_table) = statement else { return };
+    let name = object_name(&alter_table.name);
+    let Some(table) = tables.iter_mut().find(|t| t.name == name) else { return };
+    for operation in &alter_table.operations {
+        if let AlterTableOperation::AddConstraint { constraint, .. } = operation {
+            add_constraint(table, constraint);
+        }
+    }
+}
+
+/// Applies a table-level `PRIMARY KEY (...)`, `UNIQUE (...)`, `FOREIGN KEY (...)` or
+/// `CHECK (...)` constraint to the columns it names. Checks of several columns are kept
+/// as [`TableCheck`]s of the table.
+fn add_constraint(table: &mut Table, constraint: &TableConstraint) {
+    match constraint {
+        TableConstraint::PrimaryKey(primary_key) => {
+            table.set_primary_key(primary_key.columns.iter().map(index_column).collect());
+        }
+        TableConstraint::Unique(unique) => table.add_unique_key(unique.columns.iter().map(index_column).collect()),
+        TableConstraint::ForeignKey(foreign_key) => {
+            let ref_table = object_name(&foreign_key.foreign_table);
+            for (i, name) in foreign_key.columns.iter().map(identifier).enumerate() {
+                if let Some(column) = table.columns.iter_mut().find(|c| c.name == name) {
+                    column.ref_table = Some(ref_table.clone());
+                    column.ref_column = foreign_key.referred_columns.get(i).map(identifier);
+                }
+            }
+        }
+        TableConstraint::Check(check) => {
+            let mut names = vec![];
+            if check_columns(&check.expr, &mut names) {
+                names.sort();
+                names.dedup();
+                if let [name] = names.as_slice() {
+                    if let Some(column) = table.columns.iter_mut().find(|c| &c.name == name) {
+                        add_check(column, &check.expr);
+                        return;
+                    }
+                }
+            }
+            table.checks.push(table_check(&check.expr));
+        }
+        _ => {}
+    }
+}
+
+/// Returns the column name of an index column such as `a` in `PRIMARY KEY (a, b)`.
+fn index_column(column: &IndexColumn) -> String {
+    match &column.column.expr {
+        Expr::Identifier(ident) => identifier(ident),
+        expr => expr.to_string(),
+    }
+}
+
+/// Converts a parsed column definition into a `Column`.
+fn column_from_ast(column: &ColumnDef) -> Column {
+    let (column_type, length, decimal_places, labels) = match &column.data_type {
+        // MySQL declares the members of an enum with the column
+        DataType::Enum(members, _) => {
+            let labels = members.iter().map(|member| match member {
+                EnumMember::Name(name) | EnumMember::NamedValue(name, _) => name.clone(),
+            });
+            ("enum".to_string(), None, None, labels.collect())
+        }
+        data_type => {
+            let (column_type, length, decimal_places) = split_data_type(&data_type.to_string());
+            (column_type, length, decimal_places, vec![])
+        }
+    };
+    let mut is_pkey = false;
+    let mut is_not_null = false;
+    let mut is_unique = false;
+    let mut reference = None;
+    let mut comment = None;
+    let mut default_value = None;
+    let mut checks = vec![];
+    // Postgres `serial` types are integers filled from a sequence
+    let (column_type, mut auto_increment) = match column_type.as_str() {
+        "serial" => ("int".to_string(), true),
+        "bigserial" => ("bigint".to_string(), true),
+        "smallserial" => ("smallint".to_string(), true),
+        _ => (column_type, false),
+    };
+    for option in &column.options {
+        match &option.option {
+            ColumnOption::PrimaryKey(_) => is_pkey = true,
+            ColumnOption::NotNull => is_not_null = true,
+            ColumnOption::Unique(_) => is_unique = true,
+            ColumnOption::ForeignKey(foreign_key) => reference = Some(foreign_key),
+            ColumnOption::Comment(text) => comment = Some(text.clone()),
+            ColumnOption::Default(expr) => default_value = Some(expr.to_string()),
+            ColumnOption::Check(check) => checks.push(&check.expr),
+            // `GENERATED ... AS IDENTITY`, unlike `GENERATED ALWAYS AS (expr)`, has no expression
+            ColumnOption::Generated { generation_expr: None, .. } | ColumnOption::Identity(_) => auto_increment = true,
+            ColumnOption::DialectSpecific(tokens) => {
+                auto_increment |= tokens.iter().any(|token| {
+                    matches!(token.to_string().to_uppercase().as_str(), "AUTO_INCREMENT" | "AUTOINCREMENT")
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let mut column = Column {
+        name: identifier(&column.name),
+        column_type,
+        length,
+        decimal_places,
+        is_nullable: !is_pkey && !is_not_null,
+        is_pkey,
+        is_unique: is_unique && !is_pkey,
+        ref_table: reference.map(|foreign_key| object_name(&foreign_key.foreign_table)),
+        ref_column: reference.and_then(|foreign_key| foreign_key.referred_columns.first()).map(identifier),
+        provider: None,
+        null_rate: None,
+        auto_increment,
+        min_value: None,
+        max_value: None,
+        max_length_rate: None,
+        date_range: None,
+        distribution: None,
+        pool: None,
+        locale: None,
+        comment,
+        default_value,
+        check_expr: None,
+        template: None,
+        pii_free: false,
+        timezones: None,
+        attributes: vec![],
+        payload: None,
+        bounding_box: None,
+        labels,
+        blob_size: None,
+        blob_encoding: None,
+        custom_generator: None,
+    };
+    for check in checks {
+        add_check(&mut column, check);
+    }
+    column
+}
+
+/// Adds a CHECK constraint to `column`, joined to an existing one with `AND`, and narrows
+/// the generated values to the ones it accepts.
+fn add_check(column: &mut Column, check: &Expr) {
+    column.check_expr = Some(match column.check_expr.take() {
+        Some(existing) => format!("({}) AND ({})", existing, check),
+        None => check.to_string(),
+    });
+    narrow(column, check);
+}
+
+/// Narrows the generated values of `column` to a CHECK condition: comparisons of the
+/// column with numbers, `BETWEEN` and `IN (...)` lists, joined by `AND`. Other conditions
+/// leave the values unchanged.
+fn narrow(column: &mut Column, condition: &Expr) {
+    match condition {
+        Expr::Nested(inner) => narrow(column, inner),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            narrow(column, left);
+            narrow(column, right);
+        }
+        Expr::BinaryOp { left, op, right } => {
+            // `0 <= price` is `price >= 0` with the sides swapped
+            let (op, bound) = match (is_column(column, left), is_column(column, right)) {
+                (true, _) => (op.clone(), number(right)),
+                (_, true) => match op {
+                    BinaryOperator::Gt => (BinaryOperator::Lt, number(left)),
+                    BinaryOperator::GtEq => (BinaryOperator::LtEq, number(left)),
+                    BinaryOperator::Lt => (BinaryOperator::Gt, number(left)),
+                    BinaryOperator::LtEq => (BinaryOperator::GtEq, number(left)),
+                    op => (op.clone(), number(left)),
+                },
+                _ => return,
+            };
+            let Some(bound) = bound else {
+                return;
+            };
+            // Strict bounds exclude the bound itself, the smallest step of the column's scale
+            let step = 10f64.powi(-column.decimal_places.unwrap_or(0).max(0));
+            match op {
+                BinaryOperator::Gt => raise_min(column, bound + step),
+                BinaryOperator::GtEq => raise_min(column, bound),
+                BinaryOperator::Lt => lower_max(column, bound - step),
+                BinaryOperator::LtEq => lower_max(column, bound),
+                BinaryOperator::Eq => {
+                    raise_min(column, bound);
+                    lower_max(column, bound);
+                }
+                _ => {}
+            }
+        }
+        Expr::Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        } if is_column(column, expr) => {
+            if let (Some(low), Some(high)) = (number(low), number(high)) {
+                raise_min(column, low);
+                lower_max(column, high);
+            }
+        }
+        Expr::InList {
+            expr,
+            list,
+            negated: false,
+        } if is_column(column, expr) => {
+            let values: Option<Vec<(SqlValue, u32)>> = list.iter().map(|item| list_value(column, item).map(|value| (value, 1))).collect();
+            if let Some(pool) = values.and_then(|values| ValuePool::new(values).ok()) {
+                column.pool = Some(pool);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Raises the smallest generated value of a numeric column to `min` unless it is higher
+/// already, and its largest value with it if needed.
+fn raise_min(column: &mut Column, min: f64) {
+    let (current_min, current_max) = numeric_bounds(column);
+    if min > current_min {
+        column.min_value = Some(min);
+        if min > current_max {
+            column.max_value = Some(min);
+        }
+    }
+}
+
+/// Lowers the largest generated value of a numeric column to `max` unless it is lower
+/// already, and its smallest value with it if needed.
+fn lower_max(column: &mut Column, max: f64) {
+    let (current_min, current_max) = numeric_bounds(column);
+    if max < current_max {
+        column.max_value = Some(max);
+        if max < current_min {
+            column.min_value = Some(max);
+        }
+    }
+}
+
+/// Returns the smallest and largest value generated for a numeric column.
+fn numeric_bounds(column: &Column) -> (f64, f64) {
+    let factor = 10f64.powi(column.decimal_places.unwrap_or(0).max(0));
+    let (min, max) = column.numeric_range();
+    (min as f64 / factor, max as f64 / factor)
+}
+
+/// Returns `true` if `expr` refers to `column`, e.g. `price` or `p.price`.
+fn is_column(column: &Column, expr: &Expr) -> bool {
+    match expr {
+        Expr::Identifier(ident) => identifier(ident) == column.name,
+        Expr::CompoundIdentifier(idents) => idents.last().is_some_and(|ident| identifier(ident) == column.name),
+        _ => false,
+    }
+}
+
+/// Returns the value of a number literal such as `10`, `-2.5` or `(0)`.
+fn number(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Value(value) => match &value.value {
+            Value::Number(number, _) => number.parse().ok(),
+            _ => None,
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => number(expr).map(|number| -number),
+        Expr::Nested(expr) => number(expr),
+        _ => None,
+    }
+}
+
+/// Returns the value of an item of an `IN (...)` list as generated for `column`, or `None`
+/// if it is not a literal.
+fn list_value(column: &Column, item: &Expr) -> Option<SqlValue> {
+    if let Expr::Value(value) = item {
+        if let Value::SingleQuotedString(text) = &value.value {
+            return Some(SqlValue::Text(text.clone()));
+        }
+    }
+    let value = number(item)?;
+    Some(match column.decimal_places {
+        _ if !column.is_numeric() => SqlValue::Text(value.to_string()),
+        Some(scale) if scale > 0 => SqlValue::decimal(value, scale as u32),
+        _ => SqlValue::Integer(value as i64),
+    })
+}
+
+/// Reads a CHECK condition spanning several columns, e.g. `ends_at > starts_at`.
+pub(crate) fn table_check(condition: &Expr) -> TableCheck {
+    let mut columns = vec![];
+    check_columns(condition, &mut columns);
+    columns.sort();
+    columns.dedup();
+    let mut comparisons = vec![];
+    if !comparisons_of(condition, &mut comparisons) {
+        comparisons.clear();
+    }
+    TableCheck { condition: condition.to_string(), columns, comparisons }
+}
+
+/// Parses the condition of a CHECK constraint spanning several columns, as written in a
+/// configuration file.
+///
+/// # Returns
+///
+/// An error if `condition` is not a SQL expression.
+pub(crate) fn parse_table_check(condition: &str) -> Result<TableCheck, String> {
+    let expr = Parser::new(&GenericDialect {})
+        .try_with_sql(condition)
+        .and_then(|mut parser| parser.parse_expr())
+        .map_err(|err| format!("invalid check `{}`: {}", condition, err))?;
+    Ok(table_check(&expr))
+}
+
+/// Collects the comparisons of two columns joined by `AND` in `condition` into
+/// `comparisons`, or returns `false` if it holds other expressions.
+fn comparisons_of(condition: &Expr, comparisons: &mut Vec<(String, Vec<Ordering>, String)>) -> bool {
+    let name = |expr: &Expr| match expr {
+        Expr::Identifier(ident) => Some(identifier(ident)),
+        Expr::CompoundIdentifier(idents) => idents.last().map(identifier),
+        _ => None,
+    };
+    match condition {
+        Expr::Nested(inner) => comparisons_of(inner, comparisons),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => comparisons_of(left, comparisons) && comparisons_of(right, comparisons),
+        Expr::BinaryOp { left, op, right } => {
+            let orderings = match op {
+                BinaryOperator::Eq => vec![Ordering::Equal],
+                BinaryOperator::NotEq => vec![Ordering::Less, Ordering::Greater],
+                BinaryOperator::Lt => vec![Ordering::Less],
+                BinaryOperator::LtEq => vec![Ordering::Less, Ordering::Equal],
+                BinaryOperator::Gt => vec![Ordering::Greater],
+                BinaryOperator::GtEq => vec![Ordering::Greater, Ordering::Equal],
+                _ => return false,
+            };
+            match (name(left), name(right)) {
+                (Some(left), Some(right)) if left != right => {
+                    comparisons.push((left, orderings, right));
+                    true
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Collects the columns a CHECK condition refers to into `columns`, or returns `false` if
+/// it holds expressions other than column references, literals and operators.
+fn check_columns(condition: &Expr, columns: &mut Vec<String>) -> bool {
+    match condition {
+        Expr::Identifier(ident) => {
+            columns.push(identifier(ident));
+            true
+        }
+        Expr::CompoundIdentifier(idents) => {
+            columns.extend(idents.last().map(identifier));
+            true
+        }
+        Expr::Value(_) => true,
+        Expr::Nested(expr) | Expr::UnaryOp { expr, .. } | Expr::IsNull(expr) | Expr::IsNotNull(expr) => check_columns(expr, columns),
+        Expr::BinaryOp { left, right, .. } => check_columns(left, columns) && check_columns(right, columns),
+        Expr::Between { expr, low, high, .. } => {
+            check_columns(expr, columns) && check_columns(low, columns) && check_columns(high, columns)
+        }
+        Expr::InList { expr, list, .. } => check_columns(expr, columns) && list.iter().all(|item| check_columns(item, columns)),
+        _ => false,
+    }
+}
+
+/// Splits a data type such as `NUMBER(10,2)` into its lowercase name, length and scale.
+/// Postgres arrays such as `varchar(20)[]` keep the length and scale of their elements.
+fn split_data_type(data_type: &str) -> (String, Option<i32>, Option<i32>) {
+    // The bounds of `int[3]` are not enforced, so they are dropped
+    if let Some(open) = data_type.trim_end().strip_suffix(']').and_then(|rest| rest.rfind('[')) {
+        let (name, length, decimal_places) = split_data_type(&data_type[..open]);
+        return (format!("{}[]", name), length, decimal_places);
+    }
+    let (name, args) = match (data_type.find('('), data_type.find(')')) {
+        (Some(open), Some(close)) if open < close => (
+            format!("{} {}", &data_type[..open], &data_type[close + 1..]),
+            &data_type[open + 1..close],
+        ),
+        _ => (data_type.to_string(), ""),
+    };
+    // Non-numeric arguments such as `varchar(max)` are ignored
+    let mut numbers = args.split(',').map(|arg| arg.trim().parse().ok());
+    let (length, decimal_places) = (numbers.next().flatten(), numbers.next().flatten());
+    // MySQL's `int(11) unsigned` only changes the sign of the integer
+    let name = name
+        .split_whitespace()
+        .filter(|word| !["unsigned", "signed"].contains(&word.to_lowercase().as_str()))
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase();
+    match canonical_type(&name) {
+        // MySQL stores booleans as `tinyint(1)`
+        "tinyint" if length == Some(1) => ("boolean".to_string(), None, None),
+        // The length of an integer is a display width, and floats draw two decimal places
+        name @ ("int" | "smallint" | "bigint" | "tinyint") => (name.to_string(), None, None),
+        "float" => ("float".to_string(), None, Some(2)),
+        // PostGIS declares the shape and SRID, e.g. `geometry(Point, 4326)`; values always use SRID 4326
+        name @ ("geometry" | "geography") => match args.split(',').next().and_then(|shape| geo::Shape::from_name(shape.trim())) {
+            Some(shape) => (format!("{}({})", name, shape.name().to_lowercase()), None, None),
+            None => (name.to_string(), None, None),
+        },
+        name => (name.to_string(), length, decimal_places),
+    }
+}
+
+/// Returns the type values are generated for in place of a type name of any dialect, e.g.
+/// `int` for `int4` or `integer`, `number` for `numeric` or `decimal` and `varchar` for
+/// `nvarchar2`. Spatial types become `geometry`, with the shape for MySQL's `point` and the
+/// like. Names without an alias are returned as they are.
+///
+/// # Arguments
+///
+/// * `name` - The lowercase type name, without length or scale.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::schema::canonical_type;
+/// assert_eq!(canonical_type("int4"), "int");
+/// assert_eq!(canonical_type("double precision"), "float");
+/// assert_eq!(canonical_type("character varying"), "varchar");
+/// assert_eq!(canonical_type("multipolygon"), "geometry(multipolygon)");
+/// assert_eq!(canonical_type("date"), "date");
+/// ```
+pub fn canonical_type(name: &str) -> &str {
+    match name {
+        "int" | "integer" | "int4" | "mediumint" | "int32" => "int",
+        "smallint" | "int2" => "smallint",
+        "bigint" | "int8" | "int64" => "bigint",
+        "tinyint" | "int1" => "tinyint",
+        "number" | "numeric" | "decimal" | "dec" => "number",
+        "real" | "float" | "float4" | "float8" | "double" | "double precision" | "binary_float" | "binary_double" => "float",
+        "varchar" | "varchar2" | "nvarchar2" | "nvarchar" | "character varying" | "national character varying" => "varchar",
+        "text" | "clob" | "nclob" | "ntext" | "tinytext" | "mediumtext" | "longtext" => "text",
+        "bool" | "boolean" => "boolean",
+        "datetime" | "datetime2" | "smalldatetime" => "datetime",
+        "timestamp" | "timestamp without time zone" => "timestamp",
+        "timestamptz" | "timestamp with time zone" | "datetimeoffset" => "timestamptz",
+        "uuid" | "uniqueidentifier" => "uuid",
+        "time" | "time without time zone" | "timetz" | "time with time zone" => "time",
+        "geometry" | "geometrycollection" | "sdo_geometry" => "geometry",
+        "point" => "geometry(point)",
+        "linestring" => "geometry(linestring)",
+        "polygon" => "geometry(polygon)",
+        "multipoint" => "geometry(multipoint)",
+        "multilinestring" => "geometry(multilinestring)",
+        "multipolygon" => "geometry(multipolygon)",
+        // Interval fields such as `interval day to second` only restrict the precision
+        other if other.starts_with("interval") => "interval",
+        other => other,
+    }
+}
+
+/// Returns the unqualified name of a table, e.g. `orders` for `sales.orders`.
+pub(crate) fn object_name(name: &ObjectName) -> String {
+    name.0
+        .last()
+        .and_then(|part| part.as_ident())
+        .map(identifier)
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Returns the name of an identifier: quoted identifiers keep their case, unquoted
+/// identifiers are folded to lowercase.
+pub(crate) fn identifier(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_lowercase(),
+    }
+}
+
+/// Returns `true` if the statement starts with `CREATE TABLE`, ignoring case and whitespace.
+fn is_create_table(statement: &str) -> bool {
+    let words: Vec<String> = statement.split_whitespace().take(2).map(str::to_lowercase).collect();
+    words == ["create", "table"]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted_identifiers() {
+        let sql = "
+            create table \"Order\" (\"Id\" int primary key, \"select\" varchar(10));
+            create table `group` (`Key` int, `order_id` int references \"Order\"(\"Id\"));
+            create table [user] ([Name] varchar(10), [from] date);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Order", "group", "user"]);
+        let columns: Vec<&str> = tables.iter().flat_map(|t| t.columns.iter().map(|c| c.name.as_str())).collect();
+        assert_eq!(columns, ["Id", "select", "Key", "order_id", "Name", "from"]);
+        assert_eq!(tables[1].columns[1].ref_table.as_deref(), Some("Order"));
+        assert_eq!(tables[1].columns[1].ref_column.as_deref(), Some("Id"));
+    }
+
+    #[test]
+    fn test_default_schema() {
+        let tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+        let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["orders", "customers", "products"]);
+        assert_eq!(tables[2].columns[2].decimal_places, Some(2));
+    }
+
+    #[test]
+    fn test_parse_schema_multiline() {
+        let sql = "
+            -- customers
+            CREATE TABLE customers (
+                customer_id number(10) primary key,
+                customer_name varchar(255)
+            );
+            drop table legacy;
+        ";
+        let tables = parse_schema(sql).unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].name, "customers");
+        assert_eq!(tables[0].columns.len(), 2);
+        assert!(tables[0].columns[0].is_pkey);
+    }
+
+    #[test]
+    fn test_parse_real_world_ddl() {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS sales."Order Items" (
+                "Id" bigint NOT NULL PRIMARY KEY,
+                order_id integer REFERENCES orders (order_id) ON DELETE CASCADE,
+                price numeric(12, 2) DEFAULT 0 CHECK (price >= 0),
+                note varchar2(40),
+                created timestamp with time zone DEFAULT now()
+            );
+        "#;
+        let tables = parse_schema(sql).unwrap();
+        let table = &tables[0];
+        assert_eq!(table.name, "Order Items");
+        assert_eq!(table.columns[0].name, "Id");
+        assert!(table.columns[0].is_pkey);
+        assert_eq!(table.columns[1].ref_table.as_deref(), Some("orders"));
+        assert_eq!(table.columns[1].ref_column.as_deref(), Some("order_id"));
+        assert_eq!((table.columns[2].length, table.columns[2].decimal_places), (Some(12), Some(2)));
+        assert_eq!((table.columns[3].column_type.as_str(), table.columns[3].length), ("varchar", Some(40)));
+        assert_eq!(table.columns[4].column_type, "timestamptz");
+    }
+
+    #[test]
+    fn test_table_constraints() {
+        let sql = "create table t (a int, b int, c varchar(10), primary key (b, a), foreign key (b) references x(id))";
+        let table = parse_create_table(sql).unwrap();
+        let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(table.pkey_columns, ["b", "a"]);
+        assert!(table.columns[0].is_pkey && !table.columns[0].is_nullable);
+        assert!(!table.columns[2].is_pkey);
+        assert_eq!(table.columns[1].ref_table.as_deref(), Some("x"));
+        assert_eq!(table.columns[1].ref_column.as_deref(), Some("id"));
+        assert_eq!(table.primary_key().unwrap().name, "b");
+    }
+
+    #[test]
+    fn test_comments() {
+        let sql = "
+            create table orders (order_id int primary key, status varchar(10) comment 'open or closed');
+            comment on table orders is 'Customer orders';
+            comment on column sales.orders.order_id is 'The key';
+            comment on column missing.id is 'ignored';
+        ";
+        let tables = parse_schema(sql).unwrap();
+        assert_eq!(tables[0].comment.as_deref(), Some("Customer orders"));
+        assert_eq!(tables[0].columns[0].comment.as_deref(), Some("The key"));
+        assert_eq!(tables[0].columns[1].comment.as_deref(), Some("open or closed"));
+    }
+
+    #[test]
+    fn test_unique_constraints() {
+        let table = parse_create_table("create table t (id int primary key unique, a int unique, b int, c int, unique (b), unique (b, c))").unwrap();
+        let unique: Vec<bool> = table.columns.iter().map(|c| c.is_unique).collect();
+        assert_eq!(unique, [false, true, true, false]);
+        assert_eq!(table.unique_keys, [["b", "c"]]);
+    }
+
+    #[test]
+    fn test_checks_and_defaults() {
+        let sql = "
+            create table items (
+                qty int default 1 not null check (qty > 0 and qty <= 50),
+                price numeric(6, 2) check (0 <= price),
+                status varchar(10) default 'new' check (status in ('new', 'paid')),
+                rating int,
+                starts date,
+                ends date,
+                check (rating between 1 and 5),
+                check (ends > starts),
+                check (qty * price < 1000)
+            )
+        ";
+        let table = parse_create_table(sql).unwrap();
+        let [qty, price, status, rating, starts, _] = table.columns.as_slice() else {
+            panic!("expected 6 columns");
+        };
+        assert_eq!(qty.default_value.as_deref(), Some("1"));
+        assert_eq!(qty.check_expr.as_deref(), Some("qty > 0 AND qty <= 50"));
+        assert_eq!(qty.numeric_range(), (1, 50));
+        // The default range starts above 0 already
+        assert_eq!(price.min_value, None);
+        assert_eq!(status.default_value.as_deref(), Some("'new'"));
+        assert_eq!(status.pool.as_ref().unwrap().values().count(), 2);
+        assert_eq!(rating.check_expr.as_deref(), Some("rating BETWEEN 1 AND 5"));
+        assert_eq!(rating.numeric_range(), (1, 5));
+        // Checks on several columns are kept by the table
+        assert_eq!(starts.check_expr, None);
+        assert_eq!(table.checks.len(), 2);
+        assert_eq!(table.checks[0].comparisons, [("ends".to_string(), vec![Ordering::Greater], "starts".to_string())]);
+        assert!(table.checks[0].is_supported());
+        assert_eq!((table.checks[1].condition.as_str(), table.checks[1].columns.as_slice()), ("qty * price < 1000", ["price", "qty"].map(String::from).as_slice()));
+        assert!(!table.checks[1].is_supported());
+        let create = table.generate_with(crate::models::SqlType::CreateTable, Dialect::Postgres, &mut rand::thread_rng()).text;
+        assert!(create.ends_with(", CHECK (ends > starts), CHECK (qty * price < 1000));"), "{}", create);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let SqlValue::Integer(qty) = qty.generate_value(&mut rng) else {
+                panic!("expected an integer");
+            };
+            assert!((1..=50).contains(&qty));
+            assert!(matches!(status.generate_value(&mut rng), SqlValue::Text(text) if text == "new" || text == "paid"));
+            let row = table.generate_row(&mut rng);
+            match (&row[5], &row[4]) {
+                (SqlValue::Date(ends), SqlValue::Date(starts)) => assert!(ends > starts),
+                (ends, starts) => assert!(ends == &SqlValue::Null || starts == &SqlValue::Null),
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_increment_columns() {
+        let sql = "
+            create table a (id int auto_increment primary key, n int);
+            create table b (id integer primary key autoincrement);
+            create table c (id int generated by default as identity, total int generated always as (1) stored);
+            create table d (id int identity(1,1) primary key);
+            create table e (id bigserial primary key);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let flags: Vec<bool> = tables.iter().map(|t| t.columns[0].auto_increment).collect();
+        assert_eq!(flags, [true; 5]);
+        assert!(!tables[0].columns[1].auto_increment);
+        assert!(!tables[2].columns[1].auto_increment);
+        assert_eq!(tables[4].columns[0].column_type, "bigint");
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let sql = "
+            create table items (id int primary key, order_id int references orders(id), product_id int references products(id));
+            create table orders (id int primary key, customer_id int references customers(id), parent_id int references orders(id));
+            create table customers (id int primary key, region_id int references regions(id));
+            create table products (id int primary key);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        assert_eq!(dependency_order(&tables), [2, 1, 3, 0]);
+
+        let cycle = parse_schema("create table a (b_id int references b(id)); create table b (a_id int references a(id));").unwrap();
+        assert_eq!(dependency_order(&cycle), [0, 1]);
+    }
+
+    #[test]
+    fn test_parents_first() {
+        let sql = "
+            create table items (id int primary key, order_id int references orders(id), product_id int references products(id));
+            create table orders (id int primary key, customer_id int references customers(id));
+            create table customers (id int primary key);
+            create table products (id int primary key);
+            create table notes (id int primary key);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        // Independent tables keep their place; parents move just ahead of their children
+        assert_eq!(parents_first(&tables, &names(&["notes", "products", "items", "customers", "orders"])), [0, 1, 3, 4, 2]);
+        assert_eq!(parents_first(&tables, &names(&["notes", "items", "products"])), [0, 2, 1]);
+        let cycle = parse_schema("create table a (b_id int references b(id)); create table b (a_id int references a(id));").unwrap();
+        assert_eq!(parents_first(&cycle, &names(&["a", "b"])), [1, 0]);
+    }
+
+    #[test]
+    fn test_skips_unsupported_statements() {
+        let sql = "create table a (id int primary key); grant frobnicate on a to b; create table b (id int);";
+        assert_eq!(parse_schema(sql).unwrap().len(), 2);
+        assert!(parse_schema("create table a (id int primary key,").is_err());
+    }
+
+    #[test]
+    fn test_dump_quirks() {
+        let sql = "
+            /*!40101 SET NAMES utf8mb4 */;
+            DROP TABLE IF EXISTS `users`;
+            CREATE TABLE IF NOT EXISTS `shop`.`users` (
+              `id` int(11) unsigned NOT NULL AUTO_INCREMENT COMMENT 'user id',
+              `name` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'it\\'s the name; shown',
+              PRIMARY KEY (`id`),
+              KEY `idx_name` (`name`)
+            ) ENGINE=InnoDB AUTO_INCREMENT=3 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci COMMENT='people';
+            LOCK TABLES `users` WRITE;
+            UNLOCK TABLES;
+            CREATE TABLE public.orders (id integer NOT NULL, user_id integer REFERENCES public.users(id), note text COLLATE pg_catalog.\"default\");
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let (users, orders) = (&tables[0], &tables[1]);
+        assert_eq!((users.name.as_str(), orders.name.as_str()), ("users", "orders"));
+        assert_eq!((users.columns[0].column_type.as_str(), users.columns[0].auto_increment), ("int", true));
+        assert_eq!(users.columns[1].comment.as_deref(), Some("it's the name; shown"));
+        assert_eq!((users.columns[1].length, users.comment.as_deref()), (Some(50), Some("people")));
+        assert_eq!((orders.columns[1].ref_table.as_deref(), orders.columns[2].column_type.as_str()), (Some("users"), "text"));
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        let table = parse_create_table(
+            "create table t (a int4, b int8, c nvarchar2(20), d tinyint, e tinyint(1), f real, g double precision, h numeric(8, 3), i decimal, j int(11), k clob)",
+        )
+        .unwrap();
+        let types: Vec<(&str, Option<i32>, Option<i32>)> =
+            table.columns.iter().map(|c| (c.column_type.as_str(), c.length, c.decimal_places)).collect();
+        assert_eq!(
+            types,
+            [
+                ("int", None, None),
+                ("bigint", None, None),
+                ("varchar", Some(20), None),
+                ("tinyint", None, None),
+                ("boolean", None, None),
+                ("float", None, Some(2)),
+                ("float", None, Some(2)),
+                ("number", Some(8), Some(3)),
+                ("number", None, None),
+                ("int", None, None),
+                ("text", None, None),
+            ]
+        );
+        assert!(table.columns.iter().filter(|c| c.column_type != "varchar" && c.column_type != "text" && c.column_type != "boolean").all(|c| c.is_numeric()));
+    }
+
+    #[test]
+    fn test_pg_dump_schema() {
+        let sql = "
+            SET statement_timeout = 0;
+            SELECT pg_catalog.set_config('search_path', '', false);
+            CREATE FUNCTION public.touch() RETURNS trigger LANGUAGE plpgsql AS $$
+            BEGIN
+                NEW.updated := now();
+                RETURN NEW;
+            END;
+            $$;
+            CREATE TABLE customers (id integer NOT NULL, name text);
+            ALTER TABLE customers OWNER TO shop;
+            CREATE TABLE orders (id integer NOT NULL, customer_id integer REFERENCES customers (id));
+            CREATE INDEX orders_customer ON orders USING btree (customer_id);
+            ALTER TABLE ONLY customers ADD CONSTRAINT customers_pkey PRIMARY KEY (id);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["customers", "orders"]);
+        assert!(tables[0].columns[0].is_pkey);
+    }
+
+    #[test]
+    fn test_arrays_and_composites() {
+        let sql = "
+            CREATE TYPE address AS (street varchar(40), zip int4);
+            CREATE TYPE contact AS (name text, home address);
+            CREATE TABLE people (id int PRIMARY KEY, tags varchar(20)[], scores int[3], home address, contacts contact[]);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let types: Vec<(&str, Option<i32>)> = tables[0].columns.iter().map(|c| (c.column_type.as_str(), c.length)).collect();
+        assert_eq!(types, [("int", None), ("varchar[]", Some(20)), ("int[]", None), ("address", None), ("contact[]", None)]);
+        let home = &tables[0].columns[3];
+        let attributes: Vec<(&str, &str)> = home.attributes.iter().map(|a| (a.name.as_str(), a.column_type.as_str())).collect();
+        assert_eq!(attributes, [("street", "varchar"), ("zip", "int")]);
+        assert_eq!(tables[0].columns[4].attributes[1].attributes.len(), 2);
+        assert!(tables[0].columns[1].attributes.is_empty());
+    }
+
+    #[test]
+    fn test_enums() {
+        let sql = "
+            CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy');
+            CREATE TABLE people (id int PRIMARY KEY, current_mood mood NOT NULL, moods mood[]);
+            CREATE TABLE orders (id int PRIMARY KEY, status ENUM('new', 'paid') NOT NULL);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let columns: Vec<(&str, &[String])> = tables.iter().flat_map(|t| &t.columns[1..]).map(|c| (c.column_type.as_str(), c.labels.as_slice())).collect();
+        let moods = ["sad", "ok", "happy"].map(String::from);
+        assert_eq!(columns, [("mood", &moods[..]), ("mood[]", &moods[..]), ("enum", &["new".to_string(), "paid".to_string()][..])]);
+    }
+
+    #[test]
+    fn test_spatial_types() {
+        let sql = "
+            CREATE TABLE stores (location geometry(Point, 4326), area geography(POLYGON), route geometry, site point, zones multipolygon, shape sdo_geometry);
+        ";
+        let tables = parse_schema(sql).unwrap();
+        let types: Vec<&str> = tables[0].columns.iter().map(|c| c.column_type.as_str()).collect();
+        assert_eq!(types, ["geometry(point)", "geography(polygon)", "geometry", "geometry(point)", "geometry(multipolygon)", "geometry"]);
+        assert!(tables[0].columns.iter().all(|c| c.length.is_none() && c.is_spatial()));
+    }
+}
@@ -0,0 +1,276 @@
+//! An HTTP server generating statements on demand, so other services and tests can pull
+//! fake SQL for a schema registered at runtime instead of running the command line tool.
+//!
+//! Only available with the `serve` feature, which pulls in `tiny_http`.
+//!
+//! | Endpoint | Response |
+//! | --- | --- |
+//! | `GET /generate?table=orders&type=insert&count=100` | the statements as text, one per line |
+//! | `GET /schema` | the CREATE TABLE statements of the registered tables |
+//! | `POST /schema` | registers the CREATE TABLE statements of the request body and lists the tables as JSON |
+//!
+//! `/generate` takes `table` (default: every table), `type` (a comma separated list of
+//! statement types such as `insert,update`, default: all of them), `count` (default 10, at
+//! most [`MAX_COUNT`]), `dialect` and `seed`; `/schema` takes `dialect`.
+
+use crate::dialect::Dialect;
+use crate::generator::Generator;
+use crate::models::{SqlType, Table};
+use crate::schema::parse_schema;
+use std::io::Read;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::thread;
+
+/// Number of statements `/generate` returns unless `count` says otherwise.
+pub const DEFAULT_COUNT: u64 = 10;
+
+/// Largest number of statements one `/generate` request can ask for.
+pub const MAX_COUNT: u64 = 100_000;
+
+/// Largest request body in bytes, e.g. the CREATE TABLE statements of `POST /schema`.
+pub const MAX_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The answer to a request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The media type of the body.
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    /// Returns a response with status 200 and a plain text body.
+    fn text(body: String) -> Response {
+        Response { status: 200, content_type: "text/plain; charset=utf-8", body }
+    }
+
+    /// Returns an error response with `status` and `message` as plain text.
+    fn error(status: u16, message: impl Into<String>) -> Response {
+        Response { status, content_type: "text/plain; charset=utf-8", body: message.into() + "\n" }
+    }
+}
+
+/// Serves generated statements for the tables registered so far.
+///
+/// Every `/generate` request runs a generator of its own, so requests are independent and
+/// a request with a `seed` always returns the same statements for the same schema.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::serve::Server;
+/// # use fake_sql::Dialect;
+/// let server = Server::new(vec![], Dialect::Postgres);
+/// assert_eq!(server.handle("POST", "/schema", "create table orders (id int primary key)").status, 200);
+/// let response = server.handle("GET", "/generate?table=orders&type=insert&count=2&seed=1", "");
+/// assert_eq!(response.body.lines().count(), 2);
+/// assert!(response.body.starts_with("INSERT INTO orders (id) VALUES ("));
+/// ```
+pub struct Server {
+    tables: RwLock<Vec<Table>>,
+    dialect: Dialect,
+}
+
+impl Server {
+    /// Initializes a new `Server` with the tables registered at start.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - The tables statements are generated for until others are registered.
+    /// * `dialect` - The dialect of requests that name none.
+    pub fn new(tables: Vec<Table>, dialect: Dialect) -> Server {
+        Server { tables: RwLock::new(tables), dialect }
+    }
+
+    /// Answers a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method, e.g. `GET`.
+    /// * `url` - The path with its query string, e.g. `/generate?count=5`.
+    /// * `body` - The body of the request.
+    pub fn handle(&self, method: &str, url: &str, body: &str) -> Response {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let params = parse_query(query);
+        let result = match (method, path.trim_end_matches('/')) {
+            ("GET", "/generate") => self.generate(&params),
+            ("GET", "/schema") => self.schema(&params),
+            ("POST", "/schema") => self.register(body),
+            (_, "/generate" | "/schema") => Err(Response::error(405, format!("{} is not supported by {}", method, path))),
+            _ => Err(Response::error(404, format!("no endpoint at {}; try /generate or /schema", path))),
+        };
+        result.unwrap_or_else(|response| response)
+    }
+
+    /// Listens on `address`, e.g. `127.0.0.1:8080`, and answers requests on `workers`
+    /// threads until the process ends. A worker thread that panics is replaced by a new one.
+    pub fn run(self, address: &str, workers: usize) -> Result<(), String> {
+        let http = tiny_http::Server::http(address).map_err(|err| format!("cannot listen on {}: {}", address, err))?;
+        let (http, server) = (Arc::new(http), Arc::new(self));
+        thread::scope(|scope| {
+            for _ in 0..workers.max(1) {
+                let (http, server) = (http.clone(), server.clone());
+                scope.spawn(move || loop {
+                    let (http, server) = (http.clone(), server.clone());
+                    if thread::spawn(move || server.answer(&http)).join().is_ok() {
+                        break;
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    /// Answers the requests of `http` until it shuts down.
+    fn answer(&self, http: &tiny_http::Server) {
+        for mut request in http.incoming_requests() {
+            let mut body = String::new();
+            let response = match request.as_reader().take(MAX_BODY_SIZE + 1).read_to_string(&mut body) {
+                Ok(size) if size as u64 > MAX_BODY_SIZE => Response::error(413, format!("the request body is larger than {} bytes", MAX_BODY_SIZE)),
+                Ok(_) => self.handle(request.method().as_str(), request.url(), &body),
+                Err(err) => Response::error(400, format!("cannot read the request body: {}", err)),
+            };
+            let header = tiny_http::Header::from_bytes("Content-Type", response.content_type).unwrap();
+            // A client that went away needs no answer
+            let _ = request.respond(tiny_http::Response::from_string(response.body).with_status_code(response.status).with_header(header));
+        }
+    }
+
+    /// Generates the statements `/generate` asks for.
+    fn generate(&self, params: &[(String, String)]) -> Result<Response, Response> {
+        // The tables are copied so the lock is released before generating
+        let tables = {
+            let tables = self.tables.read().unwrap_or_else(PoisonError::into_inner);
+            match param(params, "table") {
+                Some(name) => {
+                    let table = tables.iter().find(|t| t.name.eq_ignore_ascii_case(name));
+                    vec![table.cloned().ok_or_else(|| Response::error(404, format!("table `{}` is not registered", name)))?]
+                }
+                None if tables.is_empty() => return Err(Response::error(409, "no tables are registered; POST their CREATE TABLE statements to /schema")),
+                None => tables.clone(),
+            }
+        };
+        let count = match param(params, "count") {
+            Some(count) => count.parse::<u64>().map_err(|_| Response::error(400, format!("invalid count `{}`", count)))?,
+            None => DEFAULT_COUNT,
+        };
+        if count > MAX_COUNT {
+            return Err(Response::error(400, format!("count {} is more than the {} statements of a request", count, MAX_COUNT)));
+        }
+        let mut generator = match param(params, "seed") {
+            Some(seed) => Generator::with_seed(tables, seed.parse().map_err(|_| Response::error(400, format!("invalid seed `{}`", seed)))?),
+            None => Generator::new(tables),
+        };
+        generator.set_dialect(self.dialect(params)?);
+        if let Some(types) = param(params, "type") {
+            let sql_types = types.split(',').map(str::parse).collect::<Result<Vec<SqlType>, String>>().map_err(|err| Response::error(400, err))?;
+            generator.set_sql_types(sql_types);
+        }
+        let mut body = String::new();
+        for _ in 0..count {
+            body.push_str(&generator.next_statement());
+            body.push('\n');
+        }
+        Ok(Response::text(body))
+    }
+
+    /// Renders the CREATE TABLE statements of the registered tables.
+    fn schema(&self, params: &[(String, String)]) -> Result<Response, Response> {
+        let mut generator = Generator::new(self.tables.read().unwrap_or_else(PoisonError::into_inner).clone());
+        generator.set_dialect(self.dialect(params)?);
+        Ok(Response::text(generator.schema_statements().iter().map(|sql| format!("{}\n", sql)).collect()))
+    }
+
+    /// Registers the tables of the CREATE TABLE statements in `body`, replacing registered
+    /// tables of the same names.
+    fn register(&self, body: &str) -> Result<Response, Response> {
+        let parsed = parse_schema(body).map_err(|err| Response::error(400, err))?;
+        if parsed.is_empty() {
+            return Err(Response::error(400, "the body holds no CREATE TABLE statements"));
+        }
+        let mut tables = self.tables.write().unwrap_or_else(PoisonError::into_inner);
+        for table in parsed {
+            match tables.iter_mut().find(|t| t.name.eq_ignore_ascii_case(&table.name)) {
+                Some(registered) => *registered = table,
+                None => tables.push(table),
+            }
+        }
+        let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+        Ok(Response { status: 200, content_type: "application/json", body: serde_json::json!({ "tables": names }).to_string() })
+    }
+
+    /// Returns the dialect a request names, or the default one.
+    fn dialect(&self, params: &[(String, String)]) -> Result<Dialect, Response> {
+        match param(params, "dialect") {
+            Some(dialect) => dialect.parse().map_err(|err: String| Response::error(400, err)),
+            None => Ok(self.dialect),
+        }
+    }
+}
+
+/// Returns the value of the first query parameter called `name`.
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+/// Splits a query string into its decoded names and values.
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+/// Decodes `+` and `%XX` escapes of a query string component; invalid escapes are kept.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() && text.is_char_boundary(i + 3) && u8::from_str_radix(&text[i + 1..i + 3], 16).is_ok() => {
+                decoded.push(u8::from_str_radix(&text[i + 1..i + 3], 16).unwrap());
+                i += 2;
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::DEFAULT_SCHEMA;
+
+    #[test]
+    fn test_handle() {
+        let server = Server::new(parse_schema(DEFAULT_SCHEMA).unwrap(), Dialect::Oracle);
+        let response = server.handle("GET", "/generate?type=insert%2Cupdate&count=50&seed=3&dialect=mysql", "");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.lines().count(), 50);
+        assert!(response.body.lines().all(|sql| sql.starts_with("INSERT INTO ") || sql.starts_with("UPDATE ")), "{}", response.body);
+        assert_eq!(response, server.handle("GET", "/generate?type=insert,update&count=50&seed=3&dialect=mysql", ""));
+
+        assert_eq!(server.handle("GET", "/generate?table=nowhere", "").status, 404);
+        assert_eq!(server.handle("GET", "/generate?count=many", "").status, 400);
+        assert_eq!(server.handle("GET", "/generate?type=merge", "").status, 400);
+        assert_eq!(server.handle("DELETE", "/schema", "").status, 405);
+        assert_eq!(server.handle("GET", "/", "").status, 404);
+
+        let response = server.handle("POST", "/schema", "create table orders (id int primary key, note varchar(20));\ncreate table notes (id int)");
+        assert_eq!(response.body, r#"{"tables":["orders","customers","products","notes"]}"#);
+        assert_eq!(server.handle("POST", "/schema", "select 1").status, 400);
+        let schema = server.handle("GET", "/schema?dialect=postgres", "").body;
+        assert!(schema.contains("CREATE TABLE orders (id integer NOT NULL PRIMARY KEY, note varchar(20));"), "{}", schema);
+        assert_eq!(decode("a+b%20c%2"), "a b c%2");
+    }
+}
@@ -0,0 +1,64 @@
+//! Generated statements together with what they do.
+
+use crate::models::SqlType;
+use crate::value::SqlValue;
+use std::fmt;
+
+/// A generated SQL statement with its type, its table and the values it inserts, so
+/// consumers can route statements, e.g. DDL to one sink and DML to another, and inspect
+/// the generated data without parsing the SQL.
+///
+/// Displays as the SQL text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statement {
+    /// The type of the statement.
+    pub sql_type: SqlType,
+    /// The name of the table the statement was generated for. Joins, views and subqueries
+    /// may read other tables too.
+    pub table: String,
+    /// The SQL text, terminated by a semicolon.
+    pub text: String,
+    /// The values of INSERT statements in the order they appear in the SQL: row after
+    /// row, one value per inserted column. Empty for other statements.
+    pub values: Vec<SqlValue>,
+}
+
+impl Statement {
+    /// Initializes a new `Statement` without values.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql_type` - The type of the statement.
+    /// * `table` - The name of the table the statement was generated for.
+    /// * `text` - The SQL text.
+    pub fn new(sql_type: SqlType, table: &str, text: String) -> Statement {
+        Statement {
+            sql_type,
+            table: table.to_string(),
+            text,
+            values: Vec::new(),
+        }
+    }
+
+    /// Returns the statement with the values it inserts.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values in the order they appear in the SQL.
+    pub fn with_values(mut self, values: Vec<SqlValue>) -> Statement {
+        self.values = values;
+        self
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<Statement> for String {
+    fn from(statement: Statement) -> String {
+        statement.text
+    }
+}
@@ -0,0 +1,196 @@
+//! Statistics of a generation run, reported when it ends.
+
+use crate::models::SqlType;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// What a generation run produced: the statements per table and type, the rows they
+/// insert and the bytes written, together with the time it took.
+///
+/// The statistics of several threads or shards are combined with [`Stats::merge`].
+/// Displays as a report with one line per table and a summary line.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::stats::Stats;
+/// # use fake_sql::SqlType;
+/// # use std::time::Duration;
+/// let mut stats = Stats::default();
+/// stats.record("orders", SqlType::Insert, 10);
+/// stats.record("orders", SqlType::Select, 0);
+/// stats.bytes = 2048;
+/// stats.elapsed = Duration::from_secs(2);
+/// assert_eq!((stats.statements(), stats.rows), (2, 10));
+/// assert_eq!(stats.to_json()["rows_per_second"], 5.0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Number of statements per table and statement type. Row-oriented formats count
+    /// every row as an INSERT.
+    pub tables: BTreeMap<String, BTreeMap<SqlType, u64>>,
+    /// Number of rows inserted.
+    pub rows: u64,
+    /// Number of bytes written, including parameter files and dirty reports.
+    pub bytes: u64,
+    /// Time the run took.
+    pub elapsed: Duration,
+}
+
+impl Stats {
+    /// Counts a generated statement.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table the statement was generated for.
+    /// * `sql_type` - The type of the statement.
+    /// * `rows` - The number of rows the statement inserts.
+    pub fn record(&mut self, table: &str, sql_type: SqlType, rows: u64) {
+        if !self.tables.contains_key(table) {
+            self.tables.insert(table.to_string(), BTreeMap::new());
+        }
+        *self.tables.get_mut(table).unwrap().entry(sql_type).or_default() += 1;
+        self.rows += rows;
+    }
+
+    /// Adds the counts of `other`, e.g. the statistics of another thread. The longer
+    /// elapsed time is kept, as threads run at the same time.
+    pub fn merge(&mut self, other: Stats) {
+        for (table, counts) in other.tables {
+            let merged = self.tables.entry(table).or_default();
+            for (sql_type, count) in counts {
+                *merged.entry(sql_type).or_default() += count;
+            }
+        }
+        self.rows += other.rows;
+        self.bytes += other.bytes;
+        self.elapsed = self.elapsed.max(other.elapsed);
+    }
+
+    /// Returns the number of statements of every table and type.
+    pub fn statements(&self) -> u64 {
+        self.tables.values().flat_map(|counts| counts.values()).sum()
+    }
+
+    /// Returns the statistics as a JSON document for `--stats-json`, e.g.
+    /// `{"statements": 30, "rows": 12, ..., "tables": {"orders": {"insert": 12, ...}}}`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let tables: serde_json::Map<String, serde_json::Value> = self
+            .tables
+            .iter()
+            .map(|(table, counts)| {
+                let counts: serde_json::Map<String, serde_json::Value> =
+                    counts.iter().map(|(sql_type, count)| (sql_type.name().to_string(), (*count).into())).collect();
+                (table.clone(), counts.into())
+            })
+            .collect();
+        serde_json::json!({
+            "statements": self.statements(),
+            "rows": self.rows,
+            "bytes": self.bytes,
+            "elapsed_seconds": self.elapsed.as_secs_f64(),
+            "statements_per_second": self.per_second(self.statements()),
+            "rows_per_second": self.per_second(self.rows),
+            "tables": tables,
+        })
+    }
+
+    /// Returns `count` divided by the elapsed time, or 0 when no time elapsed.
+    fn per_second(&self, count: u64) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            count as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sql_types: Vec<SqlType> = SqlType::ALL
+            .into_iter()
+            .filter(|sql_type| self.tables.values().any(|counts| counts.contains_key(sql_type)))
+            .collect();
+        let width = self.tables.keys().map(|table| table.len()).chain([5]).max().unwrap_or(5);
+        if !self.tables.is_empty() {
+            write!(f, "{:width$}", "table")?;
+            for sql_type in &sql_types {
+                write!(f, "  {:>w$}", sql_type.name(), w = sql_type.name().len().max(6))?;
+            }
+            writeln!(f)?;
+            for (table, counts) in &self.tables {
+                write!(f, "{:width$}", table)?;
+                for sql_type in &sql_types {
+                    let count = counts.get(sql_type).copied().unwrap_or(0);
+                    write!(f, "  {:>w$}", count, w = sql_type.name().len().max(6))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        write!(
+            f,
+            "{} statements, {} rows, {} in {:.2}s ({:.0} statements/s, {:.0} rows/s)",
+            self.statements(),
+            self.rows,
+            format_bytes(self.bytes),
+            self.elapsed.as_secs_f64(),
+            self.per_second(self.statements()),
+            self.per_second(self.rows)
+        )
+    }
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 MiB`.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut first = Stats::default();
+        first.record("orders", SqlType::Insert, 2);
+        first.elapsed = Duration::from_secs(1);
+        let mut second = Stats::default();
+        second.record("orders", SqlType::Insert, 3);
+        second.record("customers", SqlType::Delete, 0);
+        second.bytes = 100;
+        second.elapsed = Duration::from_secs(2);
+        first.merge(second);
+        assert_eq!((first.statements(), first.rows, first.bytes), (3, 5, 100));
+        assert_eq!(first.tables["orders"][&SqlType::Insert], 2);
+        assert_eq!(first.elapsed, Duration::from_secs(2));
+        assert_eq!(first.to_json()["tables"]["customers"], serde_json::json!({"delete": 1}));
+    }
+
+    #[test]
+    fn test_report() {
+        let mut stats = Stats::default();
+        stats.record("customers", SqlType::Insert, 1);
+        stats.record("orders", SqlType::Select, 0);
+        stats.bytes = 3 << 20;
+        stats.elapsed = Duration::from_millis(500);
+        assert_eq!(
+            stats.to_string(),
+            "table      insert  select\ncustomers       1       0\norders          0       1\n\
+             2 statements, 1 rows, 3.0 MiB in 0.50s (4 statements/s, 2 rows/s)"
+        );
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+    }
+}
@@ -0,0 +1,233 @@
+//! Embedded database files the generated schema and data are loaded into, for a dataset
+//! that can be queried right away without a database server, or shipped as a
+//! self-contained fixture.
+//!
+//! Only available with the `exec` feature, which loads SQLite files through `sqlx`.
+//! DuckDB files also need the `duckdb` feature, which builds DuckDB from source.
+
+use crate::dialect::Dialect;
+use crate::exec::{ExecError, ExecReport};
+use crate::rate::Rate;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A database file to load, written `duckdb:<path>` or `sqlite:<path>`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::target::Target;
+/// # use std::path::Path;
+/// let target: Target = "duckdb:shop.duckdb".parse().unwrap();
+/// assert_eq!(target.path(), Path::new("shop.duckdb"));
+/// assert!("mongodb:shop".parse::<Target>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// A DuckDB database file, loaded with statements rendered for Postgres.
+    DuckDb(PathBuf),
+    /// A SQLite database file.
+    Sqlite(PathBuf),
+}
+
+impl Target {
+    /// Returns the path of the database file.
+    pub fn path(&self) -> &Path {
+        match self {
+            Target::DuckDb(path) | Target::Sqlite(path) => path,
+        }
+    }
+
+    /// Returns the dialect the statements loaded into the target are rendered in.
+    pub fn dialect(&self) -> Dialect {
+        match self {
+            Target::DuckDb(_) => Dialect::Postgres,
+            Target::Sqlite(_) => Dialect::Sqlite,
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::DuckDb(path) => write!(f, "duckdb:{}", path.display()),
+            Target::Sqlite(path) => write!(f, "sqlite:{}", path.display()),
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    /// Parses `duckdb:<path>` or `sqlite:<path>`; `duckdb://<path>` and `sqlite://<path>`
+    /// are accepted too.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, path) = s.split_once(':').ok_or_else(|| format!("invalid target `{}`; expected duckdb:<path> or sqlite:<path>", s))?;
+        let path = path.strip_prefix("//").unwrap_or(path);
+        if path.is_empty() {
+            return Err(format!("the target `{}` names no file", s));
+        }
+        match kind.to_lowercase().as_str() {
+            "duckdb" => Ok(Target::DuckDb(PathBuf::from(path))),
+            "sqlite" => Ok(Target::Sqlite(PathBuf::from(path))),
+            _ => Err(format!("unknown target `{}`; expected duckdb:<path> or sqlite:<path>", s)),
+        }
+    }
+}
+
+/// Loads the database file of `target`: executes `setup`, e.g. the CREATE TABLE statements
+/// of the schema, and then `statements` in order on a single connection.
+///
+/// Without `keep_going` the statements run in one transaction, which loads far faster than
+/// a commit per statement, and the first failure stops the run and leaves the file
+/// without them. With `keep_going`, every statement commits on its own and failures are
+/// counted instead. See [`exec::load`](crate::exec::load) for SQLite files.
+///
+/// # Arguments
+///
+/// * `target` - The database file, created if it does not exist.
+/// * `setup` - Statements run first; any failure stops the run.
+/// * `statements` - The statements to load.
+/// * `keep_going` - Whether failed statements are counted instead of stopping the run.
+/// * `rate` - The pace of the statements, or `None` to load them as fast as possible.
+///
+/// # Returns
+///
+/// How many statements were executed and how many failed, or the first error.
+pub fn load<I>(target: &Target, setup: Vec<String>, statements: I, keep_going: bool, rate: Option<Rate>) -> Result<ExecReport, ExecError>
+where
+    I: Iterator<Item = String>,
+{
+    match target {
+        #[cfg(feature = "duckdb")]
+        Target::DuckDb(path) => duckdb::load(path, setup, statements, keep_going, rate),
+        #[cfg(not(feature = "duckdb"))]
+        Target::DuckDb(_) => {
+            let _ = (setup, statements, keep_going, rate);
+            Err("DuckDB targets need fake-sql built with the `duckdb` feature".into())
+        }
+        Target::Sqlite(path) => crate::exec::load(&format!("sqlite://{}?mode=rwc", path.display()), setup, statements, keep_going, rate),
+    }
+}
+
+#[cfg(feature = "duckdb")]
+mod duckdb {
+    use crate::exec::{ExecError, ExecReport};
+    use crate::rate::{Pacer, Rate};
+    use std::path::Path;
+    use std::time::Instant;
+
+    /// The clause of identity columns in Postgres column definitions.
+    const IDENTITY: &str = " GENERATED BY DEFAULT AS IDENTITY";
+
+    /// Loads the DuckDB database file at `path`, see [`load`](super::load).
+    pub(super) fn load<I>(path: &Path, setup: Vec<String>, statements: I, keep_going: bool, rate: Option<Rate>) -> Result<ExecReport, ExecError>
+    where
+        I: Iterator<Item = String>,
+    {
+        let start = Instant::now();
+        let connection = ::duckdb::Connection::open(path)?;
+        for sql in schema(setup) {
+            if let Err(err) = connection.execute_batch(&sql) {
+                return Err(format!("{} in setup statement: {}", err, sql).into());
+            }
+        }
+        if !keep_going {
+            connection.execute_batch("BEGIN TRANSACTION;")?;
+        }
+        let mut report = ExecReport::default();
+        let mut pacer = rate.map(Pacer::new);
+        for sql in statements {
+            if let Some(pacer) = &mut pacer {
+                pacer.wait();
+            }
+            match connection.execute_batch(&sql) {
+                Ok(()) => report.executed += 1,
+                Err(_) if keep_going => report.failed += 1,
+                Err(err) => return Err(format!("{} in statement: {}", err, sql).into()),
+            }
+        }
+        if !keep_going {
+            connection.execute_batch("COMMIT;")?;
+        }
+        report.elapsed = start.elapsed();
+        Ok(report)
+    }
+
+    /// Adapts Postgres schema statements to DuckDB, which has no identity columns: each
+    /// becomes a column defaulting to the next value of a sequence of its own, created
+    /// before the table, so the rows are numbered 1, 2, 3, ... as the generator expects.
+    pub(super) fn schema(statements: Vec<String>) -> Vec<String> {
+        let mut adapted = Vec::with_capacity(statements.len());
+        let mut sequences = 0;
+        for mut sql in statements {
+            while let Some(position) = sql.find(IDENTITY) {
+                sequences += 1;
+                let sequence = format!("fake_sql_seq_{}", sequences);
+                adapted.push(format!("CREATE SEQUENCE {};", sequence));
+                sql.replace_range(position..position + IDENTITY.len(), &format!(" DEFAULT nextval('{}')", sequence));
+            }
+            adapted.push(sql);
+        }
+        adapted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets() {
+        assert_eq!("DuckDB:///tmp/shop.db".parse(), Ok(Target::DuckDb(PathBuf::from("/tmp/shop.db"))));
+        assert_eq!("sqlite:fixtures/shop.db".parse::<Target>().map(|target| target.dialect()), Ok(Dialect::Sqlite));
+        assert!("duckdb:".parse::<Target>().is_err());
+        assert!("shop.db".parse::<Target>().is_err());
+        assert_eq!(Target::DuckDb(PathBuf::from("a.db")).to_string(), "duckdb:a.db");
+    }
+
+    #[test]
+    fn test_load_sqlite() {
+        use crate::schema::{parse_schema, DEFAULT_SCHEMA};
+        use crate::Generator;
+
+        let path = std::env::temp_dir().join(format!("fake-sql-target-{}.db", std::process::id()));
+        let target = Target::Sqlite(path.clone());
+        let mut generator = Generator::with_seed(parse_schema(DEFAULT_SCHEMA).unwrap(), 5);
+        generator.set_dialect(target.dialect());
+        let statements: Vec<String> = ["customers", "orders", "orders"].iter().map(|table| generator.insert_into(table).unwrap()).collect();
+        let report = load(&target, generator.schema_statements(), statements.into_iter(), false, None).unwrap();
+        assert_eq!((report.executed, report.failed), (3, 0));
+        // The tables are there now, so creating them again fails
+        assert!(load(&target, generator.schema_statements(), std::iter::empty(), false, None).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "duckdb")]
+    #[test]
+    fn test_load_duckdb() {
+        use crate::schema::{parse_schema, DEFAULT_SCHEMA};
+        use crate::Generator;
+
+        let path = std::env::temp_dir().join(format!("fake-sql-target-{}.duckdb", std::process::id()));
+        let mut tables = parse_schema(DEFAULT_SCHEMA).unwrap();
+        tables[0].columns[0].auto_increment = true;
+        let mut generator = Generator::with_seed(tables, 5);
+        generator.set_dialect(Dialect::Postgres);
+        let setup = generator.schema_statements();
+        assert!(duckdb::schema(setup.clone()).iter().any(|sql| sql.contains("DEFAULT nextval('fake_sql_seq_1')")));
+        let statements: Vec<String> = ["customers", "orders", "orders"].iter().map(|table| generator.insert_into(table).unwrap()).collect();
+        let target = Target::DuckDb(path.clone());
+        let report = load(&target, setup, statements.into_iter(), false, None).unwrap();
+        assert_eq!((report.executed, report.failed), (3, 0));
+
+        let connection = ::duckdb::Connection::open(&path).unwrap();
+        let count: i64 = connection.query_row("SELECT count(*) FROM orders", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+        drop(connection);
+        let report = load(&target, vec![], ["INSERT INTO nowhere VALUES (1);".to_string()].into_iter(), true, None).unwrap();
+        assert_eq!(report.failed, 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+//! Format templates for column values, such as `ORD-{YYYY}{seq:06}`.
+
+use crate::locale::Locale;
+use crate::models::DateRange;
+use crate::providers::{Provider, EMAIL_DOMAINS, RESERVED_EMAIL_DOMAINS};
+use chrono::NaiveDateTime;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Date placeholders and the `chrono` format they render with.
+const DATE_PARTS: [(&str, &str); 7] =
+    [("YYYY", "%Y"), ("YY", "%y"), ("MM", "%m"), ("DD", "%d"), ("hh", "%H"), ("mm", "%M"), ("ss", "%S")];
+
+/// A format for the values of a column, with placeholders between braces:
+///
+/// - `{seq}` counts 1, 2, 3, ... per column, `{seq:06}` pads the count with zeros to 6 digits.
+/// - `{YYYY}`, `{YY}`, `{MM}`, `{DD}`, `{hh}`, `{mm}` and `{ss}` are the parts of a random
+///   time within the column's date range; all date placeholders of a value share the time.
+/// - `{first_name}`, `{city}`, ... are values of a [`Provider`] in the column's locale, and
+///   `{domain}` an email domain such as `example.com`; PII-free columns only use the
+///   placeholder values of [`Provider::generate_pii_free`] and the domains reserved for examples.
+/// - `{digits:4}` and `{letters:3}` are random digits and uppercase letters.
+///
+/// A placeholder followed by `|lower` or `|upper` changes its case, e.g.
+/// `{first_name|lower}`, and `{{` and `}}` are literal braces.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::template::Template;
+/// # use fake_sql::locale::Locale;
+/// let template: Template = "ORD-{YYYY}{seq:06}".parse().unwrap();
+/// let value = template.render("2024-01-01..2024-12-31".parse().unwrap(), Locale::En, false, 42, &mut rand::thread_rng());
+/// assert_eq!(value, "ORD-2024000042");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+/// A run of literal text or a placeholder of a template.
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Literal(String),
+    Placeholder(Token, Case),
+}
+
+/// What a placeholder renders.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// The sequence number, padded with zeros to the width.
+    Sequence(usize),
+    /// A part of the random time, as a `chrono` format.
+    Date(&'static str),
+    Provider(Provider),
+    Domain,
+    Digits(usize),
+    Letters(usize),
+}
+
+/// The case a placeholder is rendered in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Case {
+    Keep,
+    Lower,
+    Upper,
+}
+
+impl Template {
+    /// Renders a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `date_range` - The range the time of date placeholders is drawn from.
+    /// * `locale` - The locale of provider placeholders.
+    /// * `pii_free` - Whether provider placeholders and `{domain}` only render values that
+    ///   cannot belong to a real person.
+    /// * `seq` - The number `{seq}` renders.
+    /// * `rng` - The random number generator the other placeholders are drawn from.
+    pub fn render<R: Rng + ?Sized>(&self, date_range: DateRange, locale: Locale, pii_free: bool, seq: i64, rng: &mut R) -> String {
+        let mut time: Option<NaiveDateTime> = None;
+        let mut value = String::new();
+        for part in &self.parts {
+            let (token, case) = match part {
+                Part::Literal(text) => {
+                    value.push_str(text);
+                    continue;
+                }
+                Part::Placeholder(token, case) => (token, *case),
+            };
+            let text = match token {
+                Token::Sequence(width) => format!("{:0width$}", seq, width = *width),
+                Token::Date(format) => time.get_or_insert_with(|| date_range.random_timestamp(rng)).format(format).to_string(),
+                Token::Provider(Provider::Timestamp) => {
+                    let time = time.get_or_insert_with(|| date_range.random_timestamp(rng));
+                    time.format("%Y-%m-%d %H:%M:%S").to_string()
+                }
+                Token::Provider(provider) if pii_free => provider.generate_pii_free(locale, rng),
+                Token::Provider(provider) => provider.generate_in(locale, rng),
+                Token::Domain if pii_free => RESERVED_EMAIL_DOMAINS.choose(rng).unwrap().to_string(),
+                Token::Domain => EMAIL_DOMAINS.choose(rng).unwrap().to_string(),
+                Token::Digits(count) => (0..*count).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect(),
+                Token::Letters(count) => (0..*count).map(|_| char::from(b'A' + rng.gen_range(0..26))).collect(),
+            };
+            match case {
+                Case::Keep => value.push_str(&text),
+                Case::Lower => value.push_str(&text.to_lowercase()),
+                Case::Upper => value.push_str(&text.to_uppercase()),
+            }
+        }
+        value
+    }
+
+    /// Returns whether every value of the template is a whole number: it only holds digits,
+    /// `{seq}`, `{digits:N}` and date placeholders.
+    pub fn is_numeric(&self) -> bool {
+        !self.parts.is_empty()
+            && self.parts.iter().all(|part| match part {
+                Part::Literal(text) => text.chars().all(|c| c.is_ascii_digit()),
+                Part::Placeholder(token, _) => matches!(token, Token::Sequence(_) | Token::Date(_) | Token::Digits(_)),
+            })
+    }
+
+    /// Returns whether the template renders the sequence number, so its values never repeat.
+    pub fn has_sequence(&self) -> bool {
+        self.parts.iter().any(|part| matches!(part, Part::Placeholder(Token::Sequence(_), _)))
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    /// Parses a template such as `{first_name|lower}.{last_name|lower}@{domain}`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest.find('}').ok_or_else(|| format!("unclosed placeholder in template `{}`", s))?;
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(placeholder(&rest[..end]).map_err(|err| format!("{} in template `{}`", err, s))?);
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => return Err(format!("unmatched `}}` in template `{}`; write `}}}}` for a brace", s)),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template { source: s.to_string(), parts })
+    }
+}
+
+/// Parses the text between the braces of a placeholder, e.g. `seq:06` or `city|upper`.
+fn placeholder(text: &str) -> Result<Part, String> {
+    let (text, case) = match text.split_once('|') {
+        Some((text, "lower")) => (text, Case::Lower),
+        Some((text, "upper")) => (text, Case::Upper),
+        Some((_, filter)) => return Err(format!("unknown filter `{}`", filter)),
+        None => (text, Case::Keep),
+    };
+    let (name, argument) = match text.split_once(':') {
+        Some((name, argument)) => (name.trim(), Some(argument.trim())),
+        None => (text.trim(), None),
+    };
+    let count = |default: usize| {
+        argument.map_or(Ok(default), |n| n.parse().map_err(|_| format!("invalid width `{}` of `{{{}}}`", n, name)))
+    };
+    let token = match name {
+        "seq" => Token::Sequence(count(0)?),
+        "digits" => Token::Digits(count(1)?),
+        "letters" => Token::Letters(count(1)?),
+        "domain" => Token::Domain,
+        _ => match DATE_PARTS.iter().find(|(part, _)| *part == name) {
+            Some((_, format)) => Token::Date(format),
+            None => Token::Provider(name.parse().map_err(|_| format!("unknown placeholder `{{{}}}`", name))?),
+        },
+    };
+    if argument.is_some() && !matches!(token, Token::Sequence(_) | Token::Digits(_) | Token::Letters(_)) {
+        return Err(format!("placeholder `{{{}}}` takes no width", name));
+    }
+    Ok(Part::Placeholder(token, case))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_render() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let range: DateRange = "2023-05-06..2023-05-06".parse().unwrap();
+        let template: Template = "{{{YY}/{MM}/{DD}}} #{seq} {letters:3}-{digits:2}".parse().unwrap();
+        let value = template.render(range, Locale::En, false, 7, &mut rng);
+        assert!(value.starts_with("{23/05/06} #7 "), "{}", value);
+        assert!(value[14..17].chars().all(|c| c.is_ascii_uppercase()) && value[18..].chars().all(|c| c.is_ascii_digit()), "{}", value);
+        assert!(template.has_sequence());
+
+        let email: Template = "{first_name|lower}.{last_name|upper}@{domain}".parse().unwrap();
+        let value = email.render(range, Locale::En, false, 1, &mut rng);
+        let (first, last) = value.split_once('@').unwrap().0.split_once('.').unwrap();
+        assert_eq!((first.to_lowercase(), last.to_uppercase()), (first.to_string(), last.to_string()));
+        assert!(!email.has_sequence());
+        assert_eq!(email.to_string(), "{first_name|lower}.{last_name|upper}@{domain}");
+    }
+
+    #[test]
+    fn test_from_str_errors() {
+        for invalid in ["ORD-{seq", "ORD}", "{unknown}", "{seq:wide}", "{city:3}", "{city|title}"] {
+            assert!(invalid.parse::<Template>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! Append-heavy time-series tables, such as the readings of sensors.
+
+use crate::models::{Column, Table};
+use crate::value::SqlValue;
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Number of entities a time-series table cycles through unless configured otherwise.
+pub const DEFAULT_ENTITIES: u64 = 10;
+
+/// Largest change of a metric between two readings of an entity unless configured
+/// otherwise, as a fraction of the range of the column.
+pub const DEFAULT_STEP: f64 = 0.01;
+
+/// How the rows of a time-series table are generated, e.g. the readings of a fleet of devices.
+///
+/// Every row is stamped `interval` after the previous one, give or take up to `jitter`,
+/// starting at the beginning of the date range of the timestamp column. A jitter of the
+/// interval or more is cut to just below it, so timestamps always increase. The rows cycle through `entities` entity ids, 1 to N or the first N keys of
+/// the parent table when the entity column is a foreign key, and every metric column
+/// follows a random walk per entity: each reading moves the previous one of the entity by
+/// up to `step` times the range of the column, without leaving the range.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::timeseries::TimeSeries;
+/// # use fake_sql::Table;
+/// let table = Table::init_via_sql("create table readings (device_id int, taken_at timestamp, temperature number(5, 2))");
+/// let series = TimeSeries::infer(&table).unwrap();
+/// assert_eq!((series.timestamp.as_str(), series.entity.as_deref()), ("taken_at", Some("device_id")));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeSeries {
+    /// The column holding the time of a row: a `timestamp`, `datetime`, `timestamptz` or
+    /// `date` column, or a numeric column holding Unix epoch milliseconds.
+    pub timestamp: String,
+    /// Time between two consecutive rows.
+    pub interval: Duration,
+    /// Largest random deviation from `interval` between two consecutive rows, cut to
+    /// just below `interval`.
+    pub jitter: Duration,
+    /// The column identifying the entity a row belongs to, e.g. `device_id`.
+    pub entity: Option<String>,
+    /// Number of entities the rows cycle through.
+    pub entities: u64,
+    /// The columns following a random walk; when empty, every numeric column that is
+    /// neither a key nor the timestamp or entity column.
+    pub metrics: Vec<String>,
+    /// Largest change of a metric between two readings of an entity, as a fraction of
+    /// the range of the column.
+    pub step: f64,
+}
+
+impl TimeSeries {
+    /// Initializes a new `TimeSeries` with rows one second apart, without jitter or entity
+    /// column, and every numeric column as a metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The name of the timestamp column.
+    pub fn new(timestamp: &str) -> TimeSeries {
+        TimeSeries {
+            timestamp: timestamp.to_string(),
+            interval: Duration::seconds(1),
+            jitter: Duration::zero(),
+            entity: None,
+            entities: DEFAULT_ENTITIES,
+            metrics: Vec::new(),
+            step: DEFAULT_STEP,
+        }
+    }
+
+    /// Infers the timestamp and entity columns of `table`: the first date or timestamp
+    /// column, and the first foreign key column or else the first integer column other
+    /// than the primary key whose name ends in `_id`.
+    ///
+    /// # Returns
+    ///
+    /// The time series with the default settings, or an error if the table has no date
+    /// or timestamp column.
+    pub fn infer(table: &Table) -> Result<TimeSeries, String> {
+        let timestamp = table
+            .columns
+            .iter()
+            .find(|c| matches!(c.column_type.as_str(), "timestamp" | "datetime" | "timestamptz" | "date"))
+            .ok_or_else(|| format!("table `{}` has no date or timestamp column for a time series", table.name))?;
+        let entity = table
+            .columns
+            .iter()
+            .find(|c| c.ref_table.is_some())
+            .or_else(|| table.columns.iter().find(|c| !c.is_pkey && c.is_numeric() && c.name.ends_with("_id")));
+        let mut series = TimeSeries::new(&timestamp.name);
+        series.entity = entity.map(|c| c.name.clone());
+        Ok(series)
+    }
+
+    /// Checks that the columns of the time series exist in `table` and have a suitable type.
+    pub fn check(&self, table: &Table) -> Result<(), String> {
+        let column = |name: &str| {
+            table
+                .column(name)
+                .ok_or_else(|| format!("column `{}.{}` of the time series is not defined in the schema", table.name, name))
+        };
+        let timestamp = column(&self.timestamp)?;
+        if !timestamp.is_numeric() && !matches!(timestamp.column_type.as_str(), "timestamp" | "datetime" | "timestamptz" | "date") {
+            return Err(format!("the timestamp column `{}.{}` must be a date, timestamp or number", table.name, timestamp.name));
+        }
+        if let Some(entity) = &self.entity {
+            column(entity)?;
+        }
+        for metric in &self.metrics {
+            if !column(metric)?.is_numeric() {
+                return Err(format!("the metric `{}.{}` must be a numeric column", table.name, metric));
+            }
+        }
+        if self.entities == 0 {
+            return Err("a time series needs at least one entity".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.step) {
+            return Err(format!("the step of a time series must be between 0.0 and 1.0, found {}", self.step));
+        }
+        if self.interval < Duration::zero() || self.jitter < Duration::zero() {
+            return Err("the interval and jitter of a time series must not be negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns the names of the columns the time series sets.
+    pub fn columns(&self) -> Vec<String> {
+        let mut columns = vec![self.timestamp.clone()];
+        columns.extend(self.entity.clone());
+        columns.extend(self.metrics.iter().cloned());
+        columns
+    }
+}
+
+/// The progress of a time series: the time of the next row and the last reading of every
+/// metric per entity.
+#[derive(Clone, Debug)]
+pub(crate) struct Series {
+    settings: TimeSeries,
+    /// Time of the next row; the start of the date range of the timestamp column before
+    /// the first one.
+    next: Option<NaiveDateTime>,
+    /// Number of rows generated so far.
+    rows: u64,
+    /// Index of the shard generating the rows, see [`Series::set_shard`].
+    shard: u64,
+    /// Number of shards generating rows of the time series.
+    shards: u64,
+    /// Last value of every metric, in units of its scale, per entity and column name.
+    levels: HashMap<(u64, String), i128>,
+}
+
+impl Series {
+    pub(crate) fn new(settings: TimeSeries) -> Series {
+        Series {
+            settings,
+            next: None,
+            rows: 0,
+            shard: 0,
+            shards: 1,
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Makes the series the shard `index` of `count` generating its rows in parallel: the
+    /// shard starts `index / count` of the interval late, and cycles through the entity
+    /// ids `index + 1`, `index + 1 + count`, ... so no two shards stamp an entity with the
+    /// same time. Entities that are foreign keys are the parent keys of the shard already.
+    pub(crate) fn set_shard(&mut self, index: usize, count: usize) {
+        self.shard += self.shards * index as u64;
+        self.shards *= count.max(1) as u64;
+    }
+
+    /// Returns the settings of the time series.
+    pub(crate) fn settings(&self) -> &TimeSeries {
+        &self.settings
+    }
+
+    /// Sets the timestamp, entity and metrics of the next row of `table`, whose other
+    /// values are already drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The time-series table.
+    /// * `row` - The drawn row, one value per column.
+    /// * `parent_keys` - The keys inserted into the table the entity column references, if any.
+    /// * `rng` - The random number generator used for the jitter and the random walks.
+    pub(crate) fn apply<R: Rng + ?Sized>(&mut self, table: &Table, row: &mut [SqlValue], parent_keys: Option<&[SqlValue]>, rng: &mut R) {
+        let position = |name: &str| table.columns.iter().position(|c| c.name == name);
+        let parent_keys = parent_keys.filter(|keys| !keys.is_empty());
+        let entities = match parent_keys {
+            Some(keys) => self.settings.entities.min(keys.len() as u64),
+            None => self.settings.entities,
+        };
+        let entity = match parent_keys {
+            None if self.shard < entities => self.shard + self.rows % (entities - self.shard).div_ceil(self.shards) * self.shards,
+            _ => self.rows % entities,
+        };
+        self.rows += 1;
+
+        if let Some(i) = position(&self.settings.timestamp) {
+            let column = &table.columns[i];
+            let now = self.next.unwrap_or_else(|| {
+                let offset = self.settings.interval.num_milliseconds().saturating_mul(self.shard as i64) / self.shards as i64;
+                column.date_range().start.and_time(NaiveTime::MIN) + Duration::milliseconds(offset)
+            });
+            row[i] = timestamp_value(column, now);
+            let jitter = self.settings.jitter.num_milliseconds().min(self.settings.interval.num_milliseconds() - 1);
+            let gap = self.settings.interval + Duration::milliseconds(if jitter > 0 { rng.gen_range(-jitter..=jitter) } else { 0 });
+            self.next = Some(now + gap.max(Duration::zero()));
+        }
+        if let Some(i) = self.settings.entity.as_deref().and_then(position) {
+            row[i] = match parent_keys {
+                Some(keys) => keys[entity as usize].clone(),
+                None => SqlValue::Integer(entity as i64 + 1),
+            };
+        }
+        let set = self.settings.columns();
+        for (i, column) in table.columns.iter().enumerate() {
+            let is_metric = if self.settings.metrics.is_empty() {
+                column.is_numeric() && !column.is_pkey && column.ref_table.is_none() && column.pool.is_none() && !set.contains(&column.name)
+            } else {
+                self.settings.metrics.contains(&column.name)
+            };
+            // NULL readings drawn for nullable metrics are kept
+            if !is_metric || column.auto_increment || row[i] == SqlValue::Null {
+                continue;
+            }
+            let (min, max) = column.numeric_range();
+            let step = ((max as f64 - min as f64) * self.settings.step).round().max(1.0) as i128;
+            let level = self.levels.entry((entity, column.name.clone())).or_insert_with(|| rng.gen_range(min..=max));
+            *level = level.saturating_add(rng.gen_range(-step..=step)).clamp(min, max);
+            row[i] = column.number_value(*level);
+        }
+    }
+}
+
+/// Returns the value of `column` for the time `now`.
+fn timestamp_value(column: &Column, now: NaiveDateTime) -> SqlValue {
+    match column.column_type.as_str() {
+        "date" => SqlValue::Date(now.date()),
+        "timestamptz" => SqlValue::TimestampTz(now.and_utc().fixed_offset()),
+        _ if column.is_numeric() => SqlValue::Integer(now.and_utc().timestamp_millis()),
+        _ => SqlValue::Timestamp(now),
+    }
+}
+
+/// Parses a duration such as `500ms`, `10s`, `5min`, `2h` or `1d`; a bare number is a
+/// number of seconds.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::timeseries::parse_duration;
+/// assert_eq!(parse_duration("1.5s"), Ok(chrono::Duration::milliseconds(1500)));
+/// assert_eq!(parse_duration("5min"), Ok(chrono::Duration::minutes(5)));
+/// ```
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let text = s.trim().to_lowercase();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let millis = match unit.trim() {
+        "ms" => 1.0,
+        "" | "s" | "sec" => 1_000.0,
+        "m" | "min" => 60_000.0,
+        "h" => 3_600_000.0,
+        "d" => 86_400_000.0,
+        _ => return Err(format!("invalid duration `{}`; expected e.g. `500ms`, `10s`, `5min` or `1h`", s)),
+    };
+    match number.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(Duration::milliseconds((number * millis).round() as i64)),
+        _ => Err(format!("invalid duration `{}`; expected e.g. `500ms`, `10s`, `5min` or `1h`", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("250ms"), Ok(Duration::milliseconds(250)));
+        assert_eq!(parse_duration("10"), Ok(Duration::seconds(10)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::hours(2)));
+        assert!(parse_duration("1 week").is_err());
+        assert!(parse_duration("s").is_err());
+    }
+
+    #[test]
+    fn test_infer() {
+        let table = Table::init_via_sql("create table t (id int primary key, note varchar(10))");
+        assert!(TimeSeries::infer(&table).is_err());
+        let table = Table::init_via_sql("create table t (id int primary key, sensor_id int, at date)");
+        let series = TimeSeries::infer(&table).unwrap();
+        assert_eq!((series.timestamp.as_str(), series.entity.as_deref()), ("at", Some("sensor_id")));
+        let mut series = TimeSeries::new("id");
+        series.metrics = vec!["at".to_string()];
+        assert!(series.check(&table).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let table = Table::init_via_sql("create table t (device_id int, at timestamp, level number(5, 1), note varchar(10))");
+        let mut settings = TimeSeries::infer(&table).unwrap();
+        settings.interval = Duration::seconds(10);
+        settings.jitter = Duration::seconds(3);
+        settings.entities = 3;
+        let mut series = Series::new(settings);
+        let mut rng = StdRng::seed_from_u64(7);
+        let rows: Vec<Vec<SqlValue>> = (0..300)
+            .map(|_| {
+                let mut row = table.generate_row(&mut rng);
+                series.apply(&table, &mut row, None, &mut rng);
+                row
+            })
+            .collect();
+        let start = NaiveDateTime::parse_from_str("2021-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(rows[0][1], SqlValue::Timestamp(start));
+        let times: Vec<&SqlValue> = rows.iter().map(|row| &row[1]).collect();
+        assert!(times.windows(2).all(|pair| match pair {
+            [SqlValue::Timestamp(a), SqlValue::Timestamp(b)] => *b - *a >= Duration::seconds(7) && *b - *a <= Duration::seconds(13),
+            _ => false,
+        }));
+        assert_eq!(rows.iter().take(4).map(|row| row[0].clone()).collect::<Vec<_>>(), [1, 2, 3, 1].map(SqlValue::Integer));
+        // Readings of an entity move by at most a step of 1% of the range
+        let levels: Vec<i128> = rows
+            .iter()
+            .step_by(3)
+            .map(|row| match row[2] {
+                SqlValue::Decimal(level, 1) => level,
+                ref value => panic!("unexpected level {:?}", value),
+            })
+            .collect();
+        assert!(levels.windows(2).all(|pair| (pair[1] - pair[0]).abs() <= 1000));
+        assert!(levels.iter().all(|level| (1..=99999).contains(level)));
+
+        let keys = [SqlValue::Integer(40), SqlValue::Integer(50)];
+        let mut row = table.generate_row(&mut rng);
+        series.apply(&table, &mut row, Some(&keys), &mut rng);
+        assert_eq!(row[0], SqlValue::Integer(40));
+    }
+
+    #[test]
+    fn test_shards() {
+        let table = Table::init_via_sql("create table t (device_id int, at timestamp)");
+        let mut settings = TimeSeries::infer(&table).unwrap();
+        settings.interval = Duration::seconds(10);
+        // Jitter beyond the interval is cut, so the time still moves forward
+        settings.jitter = Duration::seconds(30);
+        settings.entities = 5;
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut pairs = HashSet::new();
+        for index in 0..2 {
+            let mut series = Series::new(settings.clone());
+            series.set_shard(index, 2);
+            let mut last = None;
+            for n in 0..20 {
+                let mut row = table.generate_row(&mut rng);
+                series.apply(&table, &mut row, None, &mut rng);
+                let SqlValue::Integer(entity) = row[0] else { panic!() };
+                assert_eq!((entity - 1) % 2, index as i64);
+                if n == 0 {
+                    assert_eq!(row[1], SqlValue::Timestamp(NaiveDateTime::parse_from_str("2021-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap() + Duration::seconds(5 * index as i64)));
+                }
+                let SqlValue::Timestamp(at) = row[1] else { panic!() };
+                assert!(last.is_none_or(|last| last < at));
+                last = Some(at);
+                assert!(pairs.insert(row));
+            }
+        }
+    }
+}
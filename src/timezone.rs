@@ -0,0 +1,111 @@
+//! UTC offsets of generated `timestamptz` values.
+
+use crate::models::DateRange;
+use chrono::{DateTime, FixedOffset};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// The UTC offsets timestamps with a time zone are drawn with, written
+/// `UTC,+05:30,-08:00`.
+///
+/// Without offsets, every timestamp gets a random whole-hour offset between -12:00 and
+/// +14:00. Offsets are fixed, so names such as `Europe/Berlin` are not accepted; `UTC`
+/// and `Z` stand for `+00:00`.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::timezone::TimeZones;
+/// let timezones: TimeZones = "UTC, +05:30,-8".parse().unwrap();
+/// assert_eq!(timezones.to_string(), "+00:00,+05:30,-08:00");
+/// assert!("Europe/Berlin".parse::<TimeZones>().is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimeZones {
+    offsets: Vec<FixedOffset>,
+}
+
+impl TimeZones {
+    /// Initializes a new `TimeZones` drawing from `offsets`, or from any whole-hour offset
+    /// if there are none.
+    pub fn new(offsets: Vec<FixedOffset>) -> TimeZones {
+        TimeZones { offsets }
+    }
+
+    /// Returns the offsets drawn from, empty for any whole-hour offset.
+    pub fn offsets(&self) -> &[FixedOffset] {
+        &self.offsets
+    }
+
+    /// Returns a random timestamp within `range` with one of the offsets.
+    pub fn random_timestamp<R: Rng + ?Sized>(&self, range: DateRange, rng: &mut R) -> DateTime<FixedOffset> {
+        let offset = match self.offsets.choose(rng) {
+            Some(offset) => *offset,
+            None => FixedOffset::east_opt(rng.gen_range(-12..=14) * 3600).unwrap(),
+        };
+        range.random_timestamp(rng).and_local_timezone(offset).unwrap()
+    }
+}
+
+impl fmt::Display for TimeZones {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offsets: Vec<String> = self.offsets.iter().map(|offset| offset.to_string()).collect();
+        f.write_str(&offsets.join(","))
+    }
+}
+
+impl FromStr for TimeZones {
+    type Err = String;
+
+    /// Parses comma separated offsets such as `UTC,+05:30,-08:00`; hours may stand alone,
+    /// e.g. `+9`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let offsets = s.split(',').map(|offset| parse_offset(offset.trim())).collect::<Result<_, _>>()?;
+        Ok(TimeZones { offsets })
+    }
+}
+
+/// Parses a UTC offset such as `+05:30`, `-8` or `UTC`.
+fn parse_offset(s: &str) -> Result<FixedOffset, String> {
+    let invalid = || format!("invalid UTC offset `{}`; expected e.g. `UTC`, `+05:30` or `-08:00`", s);
+    if s.eq_ignore_ascii_case("utc") || s == "Z" {
+        return Ok(FixedOffset::east_opt(0).unwrap());
+    }
+    let (sign, rest) = match s.chars().next() {
+        Some('+') => (1, &s[1..]),
+        Some('-') => (-1, &s[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes >= 60 || (hours == 14 && minutes > 0) {
+        return Err(format!("the UTC offset `{}` is beyond ±14:00", s));
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_random_timestamp() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let range: DateRange = "2023-05-06..2023-05-06".parse().unwrap();
+        let timezones: TimeZones = "+05:30,Z".parse().unwrap();
+        for _ in 0..50 {
+            let timestamp = timezones.random_timestamp(range, &mut rng);
+            assert!([19800, 0].contains(&timestamp.offset().local_minus_utc()), "{}", timestamp);
+        }
+        let timestamp = TimeZones::default().random_timestamp(range, &mut rng);
+        assert_eq!(timestamp.offset().local_minus_utc() % 3600, 0);
+        for invalid in ["", "+15:00", "+05:75", "CET", "5"] {
+            assert!(invalid.parse::<TimeZones>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Unique value generation for primary key and unique columns.
+
+use crate::models::Column;
+use crate::value::SqlValue;
+use rand::Rng;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// Number of random draws tried before falling back to the next free sequential value.
+const MAX_RANDOM_ATTEMPTS: usize = 100;
+
+/// Enum representing how unique key values are generated.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeyStrategy {
+    /// 1, 2, 3, ... per column.
+    #[default]
+    Sequential,
+    /// Random values within the column's declared precision, never repeated.
+    Random,
+}
+
+impl KeyStrategy {
+    /// Returns the lowercase name of the strategy, as accepted by `FromStr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyStrategy::Sequential => "sequential",
+            KeyStrategy::Random => "random",
+        }
+    }
+}
+
+impl fmt::Display for KeyStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for KeyStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sequential" | "sequence" => Ok(KeyStrategy::Sequential),
+            "random" | "unique-random" => Ok(KeyStrategy::Random),
+            _ => Err(format!("unknown key strategy `{}`", s)),
+        }
+    }
+}
+
+/// Tracks the values handed out per column so every generated value is unique.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct UniqueValues {
+    strategy: KeyStrategy,
+    /// Index of the shard whose numbers are handed out, see [`UniqueValues::set_shard`].
+    shard: i64,
+    /// Number of shards sharing the key space; 0 and 1 both mean unsharded.
+    shards: i64,
+    /// Next sequential candidate per `(table, column)`.
+    next: HashMap<(String, String), i64>,
+    /// Random values handed out per `(table, column)`.
+    used: HashMap<(String, String), HashSet<SqlValue>>,
+}
+
+impl UniqueValues {
+    pub(crate) fn new(strategy: KeyStrategy) -> UniqueValues {
+        UniqueValues {
+            strategy,
+            ..UniqueValues::default()
+        }
+    }
+
+    /// Returns how the key values are generated.
+    pub(crate) fn strategy(&self) -> KeyStrategy {
+        self.strategy
+    }
+
+    /// Restricts the numbers handed out to the shard `index` of `count`, so shards
+    /// generating in parallel never produce the same key.
+    ///
+    /// Shard `index` gets the numbers `n` with `(n - 1) % count == index`: 1, 1 + count,
+    /// 1 + 2 * count, ... for the first shard. Setting a shard again splits the current one.
+    pub(crate) fn set_shard(&mut self, index: usize, count: usize) {
+        let shards = self.shards.max(1);
+        self.shard += shards * index as i64;
+        self.shards = shards * count as i64;
+    }
+
+    /// Maps `n` into the numbers of the shard, staying within `1..=max` where possible.
+    fn in_shard(&self, n: i64, max: i64) -> i64 {
+        let shards = self.shards.max(1);
+        let n = n - (n - 1) % shards + self.shard;
+        if n > max && n - shards >= 1 {
+            n - shards
+        } else {
+            n
+        }
+    }
+
+    /// Returns the `n`-th number of the shard.
+    fn nth(&self, n: i64) -> i64 {
+        (n - 1) * self.shards.max(1) + self.shard + 1
+    }
+
+    /// Moves the values handed out for the columns of the table called `old` to the name `new`.
+    pub(crate) fn rename_table(&mut self, old: &str, new: &str) {
+        rename_table_keys(&mut self.next, old, new);
+        rename_table_keys(&mut self.used, old, new);
+    }
+
+    /// Generates a value for `column` of `table` that was not handed out before.
+    pub(crate) fn generate<R: Rng + ?Sized>(&mut self, table: &str, column: &Column, rng: &mut R) -> SqlValue {
+        if self.strategy == KeyStrategy::Sequential {
+            return self.sequential(table, column);
+        }
+
+        let key = (table.to_string(), column.name.clone());
+        for _ in 0..MAX_RANDOM_ATTEMPTS {
+            let value = self.random_candidate(column, rng);
+            if self.used.get(&key).is_none_or(|used| !used.contains(&value)) {
+                self.used.entry(key).or_default().insert(value.clone());
+                return value;
+            }
+        }
+        loop {
+            let counter = self.next.entry(key.clone()).or_insert(1);
+            let n = *counter;
+            *counter += 1;
+            let value = sequential_candidate(column, self.nth(n));
+            if self.used.entry(key.clone()).or_default().insert(value.clone()) {
+                return value;
+            }
+        }
+    }
+
+    /// Returns the next value of the sequence 1, 2, 3, ... for `column` of `table`,
+    /// whatever the key strategy.
+    ///
+    /// Auto-increment columns use it to predict the values the database assigns.
+    pub(crate) fn sequential(&mut self, table: &str, column: &Column) -> SqlValue {
+        sequential_candidate(column, self.sequence(table, column))
+    }
+
+    /// Returns the next number of the sequence 1, 2, 3, ... for `column` of `table`, within
+    /// the numbers of the shard.
+    pub(crate) fn sequence(&mut self, table: &str, column: &Column) -> i64 {
+        // Sequential values never collide, so there is no need to remember them
+        let counter = self.next.entry((table.to_string(), column.name.clone())).or_insert(1);
+        *counter += 1;
+        let n = *counter - 1;
+        self.nth(n)
+    }
+
+    /// Draws values with `draw` until one was not handed out for `column` of `table`
+    /// before, for columns declared `UNIQUE`. NULL counts as a value, so at most one row
+    /// holds NULL as SQL Server requires.
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if `MAX_RANDOM_ATTEMPTS` draws were all taken.
+    pub(crate) fn distinct<R: Rng + ?Sized>(
+        &mut self,
+        table: &str,
+        column: &Column,
+        rng: &mut R,
+        mut draw: impl FnMut(&mut R) -> SqlValue,
+    ) -> Option<SqlValue> {
+        let used = self.used.entry((table.to_string(), column.name.clone())).or_default();
+        for _ in 0..MAX_RANDOM_ATTEMPTS {
+            let value = draw(rng);
+            if used.insert(value.clone()) {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Records `value` as handed out for `column` of `table`, for values drawn elsewhere
+    /// such as the values of a template.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value was not handed out before.
+    pub(crate) fn claim(&mut self, table: &str, column: &Column, value: &SqlValue) -> bool {
+        self.used.entry((table.to_string(), column.name.clone())).or_default().insert(value.clone())
+    }
+
+    /// Returns the first value of the sequence 1, 2, 3, ... that was not handed out for
+    /// `column` of `table`, for unique columns whose random values ran out.
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if the column cannot hold another sequential value, such as
+    /// a `varchar(2)` past 99, a date column or a column limited to a value pool.
+    pub(crate) fn unused(&mut self, table: &str, column: &Column) -> Option<SqlValue> {
+        if column.pool.is_some() {
+            return None;
+        }
+        // Numbers stay within the range of the column, counted in whole units
+        let factor = 10i128.pow(column.decimal_places.unwrap_or(0).clamp(0, 38) as u32);
+        let (min, max) = column.numeric_range();
+        let start = if column.is_numeric() { (min + factor - 1).div_euclid(factor).clamp(1, i64::MAX as i128) as i64 } else { 1 };
+        let key = (table.to_string(), column.name.clone());
+        loop {
+            let counter = self.next.entry(key.clone()).or_insert(start);
+            let n = *counter;
+            *counter += 1;
+            let fits = match column.column_type.as_str() {
+                "varchar" | "text" => column.length.is_none_or(|length| n.to_string().len() <= length.max(0) as usize),
+                "uuid" | "uniqueidentifier" => true,
+                _ if column.is_numeric() => (n as i128).saturating_mul(factor) <= max,
+                _ => false,
+            };
+            if !fits {
+                return None;
+            }
+            let value = match column.decimal_places {
+                Some(scale) if scale > 0 && column.is_numeric() => SqlValue::Decimal(n as i128 * factor, scale as u32),
+                _ => sequential_candidate(column, n),
+            };
+            if self.used.entry(key.clone()).or_default().insert(value.clone()) {
+                return Some(value);
+            }
+        }
+    }
+
+    /// Returns `true` if `value` belongs to the shard: values that are not numbers are
+    /// assigned to the shards by their hash.
+    fn owns(&self, value: &SqlValue) -> bool {
+        let shards = self.shards.max(1) as u64;
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        shards == 1 || hasher.finish() % shards == self.shard as u64
+    }
+
+    /// Returns a random candidate within the column's declared precision and the values
+    /// of the shard.
+    fn random_candidate<R: Rng + ?Sized>(&self, column: &Column, rng: &mut R) -> SqlValue {
+        match column.column_type.as_str() {
+            "varchar" | "text" | "date" | "datetime" | "uuid" | "uniqueidentifier" => {
+                // Values of other shards are drawn again; the last one is returned if
+                // the column holds too few values for that
+                let mut value = column.generate_value(rng);
+                for _ in 0..MAX_RANDOM_ATTEMPTS * self.shards.max(1) as usize {
+                    if self.owns(&value) {
+                        break;
+                    }
+                    value = column.generate_value(rng);
+                }
+                value
+            }
+            _ => {
+                let digits = column.length.unwrap_or(9) - column.decimal_places.unwrap_or(0);
+                let max = 10i64.pow(digits.clamp(1, 18) as u32) - 1;
+                sequential_candidate(column, self.in_shard(rng.gen_range(1..=max), max))
+            }
+        }
+    }
+}
+
+/// Returns the `n`-th sequential value for the column type.
+fn sequential_candidate(column: &Column, n: i64) -> SqlValue {
+    match column.column_type.as_str() {
+        "varchar" | "text" => SqlValue::Text(n.to_string()),
+        "uuid" | "uniqueidentifier" => SqlValue::Uuid(n as u128),
+        "number" if column.decimal_places.is_some() => {
+            let scale = column.decimal_places.unwrap().clamp(0, 38) as u32;
+            SqlValue::Decimal((n as i128).saturating_mul(10i128.pow(scale)), scale)
+        }
+        _ => SqlValue::Integer(n),
+    }
+}
+
+/// Moves the entries of `map` keyed by a column of the table called `old` to the name `new`.
+pub(crate) fn rename_table_keys<V>(map: &mut HashMap<(String, String), V>, old: &str, new: &str) {
+    let renamed: Vec<(String, String)> = map.keys().filter(|(table, _)| table == old).cloned().collect();
+    for key in renamed {
+        let value = map.remove(&key).unwrap();
+        map.insert((new.to_string(), key.1), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Table;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sequential() {
+        let table = Table::init_via_sql("create table t (id number(10) primary key)");
+        let mut unique = UniqueValues::new(KeyStrategy::Sequential);
+        let mut rng = StdRng::seed_from_u64(1);
+        let values: Vec<SqlValue> = (0..3).map(|_| unique.generate("t", &table.columns[0], &mut rng)).collect();
+        assert_eq!(values, [SqlValue::Integer(1), SqlValue::Integer(2), SqlValue::Integer(3)]);
+    }
+
+    #[test]
+    fn test_shards_are_disjoint() {
+        let table = Table::init_via_sql("create table t (id number(3) primary key)");
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut values = HashSet::new();
+        for index in 0..3 {
+            for strategy in [KeyStrategy::Sequential, KeyStrategy::Random] {
+                let mut unique = UniqueValues::new(strategy);
+                unique.set_shard(index, 3);
+                for _ in 0..50 {
+                    let SqlValue::Integer(n) = unique.generate("t", &table.columns[0], &mut rng) else { panic!() };
+                    assert_eq!((n - 1) % 3, index as i64);
+                    values.insert(n);
+                }
+            }
+        }
+        assert!((1..=150).all(|n| values.contains(&n)));
+    }
+
+    #[test]
+    fn test_random_text_shards_are_disjoint() {
+        let table = Table::init_via_sql("create table t (code varchar(6) primary key, id uuid primary key)");
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut values = HashSet::new();
+        for index in 0..3 {
+            let mut unique = UniqueValues::new(KeyStrategy::Random);
+            unique.set_shard(index, 3);
+            for column in &table.columns {
+                for _ in 0..50 {
+                    let value = unique.generate("t", column, &mut rng);
+                    assert!(values.insert(value));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unused() {
+        let table = Table::init_via_sql("create table t (code varchar(1), price numeric(4, 2), day date)");
+        let mut unique = UniqueValues::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(unique.distinct("t", &table.columns[0], &mut rng, |_| SqlValue::Text("1".to_string())), Some(SqlValue::Text("1".to_string())));
+        assert_eq!(unique.distinct("t", &table.columns[0], &mut rng, |_| SqlValue::Text("1".to_string())), None);
+        // "1" is taken, so the sequence continues at "2" and ends after "9"
+        let codes: Vec<Option<SqlValue>> = (0..9).map(|_| unique.unused("t", &table.columns[0])).collect();
+        assert_eq!(codes[0], Some(SqlValue::Text("2".to_string())));
+        assert_eq!(codes[8], None);
+        // numeric(4, 2) holds up to 99.99
+        assert_eq!((0..100).filter_map(|_| unique.unused("t", &table.columns[1])).count(), 99);
+        assert_eq!(unique.unused("t", &table.columns[2]), None);
+    }
+
+    #[test]
+    fn test_random_never_repeats() {
+        // 99 possible values: random draws collide quickly and must fall back
+        let table = Table::init_via_sql("create table t (id number(2) primary key)");
+        let mut unique = UniqueValues::new(KeyStrategy::Random);
+        let mut rng = StdRng::seed_from_u64(1);
+        let values: HashSet<SqlValue> = (0..99).map(|_| unique.generate("t", &table.columns[0], &mut rng)).collect();
+        assert_eq!(values.len(), 99);
+    }
+}
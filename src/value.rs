@@ -0,0 +1,214 @@
+//! Generated column values.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+/// A single generated column value.
+///
+/// Values are rendered as SQL literals by [`Dialect::literal`](crate::Dialect::literal),
+/// so the same value can be emitted for any dialect.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    /// An exact decimal number as a count of units of its scale and the number of digits
+    /// after the decimal point, e.g. `Decimal(1234, 2)` for 12.34.
+    Decimal(i128, u32),
+    Text(String),
+    Date(NaiveDate),
+    Boolean(bool),
+    /// A UUID, stored as its 128-bit value.
+    Uuid(u128),
+    Timestamp(NaiveDateTime),
+    /// A timestamp with a UTC offset.
+    TimestampTz(DateTime<FixedOffset>),
+    /// A time of day.
+    Time(NaiveTime),
+    /// A duration in whole seconds.
+    Interval(i64),
+    Bytes(Vec<u8>),
+    /// Binary data written as base64 text the database decodes, see
+    /// [`BlobEncoding::Base64`](crate::blob::BlobEncoding::Base64).
+    Base64(Vec<u8>),
+    Json(serde_json::Value),
+    /// The elements of a Postgres array, e.g. of an `int[]` column.
+    Array(Vec<SqlValue>),
+    /// The attributes of a Postgres composite type, by name.
+    Composite(Vec<(String, SqlValue)>),
+    /// A spatial value as Well-Known Text in SRID 4326, e.g. `POINT(13.4 52.5)`, and whether
+    /// it is a `geography` rather than a `geometry`.
+    Geometry(String, bool),
+    /// SQL text written into statements as is, such as a deliberately malformed literal.
+    Raw(String),
+}
+
+impl SqlValue {
+    /// Returns the value as plain text, without SQL quoting.
+    ///
+    /// # Returns
+    ///
+    /// `None` for `SqlValue::Null`, otherwise the text, number, ISO date, time, timestamp or
+    /// duration,
+    /// `true`/`false`, hyphenated UUID, hex or base64 encoded bytes, JSON document, raw SQL text, or
+    /// the Postgres text form of an array or composite, e.g. `{1,2}` or `("Main St",12)`, or
+    /// the Extended Well-Known Text of a spatial value, e.g. `SRID=4326;POINT(13.4 52.5)`.
+    pub fn as_text(&self) -> Option<String> {
+        match self {
+            SqlValue::Null => None,
+            SqlValue::Integer(n) => Some(n.to_string()),
+            SqlValue::Decimal(units, scale) => Some(format_decimal(*units, *scale)),
+            SqlValue::Text(s) => Some(s.clone()),
+            SqlValue::Date(date) => Some(date.to_string()),
+            SqlValue::Boolean(b) => Some(b.to_string()),
+            SqlValue::Uuid(uuid) => Some(format_uuid(*uuid)),
+            SqlValue::Timestamp(ts) => Some(ts.format("%Y-%m-%d %H:%M:%S").to_string()),
+            SqlValue::TimestampTz(ts) => Some(ts.format("%Y-%m-%d %H:%M:%S%:z").to_string()),
+            SqlValue::Time(time) => Some(time.format("%H:%M:%S").to_string()),
+            SqlValue::Interval(seconds) => Some(iso_duration(*seconds)),
+            SqlValue::Bytes(bytes) => Some(hex(bytes)),
+            SqlValue::Base64(bytes) => Some(base64(bytes)),
+            SqlValue::Json(json) => Some(json.to_string()),
+            SqlValue::Raw(sql) => Some(sql.clone()),
+            SqlValue::Geometry(wkt, _) => Some(format!("SRID={};{}", crate::geo::SRID, wkt)),
+            SqlValue::Array(values) => {
+                let elements: Vec<String> = values.iter().map(|value| element_text(value, "NULL")).collect();
+                Some(format!("{{{}}}", elements.join(",")))
+            }
+            SqlValue::Composite(fields) => {
+                let attributes: Vec<String> = fields.iter().map(|(_, value)| element_text(value, "")).collect();
+                Some(format!("({})", attributes.join(",")))
+            }
+        }
+    }
+}
+
+impl SqlValue {
+    /// Returns the decimal closest to `value` with `scale` digits after the decimal point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fake_sql::SqlValue;
+    /// assert_eq!(SqlValue::decimal(12.345, 2), SqlValue::Decimal(1235, 2));
+    /// assert_eq!(SqlValue::decimal(-0.5, 1).as_text().unwrap(), "-0.5");
+    /// ```
+    pub fn decimal(value: f64, scale: u32) -> SqlValue {
+        SqlValue::Decimal((value * 10f64.powi(scale as i32)).round() as i128, scale)
+    }
+
+    /// Returns the value as a floating point number, if it is an integer or a decimal.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SqlValue::Integer(n) => Some(*n as f64),
+            SqlValue::Decimal(units, scale) => Some(*units as f64 / 10f64.powi(*scale as i32)),
+            _ => None,
+        }
+    }
+
+    /// Converts the value to JSON: numbers, booleans and JSON documents keep their type,
+    /// everything else becomes the string returned by [`as_text`](SqlValue::as_text).
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            SqlValue::Null => serde_json::Value::Null,
+            SqlValue::Integer(n) => (*n).into(),
+            SqlValue::Decimal(..) => serde_json::Number::from_f64(self.as_f64().unwrap()).map_or(serde_json::Value::Null, Into::into),
+            SqlValue::Boolean(b) => (*b).into(),
+            SqlValue::Json(json) => json.clone(),
+            SqlValue::Array(values) => values.iter().map(SqlValue::to_json).collect(),
+            SqlValue::Composite(fields) => fields.iter().map(|(name, value)| (name.clone(), value.to_json())).collect(),
+            _ => self.as_text().into(),
+        }
+    }
+}
+
+/// Renders a value as an element of the text form of a Postgres array or composite:
+/// numbers and booleans as they are, other values in double quotes and NULL as `null`,
+/// which is `NULL` in arrays and empty in composites.
+fn element_text(value: &SqlValue, null: &str) -> String {
+    match value {
+        SqlValue::Null => null.to_string(),
+        SqlValue::Integer(_) | SqlValue::Decimal(..) | SqlValue::Boolean(_) => value.as_text().unwrap(),
+        _ => {
+            let text = value.as_text().unwrap();
+            format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+    }
+}
+
+/// Formats `units` of a decimal with `scale` digits after the decimal point exactly, e.g.
+/// `-0.05` for -5 units of scale 2.
+pub(crate) fn format_decimal(units: i128, scale: u32) -> String {
+    let sign = if units < 0 { "-" } else { "" };
+    match 10u128.checked_pow(scale) {
+        Some(factor) if scale > 0 => format!("{}{}.{:0width$}", sign, units.unsigned_abs() / factor, units.unsigned_abs() % factor, width = scale as usize),
+        Some(_) => units.to_string(),
+        // More digits after the decimal point than any i128 holds
+        None => format!("{}0.{:0>width$}", sign, units.unsigned_abs(), width = scale as usize),
+    }
+}
+
+/// Formats a UUID in its hyphenated form, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+pub(crate) fn format_uuid(uuid: u128) -> String {
+    let hex = format!("{:032x}", uuid);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// Formats a duration in seconds as ISO 8601, e.g. `P3DT4H5M6S`.
+pub(crate) fn iso_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.unsigned_abs();
+    let (days, hours, minutes, seconds) = (seconds / 86_400, seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+    format!("{}P{}DT{}H{}M{}S", sign, days, hours, minutes, seconds)
+}
+
+/// Encodes bytes as lowercase hex digits.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Encodes bytes as standard base64 with padding, e.g. `Cv8=` for `0a ff`.
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | ((b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            text.push(if i <= chunk.len() { ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char } else { '=' });
+        }
+    }
+    text
+}
+
+impl Eq for SqlValue {}
+
+impl Hash for SqlValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            SqlValue::Null => {}
+            SqlValue::Integer(n) => n.hash(state),
+            SqlValue::Decimal(units, scale) => {
+                units.hash(state);
+                scale.hash(state);
+            }
+            SqlValue::Text(s) => s.hash(state),
+            SqlValue::Date(date) => date.hash(state),
+            SqlValue::Boolean(b) => b.hash(state),
+            SqlValue::Uuid(uuid) => uuid.hash(state),
+            SqlValue::Timestamp(ts) => ts.hash(state),
+            SqlValue::TimestampTz(ts) => ts.hash(state),
+            SqlValue::Time(time) => time.hash(state),
+            SqlValue::Interval(seconds) => seconds.hash(state),
+            SqlValue::Bytes(bytes) | SqlValue::Base64(bytes) => bytes.hash(state),
+            SqlValue::Json(json) => json.to_string().hash(state),
+            SqlValue::Raw(sql) => sql.hash(state),
+            SqlValue::Array(values) => values.hash(state),
+            SqlValue::Composite(fields) => fields.hash(state),
+            SqlValue::Geometry(wkt, geography) => {
+                wkt.hash(state);
+                geography.hash(state);
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! JavaScript bindings of the generator for WebAssembly, so docs sites and browser-based SQL
+//! tools can generate fixtures client-side.
+//!
+//! Built for `wasm32-unknown-unknown`, e.g. with `wasm-pack build --target web`, the crate
+//! exports `generate(schemaSql, options)`, which returns an array of statements:
+//!
+//! ```js
+//! import init, { generate } from "./pkg/fake_sql.js";
+//! await init();
+//! const statements = generate("create table t (id int primary key)", { count: 5, dialect: "postgres", types: ["insert"] });
+//! ```
+//!
+//! The options mirror the command line and are all optional: `count` (default 10),
+//! `dialect`, `seed`, `types` (statement types such as `insert` or `select-join`) and
+//! `table`. The same options are available to Rust code as [`GenerateOptions`].
+
+use crate::dialect::Dialect;
+use crate::generator::Generator;
+use crate::models::SqlType;
+use crate::schema::{parse_schema, DEFAULT_SCHEMA};
+use serde::Deserialize;
+
+/// Number of statements generated unless the options say otherwise.
+pub const DEFAULT_COUNT: u64 = 10;
+
+/// The options of `generate`, deserialized from a JavaScript object with camelCase keys.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct GenerateOptions {
+    /// Number of statements [default: 10].
+    pub count: Option<u64>,
+    /// SQL dialect such as `postgres` or `mysql` [default: oracle].
+    pub dialect: Option<String>,
+    /// Seed of the random generator, for reproducible statements.
+    pub seed: Option<u64>,
+    /// Statement types such as `insert` or `select-join` [default: all of them].
+    pub types: Vec<String>,
+    /// Only generate statements for this table [default: every table].
+    pub table: Option<String>,
+}
+
+/// Generates statements for the CREATE TABLE statements of `schema_sql`, or for the
+/// built-in tables when it is empty.
+///
+/// # Example
+///
+/// ```
+/// # use fake_sql::wasm::{generate_statements, GenerateOptions};
+/// let options = GenerateOptions { count: Some(3), dialect: Some("mysql".into()), types: vec!["insert".into()], ..Default::default() };
+/// let statements = generate_statements("create table t (id int primary key)", &options).unwrap();
+/// assert_eq!(statements, ["INSERT INTO t (id) VALUES (1);", "INSERT INTO t (id) VALUES (2);", "INSERT INTO t (id) VALUES (3);"]);
+/// ```
+pub fn generate_statements(schema_sql: &str, options: &GenerateOptions) -> Result<Vec<String>, String> {
+    let tables = parse_schema(if schema_sql.trim().is_empty() { DEFAULT_SCHEMA } else { schema_sql })?;
+    let tables = match &options.table {
+        Some(name) => vec![tables.into_iter().find(|t| t.name.eq_ignore_ascii_case(name)).ok_or_else(|| format!("table `{}` is not in the schema", name))?],
+        None => tables,
+    };
+    if tables.is_empty() {
+        return Err("the schema holds no CREATE TABLE statements".to_string());
+    }
+    let mut generator = match options.seed {
+        Some(seed) => Generator::with_seed(tables, seed),
+        None => Generator::new(tables),
+    };
+    if let Some(dialect) = &options.dialect {
+        generator.set_dialect(dialect.parse::<Dialect>()?);
+    }
+    if !options.types.is_empty() {
+        generator.set_sql_types(options.types.iter().map(|name| name.parse()).collect::<Result<Vec<SqlType>, String>>()?);
+    }
+    Ok((0..options.count.unwrap_or(DEFAULT_COUNT)).map(|_| generator.next_statement()).collect())
+}
+
+/// Generates statements for `schemaSql` with the `options` object, which may be left out,
+/// and returns them as an array of strings; invalid options throw an `Error`.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn generate(schema_sql: &str, options: wasm_bindgen::JsValue) -> Result<Vec<String>, wasm_bindgen::JsError> {
+    let options: GenerateOptions = if options.is_undefined() || options.is_null() {
+        GenerateOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options).map_err(|err| wasm_bindgen::JsError::new(&format!("invalid options: {}", err)))?
+    };
+    generate_statements(schema_sql, &options).map_err(|err| wasm_bindgen::JsError::new(&err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_statements() {
+        let options: GenerateOptions = serde_json::from_str(r#"{"count": 40, "seed": 5, "types": ["insert", "select"], "table": "orders"}"#).unwrap();
+        let statements = generate_statements("", &options).unwrap();
+        assert_eq!(statements.len(), 40);
+        assert!(statements.iter().all(|sql| sql.starts_with("INSERT INTO orders ") || sql.starts_with("SELECT ")), "{:?}", statements);
+        assert_eq!(generate_statements("", &options).unwrap(), statements);
+
+        assert_eq!(generate_statements("", &GenerateOptions::default()).unwrap().len(), 10);
+        assert!(serde_json::from_str::<GenerateOptions>(r#"{"rows": 5}"#).is_err());
+        for options in [GenerateOptions { table: Some("nowhere".into()), ..Default::default() }, GenerateOptions { dialect: Some("db2".into()), ..Default::default() }] {
+            assert!(generate_statements("", &options).is_err());
+        }
+        assert!(generate_statements("select 1", &GenerateOptions::default()).is_err());
+    }
+}